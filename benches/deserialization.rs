@@ -0,0 +1,127 @@
+use std::net::Ipv4Addr;
+
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use hrobot::{api::rdns::RdnsEntry, AsyncHttpClient, AsyncRobot, ResponseFuture};
+use hyper::{Request, Response};
+
+/// Client which responds to every request with the same canned body.
+#[derive(Debug)]
+struct CannedClient(Bytes);
+
+impl AsyncHttpClient for CannedClient {
+    fn send_request(&self, _request: Request<String>) -> ResponseFuture<'_> {
+        let body = self.0.clone();
+        Box::pin(async move { Ok(Response::new(body)) })
+    }
+}
+
+/// Produce a reverse DNS listing of the given length, as returned by the Robot API.
+fn rdns_listing(entries: u32) -> Bytes {
+    let entries: Vec<_> = (0..entries)
+        .map(|i| {
+            format!(
+                r#"{{"rdns":{{"ip":"{}","ptr":"server{i}.example.com"}}}}"#,
+                Ipv4Addr::from(0x0A00_0000 + i)
+            )
+        })
+        .collect();
+
+    Bytes::from(format!("[{}]", entries.join(",")))
+}
+
+/// Produce a listing consisting of `entries` copies of the stored `fixture`.
+fn fixture_listing(fixture: &str, entries: usize) -> Bytes {
+    Bytes::from(format!("[{}]", vec![fixture.trim(); entries].join(",")))
+}
+
+fn list_rdns_entries(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("list_rdns_entries");
+
+    for entries in [100, 1_000, 10_000] {
+        let body = rdns_listing(entries);
+        let robot = AsyncRobot::new(CannedClient(body.clone()), "#ws+username", "p@ssw0rd");
+
+        group.throughput(Throughput::Bytes(body.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(entries), &robot, |b, robot| {
+            b.to_async(&runtime)
+                .iter(|| async { robot.list_rdns_entries().await.unwrap() })
+        });
+    }
+
+    group.finish();
+}
+
+fn list_servers(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("list_servers");
+
+    for entries in [10, 100, 1_000] {
+        let body = fixture_listing(include_str!("fixtures/server.json"), entries);
+        let robot = AsyncRobot::new(CannedClient(body.clone()), "#ws+username", "p@ssw0rd");
+
+        group.throughput(Throughput::Bytes(body.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(entries), &robot, |b, robot| {
+            b.to_async(&runtime)
+                .iter(|| async { robot.list_servers().await.unwrap() })
+        });
+    }
+
+    group.finish();
+}
+
+fn list_market_products(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("list_market_products");
+
+    // The server market regularly lists several thousand servers.
+    for entries in [100, 1_000, 5_000] {
+        let body = fixture_listing(include_str!("fixtures/market_product.json"), entries);
+        let robot = AsyncRobot::new(CannedClient(body.clone()), "#ws+username", "p@ssw0rd");
+
+        group.throughput(Throughput::Bytes(body.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(entries), &robot, |b, robot| {
+            b.to_async(&runtime)
+                .iter(|| async { robot.list_market_products().await.unwrap() })
+        });
+    }
+
+    group.finish();
+}
+
+/// Compares the previous lossy string conversion approach with deserializing
+/// directly from the response bytes.
+fn body_decoding(c: &mut Criterion) {
+    #[derive(serde::Deserialize)]
+    struct Wrapper {
+        #[allow(dead_code)]
+        rdns: RdnsEntry,
+    }
+
+    let body = rdns_listing(10_000);
+    let mut group = c.benchmark_group("body_decoding");
+    group.throughput(Throughput::Bytes(body.len() as u64));
+
+    group.bench_function("from_utf8_lossy + from_str", |b| {
+        b.iter(|| {
+            let stringified = String::from_utf8_lossy(&body);
+            serde_json::from_str::<Vec<Wrapper>>(&stringified).unwrap()
+        })
+    });
+
+    group.bench_function("from_slice", |b| {
+        b.iter(|| serde_json::from_slice::<Vec<Wrapper>>(&body).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    list_rdns_entries,
+    list_servers,
+    list_market_products,
+    body_decoding
+);
+criterion_main!(benches);
@@ -0,0 +1,180 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    net::Ipv4Addr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use hrobot::{
+    api::{
+        firewall::{FirewallConfig, FirewallTemplateConfig, Ipv4Filter, Rule, Rules},
+        ordering::{
+            AddonKind, AuthorizationMethod, ImSeriousAboutSpendingMoney, Location, ProductId,
+            ProductOrder,
+        },
+        server::ServerId,
+    },
+    error::Error,
+    AsyncHttpClient, AsyncRobot, ResponseFuture,
+};
+use hyper::{Request, Response};
+
+/// Counts allocations, to keep track of how allocation-heavy encoding is.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Number of allocations made while running `f`.
+fn allocations<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let _ = std::hint::black_box(f());
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+/// Client which is never reached, since all requests are caught by dry-run mode.
+#[derive(Debug)]
+struct UnreachableClient;
+
+impl AsyncHttpClient for UnreachableClient {
+    fn send_request(&self, _request: Request<String>) -> ResponseFuture<'_> {
+        Box::pin(async { Ok(Response::new(Bytes::new())) })
+    }
+}
+
+/// Robot which renders requests without sending them.
+fn robot() -> AsyncRobot {
+    AsyncRobot::new(UnreachableClient, "#ws+username", "p@ssw0rd").dry_run(true)
+}
+
+/// Extract the rendered body from the dry-run error.
+fn rendered(result: Result<impl std::fmt::Debug, Error>) -> String {
+    match result {
+        Err(Error::DryRun(request)) => request.body.unwrap(),
+        other => panic!("expected dry-run, got {other:?}"),
+    }
+}
+
+fn firewall_config(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let robot = robot();
+
+    let config = FirewallConfig::parse_rules_text(include_str!("fixtures/firewall.rules")).unwrap();
+    let encoded = runtime.block_on(robot.set_firewall_config(ServerId(321), &config));
+
+    let mut group = c.benchmark_group("encoding");
+    group.throughput(Throughput::Bytes(rendered(encoded).len() as u64));
+    group.bench_function("firewall_config", |b| {
+        b.to_async(&runtime)
+            .iter(|| async { rendered(robot.set_firewall_config(ServerId(321), &config).await) })
+    });
+
+    group.finish();
+}
+
+/// Template with `rules` ingress rules, each with a name, protocol, source network and port.
+fn template(index: usize, rules: usize) -> FirewallTemplateConfig {
+    FirewallTemplateConfig {
+        name: format!("Template #{index}"),
+        filter_ipv6: false,
+        whitelist_hetzner_services: true,
+        is_default: false,
+        rules: Rules {
+            ingress: (0..rules)
+                .map(|rule| {
+                    Rule::accept(&format!("Allow service {rule} from office")).matching(
+                        Ipv4Filter::tcp(None)
+                            .from_ip(Ipv4Addr::new(10, index as u8, rule as u8, 0))
+                            .to_port(8000 + rule as u16),
+                    )
+                })
+                .collect(),
+            egress: vec![Rule::accept("Allow all")],
+        },
+    }
+}
+
+fn firewall_templates(c: &mut Criterion) {
+    const TEMPLATES: usize = 10;
+    const RULES: usize = 20;
+
+    let templates: Vec<_> = (0..TEMPLATES).map(|index| template(index, RULES)).collect();
+    let encode = || {
+        templates
+            .iter()
+            .map(FirewallTemplateConfig::to_urlencoded)
+            .collect::<Vec<_>>()
+    };
+
+    // Encoding should only allocate for converting the rules and growing the
+    // output, not for each key or value, so bound allocations to a few per rule.
+    let allocations = allocations(encode);
+    assert!(
+        allocations <= TEMPLATES * RULES * 3,
+        "encoding {TEMPLATES} templates of {RULES} rules made {allocations} allocations"
+    );
+
+    let mut group = c.benchmark_group("encoding");
+    group.throughput(Throughput::Bytes(
+        encode().iter().map(String::len).sum::<usize>() as u64,
+    ));
+    group.bench_function("firewall_templates", |b| b.iter(encode));
+
+    group.finish();
+}
+
+fn product_order(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let robot = robot();
+
+    let order = ProductOrder {
+        id: ProductId::from("EX44"),
+        auth: AuthorizationMethod::Keys(vec![
+            "15:28:b0:03:95:f0:77:b3:10:56:15:6b:77:22:a5:bb"
+                .parse()
+                .unwrap(),
+            "d7:2a:b4:5c:1e:09:3f:8d:66:e2:70:4b:a1:c9:5f:30"
+                .parse()
+                .unwrap(),
+        ]),
+        distribution: Some("Debian 12 base".to_string()),
+        language: Some("en".to_string()),
+        location: Location::from("FSN1"),
+        addons: vec![AddonKind::PrimaryIpv4.into()],
+        comment: Some("Provisioned by: ci/cd & friends; see https://example.com/?a=b".to_string()),
+        i_want_to_spend_money_to_purchase_a_server: ImSeriousAboutSpendingMoney::NoThisIsJustATest,
+    };
+
+    let encoded = runtime.block_on(robot.place_product_order(order.clone()));
+
+    let mut group = c.benchmark_group("encoding");
+    group.throughput(Throughput::Bytes(rendered(encoded).len() as u64));
+    group.bench_function("product_order", |b| {
+        b.to_async(&runtime)
+            .iter(|| async { rendered(robot.place_product_order(order.clone()).await) })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, firewall_config, firewall_templates, product_order);
+criterion_main!(benches);
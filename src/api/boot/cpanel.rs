@@ -68,7 +68,11 @@ impl AsyncRobot {
     /// }
     /// # }
     /// ```
-    pub async fn get_cpanel_config(&self, server_number: ServerId) -> Result<Cpanel, Error> {
+    pub async fn get_cpanel_config(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<Cpanel, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_cpanel_config(server_number)).await?.0)
     }
 
@@ -91,8 +95,9 @@ impl AsyncRobot {
     /// ```
     pub async fn get_last_cpanel_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
     ) -> Result<ActiveCpanelConfig, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_last_cpanel_config(server_number)).await?.0)
     }
 
@@ -114,9 +119,10 @@ impl AsyncRobot {
     /// ```
     pub async fn enable_cpanel_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
         config: CpanelConfig,
     ) -> Result<ActiveCpanelConfig, Error> {
+        let server_number = server_number.into();
         Ok(self
             .go(enable_cpanel_config(server_number, config)?)
             .await?
@@ -136,14 +142,16 @@ impl AsyncRobot {
     /// ```
     pub async fn disable_cpanel_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
     ) -> Result<AvailableCpanelConfig, Error> {
+        let server_number = server_number.into();
         Ok(self.go(disable_cpanel_config(server_number)).await?.0)
     }
 }
 
 /// Applicable CPanel configuration.
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct CpanelConfig {
     /// Distribution for the Cpanel installation.
     #[serde(rename = "dist")]
@@ -158,7 +166,8 @@ pub struct CpanelConfig {
 }
 
 /// Describes available Cpanel configuration options.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AvailableCpanelConfig {
     /// Available distributions for Cpanel installation.
     #[serde(rename = "dist")]
@@ -170,7 +179,8 @@ pub struct AvailableCpanelConfig {
 }
 
 /// Currently active Cpanel configuration.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ActiveCpanelConfig {
     /// Distribution selected in currently active Cpanel installation.
     #[serde(rename = "dist")]
@@ -192,7 +202,8 @@ pub struct ActiveCpanelConfig {
 /// If a Cpanel installation system is active, it ([`ActiveCpanelConfig`]) will be returned,
 /// otherwise a struct ([`AvailableCpanelConfig`]) representing the available Cpanel distributions
 /// and languages is returned.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Cpanel {
     /// Currently active Cpanel configuration.
@@ -203,6 +214,7 @@ pub enum Cpanel {
 
 /// CPanel Distribution, e.g. "CentOS-Stream".
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct CpanelDistribution(pub Cow<'static, str>);
 
 impl From<String> for CpanelDistribution {
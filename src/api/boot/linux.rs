@@ -67,7 +67,11 @@ impl AsyncRobot {
     /// }
     /// # }
     /// ```
-    pub async fn get_linux_config(&self, server_number: ServerId) -> Result<Linux, Error> {
+    pub async fn get_linux_config(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<Linux, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_linux_config(server_number)).await?.0)
     }
 
@@ -87,8 +91,9 @@ impl AsyncRobot {
     /// ```
     pub async fn get_last_linux_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
     ) -> Result<ActiveLinuxConfig, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_last_linux_config(server_number)).await?.0)
     }
 
@@ -110,9 +115,10 @@ impl AsyncRobot {
     /// ```
     pub async fn enable_linux_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
         config: LinuxConfig,
     ) -> Result<ActiveLinuxConfig, Error> {
+        let server_number = server_number.into();
         Ok(self
             .go(enable_linux_config(server_number, config)?)
             .await?
@@ -132,14 +138,16 @@ impl AsyncRobot {
     /// ```
     pub async fn disable_linux_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
     ) -> Result<AvailableLinuxConfig, Error> {
+        let server_number = server_number.into();
         Ok(self.go(disable_linux_config(server_number)).await?.0)
     }
 }
 
 /// Applicable Linux boot configuration.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct LinuxConfig {
     /// Distribution to install.
     #[serde(rename = "dist")]
@@ -160,7 +168,8 @@ pub struct LinuxConfig {
 }
 
 /// Active Linux installation configuration.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ActiveLinuxConfig {
     /// Distribution to be installed.
     #[serde(rename = "dist")]
@@ -191,7 +200,8 @@ pub struct ActiveLinuxConfig {
 
 /// Describes the Linux distributions and languages
 /// available for installation.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AvailableLinuxConfig {
     /// Available linux distributions for installation.
     #[serde(rename = "dist")]
@@ -207,7 +217,8 @@ pub struct AvailableLinuxConfig {
 /// If a Linux installation system is active, it ([`ActiveLinuxConfig`]) will be returned,
 /// otherwise a struct ([`AvailableLinuxConfig`]) representing the available Linux distributions
 /// and languages are returned.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Linux {
     /// Linux installation config is active.
@@ -219,6 +230,7 @@ pub enum Linux {
 
 /// Linux Distribution, e.g. "CentOS-Stream".
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct LinuxDistribution(pub Cow<'static, str>);
 
 impl From<String> for LinuxDistribution {
@@ -24,7 +24,8 @@ use serde::{Deserialize, Serialize};
 use super::server::ServerId;
 
 /// Describes the status of each of the available boot configuration systems.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Config {
     /// Active or available rescue system configurations.
     pub rescue: Option<Rescue>,
@@ -103,6 +104,13 @@ impl AsyncRobot {
     /// Retrieve the status of all boot configuration systems,
     /// whether active or available or a server.
     ///
+    /// Each system can also be retrieved, enabled and disabled individually, e.g. using
+    /// [`AsyncRobot::get_rescue_config`], [`AsyncRobot::enable_rescue_config`] and
+    /// [`AsyncRobot::disable_rescue_config`], with equivalents for the
+    /// [linux](AsyncRobot::get_linux_config), [vnc](AsyncRobot::get_vnc_config),
+    /// [windows](AsyncRobot::get_windows_config), [plesk](AsyncRobot::get_plesk_config)
+    /// and [cpanel](AsyncRobot::get_cpanel_config) systems.
+    ///
     /// # Example
     /// ```rust,no_run
     /// # use hrobot::api::server::ServerId;
@@ -114,7 +122,11 @@ impl AsyncRobot {
     /// assert!(config.active().is_none());
     /// # }
     /// ```
-    pub async fn get_boot_config(&self, server_number: ServerId) -> Result<Config, Error> {
+    pub async fn get_boot_config(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<Config, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_config(server_number)).await?.0)
     }
 }
@@ -66,7 +66,11 @@ impl AsyncRobot {
     /// }
     /// # }
     /// ```
-    pub async fn get_plesk_config(&self, server_number: ServerId) -> Result<Plesk, Error> {
+    pub async fn get_plesk_config(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<Plesk, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_plesk_config(server_number)).await?.0)
     }
 
@@ -89,8 +93,9 @@ impl AsyncRobot {
     /// ```
     pub async fn get_last_plesk_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
     ) -> Result<ActivePleskConfig, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_last_plesk_config(server_number)).await?.0)
     }
 
@@ -112,9 +117,10 @@ impl AsyncRobot {
     /// ```
     pub async fn enable_plesk_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
         config: PleskConfig,
     ) -> Result<ActivePleskConfig, Error> {
+        let server_number = server_number.into();
         Ok(self
             .go(enable_plesk_config(server_number, config)?)
             .await?
@@ -134,14 +140,16 @@ impl AsyncRobot {
     /// ```
     pub async fn disable_plesk_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
     ) -> Result<AvailablePleskConfig, Error> {
+        let server_number = server_number.into();
         Ok(self.go(disable_plesk_config(server_number)).await?.0)
     }
 }
 
 /// Applicable Plesk boot configuration.
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PleskConfig {
     /// Distribution for the Plesk installation.
     #[serde(rename = "dist")]
@@ -156,7 +164,8 @@ pub struct PleskConfig {
 }
 
 /// Describes available Plesk configuration options.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AvailablePleskConfig {
     /// Available distributions for Plesk installation.
     #[serde(rename = "dist")]
@@ -168,7 +177,8 @@ pub struct AvailablePleskConfig {
 }
 
 /// Currently active Plesk configuration.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ActivePleskConfig {
     /// Distribution selected in currently active Plesk installation.
     #[serde(rename = "dist")]
@@ -190,7 +200,8 @@ pub struct ActivePleskConfig {
 /// If a Plesk installation system is active, it ([`ActivePleskConfig`]) will be returned,
 /// otherwise a struct ([`AvailablePleskConfig`]) representing the available Plesk distributions
 /// and languages is returned.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Plesk {
     /// Currently active Plesk configuration.
@@ -201,6 +212,7 @@ pub enum Plesk {
 
 /// Plesk Distribution, e.g. "CentOS-Stream".
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PleskDistribution(pub Cow<'static, str>);
 
 impl From<String> for PleskDistribution {
@@ -64,7 +64,11 @@ impl AsyncRobot {
     /// }
     /// # }
     /// ```
-    pub async fn get_rescue_config(&self, server_number: ServerId) -> Result<Rescue, Error> {
+    pub async fn get_rescue_config(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<Rescue, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_rescue_config(server_number)).await?.0)
     }
 
@@ -73,6 +77,12 @@ impl AsyncRobot {
     /// This is the last configuration that was active on the server,
     /// not the *currently* active configuration.
     ///
+    /// Note that the API does not return the root password of the last
+    /// configuration, so [`password`](ActiveRescueConfig::password) is always `None`.
+    /// If the response to [`AsyncRobot::enable_rescue_config`] was lost, the password
+    /// can instead be retrieved using [`AsyncRobot::get_rescue_config`] for as long
+    /// as the rescue system remains active.
+    ///
     /// # Example
     /// ```rust,no_run
     /// # use hrobot::api::server::ServerId;
@@ -84,8 +94,9 @@ impl AsyncRobot {
     /// ```
     pub async fn get_last_rescue_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
     ) -> Result<ActiveRescueConfig, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_last_rescue_config(server_number)).await?.0)
     }
 
@@ -107,9 +118,10 @@ impl AsyncRobot {
     /// ```
     pub async fn enable_rescue_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
         config: RescueConfig,
     ) -> Result<ActiveRescueConfig, Error> {
+        let server_number = server_number.into();
         Ok(self
             .go(enable_rescue_config(server_number, config)?)
             .await?
@@ -129,8 +141,9 @@ impl AsyncRobot {
     /// ```
     pub async fn disable_rescue_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
     ) -> Result<AvailableRescueConfig, Error> {
+        let server_number = server_number.into();
         Ok(self.go(disable_rescue_config(server_number)).await?.0)
     }
 }
@@ -142,7 +155,8 @@ use crate::api::keys::SshKeyReference;
 /// Keyboard layout.
 ///
 /// Defaults to US.
-#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Keyboard {
     /// US layout
     #[default]
@@ -172,7 +186,8 @@ pub enum Keyboard {
 }
 
 /// Configuration of the rescue system to enable.
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct RescueConfig {
     /// Rescue operating system to activate.
     #[serde(rename = "os")]
@@ -189,7 +204,8 @@ pub struct RescueConfig {
 }
 
 /// Currently active rescue system configuration.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ActiveRescueConfig {
     /// Active rescue operating system.
     #[serde(rename = "os")]
@@ -211,7 +227,8 @@ pub struct ActiveRescueConfig {
 }
 
 /// Available rescue system configurations
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AvailableRescueConfig {
     /// Available rescue operating systems.
     #[serde(rename = "os")]
@@ -220,7 +237,8 @@ pub struct AvailableRescueConfig {
 
 /// Represents the currently active rescue configuration,
 /// or if inactive, the available rescue systems.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Rescue {
     /// Currently active rescue system
@@ -232,6 +250,7 @@ pub enum Rescue {
 
 /// Rescue Distribution, e.g. "vkvm".
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct RescueOperatingSystem(pub Cow<'static, str>);
 
 impl From<String> for RescueOperatingSystem {
@@ -64,7 +64,8 @@ impl AsyncRobot {
     /// }
     /// # }
     /// ```
-    pub async fn get_vnc_config(&self, server_number: ServerId) -> Result<Vnc, Error> {
+    pub async fn get_vnc_config(&self, server_number: impl Into<ServerId>) -> Result<Vnc, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_vnc_config(server_number)).await?.0)
     }
 
@@ -87,8 +88,9 @@ impl AsyncRobot {
     /// ```
     pub async fn get_last_vnc_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
     ) -> Result<ActiveVncConfig, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_last_vnc_config(server_number)).await?.0)
     }
 
@@ -109,9 +111,10 @@ impl AsyncRobot {
     /// ```
     pub async fn enable_vnc_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
         config: VncConfig,
     ) -> Result<ActiveVncConfig, Error> {
+        let server_number = server_number.into();
         Ok(self.go(enable_vnc_config(server_number, config)?).await?.0)
     }
 
@@ -128,14 +131,16 @@ impl AsyncRobot {
     /// ```
     pub async fn disable_vnc_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
     ) -> Result<AvailableVncConfig, Error> {
+        let server_number = server_number.into();
         Ok(self.go(disable_vnc_config(server_number)).await?.0)
     }
 }
 
 /// Applicable VNC boot configuration.
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VncConfig {
     /// Distribution for the VNC installation.
     #[serde(rename = "dist")]
@@ -147,7 +152,8 @@ pub struct VncConfig {
 }
 
 /// Describes available VNC configuration options.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AvailableVncConfig {
     /// Available distributions for VNC installation.
     #[serde(rename = "dist")]
@@ -159,7 +165,8 @@ pub struct AvailableVncConfig {
 }
 
 /// Currently active VNC configuration.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ActiveVncConfig {
     /// Distribution selected in currently active VNC installation.
     #[serde(rename = "dist")]
@@ -178,7 +185,8 @@ pub struct ActiveVncConfig {
 /// If a VNC installation system is active, it ([`ActiveVncConfig`]) will be returned,
 /// otherwise a struct ([`AvailableVncConfig`]) representing the available VNC distributions
 /// and languages is returned.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Vnc {
     /// Currently active VNC configuration.
@@ -189,6 +197,7 @@ pub enum Vnc {
 
 /// VNC Distribution, e.g. "CentOS-Stream".
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VncDistribution(pub Cow<'static, str>);
 
 impl From<String> for VncDistribution {
@@ -65,7 +65,11 @@ impl AsyncRobot {
     /// }
     /// # }
     /// ```
-    pub async fn get_windows_config(&self, server_number: ServerId) -> Result<Windows, Error> {
+    pub async fn get_windows_config(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<Windows, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_windows_config(server_number)).await?.0)
     }
 
@@ -88,8 +92,9 @@ impl AsyncRobot {
     /// ```
     pub async fn get_last_windows_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
     ) -> Result<ActiveWindowsConfig, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_last_windows_config(server_number)).await?.0)
     }
 
@@ -110,9 +115,10 @@ impl AsyncRobot {
     /// ```
     pub async fn enable_windows_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
         config: WindowsConfig,
     ) -> Result<ActiveWindowsConfig, Error> {
+        let server_number = server_number.into();
         Ok(self
             .go(enable_windows_config(server_number, config)?)
             .await?
@@ -132,14 +138,16 @@ impl AsyncRobot {
     /// ```
     pub async fn disable_windows_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
     ) -> Result<AvailableWindowsConfig, Error> {
+        let server_number = server_number.into();
         Ok(self.go(disable_windows_config(server_number)).await?.0)
     }
 }
 
 /// Currently active Windows installation configuration.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ActiveWindowsConfig {
     /// Active Windows installation distribution.
     #[serde(rename = "dist")]
@@ -155,7 +163,8 @@ pub struct ActiveWindowsConfig {
 }
 
 /// availble Windows installation configuration options.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AvailableWindowsConfig {
     /// Available Windows installation distributions.
     #[serde(rename = "dist")]
@@ -167,7 +176,8 @@ pub struct AvailableWindowsConfig {
 }
 
 /// Describes either the active or available Windows configurations.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Windows {
     /// Currently active Windows installation configuration.
     Active(ActiveWindowsConfig),
@@ -176,7 +186,8 @@ pub enum Windows {
 }
 
 /// Aplicable Windows boot configuration.
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct WindowsConfig {
     /// Distribution of Windows to install.
     #[serde(rename = "dist")]
@@ -189,6 +200,7 @@ pub struct WindowsConfig {
 
 /// Windows Distribution, e.g. "standard".
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct WindowsDistribution(pub Cow<'static, str>);
 
 impl From<String> for WindowsDistribution {
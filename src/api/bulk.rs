@@ -0,0 +1,387 @@
+//! Options for operations spanning many resources.
+//!
+//! Helpers such as [`AsyncRobot::set_rdns_bulk`](crate::AsyncRobot::set_rdns_bulk),
+//! [`AsyncRobot::full_inventory`](crate::AsyncRobot::full_inventory) and
+//! [`AsyncRobot::rollout_firewall_template`](crate::AsyncRobot::rollout_firewall_template)
+//! issue many requests, and are all configured using [`BulkOptions`].
+
+use std::{future::Future, pin::pin, sync::Arc, time::Duration};
+
+use futures_util::{
+    future::{self, Either},
+    stream, StreamExt,
+};
+use tokio_util::sync::CancellationToken;
+
+#[cfg(feature = "ip")]
+use futures_util::TryStreamExt;
+
+use crate::{
+    error::{ApiError, Error},
+    pacing::TokenBucket,
+    AsyncRobot,
+};
+
+/// Options controlling how many requests a bulk operation issues, and how quickly.
+///
+/// # Example
+/// ```rust
+/// # use std::time::Duration;
+/// # use hrobot::api::bulk::BulkOptions;
+/// # use hrobot::tokio_util::sync::CancellationToken;
+/// let token = CancellationToken::new();
+///
+/// let options = BulkOptions::default()
+///     .with_concurrency(8)
+///     .with_rate_limit(200, Duration::from_secs(3600))
+///     .with_cancellation(token.clone());
+///
+/// // Calling token.cancel() from elsewhere stops the operation.
+/// ```
+#[derive(Debug, Clone)]
+pub struct BulkOptions {
+    /// Maximum number of requests in flight at a time.
+    pub concurrency: usize,
+
+    /// Maximum number of requests issued within an interval, if any.
+    ///
+    /// Requests are spread out evenly, allowing bursts of up to
+    /// [`RateLimit::max_requests`] requests. This applies in addition to the
+    /// limit of the robot itself, see [`AsyncRobot::rate_limited`].
+    pub rate_limit: Option<RateLimit>,
+
+    /// Number of times a single request is retried if the API rate limit is exceeded.
+    pub retries: usize,
+
+    /// Token which stops the operation when cancelled, if any.
+    ///
+    /// Requests which have not yet completed fail with [`Error::Cancelled`].
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl Default for BulkOptions {
+    /// Issue up to 4 requests at a time, retrying each up to 3 times
+    /// if the API rate limit is exceeded.
+    fn default() -> Self {
+        BulkOptions {
+            concurrency: 4,
+            rate_limit: None,
+            retries: 3,
+            cancellation: None,
+        }
+    }
+}
+
+impl BulkOptions {
+    /// Issue up to `concurrency` requests at a time.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Issue no more than `max_requests` requests within any `interval`.
+    pub fn with_rate_limit(mut self, max_requests: u32, interval: Duration) -> Self {
+        self.rate_limit = Some(RateLimit {
+            max_requests,
+            interval,
+        });
+        self
+    }
+
+    /// Retry each request up to `retries` times if the API rate limit is exceeded.
+    pub fn with_retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Stop the operation as soon as `token` is cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+}
+
+/// Client-side rate limit for a bulk operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Maximum number of requests within the interval.
+    pub max_requests: u32,
+
+    /// Interval within which the maximum number of requests applies.
+    pub interval: Duration,
+}
+
+/// Executes the requests of a single bulk operation according to its [`BulkOptions`].
+///
+/// All requests share a token bucket, so exceeding the API rate limit
+/// in one request holds back all other requests as well. The token bucket
+/// of the robot is paused along with it, holding back its other requests too.
+#[derive(Debug)]
+pub(crate) struct BulkExecutor {
+    options: BulkOptions,
+    bucket: TokenBucket,
+    robot_bucket: Option<Arc<TokenBucket>>,
+}
+
+impl BulkExecutor {
+    pub(crate) fn new(options: BulkOptions) -> Self {
+        let bucket = options
+            .rate_limit
+            .map_or_else(TokenBucket::unlimited, |limit| {
+                TokenBucket::new(limit.max_requests, limit.interval)
+            });
+
+        BulkExecutor {
+            options,
+            bucket,
+            robot_bucket: None,
+        }
+    }
+
+    /// Executor for requests sent using `robot`, sharing its rate limit, if any.
+    pub(crate) fn for_robot(robot: &AsyncRobot, options: BulkOptions) -> Self {
+        BulkExecutor {
+            robot_bucket: robot.limiter().cloned(),
+            ..BulkExecutor::new(options)
+        }
+    }
+
+    /// Maximum number of requests in flight at a time.
+    pub(crate) fn concurrency(&self) -> usize {
+        self.options.concurrency.max(1)
+    }
+
+    /// Issue a single request, waiting for the rate limit and retrying
+    /// if the API rate limit is exceeded.
+    pub(crate) async fn run<T, F, Fut>(&self, mut request: F) -> Result<T, Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let mut attempts = 0;
+
+        loop {
+            let result = self
+                .cancellable(async {
+                    self.bucket.acquire().await;
+                    request().await
+                })
+                .await;
+
+            match result {
+                Err(Error::Api(
+                    ApiError::RateLimitExceeded {
+                        max_requests,
+                        interval,
+                        ..
+                    },
+                    context,
+                )) if attempts < self.options.retries => {
+                    attempts += 1;
+
+                    // Prefer the delay requested by the API, otherwise wait for the
+                    // time it takes the rate limit to allow a single request.
+                    let delay = context.retry_after.unwrap_or_else(|| {
+                        Duration::from_secs_f64(
+                            f64::from(interval) / f64::from(max_requests.max(1)),
+                        )
+                    });
+
+                    self.bucket.pause(delay);
+                    if let Some(robot_bucket) = &self.robot_bucket {
+                        robot_bucket.pause(delay);
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Issue a request for each item with bounded concurrency, returning
+    /// the result of each in the same order as the items.
+    pub(crate) async fn bounded_join_all<I, T, F, Fut>(
+        &self,
+        items: impl IntoIterator<Item = I>,
+        request: F,
+    ) -> Vec<Result<T, Error>>
+    where
+        I: Clone,
+        F: Fn(I) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let request = &request;
+
+        stream::iter(items)
+            .map(|item| self.run(move || request(item.clone())))
+            .buffered(self.concurrency())
+            .collect()
+            .await
+    }
+
+    /// Issue a request for each item with bounded concurrency, returning the
+    /// results in the same order as the items, or the first error encountered.
+    ///
+    /// Requests still in flight when an error is encountered are dropped.
+    #[cfg(feature = "ip")]
+    pub(crate) async fn bounded_try_join_all<I, T, F, Fut>(
+        &self,
+        items: impl IntoIterator<Item = I>,
+        request: F,
+    ) -> Result<Vec<T>, Error>
+    where
+        I: Clone,
+        F: Fn(I) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let request = &request;
+
+        stream::iter(items)
+            .map(|item| self.run(move || request(item.clone())))
+            .buffered(self.concurrency())
+            .try_collect()
+            .await
+    }
+
+    /// Fail with [`Error::Cancelled`] if the cancellation token is cancelled
+    /// before the future completes.
+    async fn cancellable<T>(
+        &self,
+        future: impl Future<Output = Result<T, Error>>,
+    ) -> Result<T, Error> {
+        let Some(token) = &self.options.cancellation else {
+            return future.await;
+        };
+
+        if token.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+
+        match future::select(pin!(token.cancelled()), pin!(future)).await {
+            Either::Left(_) => Err(Error::Cancelled),
+            Either::Right((result, _)) => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use hyper::{HeaderMap, StatusCode};
+    use tokio::time::Instant;
+    use tokio_util::sync::CancellationToken;
+
+    use crate::{
+        error::{ApiError, Error, ErrorContext},
+        mock::MockClient,
+    };
+
+    use super::{BulkExecutor, BulkOptions};
+
+    fn rate_limit_exceeded() -> Error {
+        Error::Api(
+            ApiError::RateLimitExceeded {
+                message: "Rate limit exceeded".to_string(),
+                max_requests: 200,
+                interval: 3600,
+            },
+            ErrorContext::from_response(StatusCode::FORBIDDEN, &HeaderMap::new()),
+        )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_limited() {
+        let executor = BulkExecutor::new(
+            BulkOptions::default()
+                .with_concurrency(10)
+                .with_rate_limit(2, Duration::from_secs(10)),
+        );
+
+        let start = Instant::now();
+        let results = executor
+            .bounded_join_all(0..4, |item| async move { Ok::<_, Error>(item) })
+            .await;
+
+        assert_eq!(
+            results.into_iter().flatten().collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+
+        // Two requests are allowed immediately, the remaining two at 5 second intervals.
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(9_999) && elapsed < Duration::from_secs(11));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retry_rate_limit_exceeded() {
+        let attempts = [AtomicUsize::new(0), AtomicUsize::new(0)];
+        let executor = BulkExecutor::new(BulkOptions::default().with_retries(2));
+
+        let results = executor
+            .bounded_join_all([0, 1], |item| {
+                let attempts = &attempts;
+                async move {
+                    // Each item exceeds the rate limit twice before succeeding.
+                    if attempts[item].fetch_add(1, Ordering::SeqCst) < 2 {
+                        Err(rate_limit_exceeded())
+                    } else {
+                        Ok(())
+                    }
+                }
+            })
+            .await;
+
+        assert!(attempts
+            .iter()
+            .all(|attempts| attempts.load(Ordering::SeqCst) == 3));
+        assert!(results.iter().all(Result::is_ok));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_limit_exceeded_pauses_robot() {
+        let robot = MockClient::new()
+            .robot()
+            .rate_limited(100, Duration::from_secs(1));
+        let executor = BulkExecutor::for_robot(&robot, BulkOptions::default().with_retries(1));
+
+        let attempts = AtomicUsize::new(0);
+        let start = Instant::now();
+        let (result, elapsed) = futures_util::future::join(
+            executor.run(|| async {
+                if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Err(rate_limit_exceeded())
+                } else {
+                    Ok(())
+                }
+            }),
+            async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                robot.limiter().unwrap().acquire().await;
+                start.elapsed()
+            },
+        )
+        .await;
+
+        assert!(result.is_ok());
+
+        // The API allows 200 requests per hour, so the robot waits 18 seconds.
+        assert!(elapsed >= Duration::from_secs(18), "{elapsed:?}");
+    }
+
+    #[tokio::test]
+    async fn cancelled() {
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let executor = BulkExecutor::new(BulkOptions::default().with_cancellation(token));
+
+        let results = executor
+            .bounded_join_all([()], |_| std::future::pending::<Result<(), Error>>())
+            .await;
+
+        assert!(matches!(results[..], [Err(Error::Cancelled)]));
+    }
+}
@@ -0,0 +1,513 @@
+//! Connectivity diagnostics structs and implementation.
+//!
+//! [`AsyncRobot::diagnose`] runs a fixed sequence of checks against the
+//! Hetzner Robot API and returns a [`Diagnostics`] report describing which
+//! checks failed and what can be done about it, for use in `doctor`-style
+//! commands of tools built on top of this crate.
+
+use std::{fmt::Display, time::Duration};
+
+use hyper::{Request, StatusCode, Uri};
+use serde::{de::IgnoredAny, Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::{
+    error::{ApiError, Error, MaybeTypedResponse},
+    AsyncRobot,
+};
+
+use super::UnauthenticatedRequest;
+
+/// Hostname of the Hetzner Robot API.
+const ROBOT_HOST: &str = "robot-ws.your-server.de";
+
+impl AsyncRobot {
+    /// Run a sequence of connectivity checks against the Hetzner Robot API.
+    ///
+    /// The following checks are performed in order:
+    /// 1. [`CheckKind::DnsResolution`] resolves the API hostname, or the hostname of the
+    ///    [base URL](crate::AsyncRobotBuilder::base_url) if one is set. If a
+    ///    [proxy](crate::AsyncRobotBuilder::proxy) is configured, its hostname is
+    ///    resolved instead, since the proxy resolves the API hostname itself.
+    /// 2. [`CheckKind::TlsHandshake`] sends an unauthenticated request, which
+    ///    succeeds as soon as a TLS connection is established and any HTTP
+    ///    response is received.
+    /// 3. [`CheckKind::Authentication`] sends an authenticated request listing servers.
+    /// 4. [`CheckKind::RateLimit`] sends an authenticated request listing SSH keys,
+    ///    reporting whether the account is currently rate limited.
+    ///
+    /// DNS resolution is performed by the operating system, so it may fail even though
+    /// a custom HTTP client can reach the API, for example through its own proxy.
+    /// The authenticated checks are skipped if no connection can be established.
+    ///
+    /// This never returns an error. Failures are instead reported in the
+    /// returned [`Diagnostics`], which can be printed directly.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let diagnostics = robot.diagnose().await;
+    ///
+    /// println!("{diagnostics}");
+    /// if !diagnostics.is_healthy() {
+    ///     std::process::exit(1);
+    /// }
+    /// # }
+    /// ```
+    pub async fn diagnose(&self) -> Diagnostics {
+        let dns = timed(CheckKind::DnsResolution, self.check_dns()).await;
+        let tls = timed(CheckKind::TlsHandshake, self.check_tls()).await;
+
+        let (authentication, rate_limit) = if tls.outcome.is_failed() {
+            (
+                Check::skipped(CheckKind::Authentication),
+                Check::skipped(CheckKind::RateLimit),
+            )
+        } else {
+            (
+                timed(
+                    CheckKind::Authentication,
+                    self.check_authenticated("https://robot-ws.your-server.de/server"),
+                )
+                .await,
+                timed(
+                    CheckKind::RateLimit,
+                    self.check_authenticated("https://robot-ws.your-server.de/key"),
+                )
+                .await,
+            )
+        };
+
+        Diagnostics {
+            checks: vec![dns, tls, authentication, rate_limit],
+        }
+    }
+
+    /// Verify that the configured credentials are accepted by the API.
+    ///
+    /// Performs a single cheap authenticated request. Rejected credentials are reported
+    /// as [`Error::Unauthorized`], allowing applications to fail fast at startup with
+    /// a clear message, rather than on their first real request.
+    ///
+    /// Use [`AsyncRobot::diagnose`] for a more thorough explanation of connectivity issues.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::error::Error;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    ///
+    /// match robot.validate_credentials().await {
+    ///     Ok(()) => println!("credentials accepted"),
+    ///     Err(Error::Unauthorized { status }) => {
+    ///         eprintln!("webservice user was rejected (HTTP {status})")
+    ///     }
+    ///     Err(err) => eprintln!("could not validate credentials: {err}"),
+    /// }
+    /// # }
+    /// ```
+    pub async fn validate_credentials(&self) -> Result<(), Error> {
+        let request = UnauthenticatedRequest::<Vec<IgnoredAny>>::from(
+            "https://robot-ws.your-server.de/server",
+        );
+
+        match self.go(request).await {
+            Ok(_) => Ok(()),
+            // Accounts without servers receive a 404, which still requires valid credentials.
+            Err(Error::Api(ApiError::NotFound { .. } | ApiError::ServerNotFound { .. }, _)) => {
+                Ok(())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Resolve the host the HTTP client connects to.
+    async fn check_dns(&self) -> CheckOutcome {
+        let (target, uri) = match (self.proxy(), self.base_url()) {
+            (Some(proxy), _) => ("proxy ", proxy),
+            (None, Some(base_url)) => ("base url ", base_url),
+            (None, None) => return resolve("", ROBOT_HOST, 443).await,
+        };
+
+        match uri.host() {
+            Some(host) => resolve(target, host, port(uri)).await,
+            None => CheckOutcome::failed(
+                format!("{target}{uri} has no hostname"),
+                "Configure a URL including a hostname.",
+            ),
+        }
+    }
+
+    async fn check_tls(&self) -> CheckOutcome {
+        let uri = match self.base_url() {
+            Some(base_url) => base_url.clone(),
+            None => Uri::from_static("https://robot-ws.your-server.de/"),
+        };
+
+        let request = match Request::get(&uri).body(String::new()) {
+            Ok(request) => request,
+            Err(err) => return CheckOutcome::failed(err, "This is a bug in hrobot."),
+        };
+
+        match self.client().send_request(request).await {
+            Ok(response) => {
+                CheckOutcome::passed(format!("received HTTP {} response", response.status()))
+            }
+            Err(err) => CheckOutcome::failed(
+                err,
+                format!(
+                    "Make sure outbound connections to {} on port {} are allowed by firewalls \
+                    and proxies, and that the HTTP client has a TLS backend enabled.",
+                    uri.host().unwrap_or(ROBOT_HOST),
+                    port(&uri),
+                ),
+            ),
+        }
+    }
+
+    async fn check_authenticated(&self, uri: &str) -> CheckOutcome {
+        let response = match self.send(UnauthenticatedRequest::<()>::from(uri)).await {
+            Ok(response) => response,
+            Err(err) => {
+                return CheckOutcome::failed(
+                    err,
+                    "The connection was interrupted. Retry, and check the stability of the network connection.",
+                )
+            }
+        };
+
+        let status = response.status();
+
+        let api_error = serde_json::from_slice::<MaybeTypedResponse>(response.body())
+            .ok()
+            .map(|response| ApiError::from(response.error));
+
+        match (status, api_error) {
+            (_, Some(ApiError::RateLimitExceeded { max_requests, interval, .. })) => {
+                CheckOutcome::failed(
+                    format!("rate limit of {max_requests} requests per {interval} seconds exceeded"),
+                    "Wait for the rate limit interval to pass, and reduce the request frequency of other tools using the same webservice user.",
+                )
+            }
+            (StatusCode::UNAUTHORIZED, _) => CheckOutcome::failed(
+                "credentials were rejected",
+                "Verify the username and password of the webservice user, which can be found under \
+                Settings > Webservice and app settings in the Robot interface. The username is not the Robot login.",
+            ),
+            (StatusCode::FORBIDDEN, _) => CheckOutcome::failed(
+                "access was denied",
+                "Check that the webservice user is enabled, and that the current IP address is \
+                not excluded by IP restrictions configured for it.",
+            ),
+            // Listing endpoints return 404 if the account has no such resources.
+            (status, _) if status.is_success() || status == StatusCode::NOT_FOUND => {
+                CheckOutcome::passed(format!("received HTTP {status} response"))
+            }
+            (status, Some(err)) => {
+                CheckOutcome::failed(err, format!("Unexpected HTTP {status} response."))
+            }
+            (status, None) => CheckOutcome::failed(
+                format!("received HTTP {status} response"),
+                "The API may be undergoing maintenance. Check https://status.hetzner.com and retry later.",
+            ),
+        }
+    }
+}
+
+/// Port of `uri`, or the default port of its scheme.
+fn port(uri: &Uri) -> u16 {
+    uri.port_u16()
+        .unwrap_or(if uri.scheme_str() == Some("http") {
+            80
+        } else {
+            443
+        })
+}
+
+/// Resolve `host`, describing it as `target`, e.g. `proxy `, in the outcome.
+async fn resolve(target: &str, host: &str, port: u16) -> CheckOutcome {
+    match tokio::net::lookup_host((host, port)).await {
+        Ok(addresses) => {
+            let addresses: Vec<_> = addresses.map(|address| address.ip().to_string()).collect();
+
+            if addresses.is_empty() {
+                CheckOutcome::failed(
+                    format!("{target}{host} resolved to no addresses"),
+                    "Check the DNS resolver configuration of this machine.",
+                )
+            } else {
+                CheckOutcome::passed(format!(
+                    "resolved {target}{host} to {}",
+                    addresses.join(", ")
+                ))
+            }
+        }
+        Err(err) => CheckOutcome::failed(
+            err,
+            "Check the DNS resolver configuration of this machine, or whether DNS requests are blocked.",
+        ),
+    }
+}
+
+async fn timed(kind: CheckKind, check: impl std::future::Future<Output = CheckOutcome>) -> Check {
+    let start = Instant::now();
+    let outcome = check.await;
+
+    Check {
+        kind,
+        outcome,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Report produced by [`AsyncRobot::diagnose`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Diagnostics {
+    /// Checks performed, in the order they were run.
+    pub checks: Vec<Check>,
+}
+
+impl Diagnostics {
+    /// Returns true if none of the checks failed.
+    pub fn is_healthy(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Iterate over all failed checks.
+    pub fn failures(&self) -> impl Iterator<Item = &Check> {
+        self.checks.iter().filter(|check| check.outcome.is_failed())
+    }
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for check in &self.checks {
+            writeln!(f, "{check}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Outcome of a single diagnostic check.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Check {
+    /// Which check was performed.
+    pub kind: CheckKind,
+
+    /// Outcome of the check.
+    pub outcome: CheckOutcome,
+
+    /// Time it took to perform the check.
+    pub elapsed: Duration,
+}
+
+impl Check {
+    fn skipped(kind: CheckKind) -> Self {
+        Check {
+            kind,
+            outcome: CheckOutcome::Skipped,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.outcome {
+            CheckOutcome::Passed { detail } => write!(
+                f,
+                "[ok]      {}: {detail} ({} ms)",
+                self.kind,
+                self.elapsed.as_millis()
+            ),
+            CheckOutcome::Failed { reason, remedy } => write!(
+                f,
+                "[failed]  {}: {reason} ({} ms)\n          {remedy}",
+                self.kind,
+                self.elapsed.as_millis()
+            ),
+            CheckOutcome::Skipped => write!(f, "[skipped] {}", self.kind),
+        }
+    }
+}
+
+/// Type of diagnostic check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum CheckKind {
+    /// Resolution of the API hostname using the system resolver.
+    DnsResolution,
+    /// Establishing a TLS connection to the API.
+    TlsHandshake,
+    /// Sending an authenticated request.
+    Authentication,
+    /// Sampling whether the API rate limit has been exceeded.
+    RateLimit,
+}
+
+impl Display for CheckKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CheckKind::DnsResolution => "dns resolution",
+            CheckKind::TlsHandshake => "tls handshake",
+            CheckKind::Authentication => "authentication",
+            CheckKind::RateLimit => "rate limit",
+        })
+    }
+}
+
+/// Result of a diagnostic check.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum CheckOutcome {
+    /// The check succeeded.
+    Passed {
+        /// Human-readable description of what was observed.
+        detail: String,
+    },
+    /// The check failed.
+    Failed {
+        /// Human-readable description of the failure.
+        reason: String,
+        /// Suggested action for resolving the failure.
+        remedy: String,
+    },
+    /// The check was not performed, because a check it depends on failed.
+    Skipped,
+}
+
+impl CheckOutcome {
+    fn passed(detail: impl Into<String>) -> Self {
+        CheckOutcome::Passed {
+            detail: detail.into(),
+        }
+    }
+
+    fn failed(reason: impl Display, remedy: impl Into<String>) -> Self {
+        CheckOutcome::Failed {
+            reason: reason.to_string(),
+            remedy: remedy.into(),
+        }
+    }
+
+    /// Returns true if the check failed.
+    pub fn is_failed(&self) -> bool {
+        matches!(self, CheckOutcome::Failed { .. })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::StatusCode;
+
+    use crate::{
+        api::Credentials,
+        error::{ApiError, Error},
+        mock::{MockClient, Reply},
+        AsyncRobot,
+    };
+
+    use super::{CheckKind, CheckOutcome};
+
+    #[tokio::test]
+    async fn diagnose_rejected_credentials() {
+        // Authenticated requests are rejected, and unauthenticated ones not found.
+        let robot = MockClient::new()
+            .on_with("*", "*", |request| {
+                if request.headers().contains_key("Authorization") {
+                    Reply::status(StatusCode::UNAUTHORIZED, "")
+                } else {
+                    Reply::status(StatusCode::NOT_FOUND, "")
+                }
+            })
+            .robot();
+
+        let diagnostics = robot.diagnose().await;
+        assert!(!diagnostics.is_healthy());
+
+        let outcome = |kind| {
+            &diagnostics
+                .checks
+                .iter()
+                .find(|check| check.kind == kind)
+                .unwrap()
+                .outcome
+        };
+
+        assert!(matches!(
+            outcome(CheckKind::TlsHandshake),
+            CheckOutcome::Passed { .. }
+        ));
+        assert!(outcome(CheckKind::Authentication).is_failed());
+        assert!(diagnostics
+            .to_string()
+            .contains("credentials were rejected"));
+    }
+
+    #[tokio::test]
+    async fn diagnose_base_url() {
+        let client = MockClient::new().on("*", "*", Reply::ok("[]"));
+        let requests = client.requests();
+
+        let robot = AsyncRobot::builder()
+            .credentials(Credentials::new("#ws+username", "p@ssw0rd"))
+            .base_url("http://localhost:8080/robot")
+            .build_with_client(client)
+            .unwrap();
+
+        let diagnostics = robot.diagnose().await;
+        assert!(diagnostics.is_healthy(), "{diagnostics}");
+
+        assert!(matches!(
+            &diagnostics.checks[0].outcome,
+            CheckOutcome::Passed { detail } if detail.starts_with("resolved base url localhost to ")
+        ));
+        assert_eq!(
+            requests.map(|request| request.uri().to_string()),
+            [
+                "http://localhost:8080/robot",
+                "http://localhost:8080/robot/server",
+                "http://localhost:8080/robot/key",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn validate_credentials() {
+        let robot = |reply| MockClient::new().on("*", "*", reply).robot();
+
+        robot(Reply::ok("[]")).validate_credentials().await.unwrap();
+        robot(Reply::error(
+            StatusCode::NOT_FOUND,
+            "SERVER_NOT_FOUND",
+            "Server not found",
+        ))
+        .validate_credentials()
+        .await
+        .unwrap();
+
+        for status in [StatusCode::UNAUTHORIZED, StatusCode::FORBIDDEN] {
+            assert!(matches!(
+                robot(Reply::status(status, "")).validate_credentials().await,
+                Err(Error::Unauthorized { status: rejected }) if rejected == status
+            ));
+        }
+
+        let err = robot(Reply::rate_limited())
+            .validate_credentials()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Api(ApiError::RateLimitExceeded { .. }, _)
+        ));
+    }
+}
@@ -6,9 +6,15 @@ use serde::{Deserialize, Serialize};
 
 use crate::{error::Error, AsyncRobot};
 
+#[cfg(feature = "ordering")]
+use crate::{
+    api::ordering::{AddonOrder, TransactionStatus},
+    poll::PollOptions,
+};
+
 use super::{
     server::ServerId,
-    wrapper::{List, Single},
+    wrapper::{List, OrEmpty, Single},
     UnauthenticatedRequest,
 };
 
@@ -57,13 +63,13 @@ impl AsyncRobot {
     /// # }
     /// ```
     pub async fn list_failover_ips(&self) -> Result<Vec<Failover>, Error> {
-        Ok(self
-            .go(list_ips())
-            .await?
+        self.go(list_ips())
+            .await
+            .or_empty()?
             .0
             .into_iter()
-            .map(Failover::from)
-            .collect())
+            .map(Failover::try_from)
+            .collect()
     }
 
     /// Get information about a single failover IP address.
@@ -77,7 +83,7 @@ impl AsyncRobot {
     /// # }
     /// ```
     pub async fn get_failover_ip(&self, ip: IpAddr) -> Result<Failover, Error> {
-        Ok(self.go(get_ip(ip)).await?.0.into())
+        self.go(get_ip(ip)).await?.0.try_into()
     }
 
     /// Switch the routing of the failover IP to instead route to the given target IP.
@@ -98,11 +104,10 @@ impl AsyncRobot {
         failover: IpAddr,
         target: IpAddr,
     ) -> Result<Failover, Error> {
-        Ok(self
-            .go(switch_ip_routing(failover, target)?)
+        self.go(switch_ip_routing(failover, target)?)
             .await?
             .0
-            .into())
+            .try_into()
     }
 
     /// Switch the routing of the failover IP to instead route to the given target IP.
@@ -116,14 +121,101 @@ impl AsyncRobot {
     /// # }
     /// ```
     pub async fn disable_failover_routing(&self, failover: IpAddr) -> Result<Failover, Error> {
-        Ok(self.go(disable_routing(failover)).await?.0.into())
+        self.go(disable_routing(failover)).await?.0.try_into()
+    }
+
+    /// Order a failover IP or subnet, wait for the purchase to complete,
+    /// and route the newly acquired address to the target server.
+    ///
+    /// The addon transaction is polled according to the given [`PollOptions`]
+    /// until it is either ready or cancelled. Note that orders which are only
+    /// a test (see [`ImSeriousAboutSpendingMoney`](crate::api::ordering::ImSeriousAboutSpendingMoney))
+    /// will always be cancelled, causing this function to return
+    /// [`Error::TransactionCancelled`].
+    ///
+    /// IPv4 failover addresses are routed to the target server's primary IPv4
+    /// address, while IPv6 failover subnets are routed to its IPv6 network.
+    /// If the target server has no IPv4 address, routing an IPv4 failover address
+    /// fails with [`Error::FailoverFamilyMismatch`], leaving the purchased address unrouted.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::api::ordering::{AddonKind, AddonOrder, ImSeriousAboutSpendingMoney};
+    /// # use hrobot::api::server::ServerId;
+    /// # use hrobot::poll::PollOptions;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let failover = robot.order_and_route_failover(
+    ///     AddonOrder {
+    ///         id: AddonKind::FailoverIp.into(),
+    ///         server: ServerId(1234567),
+    ///         reason: Some("High availability".to_string()),
+    ///         gateway: None,
+    ///         // Don't forget to change this line, if you ACTUALLY want to make the purchase!
+    ///         i_want_to_spend_money_to_purchase_an_addon: ImSeriousAboutSpendingMoney::NoThisIsJustATest,
+    ///     },
+    ///     ServerId(2345678),
+    ///     PollOptions::new(Duration::from_secs(30)).with_timeout(Duration::from_secs(3600)),
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "ordering")]
+    pub async fn order_and_route_failover(
+        &self,
+        order: AddonOrder,
+        target_server: impl Into<ServerId>,
+        options: PollOptions,
+    ) -> Result<Failover, Error> {
+        let target_server = target_server.into();
+        let mut transaction = self.place_addon_order(order).await?;
+
+        if let TransactionStatus::InProcess = transaction.status {
+            transaction = self
+                .wait_for_addon_transaction(&transaction.id, options)
+                .await?;
+        }
+
+        if let TransactionStatus::Cancelled = transaction.status {
+            return Err(Error::TransactionCancelled(transaction.id.into()));
+        }
+
+        let failover = transaction
+            .resources
+            .iter()
+            .find_map(|resource| resource.id.ip())
+            .ok_or_else(|| Error::UnexpectedResponseShape {
+                expected: format!(
+                    "transaction {} to contain a failover address",
+                    transaction.id
+                ),
+                got: format!("{:?}", transaction.resources),
+            })?;
+
+        let server = self.get_server(target_server).await?;
+        let target = match (failover, server.ipv4) {
+            (IpAddr::V4(_), Some(ipv4)) => IpAddr::V4(ipv4),
+            (IpAddr::V6(_), _) => IpAddr::V6(server.ipv6_net),
+            (IpAddr::V4(_), None) => {
+                return Err(Error::FailoverFamilyMismatch {
+                    failover,
+                    server: target_server,
+                })
+            }
+        };
+
+        self.switch_failover_routing(failover, target).await
     }
 }
 
 /// A failover IP or subnet.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Failover {
     /// Failover IPv4 or IPv6 address/subnet.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub ip: IpNet,
 
     /// Unique ID of the server to which this address is routed.
@@ -145,12 +237,76 @@ struct InternalFailoverIp {
     pub active_server_ip: Option<IpAddr>,
 }
 
-impl From<InternalFailoverIp> for Failover {
-    fn from(value: InternalFailoverIp) -> Self {
-        Failover {
-            ip: IpNet::new(value.ip, value.mask).unwrap(),
+impl TryFrom<InternalFailoverIp> for Failover {
+    type Error = Error;
+
+    fn try_from(value: InternalFailoverIp) -> Result<Self, Error> {
+        Ok(Failover {
+            ip: crate::conversion::network(value.ip, value.mask)?,
             active_server: value.server_number,
             server_address: value.active_server_ip,
-        }
+        })
+    }
+}
+
+#[cfg(all(test, feature = "ordering"))]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use crate::{
+        api::{
+            ordering::{AddonKind, AddonOrder, ImSeriousAboutSpendingMoney},
+            server::ServerId,
+        },
+        error::Error,
+        mock::{fixtures, MockClient},
+        poll::PollOptions,
+    };
+
+    #[tokio::test]
+    async fn route_ipv4_failover_to_server_without_ipv4() {
+        let client = MockClient::new()
+            .on(
+                "POST",
+                "/order/server_addon/transaction",
+                fixtures::patch(
+                    fixtures::addon_transaction("failover_ip", "ready"),
+                    json!({"resources": [{"type": "failover_ip", "id": "2.2.2.2"}]}),
+                ),
+            )
+            .on(
+                "GET",
+                "/server/2",
+                fixtures::patch(fixtures::server(2), json!({"server_ip": null, "ip": []})),
+            );
+        let requests = client.requests();
+
+        let result = client
+            .robot()
+            .order_and_route_failover(
+                AddonOrder {
+                    id: AddonKind::FailoverIp.into(),
+                    server: ServerId(1),
+                    reason: None,
+                    gateway: None,
+                    i_want_to_spend_money_to_purchase_an_addon:
+                        ImSeriousAboutSpendingMoney::LetMeSpendMyMoneyAlready,
+                },
+                ServerId(2),
+                PollOptions::new(Duration::from_secs(60)),
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(Error::FailoverFamilyMismatch { failover, server: ServerId(2) })
+                if failover.to_string() == "2.2.2.2"
+        ));
+        assert_eq!(
+            requests.lines(),
+            ["POST /order/server_addon/transaction", "GET /server/2"]
+        );
     }
 }
@@ -0,0 +1,266 @@
+use std::fmt::Display;
+
+use super::{Firewall, FirewallConfig, Rule, Rules, State};
+
+/// Change of a single value from its current to its desired state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change<T> {
+    /// Current value.
+    pub from: T,
+    /// Desired value.
+    pub to: T,
+}
+
+impl<T: PartialEq> Change<T> {
+    fn between(from: T, to: T) -> Option<Self> {
+        (from != to).then_some(Change { from, to })
+    }
+}
+
+/// Differences between the current and desired rules for a single direction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RulesDiff {
+    /// Rules which are only present in the desired configuration.
+    pub added: Vec<Rule>,
+
+    /// Rules which are only present in the current configuration.
+    pub removed: Vec<Rule>,
+
+    /// Rules present in both configurations under the same name,
+    /// but with a different filter or action.
+    pub modified: Vec<Change<Rule>>,
+
+    /// Indicates whether the rules present in both configurations
+    /// appear in a different order.
+    ///
+    /// Rules are evaluated in order, so this alone changes the
+    /// behaviour of the firewall.
+    pub reordered: bool,
+}
+
+impl RulesDiff {
+    /// Rules are matched by name. If multiple rules share a name, they are
+    /// matched in the order in which they appear.
+    fn between(current: &[Rule], desired: &[Rule]) -> Self {
+        let mut matched = vec![false; current.len()];
+        let mut positions = Vec::new();
+        let mut diff = RulesDiff::default();
+
+        for rule in desired {
+            let position = current
+                .iter()
+                .enumerate()
+                .position(|(i, existing)| !matched[i] && existing.name == rule.name);
+
+            match position {
+                Some(i) => {
+                    matched[i] = true;
+                    positions.push(i);
+
+                    if let Some(change) = Change::between(current[i].clone(), rule.clone()) {
+                        diff.modified.push(change);
+                    }
+                }
+                None => diff.added.push(rule.clone()),
+            }
+        }
+
+        diff.removed = current
+            .iter()
+            .zip(matched)
+            .filter(|(_, matched)| !matched)
+            .map(|(rule, _)| rule.clone())
+            .collect();
+
+        diff.reordered = positions.windows(2).any(|pair| pair[0] > pair[1]);
+
+        diff
+    }
+
+    /// True if the rules are identical.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && !self.reordered
+    }
+
+    fn fmt_direction(&self, f: &mut std::fmt::Formatter<'_>, direction: &str) -> std::fmt::Result {
+        for rule in &self.added {
+            writeln!(
+                f,
+                "+ {direction} rule \"{}\": {} {}",
+                rule.name, rule.action, rule.filter
+            )?;
+        }
+
+        for rule in &self.removed {
+            writeln!(
+                f,
+                "- {direction} rule \"{}\": {} {}",
+                rule.name, rule.action, rule.filter
+            )?;
+        }
+
+        for Change { from, to } in &self.modified {
+            writeln!(
+                f,
+                "~ {direction} rule \"{}\": {} {} -> {} {}",
+                to.name, from.action, from.filter, to.action, to.filter
+            )?;
+        }
+
+        if self.reordered {
+            writeln!(f, "~ {direction} rules reordered")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Changes which applying a [`FirewallConfig`] would cause to a [`Firewall`].
+///
+/// Produced by [`FirewallConfig::diff`]. The [`Display`] implementation
+/// renders a human-readable plan, one change per line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FirewallDiff {
+    /// Change in firewall status.
+    pub status: Option<Change<State>>,
+
+    /// Change in IPv6 filtering.
+    pub filter_ipv6: Option<Change<bool>>,
+
+    /// Change in whitelisting of Hetzner's services.
+    pub whitelist_hetzner_services: Option<Change<bool>>,
+
+    /// Changes to rules applied to ingress traffic.
+    pub ingress: RulesDiff,
+
+    /// Changes to rules applied to egress traffic.
+    pub egress: RulesDiff,
+}
+
+impl FirewallDiff {
+    /// True if applying the configuration would not change anything.
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none()
+            && self.filter_ipv6.is_none()
+            && self.whitelist_hetzner_services.is_none()
+            && self.ingress.is_empty()
+            && self.egress.is_empty()
+    }
+}
+
+impl Display for FirewallDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no changes");
+        }
+
+        if let Some(Change { from, to }) = &self.status {
+            writeln!(f, "~ status: {from} -> {to}")?;
+        }
+
+        if let Some(Change { from, to }) = &self.filter_ipv6 {
+            writeln!(f, "~ filter_ipv6: {from} -> {to}")?;
+        }
+
+        if let Some(Change { from, to }) = &self.whitelist_hetzner_services {
+            writeln!(f, "~ whitelist_hetzner_services: {from} -> {to}")?;
+        }
+
+        self.ingress.fmt_direction(f, "ingress")?;
+        self.egress.fmt_direction(f, "egress")
+    }
+}
+
+impl FirewallConfig {
+    /// Compute the changes applying this configuration would cause to the given firewall.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::{firewall::Rule, server::ServerId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let firewall = robot.get_firewall(ServerId(1234567)).await.unwrap();
+    ///
+    /// let mut config = firewall.config();
+    /// config.rules.ingress.push(Rule::accept("Allow all"));
+    ///
+    /// print!("{}", config.diff(&firewall));
+    /// # }
+    /// ```
+    pub fn diff(&self, firewall: &Firewall) -> FirewallDiff {
+        let Rules { ingress, egress } = &firewall.rules;
+
+        FirewallDiff {
+            status: Change::between(firewall.status, self.status),
+            filter_ipv6: Change::between(firewall.filter_ipv6, self.filter_ipv6),
+            whitelist_hetzner_services: Change::between(
+                firewall.whitelist_hetzner_services,
+                self.whitelist_hetzner_services,
+            ),
+            ingress: RulesDiff::between(ingress, &self.rules.ingress),
+            egress: RulesDiff::between(egress, &self.rules.egress),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::firewall::{Firewall, Ipv4Filter, Rule, Rules, State, SwitchPort};
+
+    #[test]
+    fn firewall_diff() {
+        let firewall = Firewall {
+            status: State::Disabled,
+            filter_ipv6: false,
+            whitelist_hetzner_services: true,
+            port: SwitchPort::Main,
+            rules: Rules {
+                ingress: vec![
+                    Rule::accept("SSH").matching(Ipv4Filter::tcp(None).to_port(22)),
+                    Rule::accept("HTTP").matching(Ipv4Filter::tcp(None).to_port(80)),
+                    Rule::discard("Legacy"),
+                ],
+                egress: vec![Rule::accept("Allow all")],
+            },
+        };
+
+        assert!(firewall.config().diff(&firewall).is_empty());
+        assert_eq!(
+            firewall.config().diff(&firewall).to_string(),
+            "no changes\n"
+        );
+
+        let mut config = firewall.config();
+        config.status = State::Active;
+        config.rules.ingress = vec![
+            Rule::accept("HTTP").matching(Ipv4Filter::tcp(None).to_port(80..=443)),
+            Rule::accept("SSH").matching(Ipv4Filter::tcp(None).to_port(22)),
+            Rule::accept("DNS").matching(Ipv4Filter::udp()),
+        ];
+
+        let diff = config.diff(&firewall);
+
+        assert_eq!(diff.ingress.added.len(), 1);
+        assert_eq!(diff.ingress.removed.len(), 1);
+        assert_eq!(diff.ingress.modified.len(), 1);
+        assert!(diff.ingress.reordered);
+        assert!(diff.egress.is_empty());
+
+        assert_eq!(
+            diff.to_string(),
+            [
+                "~ status: disabled -> active",
+                "+ ingress rule \"DNS\": accept ipv4 udp",
+                "- ingress rule \"Legacy\": discard any",
+                "~ ingress rule \"HTTP\": accept ipv4 tcp dst_port=80 -> accept ipv4 tcp dst_port=80-443",
+                "~ ingress rules reordered",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+}
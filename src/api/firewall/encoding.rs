@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::urlencode::UrlEncode;
+
+use super::{
+    FirewallConfig, FirewallTemplateConfig, InternalFirewallConfig, InternalFirewallTemplateConfig,
+    Rules, State,
+};
+
+/// Error produced when decoding an `application/x-www-form-urlencoded`
+/// firewall configuration fails.
+#[derive(Debug, thiserror::Error)]
+pub enum DecodeError {
+    /// Key is neither a known top-level field, nor a rule field
+    /// of the form `rules[input][0][name]`.
+    #[error("malformed key: {0}")]
+    MalformedKey(String),
+
+    /// Key or value is not valid percent-encoded UTF-8.
+    #[error("invalid percent-encoding: {0}")]
+    InvalidEncoding(String),
+
+    /// Field is missing, or its value is invalid.
+    #[error("invalid firewall configuration: {0}")]
+    InvalidValue(#[from] serde_json::Error),
+}
+
+#[derive(Deserialize)]
+struct EncodedFirewallConfig {
+    status: State,
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
+    filter_ipv6: bool,
+    #[serde(
+        rename = "whitelist_hos",
+        deserialize_with = "crate::conversion::permissive_bool"
+    )]
+    whitelist_hetzner_services: bool,
+    rules: Rules,
+}
+
+#[derive(Deserialize)]
+struct EncodedFirewallTemplateConfig {
+    name: String,
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
+    filter_ipv6: bool,
+    #[serde(
+        rename = "whitelist_hos",
+        deserialize_with = "crate::conversion::permissive_bool"
+    )]
+    whitelist_hetzner_services: bool,
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
+    is_default: bool,
+    rules: Rules,
+}
+
+impl FirewallConfig {
+    /// Encode the configuration exactly as it is sent to the API.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use hrobot::api::firewall::{FirewallConfig, Rule, Rules, State};
+    /// let config = FirewallConfig {
+    ///     status: State::Active,
+    ///     filter_ipv6: false,
+    ///     whitelist_hetzner_services: true,
+    ///     rules: Rules {
+    ///         ingress: vec![Rule::accept("Allow all")],
+    ///         egress: vec![],
+    ///     },
+    /// };
+    ///
+    /// assert_eq!(
+    ///     config.to_urlencoded(),
+    ///     "status=active&filter_ipv6=false&whitelist_hos=true\
+    ///     &rules%5Binput%5D%5B0%5D%5Bname%5D=Allow+all\
+    ///     &rules%5Binput%5D%5B0%5D%5Baction%5D=accept"
+    /// );
+    /// ```
+    pub fn to_urlencoded(&self) -> String {
+        Into::<InternalFirewallConfig>::into(self).encode()
+    }
+
+    /// Decode a configuration from the format produced by [`FirewallConfig::to_urlencoded`],
+    /// which is also the format the API accepts.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use hrobot::api::firewall::{FirewallConfig, State};
+    /// let config = FirewallConfig::from_urlencoded(
+    ///     "status=active&filter_ipv6=false&whitelist_hos=true\
+    ///     &rules[input][0][name]=Allow+all&rules[input][0][action]=accept"
+    /// ).unwrap();
+    ///
+    /// assert_eq!(config.status, State::Active);
+    /// assert_eq!(config.rules.ingress[0].name, "Allow all");
+    /// ```
+    pub fn from_urlencoded(body: &str) -> Result<Self, DecodeError> {
+        let config: EncodedFirewallConfig = decode(body)?;
+
+        Ok(FirewallConfig {
+            status: config.status,
+            filter_ipv6: config.filter_ipv6,
+            whitelist_hetzner_services: config.whitelist_hetzner_services,
+            rules: config.rules,
+        })
+    }
+}
+
+impl FirewallTemplateConfig {
+    /// Encode the template configuration exactly as it is sent to the API.
+    pub fn to_urlencoded(&self) -> String {
+        Into::<InternalFirewallTemplateConfig>::into(self).encode()
+    }
+
+    /// Decode a template configuration from the format produced by
+    /// [`FirewallTemplateConfig::to_urlencoded`].
+    pub fn from_urlencoded(body: &str) -> Result<Self, DecodeError> {
+        let config: EncodedFirewallTemplateConfig = decode(body)?;
+
+        Ok(FirewallTemplateConfig {
+            name: config.name,
+            filter_ipv6: config.filter_ipv6,
+            whitelist_hetzner_services: config.whitelist_hetzner_services,
+            is_default: config.is_default,
+            rules: config.rules,
+        })
+    }
+}
+
+fn unescape(value: &str) -> Result<String, DecodeError> {
+    urlencoding::decode(&value.replace('+', " "))
+        .map(|value| value.into_owned())
+        .map_err(|_| DecodeError::InvalidEncoding(value.to_string()))
+}
+
+/// Decode the body into a JSON object of strings, with rules
+/// grouped by direction and ordered by their index.
+fn decode<T: for<'de> Deserialize<'de>>(body: &str) -> Result<T, DecodeError> {
+    let mut fields = Map::new();
+    let mut ingress = BTreeMap::<usize, Map<String, Value>>::new();
+    let mut egress = BTreeMap::<usize, Map<String, Value>>::new();
+
+    for pair in body.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let (key, value) = (unescape(key)?, unescape(value)?);
+
+        let Some(rule) = key.strip_prefix("rules[") else {
+            let _ = fields.insert(key, Value::String(value));
+            continue;
+        };
+
+        let malformed = || DecodeError::MalformedKey(key.clone());
+
+        let parts: Vec<_> = rule
+            .strip_suffix(']')
+            .ok_or_else(malformed)?
+            .split("][")
+            .collect();
+
+        let [direction, index, field] = parts[..] else {
+            return Err(malformed());
+        };
+
+        let rules = match direction {
+            "input" => &mut ingress,
+            "output" => &mut egress,
+            _ => return Err(malformed()),
+        };
+
+        let index = index.parse().map_err(|_| malformed())?;
+        let _ = rules
+            .entry(index)
+            .or_default()
+            .insert(field.to_string(), Value::String(value));
+    }
+
+    let rules = |rules: BTreeMap<usize, Map<String, Value>>| {
+        Value::Array(rules.into_values().map(Value::Object).collect())
+    };
+
+    let mut directions = Map::new();
+    let _ = directions.insert("input".to_string(), rules(ingress));
+    let _ = directions.insert("output".to_string(), rules(egress));
+    let _ = fields.insert("rules".to_string(), Value::Object(directions));
+
+    // Round-trip through a string, since some fields only deserialize from borrowed strings.
+    Ok(serde_json::from_str(&Value::Object(fields).to_string())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use crate::api::firewall::{
+        DecodeError, FirewallConfig, FirewallTemplateConfig, Ipv4Filter, Ipv6Filter, Rule, Rules,
+        State,
+    };
+
+    #[test]
+    fn firewall_config_round_trip() {
+        let config = FirewallConfig {
+            status: State::Active,
+            filter_ipv6: true,
+            whitelist_hetzner_services: false,
+            rules: Rules {
+                ingress: vec![
+                    Rule::accept("Allow ssh & http from home").matching(
+                        Ipv4Filter::tcp(Some("syn|ack".to_string()))
+                            .from_ip(Ipv4Addr::new(123, 123, 123, 123))
+                            .to_port(22..=80),
+                    ),
+                    Rule::discard("Deny udp").matching(Ipv6Filter::udp().from_port(53)),
+                    Rule::discard("Deny all"),
+                ],
+                egress: vec![Rule::accept("Allow all")],
+            },
+        };
+
+        let encoded = config.to_urlencoded();
+        assert_eq!(FirewallConfig::from_urlencoded(&encoded).unwrap(), config);
+
+        let template = config.to_template_config("My template");
+        let decoded = FirewallTemplateConfig::from_urlencoded(&template.to_urlencoded()).unwrap();
+        assert_eq!(decoded.name, "My template");
+        assert_eq!(decoded.rules, config.rules);
+    }
+
+    #[test]
+    fn firewall_config_decode_errors() {
+        assert!(matches!(
+            FirewallConfig::from_urlencoded("status=active&filter_ipv6=false"),
+            Err(DecodeError::InvalidValue(_))
+        ));
+
+        assert!(matches!(
+            FirewallConfig::from_urlencoded(
+                "status=active&filter_ipv6=false&whitelist_hos=true&rules[forward][0][name]=x"
+            ),
+            Err(DecodeError::MalformedKey(key)) if key == "rules[forward][0][name]"
+        ));
+
+        assert!(matches!(
+            FirewallConfig::from_urlencoded(
+                "status=active&filter_ipv6=false&whitelist_hos=true&rules[input][0][dst_port]=http"
+            ),
+            Err(DecodeError::InvalidValue(_))
+        ));
+    }
+}
@@ -0,0 +1,526 @@
+use std::net::{IpAddr, SocketAddr};
+
+use super::{
+    Action, Direction, Filter, FirewallConfig, Ipv4Filter, Ipv6Filter, PortRange, Protocol, Rule,
+    Rules, State,
+};
+
+use super::Ipv6Net;
+
+/// Describes a packet to evaluate against a firewall configuration
+/// using [`FirewallConfig::evaluate`].
+///
+/// # Example
+/// ```rust
+/// # use std::net::SocketAddr;
+/// # use hrobot::api::firewall::{Direction, PacketDescription, TcpFlags};
+/// // Incoming SSH connection attempt.
+/// let packet = PacketDescription::tcp(
+///     Direction::Ingress,
+///     "203.0.113.10:51234".parse::<SocketAddr>().unwrap(),
+///     "198.51.100.1:22".parse::<SocketAddr>().unwrap(),
+///     TcpFlags::SYN,
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketDescription {
+    /// Direction the packet is travelling, relative to the server.
+    pub direction: Direction,
+
+    /// Source IP address.
+    pub src_ip: IpAddr,
+
+    /// Destination IP address.
+    pub dst_ip: IpAddr,
+
+    /// Protocol of the packet, including protocol-specific details such as ports.
+    pub protocol: PacketProtocol,
+}
+
+impl PacketDescription {
+    /// Describe a TCP packet with the given flags set.
+    pub fn tcp(direction: Direction, src: SocketAddr, dst: SocketAddr, flags: TcpFlags) -> Self {
+        PacketDescription {
+            direction,
+            src_ip: src.ip(),
+            dst_ip: dst.ip(),
+            protocol: PacketProtocol::Tcp {
+                src_port: src.port(),
+                dst_port: dst.port(),
+                flags,
+            },
+        }
+    }
+
+    /// Describe a UDP packet.
+    pub fn udp(direction: Direction, src: SocketAddr, dst: SocketAddr) -> Self {
+        PacketDescription {
+            direction,
+            src_ip: src.ip(),
+            dst_ip: dst.ip(),
+            protocol: PacketProtocol::Udp {
+                src_port: src.port(),
+                dst_port: dst.port(),
+            },
+        }
+    }
+
+    /// Describe a packet of a protocol without ports, such as [`PacketProtocol::Icmp`].
+    pub fn new<Src: Into<IpAddr>, Dst: Into<IpAddr>>(
+        direction: Direction,
+        src_ip: Src,
+        dst_ip: Dst,
+        protocol: PacketProtocol,
+    ) -> Self {
+        PacketDescription {
+            direction,
+            src_ip: src_ip.into(),
+            dst_ip: dst_ip.into(),
+            protocol,
+        }
+    }
+
+    fn is_ipv6(&self) -> bool {
+        self.src_ip.is_ipv6() || self.dst_ip.is_ipv6()
+    }
+
+    fn ports(&self) -> Option<(u16, u16)> {
+        match self.protocol {
+            PacketProtocol::Tcp {
+                src_port, dst_port, ..
+            }
+            | PacketProtocol::Udp { src_port, dst_port } => Some((src_port, dst_port)),
+            _ => None,
+        }
+    }
+}
+
+/// Protocol of a [`PacketDescription`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketProtocol {
+    /// Transmission Control Protocol.
+    Tcp {
+        /// Source port.
+        src_port: u16,
+        /// Destination port.
+        dst_port: u16,
+        /// TCP flags set on the packet.
+        flags: TcpFlags,
+    },
+
+    /// User Datagram Protocol.
+    Udp {
+        /// Source port.
+        src_port: u16,
+        /// Destination port.
+        dst_port: u16,
+    },
+
+    /// Generic Routing Encapsulation.
+    Gre,
+
+    /// Internet Control Message Protocol.
+    Icmp,
+
+    /// IP-in-IP tunneling.
+    Ipip,
+
+    /// IPSec Authentication Header.
+    Ah,
+
+    /// IPSec Encapsulating Security Payload.
+    Esp,
+}
+
+/// TCP flags set on a packet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TcpFlags {
+    /// Synchronize sequence numbers.
+    pub syn: bool,
+    /// Acknowledgement field is significant.
+    pub ack: bool,
+    /// No more data from sender.
+    pub fin: bool,
+    /// Reset the connection.
+    pub rst: bool,
+    /// Push function.
+    pub psh: bool,
+    /// Urgent pointer field is significant.
+    pub urg: bool,
+}
+
+impl TcpFlags {
+    /// First packet of a connection attempt.
+    pub const SYN: TcpFlags = TcpFlags {
+        syn: true,
+        ack: false,
+        fin: false,
+        rst: false,
+        psh: false,
+        urg: false,
+    };
+
+    /// Reply to a connection attempt.
+    pub const SYN_ACK: TcpFlags = TcpFlags {
+        syn: true,
+        ack: true,
+        ..TcpFlags::SYN
+    };
+
+    /// Packet belonging to an established connection.
+    pub const ACK: TcpFlags = TcpFlags {
+        syn: false,
+        ack: true,
+        ..TcpFlags::SYN
+    };
+
+    fn is_set(&self, flag: &str) -> Option<bool> {
+        match flag.trim().to_ascii_lowercase().as_str() {
+            "syn" => Some(self.syn),
+            "ack" => Some(self.ack),
+            "fin" => Some(self.fin),
+            "rst" => Some(self.rst),
+            "psh" => Some(self.psh),
+            "urg" => Some(self.urg),
+            _ => None,
+        }
+    }
+
+    /// Check whether the flags match a rule's flag expression, such as `syn|ack`.
+    ///
+    /// Alternatives are separated by `|`, and each alternative may require several
+    /// flags to be set at once by joining them with `&`. Unknown flags never match.
+    fn matches(&self, expression: &str) -> bool {
+        expression.split('|').any(|alternative| {
+            alternative
+                .split('&')
+                .all(|flag| self.is_set(flag).unwrap_or(false))
+        })
+    }
+}
+
+impl FirewallConfig {
+    /// Simulate how the firewall would treat the given packet, if this configuration was applied.
+    ///
+    /// This follows the semantics documented by Hetzner: the rules for the packet's
+    /// direction are evaluated in order, and the action of the first matching rule is
+    /// taken. If no rule matches, the packet is discarded.
+    ///
+    /// A [`State::Disabled`] firewall accepts all packets, as does a firewall which
+    /// does not [`filter_ipv6`](FirewallConfig::filter_ipv6) for IPv6 packets.
+    ///
+    /// The Hetzner services whitelist is not simulated, since the addresses
+    /// of those services are not known.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use std::net::SocketAddr;
+    /// # use hrobot::api::firewall::*;
+    /// let config = FirewallConfig {
+    ///     status: State::Active,
+    ///     filter_ipv6: false,
+    ///     whitelist_hetzner_services: true,
+    ///     rules: Rules {
+    ///         ingress: vec![Rule::accept("SSH").matching(Ipv4Filter::tcp(None).to_port(22))],
+    ///         egress: vec![Rule::accept("Allow all")],
+    ///     },
+    /// };
+    ///
+    /// let packet = |port| PacketDescription::tcp(
+    ///     Direction::Ingress,
+    ///     "203.0.113.10:51234".parse::<SocketAddr>().unwrap(),
+    ///     SocketAddr::new("198.51.100.1".parse().unwrap(), port),
+    ///     TcpFlags::SYN,
+    /// );
+    ///
+    /// assert_eq!(config.evaluate(packet(22)), Action::Accept);
+    /// assert_eq!(config.evaluate(packet(80)), Action::Discard);
+    /// ```
+    pub fn evaluate(&self, packet: PacketDescription) -> Action {
+        if self.status == State::Disabled || (packet.is_ipv6() && !self.filter_ipv6) {
+            return Action::Accept;
+        }
+
+        self.rules
+            .first_match(packet)
+            .map(|rule| rule.action)
+            .unwrap_or(Action::Discard)
+    }
+}
+
+impl Rules {
+    /// Find the first rule for the packet's direction which matches the packet.
+    pub fn first_match(&self, packet: PacketDescription) -> Option<&Rule> {
+        let rules = match packet.direction {
+            Direction::Ingress => &self.ingress,
+            Direction::Egress => &self.egress,
+        };
+
+        rules.iter().find(|rule| rule.filter.matches(packet))
+    }
+}
+
+impl Filter {
+    /// Check whether the filter matches the given packet.
+    ///
+    /// The direction of the packet is not taken into account. IPv6 network filters
+    /// are always taken into account, since rules using them are either sent to the
+    /// API with the networks intact, or rejected before being sent unless the
+    /// `relaxed-firewall` feature is enabled.
+    pub fn matches(&self, packet: PacketDescription) -> bool {
+        match self {
+            Filter::Any(filter) => ports_match(&filter.src_port, &filter.dst_port, packet),
+            Filter::Ipv4(Ipv4Filter {
+                dst_ip,
+                src_ip,
+                dst_port,
+                src_port,
+                protocol,
+            }) => {
+                let (IpAddr::V4(packet_src), IpAddr::V4(packet_dst)) =
+                    (packet.src_ip, packet.dst_ip)
+                else {
+                    return false;
+                };
+
+                src_ip.is_none_or(|net| net.contains(&packet_src))
+                    && dst_ip.is_none_or(|net| net.contains(&packet_dst))
+                    && protocol_matches(protocol.as_ref(), packet.protocol)
+                    && ports_match(src_port, dst_port, packet)
+            }
+            Filter::Ipv6(Ipv6Filter {
+                protocol,
+                dst_port,
+                src_port,
+                dst_ip,
+                src_ip,
+            }) => {
+                let contains = |net: &Ipv6Net, ip: IpAddr| match ip {
+                    IpAddr::V6(ip) => net.contains(&ip),
+                    IpAddr::V4(_) => false,
+                };
+
+                packet.is_ipv6()
+                    && src_ip.is_none_or(|net| contains(&net, packet.src_ip))
+                    && dst_ip.is_none_or(|net| contains(&net, packet.dst_ip))
+                    && protocol_matches(protocol.as_ref(), packet.protocol)
+                    && ports_match(src_port, dst_port, packet)
+            }
+        }
+    }
+}
+
+fn protocol_matches(protocol: Option<&Protocol>, packet: PacketProtocol) -> bool {
+    match (protocol, packet) {
+        (None, _) => true,
+        (Some(Protocol::Tcp { flags }), PacketProtocol::Tcp { flags: set, .. }) => flags
+            .as_deref()
+            .is_none_or(|expression| set.matches(expression)),
+        (Some(Protocol::Udp), PacketProtocol::Udp { .. })
+        | (Some(Protocol::Gre), PacketProtocol::Gre)
+        | (Some(Protocol::Icmp), PacketProtocol::Icmp)
+        | (Some(Protocol::Ipip), PacketProtocol::Ipip)
+        | (Some(Protocol::Ah), PacketProtocol::Ah)
+        | (Some(Protocol::Esp), PacketProtocol::Esp) => true,
+        _ => false,
+    }
+}
+
+fn ports_match(
+    src_port: &Option<PortRange>,
+    dst_port: &Option<PortRange>,
+    packet: PacketDescription,
+) -> bool {
+    if src_port.is_none() && dst_port.is_none() {
+        return true;
+    }
+
+    // Rules filtering by port never match packets which have no ports.
+    let Some((packet_src, packet_dst)) = packet.ports() else {
+        return false;
+    };
+
+    let contains = |range: &Option<PortRange>, port: u16| {
+        range
+            .as_ref()
+            .is_none_or(|range| range.start() <= port && port <= range.end())
+    };
+
+    contains(src_port, packet_src) && contains(dst_port, packet_dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+
+    use crate::api::firewall::{
+        Action, AnyFilter, Direction, Filter, FirewallConfig, Ipv4Filter, Ipv6Filter, Rule, Rules,
+        State,
+    };
+
+    use super::{PacketDescription, PacketProtocol, TcpFlags};
+
+    fn config(ingress: Vec<Rule>) -> FirewallConfig {
+        FirewallConfig {
+            status: State::Active,
+            filter_ipv6: true,
+            whitelist_hetzner_services: true,
+            rules: Rules {
+                ingress,
+                egress: vec![Rule::accept("Allow all")],
+            },
+        }
+    }
+
+    fn tcp(src: &str, dst: &str, flags: TcpFlags) -> PacketDescription {
+        PacketDescription::tcp(
+            Direction::Ingress,
+            src.parse::<SocketAddr>().unwrap(),
+            dst.parse::<SocketAddr>().unwrap(),
+            flags,
+        )
+    }
+
+    #[test]
+    fn first_match_wins() {
+        let config = config(vec![
+            Rule::discard("Block bad actor")
+                .matching(Ipv4Filter::any().from_ip(Ipv4Addr::new(203, 0, 113, 66))),
+            Rule::accept("SSH").matching(Ipv4Filter::tcp(None).to_port(22)),
+            Rule::discard("No HTTP").matching(Ipv4Filter::tcp(None).to_port(80)),
+            Rule::accept("Web").matching(Ipv4Filter::tcp(None).to_port(80..=443)),
+        ]);
+
+        let ssh = tcp("203.0.113.10:51234", "198.51.100.1:22", TcpFlags::SYN);
+        assert_eq!(config.evaluate(ssh), Action::Accept);
+        assert_eq!(config.rules.first_match(ssh).unwrap().name, "SSH");
+
+        let blocked = tcp("203.0.113.66:51234", "198.51.100.1:22", TcpFlags::SYN);
+        assert_eq!(config.evaluate(blocked), Action::Discard);
+
+        let http = tcp("203.0.113.10:51234", "198.51.100.1:80", TcpFlags::SYN);
+        assert_eq!(config.evaluate(http), Action::Discard);
+
+        let https = tcp("203.0.113.10:51234", "198.51.100.1:443", TcpFlags::SYN);
+        assert_eq!(config.evaluate(https), Action::Accept);
+    }
+
+    #[test]
+    fn implicit_discard() {
+        let config = config(vec![
+            Rule::accept("SSH").matching(Ipv4Filter::tcp(None).to_port(22))
+        ]);
+
+        let udp = PacketDescription::udp(
+            Direction::Ingress,
+            "203.0.113.10:53".parse().unwrap(),
+            "198.51.100.1:22".parse().unwrap(),
+        );
+        assert_eq!(config.evaluate(udp), Action::Discard);
+
+        let icmp = PacketDescription::new(
+            Direction::Ingress,
+            Ipv4Addr::new(203, 0, 113, 10),
+            Ipv4Addr::new(198, 51, 100, 1),
+            PacketProtocol::Icmp,
+        );
+        assert_eq!(config.evaluate(icmp), Action::Discard);
+
+        let egress = PacketDescription {
+            direction: Direction::Egress,
+            ..icmp
+        };
+        assert_eq!(config.evaluate(egress), Action::Accept);
+
+        let disabled = FirewallConfig {
+            status: State::Disabled,
+            ..config
+        };
+        assert_eq!(disabled.evaluate(icmp), Action::Accept);
+    }
+
+    #[test]
+    fn tcp_flags() {
+        let config = config(vec![
+            Rule::accept("Established").matching(Ipv4Filter::tcp(Some("ack".to_string()))),
+            Rule::accept("Reset").matching(Ipv4Filter::tcp(Some("rst|fin&psh".to_string()))),
+        ]);
+
+        let reply = tcp("203.0.113.10:443", "198.51.100.1:51234", TcpFlags::ACK);
+        assert_eq!(config.evaluate(reply), Action::Accept);
+
+        let syn = tcp("203.0.113.10:51234", "198.51.100.1:443", TcpFlags::SYN);
+        assert_eq!(config.evaluate(syn), Action::Discard);
+
+        let fin = TcpFlags {
+            fin: true,
+            ..TcpFlags::default()
+        };
+        assert_eq!(
+            config.evaluate(tcp("203.0.113.10:1", "198.51.100.1:2", fin)),
+            Action::Discard
+        );
+
+        let fin_psh = TcpFlags { psh: true, ..fin };
+        assert_eq!(
+            config.evaluate(tcp("203.0.113.10:1", "198.51.100.1:2", fin_psh)),
+            Action::Accept
+        );
+    }
+
+    #[test]
+    fn ip_versions() {
+        let ipv6 = PacketDescription::new(
+            Direction::Ingress,
+            Ipv6Addr::LOCALHOST,
+            Ipv6Addr::LOCALHOST,
+            PacketProtocol::Esp,
+        );
+
+        let ipv4_only = config(vec![Rule::accept("IPv4").matching(Ipv4Filter::any())]);
+        assert_eq!(ipv4_only.evaluate(ipv6), Action::Discard);
+
+        let unfiltered = FirewallConfig {
+            filter_ipv6: false,
+            ..ipv4_only.clone()
+        };
+        assert_eq!(unfiltered.evaluate(ipv6), Action::Accept);
+
+        let ipv6_esp = config(vec![Rule::accept("ESP").matching(Ipv6Filter::esp())]);
+        assert_eq!(ipv6_esp.evaluate(ipv6), Action::Accept);
+
+        let any_port = config(vec![
+            Rule::accept("DNS").matching(Filter::Any(AnyFilter::default().to_port(53)))
+        ]);
+        assert_eq!(any_port.evaluate(ipv6), Action::Discard);
+        assert_eq!(
+            any_port.evaluate(PacketDescription::udp(
+                Direction::Ingress,
+                "[::1]:5353".parse().unwrap(),
+                "[::1]:53".parse().unwrap(),
+            )),
+            Action::Accept
+        );
+    }
+
+    #[test]
+    fn ipv6_networks() {
+        let config = config(vec![Rule::accept("Office").matching(
+            Ipv6Filter::tcp(None)
+                .from_ip("2001:db8::/32".parse::<ipnet::Ipv6Net>().unwrap())
+                .to_port(22),
+        )]);
+
+        let office = tcp("[2001:db8::1]:51234", "[2a01:4f8::1]:22", TcpFlags::SYN);
+        assert_eq!(config.evaluate(office), Action::Accept);
+
+        let elsewhere = tcp("[2001:db9::1]:51234", "[2a01:4f8::1]:22", TcpFlags::SYN);
+        assert_eq!(config.evaluate(elsewhere), Action::Discard);
+
+        // The evaluated rule is only ever sent with its networks intact.
+        assert_eq!(
+            config.validate().is_ok(),
+            cfg!(feature = "relaxed-firewall")
+        );
+    }
+}
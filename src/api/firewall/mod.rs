@@ -1,17 +1,29 @@
 //! Firewall & template structs and implementation.
 
+mod diff;
+mod encoding;
+mod evaluation;
 mod models;
+mod rollout;
 mod serde;
+mod text;
+mod validation;
 
 use crate::{error::Error, urlencode::UrlEncode, AsyncRobot};
 
 use self::serde::*;
 use ::serde::Serialize;
+pub use diff::*;
+pub use encoding::*;
+pub use evaluation::*;
 pub use models::*;
+pub use rollout::*;
+pub use text::*;
+pub use validation::*;
 
 use super::{
     server::ServerId,
-    wrapper::{Empty, List, Single},
+    wrapper::{Empty, List, OrEmpty, Single},
     UnauthenticatedRequest,
 };
 
@@ -76,7 +88,7 @@ pub(crate) fn create_firewall_template(
 ) -> UnauthenticatedRequest<Single<InternalFirewallTemplate>> {
     UnauthenticatedRequest::from("https://robot-ws.your-server.de/firewall/template")
         .with_method("POST")
-        .with_serialized_body(Into::<InternalFirewallTemplateConfig>::into(template).encode())
+        .with_serialized_body(Into::<InternalFirewallTemplateConfig>::into(&template).encode())
 }
 
 pub(crate) fn delete_firewall_template(
@@ -96,7 +108,7 @@ pub(crate) fn update_firewall_template(
         "https://robot-ws.your-server.de/firewall/template/{template_number}"
     ))
     .with_method("POST")
-    .with_serialized_body(Into::<InternalFirewallTemplateConfig>::into(template).encode())
+    .with_serialized_body(Into::<InternalFirewallTemplateConfig>::into(&template).encode())
 }
 
 impl AsyncRobot {
@@ -112,7 +124,11 @@ impl AsyncRobot {
     /// println!("Ingress rule count: {}", firewall.rules.ingress.len());
     /// # }
     /// ```
-    pub async fn get_firewall(&self, server_number: ServerId) -> Result<Firewall, Error> {
+    pub async fn get_firewall(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<Firewall, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_firewall(server_number)).await?.0.into())
     }
 
@@ -122,6 +138,12 @@ impl AsyncRobot {
     /// both directions! If you don't define any ingress or
     /// egress rules, only the default-deny rule will apply!
     ///
+    /// The configuration is always applied to the [`SwitchPort::Main`] port,
+    /// since the API does not allow configuring the firewall of the KVM port.
+    ///
+    /// Fails with [`Error::InvalidFirewall`] without sending anything, if the
+    /// configuration is invalid as described by [`FirewallConfig::validate`].
+    ///
     /// # Example
     /// ```rust,no_run
     /// # use std::net::Ipv4Addr;
@@ -156,9 +178,12 @@ impl AsyncRobot {
     /// ```
     pub async fn set_firewall_config(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
         firewall: &FirewallConfig,
     ) -> Result<Firewall, Error> {
+        let server_number = server_number.into();
+        firewall.rules.ensure_valid()?;
+
         Ok(self
             .go(set_firewall_config(server_number, firewall))
             .await?
@@ -185,9 +210,11 @@ impl AsyncRobot {
     /// ```
     pub async fn apply_firewall_template(
         &self,
-        server_number: ServerId,
-        template_id: TemplateId,
+        server_number: impl Into<ServerId>,
+        template_id: impl Into<TemplateId>,
     ) -> Result<Firewall, Error> {
+        let server_number = server_number.into();
+        let template_id = template_id.into();
         Ok(self
             .go(apply_firewall_template(server_number, template_id)?)
             .await?
@@ -195,6 +222,56 @@ impl AsyncRobot {
             .into())
     }
 
+    /// Replace a [`Server`](crate::api::server::Server)'s [`Firewall`] configuration
+    /// with the one defined in the given template, plus the `extra` rules.
+    ///
+    /// The API only accepts either a template or a complete configuration, so the
+    /// template is retrieved first, and the combined configuration is then applied
+    /// as described in [`FirewallTemplate::firewall_config`]. The `extra` rules are
+    /// evaluated before the rules of the template, so an extra accept rule is not
+    /// shadowed by a discard rule in the template.
+    ///
+    /// Later changes to the template are not reflected in the server's firewall.
+    ///
+    /// Fails with [`Error::InvalidFirewall`] without applying anything, if the combined
+    /// rules are invalid, for example because they exceed [`Rules::MAX_RULES`] in either direction.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::net::Ipv4Addr;
+    /// # use hrobot::api::server::ServerId;
+    /// # use hrobot::api::firewall::{Ipv4Filter, Rule, Rules, TemplateId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// robot.apply_firewall_template_with_rules(
+    ///     ServerId(1234567),
+    ///     TemplateId(1234),
+    ///     Rules {
+    ///         ingress: vec![
+    ///             Rule::accept("Allow ssh from home").matching(
+    ///                 Ipv4Filter::tcp(None)
+    ///                     .from_ip(Ipv4Addr::new(123, 123, 123, 123))
+    ///                     .to_port(22)
+    ///             )
+    ///         ],
+    ///         egress: vec![],
+    ///     },
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn apply_firewall_template_with_rules(
+        &self,
+        server_number: impl Into<ServerId>,
+        template_id: impl Into<TemplateId>,
+        extra: Rules,
+    ) -> Result<Firewall, Error> {
+        let template = self.get_firewall_template(template_id).await?;
+        let config = template.firewall_config(extra);
+
+        self.set_firewall_config(server_number, &config).await
+    }
+
     /// Clear a [`Server`](crate::api::server::Server)s [`Firewall`] configuration.
     ///
     /// This reverts the server's firewall configuration to
@@ -210,7 +287,11 @@ impl AsyncRobot {
     /// robot.delete_firewall(ServerId(1234567)).await.unwrap();
     /// # }
     /// ```
-    pub async fn delete_firewall(&self, server_number: ServerId) -> Result<Firewall, Error> {
+    pub async fn delete_firewall(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<Firewall, Error> {
+        let server_number = server_number.into();
         Ok(self.go(delete_firewall(server_number)).await?.0.into())
     }
 
@@ -232,7 +313,7 @@ impl AsyncRobot {
     /// # }
     /// ```
     pub async fn list_firewall_templates(&self) -> Result<Vec<FirewallTemplateReference>, Error> {
-        Ok(self.go(list_firewall_templates()).await?.0)
+        Ok(self.go(list_firewall_templates()).await.or_empty()?.0)
     }
 
     /// Retrieve a complete [`FirewallTemplate`].
@@ -250,8 +331,9 @@ impl AsyncRobot {
     /// ```
     pub async fn get_firewall_template(
         &self,
-        template_number: TemplateId,
+        template_number: impl Into<TemplateId>,
     ) -> Result<FirewallTemplate, Error> {
+        let template_number = template_number.into();
         Ok(self
             .go(get_firewall_template(template_number))
             .await?
@@ -261,6 +343,9 @@ impl AsyncRobot {
 
     /// Create a new [`FirewallTemplate`].
     ///
+    /// Fails with [`Error::InvalidFirewall`] without sending anything, if the
+    /// template is invalid as described by [`FirewallTemplateConfig::validate`].
+    ///
     /// # Example
     /// ```rust,no_run
     /// # use std::net::Ipv4Addr;
@@ -294,6 +379,8 @@ impl AsyncRobot {
         &self,
         template: FirewallTemplateConfig,
     ) -> Result<FirewallTemplate, Error> {
+        template.rules.ensure_valid()?;
+
         Ok(self.go(create_firewall_template(template)).await?.0.into())
     }
 
@@ -308,7 +395,11 @@ impl AsyncRobot {
     /// robot.delete_firewall_template(TemplateId(1234)).await.unwrap();
     /// # }
     /// ```
-    pub async fn delete_firewall_template(&self, template_number: TemplateId) -> Result<(), Error> {
+    pub async fn delete_firewall_template(
+        &self,
+        template_number: impl Into<TemplateId>,
+    ) -> Result<(), Error> {
+        let template_number = template_number.into();
         self.go(delete_firewall_template(template_number))
             .await?
             .throw_away();
@@ -317,6 +408,9 @@ impl AsyncRobot {
 
     /// Modify a [`FirewallTemplate`].
     ///
+    /// Fails with [`Error::InvalidFirewall`] without sending anything, if the
+    /// template is invalid as described by [`FirewallTemplateConfig::validate`].
+    ///
     /// # Example
     /// ```rust,no_run
     /// # use hrobot::api::firewall::{FirewallTemplateConfig, Rules, Rule, TemplateId};
@@ -338,9 +432,12 @@ impl AsyncRobot {
     /// ```
     pub async fn update_firewall_template(
         &self,
-        template_number: TemplateId,
+        template_number: impl Into<TemplateId>,
         template: FirewallTemplateConfig,
     ) -> Result<FirewallTemplate, Error> {
+        let template_number = template_number.into();
+        template.rules.ensure_valid()?;
+
         Ok(self
             .go(update_firewall_template(template_number, template))
             .await?
@@ -348,3 +445,125 @@ impl AsyncRobot {
             .into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        api::server::ServerId,
+        error::Error,
+        mock::{fixtures, MockClient},
+    };
+
+    use super::{Direction, Rule, Rules, TemplateId, ValidationError};
+
+    #[tokio::test]
+    async fn apply_template_with_too_many_rules() {
+        let ingress: Vec<_> = (0..6)
+            .map(|rule| json!({"ip_version": "ipv4", "name": format!("rule {rule}"), "action": "accept"}))
+            .collect();
+
+        let client = MockClient::new()
+            .on(
+                "GET",
+                "/firewall/template/1",
+                fixtures::patch(
+                    fixtures::firewall_template(1, "web"),
+                    json!({"rules": {"input": ingress, "output": []}}),
+                ),
+            )
+            .on("POST", "/firewall/1", fixtures::firewall(1, "in process"));
+        let requests = client.requests();
+        let robot = client.robot();
+
+        let extra = |count| Rules {
+            ingress: vec![Rule::accept("extra"); count],
+            egress: vec![],
+        };
+
+        assert!(matches!(
+            robot
+                .apply_firewall_template_with_rules(ServerId(1), TemplateId(1), extra(5))
+                .await,
+            Err(Error::InvalidFirewall(ValidationError::TooManyRules {
+                direction: Direction::Ingress,
+                count: 11,
+                max: Rules::MAX_RULES,
+            }))
+        ));
+        assert_eq!(requests.lines(), ["GET /firewall/template/1"]);
+
+        let _ = robot
+            .apply_firewall_template_with_rules(ServerId(1), TemplateId(1), extra(4))
+            .await
+            .unwrap();
+        assert_eq!(requests.lines()[2], "POST /firewall/1");
+    }
+
+    #[cfg(not(feature = "relaxed-firewall"))]
+    #[tokio::test]
+    async fn reject_ipv6_networks_before_sending() {
+        use super::{
+            FirewallConfig, FirewallTemplateConfig, InvalidFilter, Ipv6Filter, State, TemplateId,
+        };
+
+        let client = MockClient::new();
+        let requests = client.requests();
+        let robot = client.robot();
+
+        let rules = Rules {
+            ingress: vec![Rule::accept("Office").matching(
+                Ipv6Filter::tcp(None)
+                    .from_ip("2001:db8::/32".parse::<ipnet::Ipv6Net>().unwrap())
+                    .to_port(22),
+            )],
+            egress: vec![],
+        };
+
+        let is_rejected = |result: &Result<(), Error>| {
+            matches!(
+                result,
+                Err(Error::InvalidFirewall(ValidationError::InvalidRule {
+                    error: InvalidFilter::Ipv6NetworksNotSupported,
+                    ..
+                }))
+            )
+        };
+
+        let config = FirewallConfig {
+            status: State::Active,
+            filter_ipv6: true,
+            whitelist_hetzner_services: true,
+            rules: rules.clone(),
+        };
+        assert!(is_rejected(
+            &robot
+                .set_firewall_config(ServerId(1), &config)
+                .await
+                .map(|_| ())
+        ));
+
+        let template = FirewallTemplateConfig {
+            name: "office".to_string(),
+            filter_ipv6: true,
+            whitelist_hetzner_services: true,
+            is_default: false,
+            rules,
+        };
+        assert!(is_rejected(
+            &robot
+                .create_firewall_template(template.clone())
+                .await
+                .map(|_| ())
+        ));
+        assert!(is_rejected(
+            &robot
+                .update_firewall_template(TemplateId(1), template)
+                .await
+                .map(|_| ())
+        ));
+
+        assert_eq!(requests.len(), 0);
+    }
+}
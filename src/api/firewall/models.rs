@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize, Serializer};
-use std::{fmt::Display, ops::RangeInclusive};
+use std::{fmt::Display, num::ParseIntError, ops::RangeInclusive, str::FromStr};
 
-pub use ipnet::Ipv4Net;
+use ipnet::IpNet;
+pub use ipnet::{Ipv4Net, Ipv6Net};
+
+use super::serde::InternalRules;
 
 /// Unique Template ID.
 ///
@@ -13,6 +16,7 @@ pub use ipnet::Ipv4Net;
 /// is in range `0..N` where `N` is the number of templates I have in my account, or is it a limiter, like get first `N`
 /// templates, for example.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct TemplateId(pub u32);
 
 impl From<u32> for TemplateId {
@@ -39,8 +43,23 @@ impl PartialEq<u32> for TemplateId {
     }
 }
 
+impl PartialEq<TemplateId> for u32 {
+    fn eq(&self, other: &TemplateId) -> bool {
+        self.eq(&other.0)
+    }
+}
+
+impl FromStr for TemplateId {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u32::from_str(s).map(TemplateId)
+    }
+}
+
 /// Desired or current state of the server's firewall.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum State {
     /// Firewall is active.
     #[serde(rename = "active")]
@@ -70,7 +89,8 @@ impl Display for State {
 }
 
 /// Switch port of the server.
-#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[derive(Default, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum SwitchPort {
     #[default]
@@ -80,6 +100,15 @@ pub enum SwitchPort {
     Kvm,
 }
 
+impl Display for SwitchPort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SwitchPort::Main => "main",
+            SwitchPort::Kvm => "kvm",
+        })
+    }
+}
+
 /// Protocol types which can be used by rules.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum Protocol {
@@ -118,6 +147,12 @@ impl Protocol {
         }
     }
 
+    /// Indicates whether filtering by source or destination port
+    /// is possible for this protocol.
+    pub fn supports_ports(&self) -> bool {
+        matches!(self, Protocol::Tcp { .. } | Protocol::Udp)
+    }
+
     pub(crate) fn flags(&self) -> Option<String> {
         match self {
             Protocol::Tcp { flags } => flags.clone(),
@@ -126,8 +161,24 @@ impl Protocol {
     }
 }
 
+impl Display for Protocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Protocol::Tcp { flags: Some(flags) } => write!(f, "tcp[{flags}]"),
+            Protocol::Tcp { flags: None } => f.write_str("tcp"),
+            Protocol::Udp => f.write_str("udp"),
+            Protocol::Gre => f.write_str("gre"),
+            Protocol::Icmp => f.write_str("icmp"),
+            Protocol::Ipip => f.write_str("ipip"),
+            Protocol::Ah => f.write_str("ah"),
+            Protocol::Esp => f.write_str("esp"),
+        }
+    }
+}
+
 /// Course of action to take when a rule matches.
-#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum Action {
     /// Explicitly accept the packet.
@@ -157,7 +208,8 @@ impl Display for Action {
 ///
 /// This is only a descriptor for a template, it does not contain
 /// any firewall rules.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct FirewallTemplateReference {
     /// Unique template ID. Can be used to fetch the entire rule
     /// list using [`AsyncRobot::get_firewall_template()`](crate::AsyncRobot::get_firewall_template)
@@ -167,19 +219,25 @@ pub struct FirewallTemplateReference {
     pub name: String,
 
     /// Whether to filter IPv6 traffic.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub filter_ipv6: bool,
 
     /// Whether to whitelist Hetzner's services,
     /// granting them access through the firewall.
-    #[serde(rename = "whitelist_hos")]
+    #[serde(
+        rename = "whitelist_hos",
+        deserialize_with = "crate::conversion::permissive_bool"
+    )]
     pub whitelist_hetzner_services: bool,
 
     /// Indicates if this template is set as default.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub is_default: bool,
 }
 
 /// Describes an entire firewall template.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct FirewallTemplate {
     /// Unique firewall template id
     pub id: TemplateId,
@@ -188,20 +246,52 @@ pub struct FirewallTemplate {
     pub name: String,
 
     /// Whether to filter IPv6 traffic.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub filter_ipv6: bool,
 
     /// Whether to whitelist Hetzner's services,
     /// granting them access through the firewall.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub whitelist_hetzner_services: bool,
 
     /// Indicates whether this template shows up as the
     /// default in the Robot webpanel.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub is_default: bool,
 
     /// Firewall rules defined for this Firewall.
     pub rules: Rules,
 }
 
+impl FirewallTemplate {
+    /// Extract the template configuration from this firewall template description.
+    pub fn config(&self) -> FirewallTemplateConfig {
+        FirewallTemplateConfig {
+            name: self.name.clone(),
+            filter_ipv6: self.filter_ipv6,
+            whitelist_hetzner_services: self.whitelist_hetzner_services,
+            is_default: self.is_default,
+            rules: self.rules.clone(),
+        }
+    }
+
+    /// Firewall configuration equivalent to applying this template, with `extra`
+    /// rules evaluated before the rules of the template.
+    ///
+    /// The API does not allow applying a template along with additional rules, so
+    /// this configuration can instead be applied using
+    /// [`AsyncRobot::set_firewall_config`](crate::AsyncRobot::set_firewall_config).
+    /// See also [`AsyncRobot::apply_firewall_template_with_rules`](crate::AsyncRobot::apply_firewall_template_with_rules).
+    pub fn firewall_config(&self, extra: Rules) -> FirewallConfig {
+        FirewallConfig {
+            status: State::Active,
+            filter_ipv6: self.filter_ipv6,
+            whitelist_hetzner_services: self.whitelist_hetzner_services,
+            rules: extra.merge(self.rules.clone()),
+        }
+    }
+}
+
 /// Desired configuration for a firewall template.
 #[derive(Debug, Clone)]
 pub struct FirewallTemplateConfig {
@@ -228,7 +318,7 @@ pub struct FirewallTemplateConfig {
 /// This is returned by Hetzner when getting or updating the firewall of a server.
 /// For configuring the firewall, instead use the [`FirewallConfig`] struct,
 /// which can also be extracted using [`Firewall::config()`]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Firewall {
     /// Status of the server's firewall.
     pub status: State,
@@ -241,6 +331,9 @@ pub struct Firewall {
     pub whitelist_hetzner_services: bool,
 
     /// Switch of the server to which this firewall applies.
+    ///
+    /// Firewalls may be reported for the [`SwitchPort::Kvm`] port, but can
+    /// only be configured for the [`SwitchPort::Main`] port. See [`Firewall::validate`].
     pub port: SwitchPort,
 
     /// Firewall rules defined for this Firewall.
@@ -249,22 +342,47 @@ pub struct Firewall {
 
 impl Firewall {
     /// Extract the firewall configuration from this firewall description.
+    ///
+    /// The configuration does not include the [`Firewall::port`], since
+    /// configurations are always applied to the [`SwitchPort::Main`] port.
     pub fn config(&self) -> FirewallConfig {
         self.into()
     }
+
+    /// Compare the content of two firewall descriptions, ignoring
+    /// transient state changes.
+    ///
+    /// Unlike [`PartialEq`], a firewall which is currently [`State::InProcess`]
+    /// is considered equal to an otherwise identical firewall in any other state,
+    /// which makes this suitable for detecting actual changes when polling.
+    pub fn content_eq(&self, other: &Firewall) -> bool {
+        (self.status == other.status
+            || self.status == State::InProcess
+            || other.status == State::InProcess)
+            && self.filter_ipv6 == other.filter_ipv6
+            && self.whitelist_hetzner_services == other.whitelist_hetzner_services
+            && self.port == other.port
+            && self.rules == other.rules
+    }
 }
 
 /// Firewall configuration to apply to a server.
-#[derive(Debug)]
+///
+/// Configurations always apply to the [`SwitchPort::Main`] port of the server,
+/// since the API does not allow configuring the firewall of any other port.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct FirewallConfig {
     /// Status of the server's firewall.
     pub status: State,
 
     /// Whether to filter IPv6 traffic.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub filter_ipv6: bool,
 
     /// Whether to whitelist Hetzner's services,
     /// granting them access through the firewall.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub whitelist_hetzner_services: bool,
 
     /// Firewall rules defined for this Firewall.
@@ -297,7 +415,11 @@ impl From<&Firewall> for FirewallConfig {
 }
 
 /// Encapsulates all ingoing and outgoing rules for a Firewall.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Serializes using the same rule representation as the Hetzner Robot API.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(try_from = "InternalRules", into = "InternalRules")]
 pub struct Rules {
     /// Rules applied to ingress traffic (traffic to the server).
     pub ingress: Vec<Rule>,
@@ -306,6 +428,126 @@ pub struct Rules {
     pub egress: Vec<Rule>,
 }
 
+impl Rules {
+    /// Iterate over all rules, ingress rules first, along with the direction they apply to.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use hrobot::api::firewall::{Rule, Rules};
+    /// let rules = Rules {
+    ///     ingress: vec![Rule::accept("Allow all")],
+    ///     egress: vec![Rule::accept("Allow all")],
+    /// };
+    ///
+    /// for (direction, rule) in &rules {
+    ///     println!("{direction}: {}", rule.name);
+    /// }
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (Direction, &Rule)> {
+        self.ingress
+            .iter()
+            .map(|rule| (Direction::Ingress, rule))
+            .chain(self.egress.iter().map(|rule| (Direction::Egress, rule)))
+    }
+
+    /// Iterate over all rules with the given name, along with the direction they apply to.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use hrobot::api::firewall::{Direction, Rule, Rules};
+    /// let rules = Rules {
+    ///     ingress: vec![Rule::accept("Allow all")],
+    ///     egress: vec![Rule::accept("Allow all")],
+    /// };
+    ///
+    /// assert_eq!(rules.iter_named("Allow all").count(), 2);
+    /// ```
+    pub fn iter_named<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = (Direction, &'a Rule)> + 'a {
+        self.iter().filter(move |(_, rule)| rule.name == name)
+    }
+
+    /// Total number of ingress and egress rules.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use hrobot::api::firewall::{Rule, Rules};
+    /// let rules = Rules {
+    ///     ingress: vec![Rule::accept("Allow all")],
+    ///     egress: vec![],
+    /// };
+    ///
+    /// assert_eq!(rules.len(), 1);
+    /// assert!(!rules.is_empty());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.ingress.len() + self.egress.len()
+    }
+
+    /// True if there are neither ingress nor egress rules.
+    pub fn is_empty(&self) -> bool {
+        self.ingress.is_empty() && self.egress.is_empty()
+    }
+
+    /// Append the rules of `other` to these rules, in each direction.
+    ///
+    /// Since rules are evaluated in order, the rules of `other` only apply to
+    /// traffic not already matched by these rules. The merged rules may exceed
+    /// [`Rules::MAX_RULES`], see [`Rules::validate`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use hrobot::api::firewall::{Rule, Rules};
+    /// let ssh = Rules {
+    ///     ingress: vec![Rule::accept("Allow ssh")],
+    ///     egress: vec![],
+    /// };
+    ///
+    /// let rules = ssh.merge(Rules {
+    ///     ingress: vec![Rule::discard("Deny all")],
+    ///     egress: vec![Rule::accept("Allow all")],
+    /// });
+    ///
+    /// assert_eq!(rules.ingress[0].name, "Allow ssh");
+    /// assert_eq!(rules.len(), 3);
+    /// ```
+    #[must_use]
+    pub fn merge(mut self, other: Rules) -> Rules {
+        self.ingress.extend(other.ingress);
+        self.egress.extend(other.egress);
+        self
+    }
+}
+
+impl<'a> IntoIterator for &'a Rules {
+    type Item = (Direction, &'a Rule);
+    type IntoIter = Box<dyn Iterator<Item = (Direction, &'a Rule)> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+/// Direction of traffic a set of rules applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Traffic to the server.
+    Ingress,
+    /// Traffic leaving the server.
+    Egress,
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Direction::Ingress => "ingress",
+            Direction::Egress => "egress",
+        })
+    }
+}
+
 /// Describes a port or range of ports.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PortRange(RangeInclusive<u16>);
@@ -434,6 +676,20 @@ impl Serialize for PortRange {
     }
 }
 
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for PortRange {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "PortRange".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "pattern": "^[0-9]+(-[0-9]+)?$"
+        })
+    }
+}
+
 /// Describes a filter which narrows the scope of affected traffic for a [`Rule`]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Filter {
@@ -445,6 +701,98 @@ pub enum Filter {
     Ipv6(Ipv6Filter),
 }
 
+impl Display for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (version, protocol, src_ip, dst_ip, src_port, dst_port) = match self {
+            Filter::Any(filter) => ("any", None, None, None, &filter.src_port, &filter.dst_port),
+            Filter::Ipv4(filter) => (
+                "ipv4",
+                filter.protocol.as_ref(),
+                filter.src_ip.map(IpNet::from),
+                filter.dst_ip.map(IpNet::from),
+                &filter.src_port,
+                &filter.dst_port,
+            ),
+            Filter::Ipv6(filter) => (
+                "ipv6",
+                filter.protocol.as_ref(),
+                filter.src_ip.map(IpNet::from),
+                filter.dst_ip.map(IpNet::from),
+                &filter.src_port,
+                &filter.dst_port,
+            ),
+        };
+
+        f.write_str(version)?;
+
+        if let Some(protocol) = protocol {
+            write!(f, " {protocol}")?;
+        }
+
+        if let Some(src_ip) = src_ip {
+            write!(f, " src_ip={src_ip}")?;
+        }
+
+        if let Some(dst_ip) = dst_ip {
+            write!(f, " dst_ip={dst_ip}")?;
+        }
+
+        if let Some(src_port) = src_port {
+            write!(f, " src_port={src_port}")?;
+        }
+
+        if let Some(dst_port) = dst_port {
+            write!(f, " dst_port={dst_port}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Filter {
+    /// Check that the filter only uses port ranges with protocols that support them,
+    /// and only uses IPv6 networks with the `relaxed-firewall` feature.
+    pub fn validate(&self) -> Result<(), InvalidFilter> {
+        let (protocol, has_ports) = match self {
+            Filter::Any(_) => return Ok(()),
+            Filter::Ipv4(filter) => (
+                filter.protocol.as_ref(),
+                filter.src_port.is_some() || filter.dst_port.is_some(),
+            ),
+            Filter::Ipv6(filter) => {
+                if !cfg!(feature = "relaxed-firewall")
+                    && (filter.src_ip.is_some() || filter.dst_ip.is_some())
+                {
+                    return Err(InvalidFilter::Ipv6NetworksNotSupported);
+                }
+
+                (
+                    filter.protocol.as_ref(),
+                    filter.src_port.is_some() || filter.dst_port.is_some(),
+                )
+            }
+        };
+
+        match protocol {
+            Some(protocol) if has_ports && !protocol.supports_ports() => {
+                Err(InvalidFilter::PortsNotSupported(protocol.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Describes why a [`Filter`] is invalid.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum InvalidFilter {
+    /// Port filtering was used with a protocol which does not have ports, such as ICMP.
+    #[error("protocol {0} does not support port filtering")]
+    PortsNotSupported(Protocol),
+    /// IPv6 network filtering was used without the `relaxed-firewall` feature.
+    #[error("ipv6 network filtering requires the relaxed-firewall feature")]
+    Ipv6NetworksNotSupported,
+}
+
 impl Default for Filter {
     fn default() -> Self {
         Filter::Any(AnyFilter::default())
@@ -498,24 +846,32 @@ pub struct Ipv6Filter {
 
     /// Source Port.
     pub src_port: Option<PortRange>,
+
+    /// Destination IP address.
+    ///
+    /// Hetzner [does not officially support IPv6 address filtering](https://docs.hetzner.com/robot/dedicated-server/firewall#limitations-ipv6),
+    /// so [`Filter::validate`] rejects it unless the `relaxed-firewall` feature is
+    /// enabled, and rules using it fail with [`Error::InvalidFirewall`](crate::error::Error::InvalidFirewall)
+    /// before being sent.
+    pub dst_ip: Option<Ipv6Net>,
+
+    /// Source IP address.
+    ///
+    /// See [`Ipv6Filter::dst_ip`].
+    pub src_ip: Option<Ipv6Net>,
 }
 
 impl Ipv6Filter {
     /// Match all IPv6 traffic.
     pub fn any() -> Self {
-        Ipv6Filter {
-            protocol: None,
-            dst_port: None,
-            src_port: None,
-        }
+        Ipv6Filter::default()
     }
 
     /// Match only IPSec Authentication Header traffic.
     pub fn ah() -> Self {
         Ipv6Filter {
             protocol: Some(Protocol::Ah),
-            dst_port: None,
-            src_port: None,
+            ..Default::default()
         }
     }
 
@@ -523,8 +879,7 @@ impl Ipv6Filter {
     pub fn esp() -> Self {
         Ipv6Filter {
             protocol: Some(Protocol::Esp),
-            dst_port: None,
-            src_port: None,
+            ..Default::default()
         }
     }
 
@@ -532,8 +887,7 @@ impl Ipv6Filter {
     pub fn ipip() -> Self {
         Ipv6Filter {
             protocol: Some(Protocol::Ipip),
-            dst_port: None,
-            src_port: None,
+            ..Default::default()
         }
     }
 
@@ -541,8 +895,15 @@ impl Ipv6Filter {
     pub fn gre() -> Self {
         Ipv6Filter {
             protocol: Some(Protocol::Gre),
-            dst_port: None,
-            src_port: None,
+            ..Default::default()
+        }
+    }
+
+    /// Match only Internet Control Message Protocol traffic.
+    pub fn icmp() -> Self {
+        Ipv6Filter {
+            protocol: Some(Protocol::Icmp),
+            ..Default::default()
         }
     }
 
@@ -550,8 +911,7 @@ impl Ipv6Filter {
     pub fn udp() -> Self {
         Ipv6Filter {
             protocol: Some(Protocol::Udp),
-            dst_port: None,
-            src_port: None,
+            ..Default::default()
         }
     }
 
@@ -559,8 +919,7 @@ impl Ipv6Filter {
     pub fn tcp(flags: Option<String>) -> Self {
         Ipv6Filter {
             protocol: Some(Protocol::Tcp { flags }),
-            dst_port: None,
-            src_port: None,
+            ..Default::default()
         }
     }
 
@@ -575,6 +934,18 @@ impl Ipv6Filter {
         self.dst_port = Some(range.into());
         self
     }
+
+    /// Narrow filter to only match the given source ip address or address range.
+    pub fn from_ip<IntoIpNet: Into<Ipv6Net>>(mut self, ip: IntoIpNet) -> Self {
+        self.src_ip = Some(ip.into());
+        self
+    }
+
+    /// Narrow filter to only match the given destination ip address or address range.
+    pub fn to_ip<IntoIpNet: Into<Ipv6Net>>(mut self, ip: IntoIpNet) -> Self {
+        self.dst_ip = Some(ip.into());
+        self
+    }
 }
 
 /// Filters IPv4 traffic.
@@ -658,6 +1029,17 @@ impl Ipv4Filter {
         }
     }
 
+    /// Match only Internet Control Message Protocol traffic.
+    pub fn icmp() -> Self {
+        Ipv4Filter {
+            protocol: Some(Protocol::Icmp),
+            dst_port: None,
+            src_port: None,
+            src_ip: None,
+            dst_ip: None,
+        }
+    }
+
     /// Match only User Datagram Protocol traffic.
     pub fn udp() -> Self {
         Ipv4Filter {
@@ -755,12 +1137,16 @@ impl Rule {
 
 #[cfg(test)]
 mod tests {
-    use std::{net::Ipv4Addr, ops::RangeInclusive};
+    use std::{
+        net::{Ipv4Addr, Ipv6Addr},
+        ops::RangeInclusive,
+    };
 
     use ipnet::Ipv4Net;
 
     use crate::api::firewall::{
-        Filter, Ipv4Filter, Ipv6Filter, PortRange, Protocol, State, TemplateId,
+        Filter, FirewallTemplate, InvalidFilter, Ipv4Filter, Ipv6Filter, PortRange, Protocol, Rule,
+        Rules, State, TemplateId,
     };
 
     use super::AnyFilter;
@@ -775,6 +1161,40 @@ mod tests {
         assert_eq!(TemplateId(1337), 1337u32);
     }
 
+    #[test]
+    fn template_with_extra_rules() {
+        let template = FirewallTemplate {
+            id: TemplateId(1337),
+            name: "Web".to_string(),
+            filter_ipv6: true,
+            whitelist_hetzner_services: false,
+            is_default: false,
+            rules: Rules {
+                ingress: vec![Rule::accept("Allow http"), Rule::discard("Deny all")],
+                egress: vec![Rule::accept("Allow all")],
+            },
+        };
+
+        let config = template.firewall_config(Rules {
+            ingress: vec![Rule::accept("Allow ssh")],
+            egress: vec![],
+        });
+
+        assert_eq!(config.status, State::Active);
+        assert!(config.filter_ipv6);
+        assert!(!config.whitelist_hetzner_services);
+        assert_eq!(
+            config
+                .rules
+                .ingress
+                .iter()
+                .map(|rule| rule.name.as_str())
+                .collect::<Vec<_>>(),
+            ["Allow ssh", "Allow http", "Deny all"]
+        );
+        assert_eq!(config.rules.egress, template.rules.egress);
+    }
+
     #[test]
     fn state_display() {
         assert_eq!(State::Active.to_string(), "active");
@@ -854,7 +1274,8 @@ mod tests {
             Ipv6Filter {
                 protocol: None,
                 dst_port: None,
-                src_port: None
+                src_port: None,
+                ..Default::default()
             }
         );
 
@@ -863,7 +1284,8 @@ mod tests {
             Ipv6Filter {
                 protocol: Some(Protocol::Ah),
                 dst_port: None,
-                src_port: None
+                src_port: None,
+                ..Default::default()
             }
         );
 
@@ -872,7 +1294,8 @@ mod tests {
             Ipv6Filter {
                 protocol: Some(Protocol::Esp),
                 dst_port: None,
-                src_port: None
+                src_port: None,
+                ..Default::default()
             }
         );
 
@@ -881,7 +1304,8 @@ mod tests {
             Ipv6Filter {
                 protocol: Some(Protocol::Ipip),
                 dst_port: None,
-                src_port: None
+                src_port: None,
+                ..Default::default()
             }
         );
 
@@ -890,7 +1314,8 @@ mod tests {
             Ipv6Filter {
                 protocol: Some(Protocol::Gre),
                 dst_port: None,
-                src_port: None
+                src_port: None,
+                ..Default::default()
             }
         );
 
@@ -899,7 +1324,8 @@ mod tests {
             Ipv6Filter {
                 protocol: Some(Protocol::Udp),
                 dst_port: None,
-                src_port: None
+                src_port: None,
+                ..Default::default()
             }
         );
 
@@ -908,7 +1334,8 @@ mod tests {
             Ipv6Filter {
                 protocol: Some(Protocol::Tcp { flags: None }),
                 dst_port: None,
-                src_port: None
+                src_port: None,
+                ..Default::default()
             }
         );
 
@@ -917,7 +1344,8 @@ mod tests {
             Ipv6Filter {
                 protocol: None,
                 dst_port: Some(PortRange::from(200)),
-                src_port: Some(PortRange::from(100))
+                src_port: Some(PortRange::from(100)),
+                ..Default::default()
             }
         )
     }
@@ -1025,4 +1453,84 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn firewall_content_eq() {
+        use crate::api::firewall::{Firewall, Rule, Rules, SwitchPort};
+
+        let firewall = Firewall {
+            status: State::Active,
+            filter_ipv6: false,
+            whitelist_hetzner_services: true,
+            port: SwitchPort::Main,
+            rules: Rules {
+                ingress: vec![Rule::accept("Allow all")],
+                egress: vec![Rule::accept("Allow all")],
+            },
+        };
+
+        let processing = Firewall {
+            status: State::InProcess,
+            ..firewall.clone()
+        };
+
+        assert_ne!(firewall, processing);
+        assert!(firewall.content_eq(&processing));
+
+        let disabled = Firewall {
+            status: State::Disabled,
+            ..firewall.clone()
+        };
+
+        assert!(!firewall.content_eq(&disabled));
+
+        let changed = Firewall {
+            rules: Rules {
+                ingress: vec![],
+                ..firewall.rules.clone()
+            },
+            ..processing
+        };
+
+        assert!(!firewall.content_eq(&changed));
+    }
+
+    #[test]
+    fn port_filters_require_port_protocols() {
+        assert_eq!(
+            Filter::from(Ipv4Filter::icmp()),
+            Filter::Ipv4(Ipv4Filter {
+                protocol: Some(Protocol::Icmp),
+                ..Default::default()
+            })
+        );
+
+        assert!(Filter::from(Ipv4Filter::icmp()).validate().is_ok());
+        assert!(Filter::from(Ipv4Filter::tcp(None).to_port(22))
+            .validate()
+            .is_ok());
+        assert!(Filter::from(Ipv6Filter::udp().from_port(53))
+            .validate()
+            .is_ok());
+
+        assert_eq!(
+            Filter::from(Ipv4Filter::icmp().to_port(22)).validate(),
+            Err(InvalidFilter::PortsNotSupported(Protocol::Icmp))
+        );
+
+        assert_eq!(
+            Filter::from(Ipv6Filter::esp().from_port(500)).validate(),
+            Err(InvalidFilter::PortsNotSupported(Protocol::Esp))
+        );
+
+        let network = Filter::from(Ipv6Filter::any().from_ip(Ipv6Addr::LOCALHOST));
+        if cfg!(feature = "relaxed-firewall") {
+            assert!(network.validate().is_ok());
+        } else {
+            assert_eq!(
+                network.validate(),
+                Err(InvalidFilter::Ipv6NetworksNotSupported)
+            );
+        }
+    }
 }
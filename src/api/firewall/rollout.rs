@@ -0,0 +1,455 @@
+use std::sync::Arc;
+
+use futures_util::{stream, Stream, StreamExt};
+use tokio::sync::watch;
+
+use crate::{
+    api::{
+        bulk::{BulkExecutor, BulkOptions},
+        server::ServerId,
+    },
+    error::Error,
+    poll::{PollInterrupted, PollOptions},
+    AsyncRobot,
+};
+
+use super::{Firewall, State, TemplateId};
+
+impl AsyncRobot {
+    /// Apply a firewall template to a fleet of servers, reporting progress as a stream of [`RolloutEvent`]s.
+    ///
+    /// Servers are processed and requests issued according to [`RolloutOptions::bulk`]. For each server the
+    /// template is applied, after which its firewall is polled according to [`RolloutOptions::poll`]
+    /// until it is no longer [`State::InProcess`]. The rollout is verified if the firewall ends up
+    /// [`State::Active`] with the same configuration which was reported when the template was applied.
+    ///
+    /// Failures are reported as [`RolloutEvent::Failed`] and do not stop the rollout of other servers.
+    /// Use the [`RolloutControl`] in [`RolloutOptions::control`] to pause, resume or abort the
+    /// rollout. Pausing or aborting only affects servers which have not been started yet.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use futures_util::StreamExt;
+    /// # use hrobot::api::{firewall::{RolloutEvent, RolloutOptions, TemplateId}, server::ServerId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let options = RolloutOptions::default();
+    /// let control = options.control.clone();
+    ///
+    /// let events = robot.rollout_firewall_template(
+    ///     TemplateId(1234),
+    ///     vec![ServerId(1234567), ServerId(2345678)],
+    ///     options,
+    /// );
+    /// futures_util::pin_mut!(events);
+    ///
+    /// while let Some(event) = events.next().await {
+    ///     if let RolloutEvent::Failed { server, reason } = &event {
+    ///         eprintln!("{server}: {reason}");
+    ///         control.abort();
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn rollout_firewall_template(
+        &self,
+        template: impl Into<TemplateId>,
+        targets: impl IntoIterator<Item = impl Into<ServerId>>,
+        options: RolloutOptions,
+    ) -> impl Stream<Item = RolloutEvent> + '_ {
+        let template = template.into();
+        let targets: Vec<ServerId> = targets.into_iter().map(Into::into).collect();
+        let executor = Arc::new(BulkExecutor::for_robot(self, options.bulk.clone()));
+        let concurrency = executor.concurrency();
+
+        stream::iter(targets)
+            .map(move |server| {
+                Box::pin(self.rollout_server(template, server, options.clone(), executor.clone()))
+            })
+            .flatten_unordered(concurrency)
+    }
+
+    /// Roll out the template to a single server.
+    fn rollout_server(
+        &self,
+        template: TemplateId,
+        server: ServerId,
+        options: RolloutOptions,
+        executor: Arc<BulkExecutor>,
+    ) -> impl Stream<Item = RolloutEvent> + '_ {
+        let state = (Step::Start, options, executor);
+
+        stream::unfold(state, move |(step, options, executor)| async move {
+            match step {
+                Step::Start => {
+                    if !options.control.proceed().await {
+                        return None;
+                    }
+
+                    Some((
+                        RolloutEvent::Started { server },
+                        (Step::Apply, options, executor),
+                    ))
+                }
+                Step::Apply => match executor
+                    .run(|| self.apply_firewall_template(server, template))
+                    .await
+                {
+                    Ok(firewall) => Some((
+                        RolloutEvent::Applied {
+                            server,
+                            firewall: firewall.clone(),
+                        },
+                        (Step::Verify(firewall), options, executor),
+                    )),
+                    Err(err) => Some((
+                        RolloutEvent::Failed {
+                            server,
+                            reason: RolloutFailure::Api(err),
+                        },
+                        (Step::Done, options, executor),
+                    )),
+                },
+                Step::Verify(applied) => {
+                    let event = match self
+                        .wait_for_firewall(server, &options.poll, &executor)
+                        .await
+                    {
+                        Ok(firewall) if firewall.status != State::Active => RolloutEvent::Failed {
+                            server,
+                            reason: RolloutFailure::NotActive(firewall.status),
+                        },
+                        Ok(firewall) if !firewall.content_eq(&applied) => RolloutEvent::Failed {
+                            server,
+                            reason: RolloutFailure::Mismatch(Box::new(firewall)),
+                        },
+                        Ok(firewall) => RolloutEvent::Verified { server, firewall },
+                        Err(reason) => RolloutEvent::Failed { server, reason },
+                    };
+
+                    Some((event, (Step::Done, options, executor)))
+                }
+                Step::Done => None,
+            }
+        })
+    }
+
+    /// Poll the server's firewall until it is no longer in process.
+    async fn wait_for_firewall(
+        &self,
+        server: ServerId,
+        options: &PollOptions,
+        executor: &BulkExecutor,
+    ) -> Result<Firewall, RolloutFailure> {
+        let deadline = options.deadline();
+
+        loop {
+            let firewall = executor
+                .run(|| self.get_firewall(server))
+                .await
+                .map_err(RolloutFailure::Api)?;
+
+            if firewall.status != State::InProcess {
+                return Ok(firewall);
+            }
+
+            options
+                .sleep(deadline)
+                .await
+                .map_err(|interrupted| match interrupted {
+                    PollInterrupted::Timeout => RolloutFailure::Timeout,
+                    PollInterrupted::Cancelled => RolloutFailure::Cancelled,
+                })?;
+        }
+    }
+}
+
+/// Progress of rolling out a single server.
+enum Step {
+    Start,
+    Apply,
+    Verify(Firewall),
+    Done,
+}
+
+/// Options for [`AsyncRobot::rollout_firewall_template`].
+#[derive(Debug, Clone)]
+pub struct RolloutOptions {
+    /// Number of servers processed concurrently, and limits on the requests issued for them.
+    pub bulk: BulkOptions,
+
+    /// How to poll each firewall while waiting for the template to be applied.
+    pub poll: PollOptions,
+
+    /// Handle for pausing, resuming or aborting the rollout.
+    pub control: RolloutControl,
+}
+
+impl Default for RolloutOptions {
+    /// Process 4 servers at a time, polling each firewall every
+    /// 10 seconds for up to 10 minutes.
+    fn default() -> Self {
+        RolloutOptions {
+            bulk: BulkOptions::default(),
+            poll: PollOptions::new(std::time::Duration::from_secs(10))
+                .with_timeout(std::time::Duration::from_secs(600)),
+            control: RolloutControl::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ControlState {
+    Running,
+    Paused,
+    Aborted,
+}
+
+/// Handle for pausing, resuming or aborting a rollout in progress.
+///
+/// Clones of the handle control the same rollout.
+#[derive(Debug, Clone)]
+pub struct RolloutControl {
+    state: Arc<watch::Sender<ControlState>>,
+}
+
+impl Default for RolloutControl {
+    fn default() -> Self {
+        RolloutControl {
+            state: Arc::new(watch::Sender::new(ControlState::Running)),
+        }
+    }
+}
+
+impl RolloutControl {
+    /// Stop starting new servers until [`RolloutControl::resume`] is called.
+    pub fn pause(&self) {
+        self.set(ControlState::Paused);
+    }
+
+    /// Resume a paused rollout.
+    pub fn resume(&self) {
+        self.set(ControlState::Running);
+    }
+
+    /// Stop starting new servers for the remainder of the rollout.
+    ///
+    /// An aborted rollout cannot be resumed.
+    pub fn abort(&self) {
+        let _ = self.state.send_replace(ControlState::Aborted);
+    }
+
+    /// Returns true if the rollout has been paused.
+    pub fn is_paused(&self) -> bool {
+        *self.state.borrow() == ControlState::Paused
+    }
+
+    /// Returns true if the rollout has been aborted.
+    pub fn is_aborted(&self) -> bool {
+        *self.state.borrow() == ControlState::Aborted
+    }
+
+    fn set(&self, state: ControlState) {
+        let _ = self.state.send_if_modified(|current| {
+            if *current == ControlState::Aborted || *current == state {
+                false
+            } else {
+                *current = state;
+                true
+            }
+        });
+    }
+
+    /// Wait while the rollout is paused, returning false if it has been aborted.
+    async fn proceed(&self) -> bool {
+        let mut receiver = self.state.subscribe();
+
+        loop {
+            let state = *receiver.borrow_and_update();
+            match state {
+                ControlState::Running => return true,
+                ControlState::Aborted => return false,
+                ControlState::Paused => {
+                    if receiver.changed().await.is_err() {
+                        return false;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Progress reported by [`AsyncRobot::rollout_firewall_template`].
+#[derive(Debug)]
+pub enum RolloutEvent {
+    /// Rollout to the server has started.
+    Started {
+        /// Server being rolled out to.
+        server: ServerId,
+    },
+
+    /// Template was applied to the server, and the firewall is being updated.
+    Applied {
+        /// Server the template was applied to.
+        server: ServerId,
+        /// Firewall configuration reported when applying the template.
+        firewall: Firewall,
+    },
+
+    /// Firewall is active and matches the applied template.
+    Verified {
+        /// Server the template was applied to.
+        server: ServerId,
+        /// Final firewall configuration.
+        firewall: Firewall,
+    },
+
+    /// Rollout to the server failed.
+    Failed {
+        /// Server which failed.
+        server: ServerId,
+        /// Reason for the failure.
+        reason: RolloutFailure,
+    },
+}
+
+impl RolloutEvent {
+    /// Server the event concerns.
+    pub fn server(&self) -> ServerId {
+        match self {
+            RolloutEvent::Started { server }
+            | RolloutEvent::Applied { server, .. }
+            | RolloutEvent::Verified { server, .. }
+            | RolloutEvent::Failed { server, .. } => *server,
+        }
+    }
+}
+
+/// Describes why rolling out a firewall template to a server failed.
+#[derive(Debug, thiserror::Error)]
+pub enum RolloutFailure {
+    /// Request to the API failed.
+    #[error("{0}")]
+    Api(Error),
+
+    /// Firewall was still being processed when the poll timeout elapsed.
+    #[error("timed out waiting for firewall to become active")]
+    Timeout,
+
+    /// Waiting for the firewall was cancelled using the poll cancellation token.
+    #[error("stopped waiting for firewall to become active")]
+    Cancelled,
+
+    /// Firewall ended up in a state other than [`State::Active`].
+    #[error("firewall ended up {0} instead of active")]
+    NotActive(State),
+
+    /// Firewall configuration does not match the applied template.
+    #[error("firewall configuration does not match the applied template")]
+    Mismatch(Box<Firewall>),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures_util::StreamExt;
+    use hyper::StatusCode;
+
+    use crate::{
+        api::{bulk::BulkOptions, firewall::TemplateId, server::ServerId},
+        mock::{fixtures, MockClient, Reply},
+        poll::PollOptions,
+        AsyncRobot,
+    };
+
+    use super::{RolloutEvent, RolloutFailure, RolloutOptions};
+
+    /// Applies templates to any server except 3, which has no firewall.
+    ///
+    /// Firewalls are reported as in process when applying, and active afterwards.
+    fn firewall_robot() -> AsyncRobot {
+        MockClient::new()
+            .on(
+                "*",
+                "/firewall/3",
+                Reply::error(
+                    StatusCode::NOT_FOUND,
+                    "FIREWALL_NOT_AVAILABLE",
+                    "firewall not available",
+                ),
+            )
+            .on_with("*", "/firewall/*", |request| {
+                let server = request.uri().path().rsplit_once('/').unwrap().1;
+                let status = if request.method() == "POST" {
+                    "in process"
+                } else {
+                    "active"
+                };
+
+                fixtures::firewall(server.parse().unwrap(), status).into()
+            })
+            .robot()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rollout_reports_progress() {
+        let robot = firewall_robot();
+
+        let events: Vec<_> = robot
+            .rollout_firewall_template(
+                TemplateId(1),
+                vec![ServerId(1), ServerId(2), ServerId(3)],
+                RolloutOptions {
+                    bulk: BulkOptions::default().with_concurrency(2),
+                    poll: PollOptions::new(Duration::from_secs(1)),
+                    ..Default::default()
+                },
+            )
+            .collect()
+            .await;
+
+        for server in [ServerId(1), ServerId(2)] {
+            let server_events: Vec<_> = events
+                .iter()
+                .filter(|event| event.server() == server)
+                .collect();
+
+            assert!(matches!(
+                server_events[..],
+                [
+                    RolloutEvent::Started { .. },
+                    RolloutEvent::Applied { .. },
+                    RolloutEvent::Verified { .. }
+                ]
+            ));
+        }
+
+        assert!(events.iter().any(|event| matches!(
+            event,
+            RolloutEvent::Failed {
+                server: ServerId(3),
+                reason: RolloutFailure::Api(_)
+            }
+        )));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn aborted_rollout_starts_nothing() {
+        let robot = firewall_robot();
+
+        let options = RolloutOptions::default();
+        options.control.abort();
+        options.control.resume();
+        assert!(options.control.is_aborted());
+
+        let events: Vec<_> = robot
+            .rollout_firewall_template(TemplateId(1), vec![ServerId(1), ServerId(2)], options)
+            .collect()
+            .await;
+
+        assert!(events.is_empty());
+    }
+}
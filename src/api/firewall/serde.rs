@@ -1,9 +1,12 @@
 use std::fmt::Display;
 
-use ipnet::Ipv4Net;
+use ipnet::{IpNet, Ipv4Net, Ipv6Net};
 use serde::{Deserialize, Serialize};
 
-use crate::urlencode::{UrlEncode, UrlEncodingBuffer};
+use crate::{
+    error::Error,
+    urlencode::{UrlEncode, UrlEncodingBuffer},
+};
 
 use super::{
     Action, AnyFilter, Filter, Firewall, FirewallConfig, FirewallTemplate, FirewallTemplateConfig,
@@ -15,11 +18,16 @@ use super::{
 pub(crate) struct InternalFirewallTemplate {
     pub id: TemplateId,
     pub name: String,
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub filter_ipv6: bool,
-    #[serde(rename = "whitelist_hos")]
+    #[serde(
+        rename = "whitelist_hos",
+        deserialize_with = "crate::conversion::permissive_bool"
+    )]
     pub whitelist_hetzner_services: bool,
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub is_default: bool,
-    pub rules: InternalRules,
+    pub rules: Rules,
 }
 
 impl From<InternalFirewallTemplate> for FirewallTemplate {
@@ -30,7 +38,7 @@ impl From<InternalFirewallTemplate> for FirewallTemplate {
             filter_ipv6: value.filter_ipv6,
             whitelist_hetzner_services: value.whitelist_hetzner_services,
             is_default: value.is_default,
-            rules: value.rules.into(),
+            rules: value.rules,
         }
     }
 }
@@ -44,10 +52,10 @@ pub(crate) struct InternalFirewallTemplateConfig {
     pub rules: InternalRules,
 }
 
-impl From<FirewallTemplateConfig> for InternalFirewallTemplateConfig {
-    fn from(value: FirewallTemplateConfig) -> Self {
+impl From<&FirewallTemplateConfig> for InternalFirewallTemplateConfig {
+    fn from(value: &FirewallTemplateConfig) -> Self {
         InternalFirewallTemplateConfig {
-            name: value.name,
+            name: value.name.clone(),
             filter_ipv6: value.filter_ipv6,
             whitelist_hetzner_services: value.whitelist_hetzner_services,
             is_default: value.is_default,
@@ -59,11 +67,15 @@ impl From<FirewallTemplateConfig> for InternalFirewallTemplateConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct InternalFirewall {
     pub status: State,
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub filter_ipv6: bool,
-    #[serde(rename = "whitelist_hos")]
+    #[serde(
+        rename = "whitelist_hos",
+        deserialize_with = "crate::conversion::permissive_bool"
+    )]
     pub whitelist_hetzner_services: bool,
     pub port: SwitchPort,
-    pub rules: InternalRules,
+    pub rules: Rules,
 }
 
 impl From<InternalFirewall> for Firewall {
@@ -73,7 +85,7 @@ impl From<InternalFirewall> for Firewall {
             filter_ipv6: value.filter_ipv6,
             whitelist_hetzner_services: value.whitelist_hetzner_services,
             port: value.port,
-            rules: value.rules.into(),
+            rules: value.rules,
         }
     }
 }
@@ -97,6 +109,7 @@ impl From<&FirewallConfig> for InternalFirewallConfig {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub(crate) struct InternalRules {
     #[serde(rename = "input", default, skip_serializing_if = "Vec::is_empty")]
     pub ingress: Vec<InternalRule>,
@@ -122,22 +135,63 @@ impl From<&Rules> for InternalRules {
     }
 }
 
-impl From<InternalRules> for Rules {
-    fn from(value: InternalRules) -> Self {
-        Rules {
-            ingress: value.ingress.into_iter().map(Into::<Rule>::into).collect(),
-            egress: value.egress.into_iter().map(Into::<Rule>::into).collect(),
-        }
+impl From<Rules> for InternalRules {
+    fn from(value: Rules) -> Self {
+        (&value).into()
+    }
+}
+
+impl TryFrom<InternalRules> for Rules {
+    type Error = Error;
+
+    fn try_from(value: InternalRules) -> Result<Self, Self::Error> {
+        Ok(Rules {
+            ingress: value
+                .ingress
+                .into_iter()
+                .map(TryInto::<Rule>::try_into)
+                .collect::<Result<_, _>>()?,
+            egress: value
+                .egress
+                .into_iter()
+                .map(TryInto::<Rule>::try_into)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// IPv4 network of an IPv4 rule.
+fn ipv4_net(net: IpNet) -> Result<Ipv4Net, Error> {
+    match net {
+        IpNet::V4(net) => Ok(net),
+        IpNet::V6(net) => Err(Error::UnexpectedResponseShape {
+            expected: "an IPv4 network for an IPv4 rule".to_string(),
+            got: net.to_string(),
+        }),
+    }
+}
+
+/// IPv6 network of an IPv6 rule.
+fn ipv6_net(net: IpNet) -> Result<Ipv6Net, Error> {
+    match net {
+        IpNet::V6(net) => Ok(net),
+        IpNet::V4(net) => Err(Error::UnexpectedResponseShape {
+            expected: "an IPv6 network for an IPv6 rule".to_string(),
+            got: net.to_string(),
+        }),
     }
 }
 
 /// Describes a single Firewall rule.
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub(crate) struct InternalRule {
     pub ip_version: Option<IpVersion>,
     pub name: String,
-    pub dst_ip: Option<Ipv4Net>,
-    pub src_ip: Option<Ipv4Net>,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub dst_ip: Option<IpNet>,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    pub src_ip: Option<IpNet>,
     pub dst_port: Option<PortRange>,
     pub src_port: Option<PortRange>,
     pub protocol: Option<InternalProtocol>,
@@ -160,8 +214,8 @@ impl From<&Rule> for InternalRule {
                 name: value.name.clone(),
                 dst_port: ipv4.dst_port.clone(),
                 src_port: ipv4.src_port.clone(),
-                src_ip: ipv4.src_ip,
-                dst_ip: ipv4.dst_ip,
+                src_ip: ipv4.src_ip.map(IpNet::V4),
+                dst_ip: ipv4.dst_ip.map(IpNet::V4),
                 tcp_flags: ipv4.protocol.as_ref().and_then(Protocol::flags),
                 protocol: ipv4.protocol.as_ref().map(Into::<InternalProtocol>::into),
                 action: value.action,
@@ -171,8 +225,12 @@ impl From<&Rule> for InternalRule {
                 name: value.name.clone(),
                 dst_port: ipv6.dst_port.clone(),
                 src_port: ipv6.src_port.clone(),
-                src_ip: None,
-                dst_ip: None,
+                // Hetzner does not officially support IPv6 networks, so rules using them
+                // are rejected before being sent without the `relaxed-firewall` feature,
+                // see [`Filter::validate`]. They are never dropped here, since that would
+                // widen the rule.
+                src_ip: ipv6.src_ip.map(IpNet::V6),
+                dst_ip: ipv6.dst_ip.map(IpNet::V6),
                 tcp_flags: ipv6.protocol.as_ref().and_then(Protocol::flags),
                 protocol: ipv6.protocol.as_ref().map(Into::<InternalProtocol>::into),
                 action: value.action,
@@ -181,8 +239,10 @@ impl From<&Rule> for InternalRule {
     }
 }
 
-impl From<InternalRule> for Rule {
-    fn from(value: InternalRule) -> Self {
+impl TryFrom<InternalRule> for Rule {
+    type Error = Error;
+
+    fn try_from(value: InternalRule) -> Result<Self, Self::Error> {
         let rule = match value.action {
             Action::Accept => Rule::accept(&value.name),
             Action::Discard => Rule::discard(&value.name),
@@ -200,15 +260,17 @@ impl From<InternalRule> for Rule {
             InternalProtocol::Udp => Protocol::Udp,
         });
 
-        rule.matching(match value.ip_version {
+        Ok(rule.matching(match value.ip_version {
             Some(IpVersion::Ipv4) => Filter::Ipv4(Ipv4Filter {
-                dst_ip: value.dst_ip,
-                src_ip: value.src_ip,
+                dst_ip: value.dst_ip.map(ipv4_net).transpose()?,
+                src_ip: value.src_ip.map(ipv4_net).transpose()?,
                 dst_port: value.dst_port,
                 src_port: value.src_port,
                 protocol,
             }),
             Some(IpVersion::Ipv6) => Filter::Ipv6(Ipv6Filter {
+                dst_ip: value.dst_ip.map(ipv6_net).transpose()?,
+                src_ip: value.src_ip.map(ipv6_net).transpose()?,
                 dst_port: value.dst_port,
                 src_port: value.src_port,
                 protocol,
@@ -217,13 +279,14 @@ impl From<InternalRule> for Rule {
                 dst_port: value.dst_port,
                 src_port: value.src_port,
             }),
-        })
+        }))
     }
 }
 
 /// Version of the Internet Protocol supported by the firewall.
 #[derive(Default, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub(crate) enum IpVersion {
     /// IPv4
     #[default]
@@ -250,6 +313,7 @@ impl Display for IpVersion {
 /// Protocol types which can be used by rules.
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub(crate) enum InternalProtocol {
     /// Transmission Control Protocol.
     Tcp,
@@ -348,12 +412,14 @@ impl UrlEncode for InternalRules {
         {
             let mut ingress = f.append("[input]");
             for (index, rule) in self.ingress.iter().enumerate() {
-                rule.encode_into(ingress.append(&format!("[{index}]")));
+                rule.encode_into(ingress.append(format_args!("[{index}]")));
             }
+        }
 
+        {
             let mut egress = f.append("[output]");
             for (index, rule) in self.egress.iter().enumerate() {
-                rule.encode_into(egress.append(&format!("[{index}]")));
+                rule.encode_into(egress.append(format_args!("[{index}]")));
             }
         }
     }
@@ -380,10 +446,6 @@ impl UrlEncode for InternalFirewallTemplateConfig {
 
 #[cfg(test)]
 mod tests {
-    use std::net::Ipv4Addr;
-
-    use ipnet::Ipv4Net;
-
     use crate::{
         api::firewall::{Action, InternalProtocol, InternalRule, IpVersion, PortRange, Protocol},
         urlencode::UrlEncode,
@@ -440,8 +502,8 @@ mod tests {
         let rule = InternalRule {
             ip_version: Some(IpVersion::Ipv4),
             name: "IPv4 Rule".to_string(),
-            dst_ip: Some(Ipv4Net::new(Ipv4Addr::new(192, 168, 0, 0), 24).unwrap()),
-            src_ip: Some(Ipv4Net::new(Ipv4Addr::new(172, 16, 0, 0), 20).unwrap()),
+            dst_ip: Some("192.168.0.0/24".parse().unwrap()),
+            src_ip: Some("172.16.0.0/20".parse().unwrap()),
             dst_port: Some(PortRange::from(32000..=34000)),
             src_port: Some(PortRange::from(10)),
             protocol: Some(InternalProtocol::Tcp),
@@ -496,6 +558,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rule_conversion_ipv6_networks() {
+        use crate::api::firewall::{Ipv6Filter, Rule};
+
+        let rule = Rule::accept("IPv6 Rule").matching(
+            Ipv6Filter::tcp(None)
+                .from_ip("2001:db8::/32".parse::<ipnet::Ipv6Net>().unwrap())
+                .to_port(22),
+        );
+
+        let internal = InternalRule::from(&rule);
+        assert_eq!(
+            internal.encode(),
+            [
+                "%5Bname%5D=IPv6+Rule",
+                "%5Bip_version%5D=ipv6",
+                "%5Bsrc_ip%5D=2001%3Adb8%3A%3A%2F32",
+                "%5Bdst_port%5D=22",
+                "%5Bprotocol%5D=tcp",
+                "%5Baction%5D=accept"
+            ]
+            .join("&")
+        );
+
+        assert_eq!(Rule::try_from(internal).unwrap(), rule);
+    }
+
+    #[test]
+    fn rule_conversion_mismatched_networks() {
+        use crate::{api::firewall::Rule, error::Error};
+
+        for rule in [
+            r#"{"ip_version":"ipv6","name":"x","src_ip":"10.0.0.0/8","action":"accept"}"#,
+            r#"{"ip_version":"ipv4","name":"x","dst_ip":"2001:db8::/32","action":"accept"}"#,
+        ] {
+            let internal: InternalRule = serde_json::from_str(rule).unwrap();
+            assert!(matches!(
+                Rule::try_from(internal),
+                Err(Error::UnexpectedResponseShape { .. })
+            ));
+        }
+    }
+
     #[test]
     fn rule_encoding_icmp() {
         let rule = InternalRule {
@@ -0,0 +1,575 @@
+//! Line-based textual representation of firewall configurations,
+//! suitable for reviewing firewall rules in diffs.
+
+use std::{
+    fmt::{Display, Write},
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+use ipnet::{Ipv4Net, Ipv6Net};
+
+use super::{
+    AnyFilter, Direction, Filter, FirewallConfig, FirewallTemplateConfig, Ipv4Filter, Ipv6Filter,
+    PortRange, Protocol, Rule, Rules, State,
+};
+
+/// Error produced when parsing the textual representation
+/// of a firewall configuration fails.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("line {line}: {message}")]
+pub struct ParseRulesError {
+    /// Line number on which the error occurred, starting at 1.
+    ///
+    /// Errors concerning missing settings are reported on the line following the last.
+    pub line: usize,
+
+    /// Description of the error.
+    pub message: String,
+}
+
+impl FirewallConfig {
+    /// Render the configuration in the textual format
+    /// described in [`FirewallConfig::parse_rules_text`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use hrobot::api::firewall::{FirewallConfig, Ipv4Filter, Rule, Rules, State};
+    /// let config = FirewallConfig {
+    ///     status: State::Active,
+    ///     filter_ipv6: false,
+    ///     whitelist_hetzner_services: true,
+    ///     rules: Rules {
+    ///         ingress: vec![
+    ///             Rule::accept("Allow ssh").matching(Ipv4Filter::tcp(None).to_port(22)),
+    ///         ],
+    ///         egress: vec![Rule::accept("Allow all")],
+    ///     },
+    /// };
+    ///
+    /// assert_eq!(
+    ///     config.to_rules_text(),
+    ///     "status=active\n\
+    ///     filter_ipv6=false\n\
+    ///     whitelist_hetzner_services=true\n\
+    ///     \n\
+    ///     ingress accept \"Allow ssh\" ipv4 tcp dst_port=22\n\
+    ///     egress accept \"Allow all\" any\n"
+    /// );
+    /// ```
+    pub fn to_rules_text(&self) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "status={}", self.status);
+        let _ = writeln!(text, "filter_ipv6={}", self.filter_ipv6);
+        let _ = writeln!(
+            text,
+            "whitelist_hetzner_services={}",
+            self.whitelist_hetzner_services
+        );
+
+        write_rules(&mut text, &self.rules);
+        text
+    }
+
+    /// Parse a configuration from its textual format,
+    /// as produced by [`FirewallConfig::to_rules_text`].
+    ///
+    /// The format consists of settings of the form `key=value`, followed by one rule per line:
+    ///
+    /// ```text
+    /// # Lines starting with '#' are comments, blank lines are ignored.
+    /// status=active
+    /// filter_ipv6=false
+    /// whitelist_hetzner_services=true
+    ///
+    /// ingress accept "Allow ssh" ipv4 tcp[syn|ack] src_ip=10.0.0.0/8 dst_port=22
+    /// ingress accept "Allow dns" ipv6 udp src_port=53
+    /// ingress discard "Deny all" any
+    /// egress accept "Allow all" any
+    /// ```
+    ///
+    /// Each rule consists of:
+    /// 1. its direction, either `ingress` or `egress`,
+    /// 2. its action, either `accept` or `discard`,
+    /// 3. its name in double quotes, escaping `"` and `\` with a backslash,
+    /// 4. its IP version, one of `any`, `ipv4` or `ipv6`,
+    /// 5. optionally its protocol, one of `tcp`, `udp`, `gre`, `icmp`, `ipip`, `ah` or `esp`,
+    ///    with TCP flags given in brackets, e.g. `tcp[syn|ack]`,
+    /// 6. optionally `src_ip`, `dst_ip` (IPv4 only, unless the `relaxed-firewall` feature
+    ///    is enabled), `src_port` and `dst_port` filters,
+    ///    where ports are either a single port like `22`, or a range like `1024-65535`.
+    ///
+    /// Rules are kept in the order they appear in.
+    ///
+    /// Template configurations additionally have the `name` and `is_default` settings,
+    /// and no `status` setting.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use hrobot::api::firewall::{FirewallConfig, Ipv4Filter, Rule};
+    /// let config = FirewallConfig::parse_rules_text(r#"
+    ///     status=active
+    ///     filter_ipv6=false
+    ///     whitelist_hetzner_services=true
+    ///
+    ///     ## Only allow SSH from the office.
+    ///     ingress accept "Allow ssh" ipv4 tcp src_ip=203.0.113.0/24 dst_port=22
+    ///     ingress discard "Deny all" any
+    /// "#).unwrap();
+    ///
+    /// assert_eq!(config.rules.ingress[1], Rule::discard("Deny all"));
+    /// ```
+    pub fn parse_rules_text(text: &str) -> Result<Self, ParseRulesError> {
+        let mut parsed = parse(text)?;
+
+        Ok(FirewallConfig {
+            status: parsed.take("status", parse_state)?,
+            filter_ipv6: parsed.take("filter_ipv6", parse_bool)?,
+            whitelist_hetzner_services: parsed.take("whitelist_hetzner_services", parse_bool)?,
+            rules: parsed.finish()?,
+        })
+    }
+}
+
+impl FirewallTemplateConfig {
+    /// Render the template configuration in the textual format
+    /// described in [`FirewallConfig::parse_rules_text`].
+    pub fn to_rules_text(&self) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "name={}", self.name);
+        let _ = writeln!(text, "is_default={}", self.is_default);
+        let _ = writeln!(text, "filter_ipv6={}", self.filter_ipv6);
+        let _ = writeln!(
+            text,
+            "whitelist_hetzner_services={}",
+            self.whitelist_hetzner_services
+        );
+
+        write_rules(&mut text, &self.rules);
+        text
+    }
+
+    /// Parse a template configuration from the textual format
+    /// described in [`FirewallConfig::parse_rules_text`],
+    /// as produced by [`FirewallTemplateConfig::to_rules_text`].
+    pub fn parse_rules_text(text: &str) -> Result<Self, ParseRulesError> {
+        let mut parsed = parse(text)?;
+
+        Ok(FirewallTemplateConfig {
+            name: parsed.take("name", |value| Ok(value.to_string()))?,
+            is_default: parsed.take("is_default", parse_bool)?,
+            filter_ipv6: parsed.take("filter_ipv6", parse_bool)?,
+            whitelist_hetzner_services: parsed.take("whitelist_hetzner_services", parse_bool)?,
+            rules: parsed.finish()?,
+        })
+    }
+}
+
+fn write_rules(text: &mut String, rules: &Rules) {
+    if !rules.is_empty() {
+        text.push('\n');
+    }
+
+    for (direction, rule) in rules {
+        let _ = writeln!(text, "{}", RuleLine(direction, rule));
+    }
+}
+
+/// Single rule rendered as a line of text.
+struct RuleLine<'a>(Direction, &'a Rule);
+
+impl Display for RuleLine<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let RuleLine(direction, rule) = self;
+
+        write!(f, "{direction} {} \"", rule.action)?;
+        for c in rule.name.chars() {
+            if c == '"' || c == '\\' {
+                f.write_char('\\')?;
+            }
+            f.write_char(c)?;
+        }
+        write!(f, "\" {}", rule.filter)
+    }
+}
+
+/// Settings and rules of a parsed configuration, whose settings
+/// are consumed by the caller.
+struct Parsed {
+    settings: Vec<(usize, String, String)>,
+    rules: Rules,
+    lines: usize,
+}
+
+impl Parsed {
+    fn take<T>(
+        &mut self,
+        key: &str,
+        parse: impl FnOnce(&str) -> Result<T, String>,
+    ) -> Result<T, ParseRulesError> {
+        let Some(index) = self.settings.iter().position(|(_, name, _)| name == key) else {
+            return Err(ParseRulesError {
+                line: self.lines + 1,
+                message: format!("missing setting {key:?}"),
+            });
+        };
+
+        let (line, _, value) = self.settings.remove(index);
+        parse(&value).map_err(|message| ParseRulesError { line, message })
+    }
+
+    /// Return the rules, failing if any settings were not consumed.
+    fn finish(self) -> Result<Rules, ParseRulesError> {
+        match self.settings.into_iter().next() {
+            Some((line, key, _)) => Err(ParseRulesError {
+                line,
+                message: format!("unknown setting {key:?}"),
+            }),
+            None => Ok(self.rules),
+        }
+    }
+}
+
+fn parse(text: &str) -> Result<Parsed, ParseRulesError> {
+    let mut parsed = Parsed {
+        settings: Vec::new(),
+        rules: Rules {
+            ingress: Vec::new(),
+            egress: Vec::new(),
+        },
+        lines: 0,
+    };
+
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        parsed.lines = number;
+
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let error = |message: String| ParseRulesError {
+            line: number,
+            message,
+        };
+
+        let (word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rules = match word {
+            "ingress" => &mut parsed.rules.ingress,
+            "egress" => &mut parsed.rules.egress,
+            _ => {
+                let Some((key, value)) = line.split_once('=') else {
+                    return Err(error(format!("expected a rule or setting, found {line:?}")));
+                };
+
+                let key = key.trim().to_string();
+                if parsed.settings.iter().any(|(_, name, _)| *name == key) {
+                    return Err(error(format!("duplicate setting {key:?}")));
+                }
+
+                parsed
+                    .settings
+                    .push((number, key, value.trim().to_string()));
+                continue;
+            }
+        };
+
+        rules.push(parse_rule(rest).map_err(error)?);
+    }
+
+    Ok(parsed)
+}
+
+fn parse_rule(line: &str) -> Result<Rule, String> {
+    let (action, rest) = line
+        .trim_start()
+        .split_once(char::is_whitespace)
+        .ok_or("expected action and name")?;
+
+    let action = match action {
+        "accept" => super::Action::Accept,
+        "discard" => super::Action::Discard,
+        action => return Err(format!("unknown action {action:?}")),
+    };
+
+    let (name, rest) = parse_name(rest.trim_start())?;
+
+    let mut words = rest.split_whitespace();
+    let version = words.next().ok_or("expected ip version")?;
+
+    let mut words = words.peekable();
+    let protocol = match words.peek() {
+        Some(word) if !word.contains('=') => words.next().map(parse_protocol).transpose()?,
+        _ => None,
+    };
+
+    let mut src_ip = None;
+    let mut dst_ip = None;
+    let mut src_port = None;
+    let mut dst_port = None;
+
+    for word in words {
+        let (key, value) = word
+            .split_once('=')
+            .ok_or_else(|| format!("expected filter of the form key=value, found {word:?}"))?;
+
+        let duplicate = match key {
+            "src_ip" => src_ip.replace(value).is_some(),
+            "dst_ip" => dst_ip.replace(value).is_some(),
+            "src_port" => src_port.replace(parse_port(value)?).is_some(),
+            "dst_port" => dst_port.replace(parse_port(value)?).is_some(),
+            key => return Err(format!("unknown filter {key:?}")),
+        };
+
+        if duplicate {
+            return Err(format!("duplicate filter {key:?}"));
+        }
+    }
+
+    let filter = match version {
+        "ipv4" => Filter::Ipv4(Ipv4Filter {
+            dst_ip: dst_ip.map(parse_ip).transpose()?,
+            src_ip: src_ip.map(parse_ip).transpose()?,
+            dst_port,
+            src_port,
+            protocol,
+        }),
+        "any" if src_ip.is_some() || dst_ip.is_some() => {
+            return Err(format!("ip filters are not supported for {version} rules"))
+        }
+        "ipv6" => Filter::Ipv6(Ipv6Filter {
+            protocol,
+            dst_port,
+            src_port,
+            dst_ip: dst_ip.map(parse_ipv6).transpose()?,
+            src_ip: src_ip.map(parse_ipv6).transpose()?,
+        }),
+        "any" if protocol.is_some() => {
+            return Err("protocol filters are not supported for any rules".to_string())
+        }
+        "any" => Filter::Any(AnyFilter { dst_port, src_port }),
+        version => return Err(format!("unknown ip version {version:?}")),
+    };
+
+    filter.validate().map_err(|err| err.to_string())?;
+
+    Ok(Rule {
+        name,
+        filter,
+        action,
+    })
+}
+
+/// Parse a quoted name, returning it along with the remainder of the line.
+fn parse_name(line: &str) -> Result<(String, &str), String> {
+    let quoted = line
+        .strip_prefix('"')
+        .ok_or("expected rule name in double quotes")?;
+
+    let mut name = String::new();
+    let mut chars = quoted.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Ok((name, &quoted[index + 1..])),
+            '\\' => name.push(chars.next().ok_or("unterminated rule name")?.1),
+            c => name.push(c),
+        }
+    }
+
+    Err("unterminated rule name".to_string())
+}
+
+fn parse_protocol(value: &str) -> Result<Protocol, String> {
+    if let Some(flags) = value
+        .strip_prefix("tcp[")
+        .and_then(|flags| flags.strip_suffix(']'))
+    {
+        return Ok(Protocol::tcp_with_flags(flags));
+    }
+
+    Ok(match value {
+        "tcp" => Protocol::Tcp { flags: None },
+        "udp" => Protocol::Udp,
+        "gre" => Protocol::Gre,
+        "icmp" => Protocol::Icmp,
+        "ipip" => Protocol::Ipip,
+        "ah" => Protocol::Ah,
+        "esp" => Protocol::Esp,
+        protocol => return Err(format!("unknown protocol {protocol:?}")),
+    })
+}
+
+fn parse_ip(value: &str) -> Result<Ipv4Net, String> {
+    value
+        .parse::<Ipv4Net>()
+        .or_else(|_| value.parse::<Ipv4Addr>().map(Ipv4Net::from))
+        .map_err(|_| format!("invalid ipv4 network {value:?}"))
+}
+
+fn parse_ipv6(value: &str) -> Result<Ipv6Net, String> {
+    value
+        .parse::<Ipv6Net>()
+        .or_else(|_| value.parse::<Ipv6Addr>().map(Ipv6Net::from))
+        .map_err(|_| format!("invalid ipv6 network {value:?}"))
+}
+
+fn parse_port(value: &str) -> Result<PortRange, String> {
+    let invalid = || format!("invalid port or port range {value:?}");
+
+    match value.split_once('-') {
+        Some((start, end)) => Ok(PortRange::range(
+            start.parse().map_err(|_| invalid())?,
+            end.parse().map_err(|_| invalid())?,
+        )),
+        None => value.parse().map(PortRange::port).map_err(|_| invalid()),
+    }
+}
+
+fn parse_state(value: &str) -> Result<State, String> {
+    match value {
+        "active" => Ok(State::Active),
+        "in process" => Ok(State::InProcess),
+        "disabled" => Ok(State::Disabled),
+        state => Err(format!("unknown status {state:?}")),
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    value
+        .parse()
+        .map_err(|_| format!("expected true or false, found {value:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use ipnet::Ipv4Net;
+
+    use crate::api::firewall::{
+        AnyFilter, Filter, FirewallConfig, FirewallTemplateConfig, Ipv4Filter, Ipv6Filter,
+        ParseRulesError, Protocol, Rule, Rules, State,
+    };
+
+    #[test]
+    fn rules_text_round_trip() {
+        let protocols = [
+            Protocol::Tcp { flags: None },
+            Protocol::tcp_with_flags("syn|ack"),
+            Protocol::Udp,
+            Protocol::Gre,
+            Protocol::Icmp,
+            Protocol::Ipip,
+            Protocol::Ah,
+            Protocol::Esp,
+        ];
+
+        let mut ingress = vec![
+            Rule::accept("Allow \"quoted\" \\ names").matching(
+                Ipv4Filter::tcp(None)
+                    .from_ip(Ipv4Addr::new(10, 0, 0, 1))
+                    .to_ip(Ipv4Net::new(Ipv4Addr::new(192, 168, 0, 0), 16).unwrap())
+                    .from_port(1024..=65535)
+                    .to_port(22),
+            ),
+            Rule::discard("Deny udp from port 53").matching(Ipv6Filter::udp().from_port(53)),
+            Rule::accept("Any to port range")
+                .matching(Filter::Any(AnyFilter::default().to_port(8000..=8080))),
+        ];
+
+        for protocol in protocols {
+            ingress.push(
+                Rule::accept(&format!("v4 {protocol}")).matching(Ipv4Filter {
+                    protocol: Some(protocol.clone()),
+                    ..Ipv4Filter::any()
+                }),
+            );
+            ingress.push(
+                Rule::discard(&format!("v6 {protocol}")).matching(Ipv6Filter {
+                    protocol: Some(protocol),
+                    ..Ipv6Filter::any()
+                }),
+            );
+        }
+
+        let config = FirewallConfig {
+            status: State::InProcess,
+            filter_ipv6: true,
+            whitelist_hetzner_services: false,
+            rules: Rules {
+                ingress,
+                egress: vec![Rule::accept("Allow all")],
+            },
+        };
+
+        let text = config.to_rules_text();
+        assert_eq!(FirewallConfig::parse_rules_text(&text).unwrap(), config);
+
+        let template = config.to_template_config("Reviewed template");
+        let text = template.to_rules_text();
+        let parsed = FirewallTemplateConfig::parse_rules_text(&text).unwrap();
+        assert_eq!(parsed.name, "Reviewed template");
+        assert!(!parsed.is_default);
+        assert_eq!(parsed.rules, template.rules);
+    }
+
+    #[cfg(feature = "relaxed-firewall")]
+    #[test]
+    fn rules_text_ipv6_networks() {
+        let rule = Rule::accept("Office").matching(
+            Ipv6Filter::tcp(None)
+                .from_ip("2001:db8::/32".parse::<ipnet::Ipv6Net>().unwrap())
+                .to_ip(std::net::Ipv6Addr::LOCALHOST),
+        );
+
+        assert_eq!(
+            rule.filter.to_string(),
+            "ipv6 tcp src_ip=2001:db8::/32 dst_ip=::1/128"
+        );
+
+        let config = FirewallConfig::parse_rules_text(
+            "status=active\n\
+            filter_ipv6=true\n\
+            whitelist_hetzner_services=false\n\
+            ingress accept \"Office\" ipv6 tcp src_ip=2001:db8::/32 dst_ip=::1",
+        )
+        .unwrap();
+        assert_eq!(config.rules.ingress, [rule]);
+    }
+
+    #[test]
+    fn rules_text_errors() {
+        let error = |text: &str| FirewallConfig::parse_rules_text(text).unwrap_err();
+        let settings = "status=active\nfilter_ipv6=false\nwhitelist_hetzner_services=true\n";
+
+        assert_eq!(
+            error("status=active\nfilter_ipv6=false"),
+            ParseRulesError {
+                line: 3,
+                message: "missing setting \"whitelist_hetzner_services\"".to_string()
+            }
+        );
+
+        assert_eq!(error(&format!("{settings}colour=blue")).line, 4);
+        assert_eq!(error(&format!("{settings}status=disabled")).line, 4);
+
+        for rule in [
+            "ingress reject \"x\" any",
+            "ingress accept x any",
+            "ingress accept \"x any",
+            "ingress accept \"x\" ipv5",
+            "ingress accept \"x\" ipv4 http",
+            "ingress accept \"x\" ipv4 icmp dst_port=22",
+            "ingress accept \"x\" ipv6 src_ip=10.0.0.0/8",
+            "ingress accept \"x\" any tcp",
+            "ingress accept \"x\" ipv4 dst_port=22 dst_port=23",
+            "ingress accept \"x\" ipv4 dst_port=http",
+            "ingress accept \"x\" ipv4 src_ip=10.0.0.0/33",
+            "ingress accept \"x\" any src_ip=10.0.0.0/8",
+            "egress accept \"x\" ipv4 src_mac=00:00:00:00:00:00",
+        ] {
+            assert_eq!(error(&format!("{settings}\n{rule}")).line, 5, "{rule}");
+        }
+    }
+}
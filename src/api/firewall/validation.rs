@@ -0,0 +1,214 @@
+use crate::error::Error;
+
+use super::{
+    Direction, Firewall, FirewallConfig, FirewallTemplateConfig, InvalidFilter, Rule, Rules,
+    SwitchPort,
+};
+
+/// Constraint imposed by Hetzner which a firewall configuration violates.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ValidationError {
+    /// More rules were defined for a direction than Hetzner allows.
+    #[error("{count} {direction} rules defined, but at most {max} are allowed")]
+    TooManyRules {
+        /// Direction with too many rules.
+        direction: Direction,
+        /// Number of rules defined.
+        count: usize,
+        /// Maximum number of rules allowed.
+        max: usize,
+    },
+
+    /// A single rule is invalid.
+    #[error("{direction} rule #{index} \"{name}\" is invalid: {error}")]
+    InvalidRule {
+        /// Direction of the invalid rule.
+        direction: Direction,
+        /// Position of the rule within its direction.
+        index: usize,
+        /// Name of the rule.
+        name: String,
+        /// Reason the rule is invalid.
+        error: InvalidFilter,
+    },
+
+    /// Firewall applies to a switch port other than [`SwitchPort::Main`],
+    /// which cannot be configured through the API.
+    #[error("firewall applies to the {0} switch port, but only the main port can be configured")]
+    UnsupportedPort(SwitchPort),
+}
+
+impl Rules {
+    /// Maximum number of rules Hetzner allows per direction.
+    pub const MAX_RULES: usize = 10;
+
+    /// Check the rules against the constraints imposed by Hetzner, returning
+    /// every violation found.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let errors: Vec<_> = validate_direction(Direction::Ingress, &self.ingress)
+            .chain(validate_direction(Direction::Egress, &self.egress))
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Fail with the first violation found as an [`Error::InvalidFirewall`],
+    /// so invalid rules are rejected before being sent.
+    pub(crate) fn ensure_valid(&self) -> Result<(), Error> {
+        self.validate()
+            .map_err(|mut errors| Error::InvalidFirewall(errors.remove(0)))
+    }
+}
+
+fn validate_direction(
+    direction: Direction,
+    rules: &[Rule],
+) -> impl Iterator<Item = ValidationError> + '_ {
+    let too_many = (rules.len() > Rules::MAX_RULES).then_some(ValidationError::TooManyRules {
+        direction,
+        count: rules.len(),
+        max: Rules::MAX_RULES,
+    });
+
+    let invalid = rules.iter().enumerate().filter_map(move |(index, rule)| {
+        rule.filter
+            .validate()
+            .err()
+            .map(|error| ValidationError::InvalidRule {
+                direction,
+                index,
+                name: rule.name.clone(),
+                error,
+            })
+    });
+
+    too_many.into_iter().chain(invalid)
+}
+
+impl FirewallConfig {
+    /// Check the configuration against the constraints imposed by Hetzner,
+    /// returning every violation found.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use hrobot::api::firewall::{FirewallConfig, Ipv4Filter, Rule, Rules, State};
+    /// let config = FirewallConfig {
+    ///     status: State::Active,
+    ///     filter_ipv6: false,
+    ///     whitelist_hetzner_services: true,
+    ///     rules: Rules {
+    ///         ingress: vec![Rule::accept("Ping").matching(Ipv4Filter::icmp().to_port(22))],
+    ///         egress: vec![],
+    ///     },
+    /// };
+    ///
+    /// for error in config.validate().unwrap_err() {
+    ///     println!("{error}");
+    /// }
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        self.rules.validate()
+    }
+}
+
+impl Firewall {
+    /// Check whether the firewall can be written back using
+    /// [`AsyncRobot::set_firewall_config`](crate::AsyncRobot::set_firewall_config)
+    /// without changing its meaning, returning every violation found.
+    ///
+    /// Besides the rule constraints checked by [`FirewallConfig::validate`], this
+    /// fails with [`ValidationError::UnsupportedPort`] for firewalls reported for the
+    /// [`SwitchPort::Kvm`] port, since configurations are always applied to the main port.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = self.rules.validate().err().unwrap_or_default();
+
+        if self.port != SwitchPort::Main {
+            errors.insert(0, ValidationError::UnsupportedPort(self.port.clone()));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl FirewallTemplateConfig {
+    /// Check the template against the constraints imposed by Hetzner,
+    /// returning every violation found.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        self.rules.validate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::firewall::{
+        Direction, Firewall, InvalidFilter, Ipv4Filter, Protocol, Rule, Rules, State, SwitchPort,
+        ValidationError,
+    };
+
+    #[test]
+    fn validate_rules() {
+        let valid = Rules {
+            ingress: vec![Rule::accept("SSH").matching(Ipv4Filter::tcp(None).to_port(22))],
+            egress: vec![Rule::accept("Allow all")],
+        };
+
+        assert!(valid.validate().is_ok());
+
+        let invalid = Rules {
+            ingress: vec![Rule::accept("Ping").matching(Ipv4Filter::icmp().to_port(22))],
+            egress: vec![Rule::accept("Allow all"); 11],
+        };
+
+        assert_eq!(
+            invalid.validate(),
+            Err(vec![
+                ValidationError::InvalidRule {
+                    direction: Direction::Ingress,
+                    index: 0,
+                    name: "Ping".to_string(),
+                    error: InvalidFilter::PortsNotSupported(Protocol::Icmp),
+                },
+                ValidationError::TooManyRules {
+                    direction: Direction::Egress,
+                    count: 11,
+                    max: 10,
+                }
+            ])
+        );
+
+        assert_eq!(
+            invalid.validate().unwrap_err()[0].to_string(),
+            "ingress rule #0 \"Ping\" is invalid: protocol icmp does not support port filtering"
+        );
+    }
+
+    #[test]
+    fn validate_firewall_port() {
+        let mut firewall = Firewall {
+            status: State::Active,
+            filter_ipv6: false,
+            whitelist_hetzner_services: true,
+            port: SwitchPort::Main,
+            rules: Rules {
+                ingress: vec![],
+                egress: vec![Rule::accept("Allow all")],
+            },
+        };
+
+        assert!(firewall.validate().is_ok());
+
+        firewall.port = SwitchPort::Kvm;
+        assert_eq!(
+            firewall.validate(),
+            Err(vec![ValidationError::UnsupportedPort(SwitchPort::Kvm)])
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Account inventory structs and implementation.
+//!
+//! An [`Inventory`] is a point-in-time snapshot of all servers, addresses,
+//! storageboxes and vSwitches in an account, suitable for dumping as JSON
+//! for configuration management databases or compliance checks.
+
+use futures_util::future;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::{
+    error::{ApiError, Error},
+    AsyncRobot,
+};
+
+use super::{
+    bulk::{BulkExecutor, BulkOptions},
+    firewall::FirewallConfig,
+    ip::ServerAddresses,
+    server::Server,
+    storagebox::StorageBox,
+    vswitch::VSwitch,
+};
+
+impl AsyncRobot {
+    /// Gather a snapshot of all servers, IP addresses, subnets, storageboxes and vSwitches
+    /// in the account into a single [`Inventory`].
+    ///
+    /// Requests are issued according to the given [`BulkOptions`]. If the API rate limit
+    /// is exceeded, the affected request is retried after waiting long enough
+    /// for the rate limit to allow another request.
+    ///
+    /// Note that this issues a large number of requests, proportional to the
+    /// number of resources in the account.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::bulk::BulkOptions;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let inventory = robot.full_inventory(BulkOptions::default()).await.unwrap();
+    /// println!("{}", serde_json::to_string_pretty(&inventory).unwrap());
+    /// # }
+    /// ```
+    pub async fn full_inventory(&self, options: BulkOptions) -> Result<Inventory, Error> {
+        let created_at = OffsetDateTime::now_utc();
+        let executor = BulkExecutor::for_robot(self, options);
+
+        let (servers, mut ips, mut subnets, storageboxes, vswitches) = future::try_join5(
+            executor.run(|| self.list_servers()),
+            executor.run(|| self.list_ips()),
+            executor.run(|| self.list_subnets()),
+            executor.run(|| self.list_storageboxes()),
+            executor.run(|| self.list_vswitches()),
+        )
+        .await?;
+
+        let firewalls = executor
+            .bounded_try_join_all(
+                servers.iter().map(|server| server.id),
+                |server| async move {
+                    match self.get_firewall(server).await {
+                        Ok(firewall) => Ok(Some(firewall.config())),
+                        Err(Error::Api(ApiError::FirewallNotAvailable { .. }, _)) => Ok(None),
+                        Err(err) => Err(err),
+                    }
+                },
+            )
+            .await?;
+
+        let servers = servers
+            .into_iter()
+            .zip(firewalls)
+            .map(|(server, firewall)| ServerInventory {
+                addresses: ServerAddresses {
+                    ips: ips.remove(&server.id).unwrap_or_default(),
+                    subnets: subnets.remove(&server.id).unwrap_or_default(),
+                },
+                firewall,
+                server,
+            })
+            .collect();
+
+        let storageboxes = executor
+            .bounded_try_join_all(
+                storageboxes.iter().map(|storagebox| storagebox.id),
+                |storagebox| self.get_storagebox(storagebox),
+            )
+            .await?;
+
+        let vswitches = executor
+            .bounded_try_join_all(vswitches.iter().map(|vswitch| vswitch.id), |vswitch| {
+                self.get_vswitch(vswitch)
+            })
+            .await?;
+
+        Ok(Inventory {
+            created_at,
+            servers,
+            storageboxes,
+            vswitches,
+        })
+    }
+}
+
+/// Snapshot of the resources in an account, as returned by [`AsyncRobot::full_inventory`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Inventory {
+    /// Time at which the inventory was gathered.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+
+    /// All servers in the account, along with their addresses and firewall.
+    pub servers: Vec<ServerInventory>,
+
+    /// All storageboxes in the account.
+    pub storageboxes: Vec<StorageBox>,
+
+    /// All vSwitches in the account, including connected servers and subnets.
+    pub vswitches: Vec<VSwitch>,
+}
+
+/// A single server within an [`Inventory`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ServerInventory {
+    /// Server description.
+    pub server: Server,
+
+    /// IP addresses and subnets assigned to the server.
+    pub addresses: ServerAddresses,
+
+    /// Firewall configuration of the server, if the server supports firewalls.
+    pub firewall: Option<FirewallConfig>,
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::StatusCode;
+    use serde_json::json;
+
+    use crate::{
+        api::{bulk::BulkOptions, server::ServerId, ById},
+        mock::{fixtures, MockClient, Reply},
+    };
+
+    #[tokio::test(start_paused = true)]
+    async fn gather_inventory() {
+        // An account with a single server and nothing else, exceeding the
+        // rate limit on the first request for the server list.
+        let robot = MockClient::new()
+            .on_sequence(
+                "GET",
+                "/server",
+                [Reply::rate_limited(), json!([fixtures::server(123)]).into()],
+            )
+            .on(
+                "GET",
+                "/firewall/123",
+                Reply::error(
+                    StatusCode::NOT_FOUND,
+                    "FIREWALL_NOT_AVAILABLE",
+                    "Firewall not available",
+                ),
+            )
+            .on("GET", "/vswitch", "[]")
+            .robot();
+
+        let inventory = robot.full_inventory(BulkOptions::default()).await.unwrap();
+
+        assert_eq!(inventory.servers.len(), 1);
+        assert_eq!(inventory.servers[0].server.id, ServerId(123));
+        assert!(inventory.servers[0].addresses.is_empty());
+        assert!(inventory.servers[0].firewall.is_none());
+        assert!(inventory.storageboxes.is_empty());
+        assert!(inventory.vswitches.is_empty());
+
+        assert!(inventory.servers.by_id(ServerId(123)).is_some());
+        assert!(inventory.servers.by_id(ServerId(124)).is_none());
+
+        let json = serde_json::to_value(&inventory).unwrap();
+        assert_eq!(json["servers"][0]["server"]["server_number"], 123);
+    }
+}
@@ -1,16 +1,28 @@
 //! IP structs and implementation.
 
-use std::{collections::HashMap, net::Ipv4Addr};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    net::IpAddr,
+    str::FromStr,
+};
 
 use bytesize::ByteSize;
+use futures_util::future;
+use ipnet::IpNet;
 use serde::{Deserialize, Serialize};
 use time::Date;
 
 use crate::{error::Error, AsyncRobot};
 
+#[cfg(feature = "ordering")]
+use super::ordering::{AddonKind, AddonOrder, AddonTransaction, ImSeriousAboutSpendingMoney};
+
 use super::{
+    bulk::{BulkExecutor, BulkOptions},
     server::ServerId,
-    wrapper::{List, Single},
+    subnet::Subnet,
+    wrapper::{List, OrEmpty, Single},
     UnauthenticatedRequest,
 };
 
@@ -18,12 +30,12 @@ fn list_ips() -> UnauthenticatedRequest<List<Ip>> {
     UnauthenticatedRequest::from("https://robot-ws.your-server.de/ip")
 }
 
-fn get_ip(ip: Ipv4Addr) -> UnauthenticatedRequest<Single<Ip>> {
+fn get_ip(ip: IpAddr) -> UnauthenticatedRequest<Single<Ip>> {
     UnauthenticatedRequest::from(&format!("https://robot-ws.your-server.de/ip/{ip}"))
 }
 
 fn enable_traffic_warnings(
-    ip: Ipv4Addr,
+    ip: IpAddr,
     traffic_warnings: Option<TrafficWarnings>,
 ) -> Result<UnauthenticatedRequest<Single<Ip>>, serde_html_form::ser::Error> {
     let request = UnauthenticatedRequest::from(&format!("https://robot-ws.your-server.de/ip/{ip}"))
@@ -36,33 +48,33 @@ fn enable_traffic_warnings(
     }
 }
 
-fn disable_traffic_warnings(ip: Ipv4Addr) -> UnauthenticatedRequest<Single<Ip>> {
+fn disable_traffic_warnings(ip: IpAddr) -> UnauthenticatedRequest<Single<Ip>> {
     UnauthenticatedRequest::from(&format!("https://robot-ws.your-server.de/ip/{ip}"))
         .with_method("POST")
         .with_serialized_body("traffic_warnings=false".to_string())
 }
 
-fn get_separate_mac(ip: Ipv4Addr) -> UnauthenticatedRequest<Single<InternalMac>> {
+fn get_separate_mac(ip: IpAddr) -> UnauthenticatedRequest<Single<InternalMac>> {
     UnauthenticatedRequest::from(&format!("https://robot-ws.your-server.de/ip/{ip}/mac"))
 }
 
-fn generate_separate_mac(ip: Ipv4Addr) -> UnauthenticatedRequest<Single<InternalMac>> {
+fn generate_separate_mac(ip: IpAddr) -> UnauthenticatedRequest<Single<InternalMac>> {
     UnauthenticatedRequest::from(&format!("https://robot-ws.your-server.de/ip/{ip}/mac"))
         .with_method("PUT")
 }
 
-fn delete_separate_mac(ip: Ipv4Addr) -> UnauthenticatedRequest<Single<ExecutedMacRemoval>> {
+fn delete_separate_mac(ip: IpAddr) -> UnauthenticatedRequest<Single<ExecutedMacRemoval>> {
     UnauthenticatedRequest::from(&format!("https://robot-ws.your-server.de/ip/{ip}/mac"))
         .with_method("DELETE")
 }
 
-fn get_ip_cancellation(ip: Ipv4Addr) -> UnauthenticatedRequest<Single<Cancellation>> {
+fn get_ip_cancellation(ip: IpAddr) -> UnauthenticatedRequest<Single<Cancellation>> {
     UnauthenticatedRequest::from(&format!(
         "https://robot-ws.your-server.de/ip/{ip}/cancellation"
     ))
 }
 
-fn cancel_ip(ip: Ipv4Addr, date: Date) -> UnauthenticatedRequest<Single<Cancelled>> {
+fn cancel_ip(ip: IpAddr, date: Date) -> UnauthenticatedRequest<Single<Cancelled>> {
     UnauthenticatedRequest::from(&format!(
         "https://robot-ws.your-server.de/ip/{ip}/cancellation"
     ))
@@ -70,7 +82,7 @@ fn cancel_ip(ip: Ipv4Addr, date: Date) -> UnauthenticatedRequest<Single<Cancelle
     .with_serialized_body(format!("cancellation_date={date}"))
 }
 
-fn withdraw_ip_cancellation(ip: Ipv4Addr) -> UnauthenticatedRequest<Single<Cancellable>> {
+fn withdraw_ip_cancellation(ip: IpAddr) -> UnauthenticatedRequest<Single<Cancellable>> {
     UnauthenticatedRequest::from(&format!(
         "https://robot-ws.your-server.de/ip/{ip}/cancellation"
     ))
@@ -92,7 +104,7 @@ impl AsyncRobot {
     pub async fn list_ips(&self) -> Result<HashMap<ServerId, Vec<Ip>>, Error> {
         let mut ips: HashMap<ServerId, Vec<Ip>> = HashMap::new();
 
-        for ip in self.go(list_ips()).await?.0 {
+        for ip in self.go(list_ips()).await.or_empty()?.0 {
             ips.entry(ip.server_number).or_default().push(ip);
         }
 
@@ -109,7 +121,7 @@ impl AsyncRobot {
     /// robot.get_ip("123.123.123.123".parse().unwrap()).await.unwrap();
     /// # }
     /// ```
-    pub async fn get_ip(&self, ip: Ipv4Addr) -> Result<Ip, Error> {
+    pub async fn get_ip(&self, ip: IpAddr) -> Result<Ip, Error> {
         Ok(self.go(get_ip(ip)).await?.0)
     }
 
@@ -135,7 +147,7 @@ impl AsyncRobot {
     /// ```
     pub async fn enable_ip_traffic_warnings(
         &self,
-        ip: Ipv4Addr,
+        ip: IpAddr,
         traffic_warnings: Option<TrafficWarnings>,
     ) -> Result<Ip, Error> {
         Ok(self
@@ -154,7 +166,7 @@ impl AsyncRobot {
     /// robot.disable_ip_traffic_warnings("123.123.123.123".parse().unwrap()).await.unwrap();
     /// # }
     /// ```
-    pub async fn disable_ip_traffic_warnings(&self, ip: Ipv4Addr) -> Result<Ip, Error> {
+    pub async fn disable_ip_traffic_warnings(&self, ip: IpAddr) -> Result<Ip, Error> {
         Ok(self.go(disable_traffic_warnings(ip)).await?.0)
     }
 
@@ -170,7 +182,7 @@ impl AsyncRobot {
     /// robot.get_ip_separate_mac("123.123.123.123".parse().unwrap()).await.unwrap();
     /// # }
     /// ```
-    pub async fn get_ip_separate_mac(&self, ip: Ipv4Addr) -> Result<String, Error> {
+    pub async fn get_ip_separate_mac(&self, ip: IpAddr) -> Result<MacAddress, Error> {
         Ok(self.go(get_separate_mac(ip)).await?.0.mac)
     }
 
@@ -186,7 +198,7 @@ impl AsyncRobot {
     /// robot.generate_ip_separate_mac("123.123.123.123".parse().unwrap()).await.unwrap();
     /// # }
     /// ```
-    pub async fn generate_ip_separate_mac(&self, ip: Ipv4Addr) -> Result<String, Error> {
+    pub async fn generate_ip_separate_mac(&self, ip: IpAddr) -> Result<MacAddress, Error> {
         Ok(self.go(generate_separate_mac(ip)).await?.0.mac)
     }
 
@@ -202,7 +214,7 @@ impl AsyncRobot {
     /// robot.remove_ip_separate_mac("123.123.123.123".parse().unwrap()).await.unwrap();
     /// # }
     /// ```
-    pub async fn remove_ip_separate_mac(&self, ip: Ipv4Addr) -> Result<(), Error> {
+    pub async fn remove_ip_separate_mac(&self, ip: IpAddr) -> Result<(), Error> {
         self.go(delete_separate_mac(ip)).await.map(|_| ())
     }
 
@@ -216,7 +228,7 @@ impl AsyncRobot {
     /// robot.get_ip_cancellation("123.123.123.123".parse().unwrap()).await.unwrap();
     /// # }
     /// ```
-    pub async fn get_ip_cancellation(&self, ip: Ipv4Addr) -> Result<Cancellation, Error> {
+    pub async fn get_ip_cancellation(&self, ip: IpAddr) -> Result<Cancellation, Error> {
         Ok(self.go(get_ip_cancellation(ip)).await?.0)
     }
 
@@ -235,7 +247,7 @@ impl AsyncRobot {
     /// ).await.unwrap();
     /// # }
     /// ```
-    pub async fn cancel_ip(&self, ip: Ipv4Addr, date: Date) -> Result<Cancelled, Error> {
+    pub async fn cancel_ip(&self, ip: IpAddr, date: Date) -> Result<Cancelled, Error> {
         Ok(self.go(cancel_ip(ip, date)).await?.0)
     }
 
@@ -249,27 +261,187 @@ impl AsyncRobot {
     /// robot.withdraw_ip_cancellation("123.123.123.123".parse().unwrap()).await.unwrap();
     /// # }
     /// ```
-    pub async fn withdraw_ip_cancellation(&self, ip: Ipv4Addr) -> Result<Cancellable, Error> {
+    pub async fn withdraw_ip_cancellation(&self, ip: IpAddr) -> Result<Cancellable, Error> {
         Ok(self.go(withdraw_ip_cancellation(ip)).await?.0)
     }
+
+    /// Get the cancellation status of every additional IPv4 address in the account.
+    ///
+    /// Primary IPv4 addresses are excluded, since they are cancelled along with the
+    /// server, as are IPv6 addresses. Requests are issued according to the given [`BulkOptions`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::{bulk::BulkOptions, ip::Cancellation};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// for (ip, cancellation) in robot.list_ip_cancellations(BulkOptions::default()).await.unwrap() {
+    ///     if let Cancellation::Cancelled(cancelled) = cancellation {
+    ///         println!("{ip} terminates on {}", cancelled.date);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub async fn list_ip_cancellations(
+        &self,
+        options: BulkOptions,
+    ) -> Result<HashMap<IpAddr, Cancellation>, Error> {
+        let executor = BulkExecutor::for_robot(self, options);
+
+        let (ips, servers) = future::try_join(
+            executor.run(|| self.list_ips()),
+            executor.run(|| self.list_servers()),
+        )
+        .await?;
+
+        let primary: HashSet<IpAddr> = servers
+            .into_iter()
+            .filter_map(|server| server.ipv4.map(IpAddr::V4))
+            .collect();
+
+        let additional: Vec<IpAddr> = ips
+            .into_values()
+            .flatten()
+            .map(|ip| ip.ip)
+            .filter(|ip| ip.is_ipv4() && !primary.contains(ip))
+            .collect();
+
+        let cancellations = executor
+            .bounded_try_join_all(additional.iter().copied(), |ip| {
+                self.get_ip_cancellation(ip)
+            })
+            .await?;
+
+        Ok(additional.into_iter().zip(cancellations).collect())
+    }
+
+    /// Order an additional IPv4 address for the server.
+    ///
+    /// Shorthand for [`AsyncRobot::place_addon_order`] with [`AddonKind::AdditionalIpv4`].
+    /// The `reason` is required by RIPE, and should describe what the address is used for.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::{ordering::ImSeriousAboutSpendingMoney, server::ServerId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let transaction = robot.order_additional_ipv4(
+    ///     ServerId(1234567),
+    ///     "Virtual machine",
+    ///     // Don't forget to change this line, if you ACTUALLY want to make the purchase!
+    ///     ImSeriousAboutSpendingMoney::NoThisIsJustATest,
+    /// ).await.unwrap();
+    /// println!("{}", transaction.id);
+    /// # }
+    /// ```
+    #[cfg(feature = "ordering")]
+    pub async fn order_additional_ipv4(
+        &self,
+        server: impl Into<ServerId>,
+        reason: &str,
+        i_want_to_spend_money_to_purchase_an_addon: ImSeriousAboutSpendingMoney,
+    ) -> Result<AddonTransaction, Error> {
+        self.place_addon_order(AddonOrder {
+            id: AddonKind::AdditionalIpv4.into(),
+            server: server.into(),
+            reason: Some(reason.to_string()),
+            gateway: None,
+            i_want_to_spend_money_to_purchase_an_addon,
+        })
+        .await
+    }
+
+    /// Get all single IP addresses and subnets, both IPv4 and IPv6, assigned to a server.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::server::ServerId;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let addresses = robot.get_server_addresses(ServerId(1234567)).await.unwrap();
+    ///
+    /// for network in addresses.networks() {
+    ///     println!("{network}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn get_server_addresses(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<ServerAddresses, Error> {
+        let server_number = server_number.into();
+        let (mut ips, mut subnets) = future::try_join(self.list_ips(), self.list_subnets()).await?;
+
+        Ok(ServerAddresses {
+            ips: ips.remove(&server_number).unwrap_or_default(),
+            subnets: subnets.remove(&server_number).unwrap_or_default(),
+        })
+    }
+}
+
+/// Complete address inventory of a single server, as returned by
+/// [`AsyncRobot::get_server_addresses`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ServerAddresses {
+    /// Single IP addresses assigned to the server.
+    pub ips: Vec<Ip>,
+
+    /// Subnets assigned to the server, including its primary IPv6 subnet.
+    pub subnets: Vec<Subnet>,
+}
+
+impl ServerAddresses {
+    /// Iterate over all addresses as networks, with single IP addresses
+    /// represented as `/32` or `/128` networks.
+    pub fn networks(&self) -> impl Iterator<Item = IpNet> + '_ {
+        self.ips
+            .iter()
+            .map(|ip| IpNet::from(ip.ip))
+            .chain(self.subnets.iter().map(|subnet| subnet.ip))
+    }
+
+    /// Iterate over all IPv4 single addresses and subnets.
+    pub fn ipv4(&self) -> impl Iterator<Item = IpNet> + '_ {
+        self.networks()
+            .filter(|network| matches!(network, IpNet::V4(_)))
+    }
+
+    /// Iterate over all IPv6 single addresses and subnets.
+    pub fn ipv6(&self) -> impl Iterator<Item = IpNet> + '_ {
+        self.networks()
+            .filter(|network| matches!(network, IpNet::V6(_)))
+    }
+
+    /// True if the server has neither single IP addresses nor subnets.
+    pub fn is_empty(&self) -> bool {
+        self.ips.is_empty() && self.subnets.is_empty()
+    }
 }
 
 /// Traffic warning configuration.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(try_from = "InternalTrafficWarnings")]
 pub struct TrafficWarnings {
     /// Produce a warning if the hourly traffic exceeds this limit.
     #[serde(rename = "traffic_hourly")]
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
     #[serde(with = "crate::conversion::mib")]
     pub hourly: ByteSize,
 
     /// Produce a warning if the daily traffic exceeds this limit.
     #[serde(rename = "traffic_daily")]
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
     #[serde(with = "crate::conversion::mib")]
     pub daily: ByteSize,
 
     /// Produce a warning if the monthly traffic exceeds this limit.
     #[serde(rename = "traffic_monthly")]
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
     #[serde(with = "crate::conversion::gib")]
     pub monthly: ByteSize,
 }
@@ -289,12 +461,17 @@ impl Default for TrafficWarnings {
 // This structure is used to deserialize and convert from for traffic warnings,
 // yielding None if the traffic warnings are disabled.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub(crate) struct InternalTrafficWarnings {
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     traffic_warnings: bool,
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
     #[serde(with = "crate::conversion::mib")]
     traffic_hourly: ByteSize,
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
     #[serde(with = "crate::conversion::mib")]
     traffic_daily: ByteSize,
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
     #[serde(with = "crate::conversion::gib")]
     traffic_monthly: ByteSize,
 }
@@ -327,28 +504,34 @@ impl From<TrafficWarnings> for InternalTrafficWarnings {
 }
 
 /// Describes a network.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Network {
     /// Gateway for the IP address.
-    pub gateway: Ipv4Addr,
+    pub gateway: IpAddr,
 
     /// Netmask for the IP address.
     pub mask: u8,
 
     /// Broadcast address for the IP address.
-    pub broadcast: Ipv4Addr,
+    ///
+    /// IPv6 networks have no broadcast address.
+    #[serde(default)]
+    pub broadcast: Option<IpAddr>,
 }
 
-/// Describes a single server-attached IPv4 Address.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Describes a single server-attached IPv4 or IPv6 Address.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Ip {
     /// Address
-    pub ip: Ipv4Addr,
+    pub ip: IpAddr,
 
     /// Server the ip belongs to
     pub server_number: ServerId,
 
     /// Status of locking.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub locked: bool,
 
     /// Network subnet this IP address exists in.
@@ -359,7 +542,7 @@ pub struct Ip {
     pub network: Option<Network>,
 
     /// Separate MAC address, if any.
-    pub separate_mac: Option<String>,
+    pub separate_mac: Option<MacAddress>,
 
     /// Traffic warnings for this IP address.
     #[serde(flatten)]
@@ -368,7 +551,124 @@ pub struct Ip {
 
 #[derive(Serialize, Deserialize)]
 pub(crate) struct InternalMac {
-    pub mac: String,
+    pub mac: MacAddress,
+}
+
+/// MAC address, such as a separate MAC address assigned to an IP address or subnet.
+///
+/// Parsed from six hexadecimal octets separated by either colons or hyphens,
+/// in either case, and displayed in the lowercase colon-separated form used
+/// by the API, e.g. `00:50:56:00:ab:cd`.
+///
+/// # Example
+/// ```rust
+/// # use hrobot::api::ip::MacAddress;
+/// let mac: MacAddress = "00-50-56-00-AB-CD".parse().unwrap();
+///
+/// assert_eq!(mac.to_string(), "00:50:56:00:ab:cd");
+/// assert_eq!(mac, MacAddress([0x00, 0x50, 0x56, 0x00, 0xab, 0xcd]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MacAddress(pub [u8; 6]);
+
+impl MacAddress {
+    /// Octets of the address.
+    pub fn octets(&self) -> [u8; 6] {
+        self.0
+    }
+}
+
+/// MAC address is not six hexadecimal octets separated by colons or hyphens.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid mac address {0:?}")]
+pub struct InvalidMacAddress(pub String);
+
+impl FromStr for MacAddress {
+    type Err = InvalidMacAddress;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidMacAddress(s.to_string());
+
+        let separator = if s.contains('-') { '-' } else { ':' };
+
+        let mut octets = [0u8; 6];
+        let mut parts = s.split(separator);
+
+        for octet in &mut octets {
+            let part = parts.next().ok_or_else(invalid)?;
+            if part.len() != 2 || !part.bytes().all(|c| c.is_ascii_hexdigit()) {
+                return Err(invalid());
+            }
+
+            *octet = u8::from_str_radix(part, 16).map_err(|_| invalid())?;
+        }
+
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+
+        Ok(MacAddress(octets))
+    }
+}
+
+impl TryFrom<&str> for MacAddress {
+    type Error = InvalidMacAddress;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<[u8; 6]> for MacAddress {
+    fn from(value: [u8; 6]) -> Self {
+        MacAddress(value)
+    }
+}
+
+impl From<MacAddress> for [u8; 6] {
+    fn from(value: MacAddress) -> Self {
+        value.0
+    }
+}
+
+impl Display for MacAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(f, "{a:02x}:{b:02x}:{c:02x}:{d:02x}:{e:02x}:{g:02x}")
+    }
+}
+
+impl Serialize for MacAddress {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MacAddress {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mac = String::deserialize(deserializer)?;
+        mac.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for MacAddress {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "MacAddress".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "pattern": "^[0-9a-fA-F]{2}([:-][0-9a-fA-F]{2}){5}$"
+        })
+    }
 }
 
 /// Deleting the separate MAC address for an IP returns a `mac` object
@@ -378,21 +678,26 @@ pub(crate) struct InternalMac {
 #[derive(Serialize, Deserialize)]
 pub(crate) struct ExecutedMacRemoval {
     #[serde(rename = "ip")]
-    _ip: Ipv4Addr,
+    _ip: IpAddr,
 }
 
 /// IP address has been cancelled.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Cancelled {
     /// Date at which the IP address is terminated.
-    #[serde(rename = "cancellation_date")]
+    #[serde(rename = "cancellation_date", with = "crate::conversion::date")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub date: Date,
 }
 
 /// IP address has not yet been cancelled.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Cancellable {
     /// Earliest possible date at which the IP address can be cancelled.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    #[serde(with = "crate::conversion::date")]
     pub earliest_cancellation_date: Date,
 }
 
@@ -401,7 +706,8 @@ pub struct Cancellable {
 /// If the address has already been cancelled, this contains a [`Cancelled`]
 /// otherwise a [`Cancellable`] structure which describes the earliest date
 /// at which the IP address can be cancelled.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Cancellation {
     /// IP address has been cancelled.
@@ -409,3 +715,212 @@ pub enum Cancellation {
     /// IP address has not yet been cancelled.
     Cancellable(Cancellable),
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use ipnet::IpNet;
+    use serde_json::json;
+    use time::macros::date;
+
+    use crate::{
+        api::{bulk::BulkOptions, server::ServerId, subnet::Subnet, wrapper::List},
+        mock::{fixtures, MockClient},
+    };
+
+    use super::{Cancellation, Ip, MacAddress, ServerAddresses};
+
+    #[cfg(feature = "ordering")]
+    use crate::api::ordering::{AddonKind, ImSeriousAboutSpendingMoney};
+
+    #[test]
+    fn deserialize_ipv6_address() {
+        let ips: List<Ip> = serde_json::from_str(
+            r#"[
+                {
+                    "ip":{
+                        "ip":"2a01:4f8:111:4221::2",
+                        "server_ip":"123.123.123.123",
+                        "server_number":321,
+                        "locked":false,
+                        "separate_mac":null,
+                        "traffic_warnings":false,
+                        "traffic_hourly":50,
+                        "traffic_daily":50,
+                        "traffic_monthly":1
+                    }
+                },
+                {
+                    "ip":{
+                        "ip":"123.123.123.123",
+                        "server_ip":"123.123.123.123",
+                        "server_number":321,
+                        "locked":false,
+                        "separate_mac":null,
+                        "traffic_warnings":false,
+                        "traffic_hourly":50,
+                        "traffic_daily":50,
+                        "traffic_monthly":1,
+                        "gateway":"123.123.123.97",
+                        "mask":27,
+                        "broadcast":"123.123.123.127"
+                    }
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            ips.0[0].ip,
+            "2a01:4f8:111:4221::2".parse::<IpAddr>().unwrap()
+        );
+        assert!(ips.0[0].network.is_none());
+        assert!(ips.0[1].network.as_ref().unwrap().broadcast.is_some());
+    }
+
+    #[test]
+    fn server_address_networks() {
+        let ips: List<Ip> = serde_json::from_str(
+            r#"[{"ip":{"ip":"123.123.123.123","server_ip":"123.123.123.123","server_number":321,"locked":false,"separate_mac":null,"traffic_warnings":false,"traffic_hourly":50,"traffic_daily":50,"traffic_monthly":1}}]"#,
+        )
+        .unwrap();
+
+        let subnet = Subnet {
+            ip: "2a01:4f8:111:4221::/64".parse().unwrap(),
+            server_number: ServerId(321),
+            gateway: "fe80::1".parse().unwrap(),
+            locked: false,
+            failover: false,
+            traffic_warnings: None,
+        };
+
+        let addresses = ServerAddresses {
+            ips: ips.0,
+            subnets: vec![subnet],
+        };
+
+        assert_eq!(
+            addresses.ipv4().collect::<Vec<_>>(),
+            vec!["123.123.123.123/32".parse::<IpNet>().unwrap()]
+        );
+        assert_eq!(
+            addresses.ipv6().collect::<Vec<_>>(),
+            vec!["2a01:4f8:111:4221::/64".parse::<IpNet>().unwrap()]
+        );
+        assert_eq!(addresses.networks().count(), 2);
+    }
+
+    #[test]
+    fn mac_address() {
+        let mac = MacAddress([0x00, 0x50, 0x56, 0x00, 0xab, 0xcd]);
+
+        for valid in [
+            "00:50:56:00:ab:cd",
+            "00:50:56:00:AB:CD",
+            "00-50-56-00-ab-cd",
+        ] {
+            assert_eq!(valid.parse::<MacAddress>().unwrap(), mac, "{valid}");
+        }
+
+        for invalid in [
+            "",
+            "00:50:56:00:ab",
+            "00:50:56:00:ab:cd:ef",
+            "00:50:56:00:ab:c",
+            "00:50:56:00:ab:+c",
+            "00:50-56:00:ab:cd",
+            "0050.5600.abcd",
+        ] {
+            assert!(invalid.parse::<MacAddress>().is_err(), "{invalid}");
+        }
+
+        assert_eq!(mac.to_string(), "00:50:56:00:ab:cd");
+        assert_eq!(
+            serde_json::from_str::<MacAddress>(r#""00:50:56:00:AB:CD""#).unwrap(),
+            mac
+        );
+        assert_eq!(
+            serde_json::to_string(&mac).unwrap(),
+            r#""00:50:56:00:ab:cd""#
+        );
+    }
+
+    /// Serves two servers with one additional IPv4 address each.
+    fn ip_client() -> MockClient {
+        MockClient::new()
+            .on(
+                "GET",
+                "/server",
+                json!([fixtures::server(1), fixtures::server(2)]),
+            )
+            .on(
+                "GET",
+                "/ip",
+                json!([
+                    fixtures::ip("1.1.1.1", 1),
+                    fixtures::ip("1.1.1.3", 1),
+                    fixtures::ip("1.1.1.2", 2),
+                    fixtures::ip("1.1.1.4", 2)
+                ]),
+            )
+            .on(
+                "GET",
+                "/ip/1.1.1.3/cancellation",
+                fixtures::ip_cancellation("1.1.1.3", 1, None),
+            )
+            .on(
+                "GET",
+                "/ip/1.1.1.4/cancellation",
+                fixtures::ip_cancellation("1.1.1.4", 2, Some("2024-02-29")),
+            )
+            .on(
+                "POST",
+                "/order/server_addon/transaction",
+                fixtures::addon_transaction("additional_ipv4", "in process"),
+            )
+    }
+
+    #[tokio::test]
+    async fn ip_cancellations() {
+        let robot = ip_client().robot();
+        let cancellations = robot
+            .list_ip_cancellations(BulkOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(cancellations.len(), 2);
+        assert!(matches!(
+            &cancellations[&"1.1.1.3".parse::<IpAddr>().unwrap()],
+            Cancellation::Cancellable(cancellable)
+                if cancellable.earliest_cancellation_date == date!(2024-01-31)
+        ));
+        assert!(matches!(
+            &cancellations[&"1.1.1.4".parse::<IpAddr>().unwrap()],
+            Cancellation::Cancelled(cancelled) if cancelled.date == date!(2024-02-29)
+        ));
+    }
+
+    #[cfg(feature = "ordering")]
+    #[tokio::test]
+    async fn order_additional_ipv4() {
+        let client = ip_client();
+        let requests = client.requests();
+        let robot = client.robot();
+
+        let transaction = robot
+            .order_additional_ipv4(
+                ServerId(1),
+                "Virtual machine",
+                ImSeriousAboutSpendingMoney::NoThisIsJustATest,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(transaction.product.id, AddonKind::AdditionalIpv4);
+        assert_eq!(
+            requests.bodies(),
+            ["product_id=additional_ipv4&server_number=1&reason=Virtual+machine&test=true"]
+        );
+    }
+}
@@ -1,23 +1,155 @@
 //! SSH Key structs and implementations.
 
+use std::{fmt::Display, str::FromStr};
+
+use base64::{engine::general_purpose::STANDARD_NO_PAD as BASE64, Engine};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
 use crate::{error::Error, AsyncRobot};
 
 use super::{
-    wrapper::{Empty, List, Single},
+    wrapper::{Empty, List, OrEmpty, Single},
     UnauthenticatedRequest,
 };
 
+/// Fingerprint of an SSH public key.
+///
+/// Parsed from either the MD5 format used by the Hetzner Robot API, e.g.
+/// `d7:34:1c:8c:4e:20:e0:1f:07:66:45:d9:97:22:ec:07`, or the SHA256 format
+/// printed by recent versions of `ssh-keygen -l`, e.g. `SHA256:tSsyRAU9m1HXBxbzkalG6f1q2c6gKpEX5fw/UUjcA7A`.
+///
+/// MD5 fingerprints are normalized to lowercase colon-separated hex,
+/// and an optional `MD5:` prefix is accepted. SHA256 fingerprints are
+/// normalized to unpadded base64.
+///
+/// Note that the Hetzner Robot API only identifies keys by their MD5 fingerprint.
+///
+/// # Example
+/// ```rust
+/// # use hrobot::api::keys::Fingerprint;
+/// let fingerprint: Fingerprint = "MD5:D7:34:1C:8C:4E:20:E0:1F:07:66:45:D9:97:22:EC:07".parse().unwrap();
+///
+/// assert!(fingerprint.is_md5());
+/// assert_eq!(fingerprint.to_string(), "d7:34:1c:8c:4e:20:e0:1f:07:66:45:d9:97:22:ec:07");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(try_from = "String", into = "String")]
+pub enum Fingerprint {
+    /// MD5 digest of the public key.
+    Md5([u8; 16]),
+    /// SHA256 digest of the public key.
+    Sha256([u8; 32]),
+}
+
+impl Fingerprint {
+    /// Returns true if this is an MD5 fingerprint, as used by the Hetzner Robot API.
+    pub fn is_md5(&self) -> bool {
+        matches!(self, Fingerprint::Md5(_))
+    }
+}
+
+/// Describes why a string could not be parsed as a [`Fingerprint`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum InvalidFingerprint {
+    /// MD5 fingerprint did not consist of 16 hex-encoded bytes.
+    #[error("md5 fingerprint {0:?} must consist of 16 hex-encoded bytes")]
+    Md5(String),
+
+    /// SHA256 fingerprint did not consist of 32 base64-encoded bytes.
+    #[error("sha256 fingerprint {0:?} must consist of 32 base64-encoded bytes")]
+    Sha256(String),
+}
+
+impl FromStr for Fingerprint {
+    type Err = InvalidFingerprint;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if let Some(encoded) = s.strip_prefix("SHA256:") {
+            let mut digest = [0u8; 32];
+
+            return match BASE64.decode_slice(encoded.trim_end_matches('='), &mut digest) {
+                Ok(32) => Ok(Fingerprint::Sha256(digest)),
+                _ => Err(InvalidFingerprint::Sha256(s.to_string())),
+            };
+        }
+
+        let hex = s.strip_prefix("MD5:").unwrap_or(s);
+        let hex: String = if hex.contains(':') {
+            // Each byte must be exactly two digits when separated.
+            if !hex.split(':').all(|byte| byte.len() == 2) {
+                return Err(InvalidFingerprint::Md5(s.to_string()));
+            }
+            hex.split(':').collect()
+        } else {
+            hex.to_string()
+        };
+
+        if hex.len() != 32 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(InvalidFingerprint::Md5(s.to_string()));
+        }
+
+        let mut digest = [0u8; 16];
+        for (i, byte) in digest.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+                .map_err(|_| InvalidFingerprint::Md5(s.to_string()))?;
+        }
+
+        Ok(Fingerprint::Md5(digest))
+    }
+}
+
+impl TryFrom<String> for Fingerprint {
+    type Error = InvalidFingerprint;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<&str> for Fingerprint {
+    type Error = InvalidFingerprint;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Fingerprint> for String {
+    fn from(value: Fingerprint) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Fingerprint::Md5(digest) => {
+                for (i, byte) in digest.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(":")?;
+                    }
+                    write!(f, "{byte:02x}")?;
+                }
+                Ok(())
+            }
+            Fingerprint::Sha256(digest) => write!(f, "SHA256:{}", BASE64.encode(digest)),
+        }
+    }
+}
+
 /// SSH Public Key
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SshKey {
     /// Unique name for the key.
     pub name: String,
 
     /// Fingerprint of the public key.
-    pub fingerprint: String,
+    pub fingerprint: Fingerprint,
 
     /// Key algorithm (ED25519, RSA)
     #[serde(rename = "type")]
@@ -31,6 +163,7 @@ pub struct SshKey {
     pub data: String,
 
     /// Timestamp for the creation of the ssh key within the Hetzner Robot system.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     #[serde(with = "time::serde::iso8601")]
     pub created_at: OffsetDateTime,
 }
@@ -40,13 +173,14 @@ pub struct SshKey {
 /// This is just key metadata, it does not contain the key itself.
 ///
 /// To retrieve the key, see [`AsyncRobot::get_ssh_key`].
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SshKeyReference {
     /// Unique name for the key.
     pub name: String,
 
     /// Fingerprint of the public key.
-    pub fingerprint: String,
+    pub fingerprint: Fingerprint,
 
     /// Key algorithm (ED25519, RSA)
     #[serde(rename = "type")]
@@ -58,6 +192,7 @@ pub struct SshKeyReference {
 
     /// Timestamp for the creation of the ssh key within the Hetzner Robot system.
     #[serde(deserialize_with = "crate::conversion::assume_berlin_timezone")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub created_at: OffsetDateTime,
 }
 
@@ -80,33 +215,38 @@ fn create_ssh_key(
         .with_body(CreateSshKey { name, data: key })
 }
 
-fn get_ssh_key(fingerprint: &str) -> UnauthenticatedRequest<Single<SshKey>> {
-    UnauthenticatedRequest::from(&format!(
-        "https://robot-ws.your-server.de/key/{fingerprint}"
-    ))
+/// URL of the key with the given fingerprint.
+///
+/// The API only identifies keys by their MD5 fingerprint, so SHA256
+/// fingerprints, which may also contain `/`, are rejected outright.
+fn ssh_key_url(fingerprint: &Fingerprint) -> Result<String, Error> {
+    if !fingerprint.is_md5() {
+        return Err(Error::UnsupportedFingerprint(*fingerprint));
+    }
+
+    Ok(format!("https://robot-ws.your-server.de/key/{fingerprint}"))
 }
 
-fn remove_ssh_key(fingerprint: &str) -> UnauthenticatedRequest<Empty> {
-    UnauthenticatedRequest::from(&format!(
-        "https://robot-ws.your-server.de/key/{fingerprint}"
-    ))
-    .with_method("DELETE")
+fn get_ssh_key(fingerprint: &Fingerprint) -> Result<UnauthenticatedRequest<Single<SshKey>>, Error> {
+    Ok(UnauthenticatedRequest::from(&ssh_key_url(fingerprint)?))
+}
+
+fn remove_ssh_key(fingerprint: &Fingerprint) -> Result<UnauthenticatedRequest<Empty>, Error> {
+    Ok(UnauthenticatedRequest::from(&ssh_key_url(fingerprint)?).with_method("DELETE"))
 }
 
 fn rename_ssh_key(
-    fingerprint: &str,
+    fingerprint: &Fingerprint,
     new_name: &str,
-) -> Result<UnauthenticatedRequest<Single<SshKey>>, serde_html_form::ser::Error> {
+) -> Result<UnauthenticatedRequest<Single<SshKey>>, Error> {
     #[derive(Serialize)]
     struct RenameSshKey<'a> {
         name: &'a str,
     }
 
-    UnauthenticatedRequest::from(&format!(
-        "https://robot-ws.your-server.de/key/{fingerprint}"
-    ))
-    .with_method("POST")
-    .with_body(RenameSshKey { name: new_name })
+    Ok(UnauthenticatedRequest::from(&ssh_key_url(fingerprint)?)
+        .with_method("POST")
+        .with_body(RenameSshKey { name: new_name })?)
 }
 
 impl AsyncRobot {
@@ -124,24 +264,28 @@ impl AsyncRobot {
     /// # }
     /// ```
     pub async fn list_ssh_keys(&self) -> Result<Vec<SshKey>, Error> {
-        Ok(self.go(list_ssh_keys()).await?.0)
+        Ok(self.go(list_ssh_keys()).await.or_empty()?.0)
     }
 
     /// Retrieve a single SSH [`SshKey`].
     ///
+    /// Fails with [`Error::UnsupportedFingerprint`] if the fingerprint is not in MD5 format.
+    ///
     /// # Example
     /// ```rust,no_run
     /// # #[tokio::main]
     /// # async fn main() {
     /// # let _ = dotenvy::dotenv().ok();
     /// let robot = hrobot::AsyncRobot::default();
-    /// let key = robot.get_ssh_key("d7:34:1c:8c:4e:20:e0:1f:07:66:45:d9:97:22:ec:07").await.unwrap();
+    /// let key = robot.get_ssh_key(
+    ///     &"d7:34:1c:8c:4e:20:e0:1f:07:66:45:d9:97:22:ec:07".parse().unwrap()
+    /// ).await.unwrap();
     ///
     /// println!("{key:#?}");
     /// # }
     /// ```
-    pub async fn get_ssh_key(&self, fingerprint: &str) -> Result<SshKey, Error> {
-        Ok(self.go(get_ssh_key(fingerprint)).await?.0)
+    pub async fn get_ssh_key(&self, fingerprint: &Fingerprint) -> Result<SshKey, Error> {
+        Ok(self.go(get_ssh_key(fingerprint)?).await?.0)
     }
 
     /// Upload a new SSH [`SshKey`].
@@ -166,6 +310,8 @@ impl AsyncRobot {
 
     /// Remove an SSH [`SshKey`].
     ///
+    /// Fails with [`Error::UnsupportedFingerprint`] if the fingerprint is not in MD5 format.
+    ///
     /// # Example
     /// ```rust,no_run
     /// # #[tokio::main]
@@ -173,17 +319,19 @@ impl AsyncRobot {
     /// # let _ = dotenvy::dotenv().ok();
     /// let robot = hrobot::AsyncRobot::default();
     /// robot.remove_ssh_key(
-    ///     "d7:34:1c:8c:4e:20:e0:1f:07:66:45:d9:97:22:ec:07"
+    ///     &"d7:34:1c:8c:4e:20:e0:1f:07:66:45:d9:97:22:ec:07".parse().unwrap()
     /// ).await.unwrap();
     /// # }
     /// ```
-    pub async fn remove_ssh_key(&self, fingerprint: &str) -> Result<(), Error> {
-        self.go(remove_ssh_key(fingerprint)).await?.throw_away();
+    pub async fn remove_ssh_key(&self, fingerprint: &Fingerprint) -> Result<(), Error> {
+        self.go(remove_ssh_key(fingerprint)?).await?.throw_away();
         Ok(())
     }
 
     /// Rename an SSH [`SshKey`].
     ///
+    /// Fails with [`Error::UnsupportedFingerprint`] if the fingerprint is not in MD5 format.
+    ///
     /// # Example
     /// ```rust,no_run
     /// # #[tokio::main]
@@ -191,12 +339,16 @@ impl AsyncRobot {
     /// # let _ = dotenvy::dotenv().ok();
     /// let robot = hrobot::AsyncRobot::default();
     /// robot.rename_ssh_key(
-    ///     "d7:34:1c:8c:4e:20:e0:1f:07:66:45:d9:97:22:ec:07",
+    ///     &"d7:34:1c:8c:4e:20:e0:1f:07:66:45:d9:97:22:ec:07".parse().unwrap(),
     ///     "new-name"
     /// ).await.unwrap();
     /// # }
     /// ```
-    pub async fn rename_ssh_key(&self, fingerprint: &str, new_name: &str) -> Result<SshKey, Error> {
+    pub async fn rename_ssh_key(
+        &self,
+        fingerprint: &Fingerprint,
+        new_name: &str,
+    ) -> Result<SshKey, Error> {
         Ok(self.go(rename_ssh_key(fingerprint, new_name)?).await?.0)
     }
 }
@@ -205,7 +357,10 @@ impl AsyncRobot {
 mod tests {
     use time::macros::datetime;
 
-    use crate::api::keys::SshKeyReference;
+    use crate::{
+        api::keys::{rename_ssh_key, Fingerprint, InvalidFingerprint, SshKeyReference},
+        error::Error,
+    };
 
     #[test]
     fn test_key_deserialization() {
@@ -222,7 +377,9 @@ mod tests {
         assert_eq!(
             SshKeyReference {
                 name: "hrobot-rs-test-key".to_string(),
-                fingerprint: "d7:34:1c:8c:4e:20:e0:1f:07:66:45:d9:97:22:ec:07".to_string(),
+                fingerprint: "d7:34:1c:8c:4e:20:e0:1f:07:66:45:d9:97:22:ec:07"
+                    .parse()
+                    .unwrap(),
                 algorithm: "ED25519".to_string(),
                 bits: 256,
                 created_at: datetime!(2023-06-10 21:34:12 +02:00)
@@ -230,4 +387,76 @@ mod tests {
             serde_json::from_str(key).unwrap()
         )
     }
+
+    #[test]
+    fn fingerprint_normalization() {
+        let md5: Fingerprint = "d7:34:1c:8c:4e:20:e0:1f:07:66:45:d9:97:22:ec:07"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            "MD5:D7:34:1C:8C:4E:20:E0:1F:07:66:45:D9:97:22:EC:07"
+                .parse::<Fingerprint>()
+                .unwrap(),
+            md5
+        );
+        assert_eq!(
+            "d7341c8c4e20e01f076645d99722ec07"
+                .parse::<Fingerprint>()
+                .unwrap(),
+            md5
+        );
+        assert_eq!(
+            md5.to_string(),
+            "d7:34:1c:8c:4e:20:e0:1f:07:66:45:d9:97:22:ec:07"
+        );
+
+        let sha256: Fingerprint = "SHA256:tSsyRAU9m1HXBxbzkalG6f1q2c6gKpEX5fw/UUjcA7A"
+            .parse()
+            .unwrap();
+
+        assert!(!sha256.is_md5());
+        assert_eq!(
+            "SHA256:tSsyRAU9m1HXBxbzkalG6f1q2c6gKpEX5fw/UUjcA7A="
+                .parse::<Fingerprint>()
+                .unwrap(),
+            sha256
+        );
+        assert_eq!(
+            sha256.to_string(),
+            "SHA256:tSsyRAU9m1HXBxbzkalG6f1q2c6gKpEX5fw/UUjcA7A"
+        );
+    }
+
+    #[test]
+    fn invalid_fingerprints() {
+        assert!(matches!(
+            "d7:34:1c:8c:4e:20:e0:1f:07:66:45:d9:97:22:ec".parse::<Fingerprint>(),
+            Err(InvalidFingerprint::Md5(_))
+        ));
+        assert!(matches!(
+            "d7:34:1c:8c:4e:20:e0:1f:07:66:45:d9:97:22:ec:0g".parse::<Fingerprint>(),
+            Err(InvalidFingerprint::Md5(_))
+        ));
+        assert!(matches!(
+            "d73:4:1c:8c:4e:20:e0:1f:07:66:45:d9:97:22:ec:07".parse::<Fingerprint>(),
+            Err(InvalidFingerprint::Md5(_))
+        ));
+        assert!(matches!(
+            "SHA256:tSsyRAU9m1HXBxbzkalG6f1q2c6gKpEX5fw".parse::<Fingerprint>(),
+            Err(InvalidFingerprint::Sha256(_))
+        ));
+    }
+
+    #[test]
+    fn sha256_fingerprints_are_not_addressable() {
+        let sha256: Fingerprint = "SHA256:tSsyRAU9m1HXBxbzkalG6f1q2c6gKpEX5fw/UUjcA7A"
+            .parse()
+            .unwrap();
+
+        assert!(matches!(
+            rename_ssh_key(&sha256, "new-name"),
+            Err(Error::UnsupportedFingerprint(fingerprint)) if fingerprint == sha256
+        ));
+    }
 }
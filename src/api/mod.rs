@@ -5,44 +5,156 @@ use std::{marker::PhantomData, str::FromStr};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use hyper::Uri;
 use serde::Serialize;
+use zeroize::Zeroizing;
+
+use crate::telemetry::Endpoint;
 
 mod wrapper;
 
 pub mod boot;
+pub mod bulk;
+pub mod diagnostics;
+#[cfg(feature = "ip")]
 pub mod failover;
+#[cfg(feature = "firewall")]
 pub mod firewall;
+#[cfg(all(
+    feature = "firewall",
+    feature = "ip",
+    feature = "storagebox",
+    feature = "vswitch"
+))]
+pub mod inventory;
+#[cfg(feature = "ip")]
 pub mod ip;
 pub mod keys;
+#[cfg(feature = "ordering")]
 pub mod ordering;
+#[cfg(all(
+    feature = "firewall",
+    feature = "ip",
+    feature = "storagebox",
+    feature = "vswitch"
+))]
+pub mod orphans;
 pub mod rdns;
+#[cfg(all(feature = "firewall", feature = "storagebox", feature = "vswitch"))]
+pub mod recovery;
 pub mod reset;
 pub mod server;
+#[cfg(feature = "storagebox")]
 pub mod storagebox;
+#[cfg(feature = "ip")]
 pub mod subnet;
 pub mod traffic;
+#[cfg(feature = "vswitch")]
 pub mod vswitch;
+#[cfg(all(
+    feature = "firewall",
+    feature = "ip",
+    feature = "storagebox",
+    feature = "vswitch"
+))]
+pub mod watch;
 pub mod wol;
 
+/// Lookup of resources by their unique ID within a list, such as the one
+/// returned by [`AsyncRobot::list_servers`](crate::AsyncRobot::list_servers).
+///
+/// # Example
+/// ```rust,no_run
+/// # use hrobot::api::{ById, server::ServerId};
+/// # #[tokio::main]
+/// # async fn main() {
+/// let robot = hrobot::AsyncRobot::default();
+/// let servers = robot.list_servers().await.unwrap();
+///
+/// if let Some(server) = servers.by_id(ServerId(1234567)) {
+///     println!("{}", server.name);
+/// }
+/// # }
+/// ```
+pub trait ById {
+    /// Unique ID of the resource.
+    type Id;
+
+    /// Resource type.
+    type Item;
+
+    /// Find the resource with the given ID.
+    fn by_id(&self, id: Self::Id) -> Option<&Self::Item>;
+}
+
+/// Implements [`ById`] for slices of the given items, comparing their `id` field,
+/// or the field given in brackets after the ID type.
+macro_rules! impl_by_id {
+    (@impl $item:ty => $id:ty []) => {
+        impl_by_id!(@impl $item => $id [id]);
+    };
+    (@impl $item:ty => $id:ty [$($field:ident).+]) => {
+        impl ById for [$item] {
+            type Id = $id;
+            type Item = $item;
+
+            fn by_id(&self, id: Self::Id) -> Option<&Self::Item> {
+                self.iter().find(|item| item.$($field).+ == id)
+            }
+        }
+    };
+    ($($item:ty => $id:ty $([$($field:ident).+])?),* $(,)?) => {
+        $(impl_by_id!(@impl $item => $id [$($($field).+)?]);)*
+    };
+}
+
+impl_by_id!(
+    server::Server => server::ServerId,
+);
+
+#[cfg(feature = "storagebox")]
+impl_by_id!(
+    storagebox::StorageBoxReference => storagebox::StorageBoxId,
+    storagebox::StorageBox => storagebox::StorageBoxId,
+);
+
+#[cfg(feature = "vswitch")]
+impl_by_id!(
+    vswitch::VSwitchReference => vswitch::VSwitchId,
+    vswitch::VSwitch => vswitch::VSwitchId,
+);
+
+#[cfg(feature = "firewall")]
+impl_by_id!(
+    firewall::FirewallTemplateReference => firewall::TemplateId,
+    firewall::FirewallTemplate => firewall::TemplateId,
+);
+
+#[cfg(all(
+    feature = "firewall",
+    feature = "ip",
+    feature = "storagebox",
+    feature = "vswitch"
+))]
+impl_by_id!(
+    inventory::ServerInventory => server::ServerId [server.id],
+);
+
 /// Base64-encoded credentials used to authenticate against
 /// the Hetzner Robot API.
 ///
 /// Used by [`AsyncRobot`](crate::AsyncRobot) to authenticate requests, before it
 /// is transformed into a client-dependent request type and sent.
+///
+/// The encoded password is wiped from memory when the credentials are dropped.
 #[derive(Clone)]
 pub struct Credentials {
-    header_value: String,
+    username: String,
+    header_value: Zeroizing<String>,
 }
 
 impl std::fmt::Debug for Credentials {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let encoded = &self.header_value.strip_prefix("Basic ").unwrap();
-        let decoded = BASE64.decode(encoded).unwrap();
-        let stringified = String::from_utf8_lossy(&decoded);
-
-        let username = stringified.split_once(':').unwrap().0;
-
         f.debug_struct("Credentials")
-            .field("username", &username)
+            .field("username", &self.username)
             .field("password", &"<hidden>")
             .finish()
     }
@@ -58,12 +170,36 @@ impl Credentials {
     /// let credentials = Credentials::new("#ws+user", "p4ssw0rd");
     /// # }
     pub fn new(username: &str, password: &str) -> Self {
-        let header = format!("Basic {}", BASE64.encode(format!("{username}:{password}")));
+        let plaintext = Zeroizing::new(format!("{username}:{password}"));
+        let encoded = Zeroizing::new(BASE64.encode(plaintext.as_bytes()));
 
         Credentials {
-            header_value: header,
+            username: username.to_string(),
+            header_value: Zeroizing::new(format!("Basic {}", encoded.as_str())),
         }
     }
+
+    /// Read credentials from the `{prefix}USERNAME` and `{prefix}PASSWORD` environment variables.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use hrobot::api::Credentials;
+    /// # std::env::set_var("STAGING_USERNAME", "#ws+user");
+    /// # std::env::set_var("STAGING_PASSWORD", "p4ssw0rd");
+    /// let credentials = Credentials::from_env_with_prefix("STAGING_").unwrap();
+    /// assert_eq!(credentials.username(), "#ws+user");
+    /// ```
+    pub fn from_env_with_prefix(prefix: &str) -> Result<Self, std::env::VarError> {
+        Ok(Credentials::new(
+            &std::env::var(format!("{prefix}USERNAME"))?,
+            &std::env::var(format!("{prefix}PASSWORD"))?,
+        ))
+    }
+
+    /// Username of the Hetzner Robot webservice user.
+    pub fn username(&self) -> &str {
+        &self.username
+    }
 }
 
 /// Single API Request, and the expected `Response`.
@@ -86,7 +222,10 @@ impl<Response> std::fmt::Debug for UnauthenticatedRequest<Response> {
         f.debug_struct("UnauthenticatedRequest")
             .field("uri", &self.uri)
             .field("method", &self.method)
-            .field("body", &self.body)
+            .field(
+                "body",
+                &self.body.as_deref().map(crate::telemetry::redact_form),
+            )
             .field(
                 "response type",
                 &std::any::type_name::<Response>().to_string(),
@@ -95,6 +234,17 @@ impl<Response> std::fmt::Debug for UnauthenticatedRequest<Response> {
     }
 }
 
+impl<Response> Clone for UnauthenticatedRequest<Response> {
+    fn clone(&self) -> Self {
+        UnauthenticatedRequest {
+            uri: self.uri.clone(),
+            method: self.method,
+            body: self.body.clone(),
+            _response: PhantomData,
+        }
+    }
+}
+
 impl<Response> UnauthenticatedRequest<Response> {
     /// Construct a new [`UnauthenticatedRequest`] GET from a Uri.
     pub(crate) fn new(uri: Uri) -> Self {
@@ -113,6 +263,26 @@ impl<Response> UnauthenticatedRequest<Response> {
         Self::new(Uri::from_str(uri).expect("constructing the uri should never fail."))
     }
 
+    /// Endpoint this request is sent to, with path parameters replaced by placeholders.
+    pub(crate) fn endpoint(&self) -> Endpoint {
+        Endpoint::from(&self.uri)
+    }
+
+    /// HTTP method of the request.
+    pub(crate) fn method(&self) -> &'static str {
+        self.method
+    }
+
+    /// URI of the resource.
+    pub(crate) fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    /// application/x-www-form-urlencoded body of the request, if any.
+    pub(crate) fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
+
     /// Set the HTTP Request Method of the request.
     pub(crate) fn with_method(mut self, method: &'static str) -> Self {
         self.method = method;
@@ -122,6 +292,7 @@ impl<Response> UnauthenticatedRequest<Response> {
     /// Append the provided query parameters to the request.
     ///
     /// Panics if the query parameters are malformed.
+    #[cfg(feature = "ordering")]
     pub(crate) fn with_query_params<T: Serialize>(
         mut self,
         params: T,
@@ -226,12 +397,52 @@ impl<Response> AuthenticatedRequest<Response> {
 
 #[cfg(test)]
 mod tests {
-    use serde::Serialize;
+    use super::{Credentials, UnauthenticatedRequest};
+
+    #[test]
+    fn credentials_debug_hides_password() {
+        let credentials = Credentials::new("#ws+user", "p4ssw0rd");
 
-    use super::UnauthenticatedRequest;
+        assert_eq!(credentials.username(), "#ws+user");
+        assert_eq!(
+            UnauthenticatedRequest::<()>::from("https://robot-ws.your-server.de/server")
+                .authenticate(&credentials)
+                .authorization_header(),
+            "Basic I3dzK3VzZXI6cDRzc3cwcmQ="
+        );
 
+        let debug = format!("{credentials:?}");
+        assert!(debug.contains("#ws+user"));
+        assert!(!debug.contains("p4ssw0rd"));
+    }
+
+    #[cfg(feature = "firewall")]
+    #[test]
+    fn lookup_by_id() {
+        use super::{
+            firewall::{FirewallTemplateReference, TemplateId},
+            ById,
+        };
+
+        let templates: Vec<_> = (1..=3)
+            .map(|id| FirewallTemplateReference {
+                id: TemplateId(id),
+                name: format!("template-{id}"),
+                filter_ipv6: false,
+                whitelist_hetzner_services: true,
+                is_default: false,
+            })
+            .collect();
+
+        assert_eq!(templates.by_id(TemplateId(2)).unwrap().name, "template-2");
+        assert!(templates.by_id(TemplateId(4)).is_none());
+    }
+
+    #[cfg(feature = "ordering")]
     #[test]
     fn extend_query_parameters() {
+        use serde::Serialize;
+
         #[derive(Serialize)]
         struct QueryParams {
             example: &'static str,
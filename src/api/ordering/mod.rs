@@ -1,17 +1,25 @@
 //! Server and addon purchasing structs and implementation.
 
 mod models;
-use std::ops::RangeBounds;
+use std::{collections::HashSet, future::Future, ops::RangeBounds, time::Duration};
 
+use futures_util::{stream, Stream, StreamExt};
 pub use models::*;
+
+/// Moved to [`crate::poll`], re-exported for compatibility.
+pub use crate::poll::PollOptions;
+
+/// Moved to [`crate::api::server`], re-exported for compatibility.
+pub use super::server::{Datacenter, Location};
 use rust_decimal::prelude::Zero;
 use serde::Serialize;
+use time::OffsetDateTime;
 
-use crate::{error::Error, urlencode::UrlEncode, AsyncRobot};
+use crate::{error::Error, poll::PollInterrupted, urlencode::UrlEncode, AsyncRobot};
 
 use super::{
     server::ServerId,
-    wrapper::{List, Single},
+    wrapper::{List, OrEmpty, Single, TolerantList},
     UnauthenticatedRequest,
 };
 
@@ -19,7 +27,7 @@ fn list_products(
     monthly_price: impl RangeBounds<u32>,
     setup_price: impl RangeBounds<u32>,
     location: Option<&Location>,
-) -> Result<UnauthenticatedRequest<List<Product>>, serde_html_form::ser::Error> {
+) -> Result<UnauthenticatedRequest<TolerantList<Product>>, serde_html_form::ser::Error> {
     fn is_max(value: &u32) -> bool {
         *value == u32::MAX
     }
@@ -85,7 +93,7 @@ fn get_product_transaction(
     ))
 }
 
-fn list_market_products() -> UnauthenticatedRequest<List<MarketProduct>> {
+fn list_market_products() -> UnauthenticatedRequest<TolerantList<MarketProduct>> {
     UnauthenticatedRequest::from("https://robot-ws.your-server.de/order/server_market/product/")
 }
 
@@ -150,6 +158,9 @@ fn place_addon_purchase_order(
 impl AsyncRobot {
     /// List all available products.
     ///
+    /// Products which cannot be parsed are skipped and logged as warnings,
+    /// rather than failing the entire listing.
+    ///
     /// # Example
     /// ```rust,no_run
     /// # use hrobot::api::ordering::Location;
@@ -175,7 +186,8 @@ impl AsyncRobot {
     ) -> Result<Vec<Product>, Error> {
         Ok(self
             .go(list_products(monthly_price, setup_price, location)?)
-            .await?
+            .await
+            .or_empty()?
             .0)
     }
 
@@ -199,10 +211,13 @@ impl AsyncRobot {
 
     /// Purchase a standard server product.
     ///
+    /// Fails with [`Error::UnsupportedFingerprint`] without placing the order,
+    /// if any authorized key fingerprint is not in MD5 format.
+    ///
     /// # Example
     /// ```rust,no_run
     /// # use hrobot::api::ordering::{
-    /// #   AddonId, ProductId, AuthorizationMethod, ProductOrder,
+    /// #   AddonKind, ProductId, AuthorizationMethod, ProductOrder,
     /// #   ImSeriousAboutSpendingMoney, Location,
     /// # };
     /// # use tracing::info;
@@ -214,12 +229,12 @@ impl AsyncRobot {
     ///     ProductOrder {
     ///         id: ProductId::from("EX41"),
     ///         auth: AuthorizationMethod::Keys(vec![
-    ///             "15:28:b0:03:95:f0:77:b3:10:56:15:6b:77:22:a5:bb".to_string()
+    ///             "15:28:b0:03:95:f0:77:b3:10:56:15:6b:77:22:a5:bb".parse().unwrap()
     ///         ]),
     ///         distribution: Some("Rescue system".to_string()),
     ///         language: Some("en".to_string()),
     ///         location: Location::from("FSN1"),
-    ///         addons: vec![AddonId::from("primary_ipv4")],
+    ///         addons: vec![AddonKind::PrimaryIpv4.into()],
     ///         comment: None,
     ///         // Don't forget to change this line, if you ACTUALLY want to make the purchase!
     ///         i_want_to_spend_money_to_purchase_a_server: ImSeriousAboutSpendingMoney::NoThisIsJustATest,
@@ -232,9 +247,56 @@ impl AsyncRobot {
         &self,
         order: ProductOrder,
     ) -> Result<ProductTransaction, Error> {
+        if let Some(key) = order.auth.unsupported_fingerprint() {
+            return Err(Error::UnsupportedFingerprint(key));
+        }
+
         Ok(self.go(place_purchase_order(order)).await?.0)
     }
 
+    /// Check that every ssh key fingerprint authorized by `auth` belongs
+    /// to a key in [`AsyncRobot::list_ssh_keys`], before placing an order with it.
+    ///
+    /// Fails with [`Error::UnknownSshKeys`] listing the unknown fingerprints, if any.
+    /// Password authorization is always valid, and does not issue any requests.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::ordering::AuthorizationMethod;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// robot.validate_order_auth(&AuthorizationMethod::Keys(vec![
+    ///     "15:28:b0:03:95:f0:77:b3:10:56:15:6b:77:22:a5:bb".parse().unwrap()
+    /// ])).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn validate_order_auth(&self, auth: &AuthorizationMethod) -> Result<(), Error> {
+        let AuthorizationMethod::Keys(fingerprints) = auth else {
+            return Ok(());
+        };
+
+        let known: HashSet<_> = self
+            .list_ssh_keys()
+            .await?
+            .into_iter()
+            .map(|key| key.fingerprint)
+            .collect();
+
+        let unknown: Vec<_> = fingerprints
+            .iter()
+            .filter(|fingerprint| !known.contains(fingerprint))
+            .copied()
+            .collect();
+
+        if unknown.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::UnknownSshKeys(unknown))
+        }
+    }
+
     /// List product transactions from the last 30 days.
     ///
     /// # Example
@@ -249,7 +311,46 @@ impl AsyncRobot {
     /// # }
     /// ```
     pub async fn list_recent_product_transactions(&self) -> Result<Vec<ProductTransaction>, Error> {
-        Ok(self.go(list_product_tranactions()).await?.0)
+        Ok(self.go(list_product_tranactions()).await.or_empty()?.0)
+    }
+
+    /// Find a recent product or market transaction by the [`CorrelationId`]
+    /// embedded in its comment using [`ProductOrder::with_correlation_id`]
+    /// or [`MarketProductOrder::with_correlation_id`].
+    ///
+    /// Only transactions from the last 30 days are searched.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::ordering::CorrelationId;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let transaction = robot.find_transaction_by_correlation_id(
+    ///     &CorrelationId::from("provisioning-run-1234")
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn find_transaction_by_correlation_id(
+        &self,
+        id: &CorrelationId,
+    ) -> Result<Option<Transaction>, Error> {
+        if let Some(transaction) = self
+            .list_recent_product_transactions()
+            .await?
+            .into_iter()
+            .find(|transaction| transaction.correlation_id().as_ref() == Some(id))
+        {
+            return Ok(Some(Transaction::Product(transaction)));
+        }
+
+        Ok(self
+            .list_recent_market_transactions()
+            .await?
+            .into_iter()
+            .find(|transaction| transaction.correlation_id().as_ref() == Some(id))
+            .map(Transaction::Market))
     }
 
     /// Get specific product transactions by ID.
@@ -275,6 +376,9 @@ impl AsyncRobot {
 
     /// List market (auction) products.
     ///
+    /// Products which cannot be parsed are skipped and logged as warnings,
+    /// rather than failing the entire listing.
+    ///
     /// # Example
     /// ```rust,no_run
     /// # #[tokio::main]
@@ -287,7 +391,7 @@ impl AsyncRobot {
     /// # }
     /// ```
     pub async fn list_market_products(&self) -> Result<Vec<MarketProduct>, Error> {
-        Ok(self.go(list_market_products()).await?.0)
+        Ok(self.go(list_market_products()).await.or_empty()?.0)
     }
 
     /// Get description of a single market (auction) product.
@@ -308,6 +412,128 @@ impl AsyncRobot {
         Ok(self.go(get_market_product(id)).await?.0)
     }
 
+    /// List market (auction) products matching the query.
+    ///
+    /// The market product listing does not support any search parameters,
+    /// so all products are fetched and filtered according to [`MarketQuery::matches`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::ordering::MarketQuery;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let query = MarketQuery::default()
+    ///     .with_min_cpu_benchmark(20000)
+    ///     .with_feature("ECC");
+    ///
+    /// for market_product in robot.search_market_products(&query).await.unwrap() {
+    ///     println!("{}: {}", market_product.id, market_product.name);
+    /// }
+    /// # }
+    /// ```
+    pub async fn search_market_products(
+        &self,
+        query: &MarketQuery,
+    ) -> Result<Vec<MarketProduct>, Error> {
+        let mut products = self.list_market_products().await?;
+        products.retain(|product| query.matches(product));
+        Ok(products)
+    }
+
+    /// Record the current price of every market (auction) product.
+    ///
+    /// All price points share the same [`recorded_at`](MarketPricePoint::recorded_at)
+    /// timestamp, taken once the listing has been retrieved, which makes calling
+    /// this periodically and storing the results a simple way of tracking prices.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// for point in robot.snapshot_market_prices().await.unwrap() {
+    ///     println!("{}", serde_json::to_string(&point).unwrap());
+    /// }
+    /// # }
+    /// ```
+    pub async fn snapshot_market_prices(&self) -> Result<Vec<MarketPricePoint>, Error> {
+        let products = self.list_market_products().await?;
+        let recorded_at = OffsetDateTime::now_utc();
+
+        Ok(products
+            .iter()
+            .map(|product| product.price_point(recorded_at))
+            .collect())
+    }
+
+    /// Poll the market every `interval`, reporting products as they start matching the query.
+    ///
+    /// All matching products are reported on the first poll. After that, a product is
+    /// reported whenever it matches the query but did not on the previous poll, for example
+    /// because it was just listed, or because its price was reduced below
+    /// [`MarketQuery::max_price`].
+    ///
+    /// Failed requests are reported as errors, after which polling continues.
+    /// The stream never ends, so stop polling it once no longer interested.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use futures_util::StreamExt;
+    /// # use hrobot::api::ordering::MarketQuery;
+    /// # use hrobot::rust_decimal::Decimal;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let query = MarketQuery::default().with_max_price(Decimal::from(40));
+    ///
+    /// let products = robot.watch_market(query, Duration::from_secs(300));
+    /// futures_util::pin_mut!(products);
+    ///
+    /// while let Some(product) = products.next().await {
+    ///     let product = product.unwrap();
+    ///     println!("{}: {} for {}", product.id, product.name, product.price.recurring.net);
+    /// }
+    /// # }
+    /// ```
+    pub fn watch_market(
+        &self,
+        query: MarketQuery,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<MarketProduct, Error>> + '_ {
+        stream::unfold(
+            (query, HashSet::<MarketProductId>::new(), true),
+            move |(query, mut matching, first)| async move {
+                if !first {
+                    tokio::time::sleep(interval).await;
+                }
+
+                let products = match self.search_market_products(&query).await {
+                    Ok(products) => {
+                        let previous = std::mem::replace(
+                            &mut matching,
+                            products.iter().map(|product| product.id).collect(),
+                        );
+
+                        products
+                            .into_iter()
+                            .filter(|product| !previous.contains(&product.id))
+                            .map(Ok)
+                            .collect()
+                    }
+                    Err(err) => vec![Err(err)],
+                };
+
+                Some((stream::iter(products), (query, matching, false)))
+            },
+        )
+        .flatten()
+    }
+
     /// List market (auction) transactions from the last 30 days.
     ///
     /// # Example
@@ -322,7 +548,11 @@ impl AsyncRobot {
     /// # }
     /// ```
     pub async fn list_recent_market_transactions(&self) -> Result<Vec<MarketTransaction>, Error> {
-        Ok(self.go(list_market_product_transactions()).await?.0)
+        Ok(self
+            .go(list_market_product_transactions())
+            .await
+            .or_empty()?
+            .0)
     }
 
     /// Get specific market (auction) transaction by ID.
@@ -351,10 +581,13 @@ impl AsyncRobot {
 
     /// Purchase a server product from the market.
     ///
+    /// Fails with [`Error::UnsupportedFingerprint`] without placing the order,
+    /// if any authorized key fingerprint is not in MD5 format.
+    ///
     /// # Example
     /// ```rust,no_run
     /// # use hrobot::api::ordering::{
-    /// #   AddonId, MarketProductId, AuthorizationMethod, MarketProductOrder,
+    /// #   AddonKind, MarketProductId, AuthorizationMethod, MarketProductOrder,
     /// #   ImSeriousAboutSpendingMoney,
     /// # };
     /// # use tracing::info;
@@ -366,11 +599,11 @@ impl AsyncRobot {
     ///     MarketProductOrder {
     ///         id: MarketProductId(12345678),
     ///         auth: AuthorizationMethod::Keys(vec![
-    ///             "15:28:b0:03:95:f0:77:b3:10:56:15:6b:77:22:a5:bb".to_string()
+    ///             "15:28:b0:03:95:f0:77:b3:10:56:15:6b:77:22:a5:bb".parse().unwrap()
     ///         ]),
     ///         distribution: Some("Rescue system".to_string()),
     ///         language: Some("en".to_string()),
-    ///         addons: vec![AddonId::from("primary_ipv4")],
+    ///         addons: vec![AddonKind::PrimaryIpv4.into()],
     ///         comment: None,
     ///         // Don't forget to change this line, if you ACTUALLY want to make the purchase!
     ///         i_want_to_spend_money_to_purchase_a_server: ImSeriousAboutSpendingMoney::NoThisIsJustATest,
@@ -383,6 +616,10 @@ impl AsyncRobot {
         &self,
         order: MarketProductOrder,
     ) -> Result<MarketTransaction, Error> {
+        if let Some(key) = order.auth.unsupported_fingerprint() {
+            return Err(Error::UnsupportedFingerprint(key));
+        }
+
         Ok(self.go(place_market_purchase_order(order)).await?.0)
     }
 
@@ -402,7 +639,11 @@ impl AsyncRobot {
     /// }
     /// # }
     /// ```
-    pub async fn list_available_addons(&self, id: ServerId) -> Result<Vec<AvailableAddon>, Error> {
+    pub async fn list_available_addons(
+        &self,
+        id: impl Into<ServerId>,
+    ) -> Result<Vec<AvailableAddon>, Error> {
+        let id = id.into();
         Ok(self.go(list_available_addons(id)).await?.0)
     }
 
@@ -411,7 +652,7 @@ impl AsyncRobot {
     /// # Example
     /// ```rust,no_run
     /// # use hrobot::api::ordering::{
-    /// #   AddonId, AddonOrder,
+    /// #   AddonKind, AddonOrder,
     /// #   ImSeriousAboutSpendingMoney,
     /// # };
     /// # use hrobot::api::server::ServerId;
@@ -422,7 +663,7 @@ impl AsyncRobot {
     /// let robot = hrobot::AsyncRobot::default();
     /// let transaction = robot.place_addon_order(
     ///     AddonOrder {
-    ///         id: AddonId::from("additional_ipv4"),
+    ///         id: AddonKind::AdditionalIpv4.into(),
     ///         server: ServerId(1234567),
     ///         reason: Some("VPS".to_string()),
     ///         gateway: Some("10.0.0.1".parse().unwrap()),
@@ -451,7 +692,7 @@ impl AsyncRobot {
     /// # }
     /// ```
     pub async fn list_recent_addon_transactions(&self) -> Result<Vec<AddonTransaction>, Error> {
-        Ok(self.go(list_addon_transactions()).await?.0)
+        Ok(self.go(list_addon_transactions()).await.or_empty()?.0)
     }
 
     /// Get specific addon transaction by ID.
@@ -474,24 +715,290 @@ impl AsyncRobot {
     ) -> Result<AddonTransaction, Error> {
         Ok(self.go(get_addon_transaction(transaction)).await?.0)
     }
+
+    /// Wait for a product transaction to either become ready or get cancelled.
+    ///
+    /// The transaction is polled according to the given [`PollOptions`],
+    /// and returned once it is no longer [`TransactionStatus::InProcess`].
+    /// Note that orders which are only a test (see [`ImSeriousAboutSpendingMoney`])
+    /// are always cancelled.
+    ///
+    /// Returns [`Error::TransactionTimeout`] if the timeout elapses first, or
+    /// [`Error::TransactionWaitCancelled`] if the cancellation token is cancelled.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::api::ordering::TransactionId;
+    /// # use hrobot::poll::PollOptions;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let transaction = robot.wait_for_transaction(
+    ///     &TransactionId::from("B20150121-344958-251479"),
+    ///     PollOptions::new(Duration::from_secs(60)).with_timeout(Duration::from_secs(3600)),
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn wait_for_transaction(
+        &self,
+        transaction: &TransactionId,
+        options: PollOptions,
+    ) -> Result<ProductTransaction, Error> {
+        poll_transaction(
+            &transaction.0,
+            &options,
+            || self.get_product_transaction(transaction),
+            |transaction| &transaction.status,
+        )
+        .await
+    }
+
+    /// Wait for a market (auction) transaction to either become ready or get cancelled.
+    ///
+    /// See [`AsyncRobot::wait_for_transaction`] for details.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::ordering::MarketTransactionId;
+    /// # use hrobot::poll::PollOptions;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let transaction = robot.wait_for_market_transaction(
+    ///     &MarketTransactionId::from("B20150121-344958-251479"),
+    ///     PollOptions::default(),
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn wait_for_market_transaction(
+        &self,
+        transaction: &MarketTransactionId,
+        options: PollOptions,
+    ) -> Result<MarketTransaction, Error> {
+        poll_transaction(
+            &transaction.0,
+            &options,
+            || self.get_market_transaction(transaction),
+            |transaction| &transaction.status,
+        )
+        .await
+    }
+
+    /// Wait for an addon transaction to either become ready or get cancelled.
+    ///
+    /// See [`AsyncRobot::wait_for_transaction`] for details.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::ordering::AddonTransactionId;
+    /// # use hrobot::poll::PollOptions;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let transaction = robot.wait_for_addon_transaction(
+    ///     &AddonTransactionId::from("B20150121-344958-251479"),
+    ///     PollOptions::default(),
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn wait_for_addon_transaction(
+        &self,
+        transaction: &AddonTransactionId,
+        options: PollOptions,
+    ) -> Result<AddonTransaction, Error> {
+        poll_transaction(
+            &transaction.0,
+            &options,
+            || self.get_addon_transaction(transaction),
+            |transaction| &transaction.status,
+        )
+        .await
+    }
+}
+
+/// Fetch the transaction until it is no longer in process, sleeping between
+/// each attempt and giving up if the timeout elapses or the wait is cancelled.
+async fn poll_transaction<T, Fut>(
+    id: &str,
+    options: &PollOptions,
+    fetch: impl Fn() -> Fut,
+    status: impl Fn(&T) -> &TransactionStatus,
+) -> Result<T, Error>
+where
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let deadline = options.deadline();
+
+    loop {
+        let transaction = fetch().await?;
+
+        if !matches!(status(&transaction), TransactionStatus::InProcess) {
+            return Ok(transaction);
+        }
+
+        match options.sleep(deadline).await {
+            Ok(()) => (),
+            Err(PollInterrupted::Timeout) => return Err(Error::TransactionTimeout(id.to_string())),
+            Err(PollInterrupted::Cancelled) => {
+                return Err(Error::TransactionWaitCancelled(id.to_string()))
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{collections::HashMap, time::Duration};
+
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+
     use crate::{
         api::{
+            keys::Fingerprint,
             ordering::{
-                AddonId, AddonOrder, AddonTransactionId, Datacenter, ImSeriousAboutSpendingMoney,
-                Location, MarketProductId, MarketProductOrder, MarketTransactionId, ProductId,
-                TransactionId,
+                Addon, AddonId, AddonKind, AddonOrder, AddonTransactionId, AuthorizationMethod,
+                CorrelationId, Datacenter, ImSeriousAboutSpendingMoney, InvalidProductOrder,
+                Location, MarketProductId, MarketProductOrder, MarketQuery, MarketTransactionId,
+                Product, ProductId, ProductOrderBuilder, TransactionId, TransactionStatus,
             },
             server::ServerId,
+            traffic::TrafficQuota,
         },
+        error::Error,
+        mock::{fixtures, MockClient},
+        poll::PollOptions,
         urlencode::UrlEncode,
+        AsyncRobot,
     };
 
     use super::ProductOrder;
 
+    /// Reports an addon transaction as in process for the first
+    /// `in_process` requests, and ready afterwards.
+    fn transaction_robot(in_process: usize) -> AsyncRobot {
+        let transaction = |status| fixtures::addon_transaction("failover_subnet_ipv4_29", status);
+
+        MockClient::new()
+            .on_sequence(
+                "GET",
+                "/order/server_addon/transaction/*",
+                std::iter::repeat_n(transaction("in process"), in_process)
+                    .chain([transaction("ready")]),
+            )
+            .robot()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn wait_for_addon_transaction() {
+        let transaction = transaction_robot(3)
+            .wait_for_addon_transaction(
+                &AddonTransactionId::from("B20220210-1843193-S33055"),
+                PollOptions::new(Duration::from_secs(60)),
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(transaction.status, TransactionStatus::Ready));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn wait_for_addon_transaction_timeout() {
+        let result = transaction_robot(usize::MAX)
+            .wait_for_addon_transaction(
+                &AddonTransactionId::from("B20220210-1843193-S33055"),
+                PollOptions::new(Duration::from_secs(60)).with_timeout(Duration::from_secs(300)),
+            )
+            .await;
+
+        assert!(
+            matches!(result, Err(Error::TransactionTimeout(id)) if id == "B20220210-1843193-S33055")
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn wait_for_addon_transaction_cancelled() {
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let result = transaction_robot(usize::MAX)
+            .wait_for_addon_transaction(
+                &AddonTransactionId::from("B20220210-1843193-S33055"),
+                PollOptions::new(Duration::from_secs(60)).with_cancellation(token),
+            )
+            .await;
+
+        assert!(matches!(result, Err(Error::TransactionWaitCancelled(_))));
+    }
+
+    /// Lists a single ssh key.
+    fn key_robot() -> AsyncRobot {
+        MockClient::new()
+            .on(
+                "GET",
+                "/key",
+                json!([fixtures::ssh_key(
+                    "ops",
+                    "15:28:b0:03:95:f0:77:b3:10:56:15:6b:77:22:a5:bb"
+                )]),
+            )
+            .robot()
+    }
+
+    #[tokio::test]
+    async fn validate_order_auth() {
+        let robot = key_robot();
+
+        let keys = robot.list_ssh_keys().await.unwrap();
+        robot
+            .validate_order_auth(&AuthorizationMethod::keys(&keys))
+            .await
+            .unwrap();
+
+        let unknown: Fingerprint = "c1:e4:08:73:dd:f7:e9:d1:94:ab:e9:0f:28:b2:d2:ed"
+            .parse()
+            .unwrap();
+
+        let result = robot
+            .validate_order_auth(&AuthorizationMethod::Keys(vec![
+                keys[0].fingerprint,
+                unknown,
+            ]))
+            .await;
+
+        assert!(
+            matches!(result, Err(Error::UnknownSshKeys(fingerprints)) if fingerprints == [unknown])
+        );
+    }
+
+    #[tokio::test]
+    async fn orders_reject_sha256_fingerprints() {
+        let robot = key_robot();
+
+        let sha256: Fingerprint = "SHA256:tSsyRAU9m1HXBxbzkalG6f1q2c6gKpEX5fw/UUjcA7A"
+            .parse()
+            .unwrap();
+
+        let result = robot
+            .place_market_order(MarketProductOrder {
+                id: MarketProductId(12345678),
+                auth: AuthorizationMethod::Keys(vec![sha256]),
+                distribution: None,
+                language: None,
+                addons: vec![],
+                comment: None,
+                i_want_to_spend_money_to_purchase_a_server:
+                    ImSeriousAboutSpendingMoney::NoThisIsJustATest,
+            })
+            .await;
+
+        assert!(matches!(result, Err(Error::UnsupportedFingerprint(key)) if key == sha256));
+    }
+
     #[test]
     fn location_conversion() {
         assert_eq!(Location::from("FSN1"), Location::from("FSN1".to_string()));
@@ -512,6 +1019,15 @@ mod tests {
             Datacenter::from("FSN1-DC8").to_string(),
             "FSN1-DC8".to_string()
         );
+
+        assert_eq!(
+            Datacenter::from("FSN1-DC8").location(),
+            Location::from("FSN1")
+        );
+        assert_eq!(
+            Location::from(Datacenter::from("HEL1")),
+            Location::from("HEL1")
+        );
     }
 
     #[test]
@@ -603,11 +1119,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn addon_kind() {
+        for kind in [
+            AddonKind::PrimaryIpv4,
+            AddonKind::AdditionalIpv4,
+            AddonKind::FailoverIp,
+            AddonKind::SubnetIpv4_29,
+            AddonKind::FailoverSubnetIpv4_29,
+            AddonKind::Other("Ipv4-Primary".to_string()),
+        ] {
+            let id = AddonId::from(kind.clone());
+            assert_eq!(id, kind);
+            assert_eq!(id.kind(), kind);
+            assert_eq!(id.to_string(), kind.to_string());
+        }
+
+        assert_eq!(AddonId::from("failover_ip"), AddonKind::FailoverIp);
+        assert_eq!(
+            AddonKind::from("failover_subnet_ipv4_29"),
+            AddonKind::FailoverSubnetIpv4_29
+        );
+    }
+
     #[test]
     fn product_order_encoding() {
         let order = ProductOrder {
             id: ProductId::from("AX41"),
-            auth: super::AuthorizationMethod::Password("trustno1".to_string()),
+            auth: AuthorizationMethod::Password("trustno1".to_string()),
             location: Location::from("FSN1"),
             distribution: Some("CentOS-7.9".to_string()),
             language: Some("en_US".to_string()),
@@ -637,7 +1176,7 @@ mod tests {
     fn market_product_order_encoding() {
         let order = MarketProductOrder {
             id: MarketProductId::from(101),
-            auth: super::AuthorizationMethod::Password("trustno1".to_string()),
+            auth: AuthorizationMethod::Password("trustno1".to_string()),
             distribution: Some("CentOS-7.9".to_string()),
             language: Some("en_US".to_string()),
             comment: Some("Comment goes here".to_string()),
@@ -683,4 +1222,192 @@ mod tests {
             .join("&")
         );
     }
+
+    #[test]
+    fn correlation_id_roundtrip() {
+        let id = CorrelationId::from("run-1234");
+
+        let order = ProductOrder {
+            id: ProductId::from("AX41"),
+            auth: AuthorizationMethod::Password("trustno1".to_string()),
+            location: Location::from("FSN1"),
+            distribution: None,
+            language: None,
+            comment: Some("Comment goes here".to_string()),
+            addons: vec![],
+            i_want_to_spend_money_to_purchase_a_server:
+                ImSeriousAboutSpendingMoney::NoThisIsJustATest,
+        }
+        .with_correlation_id(&id);
+
+        assert_eq!(
+            order.comment.as_deref(),
+            Some("Comment goes here [correlation-id:run-1234]")
+        );
+
+        assert_eq!(
+            CorrelationId::from_comment(order.comment.as_deref().unwrap()),
+            Some(id)
+        );
+
+        assert_eq!(CorrelationId::from_comment("Comment goes here"), None);
+
+        let escaped = CorrelationId::from(r"run]\1234");
+        let comment = escaped.tag(None);
+        assert_eq!(comment, r"[correlation-id:run\]\\1234]");
+        assert_eq!(CorrelationId::from_comment(&comment), Some(escaped));
+    }
+
+    #[test]
+    fn product_order_builder() {
+        let product = Product {
+            id: ProductId::from("AX41"),
+            name: "AX41".to_string(),
+            description: vec![],
+            traffic_limit: TrafficQuota::Unlimited,
+            distributions: vec!["Rescue system".to_string()],
+            languages: vec!["en".to_string()],
+            locations: vec![Location::from("FSN1"), Location::from("HEL1")],
+            prices: HashMap::new(),
+            orderable_addons: vec![Addon {
+                id: AddonId::from("primary_ipv4"),
+                name: "Primary IPv4".to_string(),
+                location: Some(Location::from("FSN1")),
+                min: 0,
+                max: 1,
+                prices: HashMap::new(),
+            }],
+        };
+
+        let auth = || AuthorizationMethod::Password("trustno1".to_string());
+
+        assert!(matches!(
+            ProductOrderBuilder::new(&product, Location::from("NBG1"), auth()),
+            Err(InvalidProductOrder::Location { .. })
+        ));
+
+        assert!(matches!(
+            ProductOrderBuilder::new(
+                &product,
+                Location::from("FSN1"),
+                AuthorizationMethod::Keys(vec![
+                    "SHA256:tSsyRAU9m1HXBxbzkalG6f1q2c6gKpEX5fw/UUjcA7A"
+                        .parse()
+                        .unwrap()
+                ])
+            ),
+            Err(InvalidProductOrder::Fingerprint(_))
+        ));
+
+        let builder = ProductOrderBuilder::new(&product, Location::from("FSN1"), auth()).unwrap();
+
+        assert!(matches!(
+            builder.clone().distribution("Arch Linux"),
+            Err(InvalidProductOrder::Distribution { .. })
+        ));
+
+        assert!(matches!(
+            builder.clone().language("de"),
+            Err(InvalidProductOrder::Language { .. })
+        ));
+
+        assert!(matches!(
+            ProductOrderBuilder::new(&product, Location::from("HEL1"), auth())
+                .unwrap()
+                .addon(AddonKind::PrimaryIpv4),
+            Err(InvalidProductOrder::Addon { .. })
+        ));
+
+        let order = builder
+            .distribution("Rescue system")
+            .unwrap()
+            .language("en")
+            .unwrap()
+            .addon("primary_ipv4")
+            .unwrap()
+            .build();
+
+        assert_eq!(
+            order.encode(),
+            [
+                "product_id=AX41",
+                "password=trustno1",
+                "location=FSN1",
+                "dist=Rescue+system",
+                "lang=en",
+                "addon%5B%5D=primary_ipv4",
+                "test=true"
+            ]
+            .join("&")
+        );
+    }
+
+    /// Serves three market products, lowering the price of
+    /// the second below 50 euros after the first request.
+    fn market_robot() -> AsyncRobot {
+        let products = |price| {
+            json!([
+                fixtures::market_product(1, "30.0000", "FSN1-DC5", "ENT.HDD ECC"),
+                fixtures::market_product(2, price, "FSN1-DC7", "ECC INIC"),
+                fixtures::market_product(3, "20.0000", "HEL1-DC2", "ECC"),
+            ])
+        };
+
+        MockClient::new()
+            .on_sequence(
+                "GET",
+                "/order/server_market/product/",
+                [products("60.0000"), products("45.0000")],
+            )
+            .robot()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn watch_market() {
+        use futures_util::StreamExt;
+        use rust_decimal::Decimal;
+
+        let robot = market_robot();
+
+        let query = MarketQuery::default()
+            .with_max_price(Decimal::from(50))
+            .with_location(Location::from("FSN1"))
+            .with_feature("ecc");
+
+        let products: Vec<_> = robot
+            .watch_market(query, Duration::from_secs(60))
+            .take(2)
+            .map(|product| product.unwrap().id)
+            .collect()
+            .await;
+
+        assert_eq!(products, [MarketProductId(1), MarketProductId(2)]);
+    }
+
+    #[tokio::test]
+    async fn snapshot_market_prices() {
+        use crate::api::ordering::MarketPricePoint;
+        use rust_decimal::Decimal;
+
+        let robot = market_robot();
+
+        let first = robot.snapshot_market_prices().await.unwrap();
+        let second = robot.snapshot_market_prices().await.unwrap();
+
+        assert_eq!(first.len(), 3);
+        assert!(first
+            .iter()
+            .all(|point| point.recorded_at == first[0].recorded_at));
+
+        let (id, price, _) = second[1].clone().into();
+        assert_eq!(id, MarketProductId(2));
+        assert_eq!(price.recurring.net, Decimal::from(45));
+        assert_eq!(first[1].price.recurring.net, Decimal::from(60));
+
+        let json = serde_json::to_string(&first[1]).unwrap();
+        assert_eq!(
+            serde_json::from_str::<MarketPricePoint>(&json).unwrap(),
+            first[1]
+        );
+    }
 }
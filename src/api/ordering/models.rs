@@ -1,15 +1,28 @@
-use std::{collections::HashMap, fmt::Display, net::IpAddr};
+use std::{
+    collections::HashMap, fmt::Display, net::IpAddr, num::ParseIntError, str::FromStr,
+    time::Duration,
+};
 
 use bytesize::ByteSize;
+use ipnet::IpNet;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use time::{OffsetDateTime, PrimitiveDateTime};
 use time_tz::PrimitiveDateTimeExt;
 
-use crate::{api::server::ServerId, urlencode::UrlEncode};
+use crate::{
+    api::{
+        ip::MacAddress,
+        keys::{Fingerprint, SshKey},
+        server::{hardware::HardwareComponent, Datacenter, Location, ServerId},
+        traffic::TrafficQuota,
+    },
+    urlencode::UrlEncode,
+};
 
 /// Describes a product available for purchase.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Product {
     /// Unique identifier for this product type.
     pub id: ProductId,
@@ -20,9 +33,9 @@ pub struct Product {
     /// Human-readable list of features for this product.
     pub description: Vec<String>,
 
-    /// Monthly traffic limitation if any, e.g. `5 TB`.
-    #[serde(rename = "traffic", deserialize_with = "crate::conversion::traffic")]
-    pub traffic_limit: Option<ByteSize>,
+    /// Monthly traffic limitation, e.g. `5 TB`.
+    #[serde(rename = "traffic")]
+    pub traffic_limit: TrafficQuota,
 
     /// Available distributions for this product.
     #[serde(rename = "dist")]
@@ -37,6 +50,7 @@ pub struct Product {
     pub locations: Vec<Location>,
 
     /// Prices for this product in each location
+    #[cfg_attr(feature = "schemars", schemars(with = "Vec<SingleLocationPrice>"))]
     #[serde(with = "location_prices")]
     pub prices: HashMap<Location, LocationPrice>,
 
@@ -45,7 +59,8 @@ pub struct Product {
 }
 
 /// Describes a product purchase, as listed in a [`ProductTransaction`].
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PurchasedProduct {
     /// Unique identifier for this product type.
     pub id: ProductId,
@@ -56,14 +71,21 @@ pub struct PurchasedProduct {
     /// Human-readable list of features for this product.
     pub description: Vec<String>,
 
-    /// Monthly traffic limitation if any, e.g. `5 TB`.
-    #[serde(rename = "traffic", deserialize_with = "crate::conversion::traffic")]
-    pub traffic_limit: Option<ByteSize>,
+    /// Monthly traffic limitation, e.g. `5 TB`.
+    #[serde(rename = "traffic")]
+    pub traffic_limit: TrafficQuota,
 
     /// Distribution selected for the purchased product.
     #[serde(rename = "dist")]
     pub distribution: String,
 
+    /// Architecture of the selected distribution, e.g. `64`.
+    ///
+    /// This field is deprecated by Hetzner, and is returned under the
+    /// `@deprecated arch` key. It may be removed from the API at any point.
+    #[serde(rename = "@deprecated arch", default)]
+    pub architecture: Option<String>,
+
     /// Language selected for the product.
     #[serde(rename = "lang")]
     pub language: String,
@@ -74,7 +96,8 @@ pub struct PurchasedProduct {
 }
 
 /// Describes a purchased market (auction) product, as described in a [`MarketTransaction`]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PurchasedMarketProduct {
     /// Unique identifier for this product type.
     pub id: MarketProductId,
@@ -85,14 +108,21 @@ pub struct PurchasedMarketProduct {
     /// Human-readable list of features for this product.
     pub description: Vec<String>,
 
-    /// Monthly traffic limitation if any, e.g. `5 TB`.
-    #[serde(rename = "traffic", deserialize_with = "crate::conversion::traffic")]
-    pub traffic_limit: Option<ByteSize>,
+    /// Monthly traffic limitation, e.g. `5 TB`.
+    #[serde(rename = "traffic")]
+    pub traffic_limit: TrafficQuota,
 
     /// Distribution selected for the purchased product.
     #[serde(rename = "dist")]
     pub distribution: String,
 
+    /// Architecture of the selected distribution, e.g. `64`.
+    ///
+    /// This field is deprecated by Hetzner, and is returned under the
+    /// `@deprecated arch` key. It may be removed from the API at any point.
+    #[serde(rename = "@deprecated arch", default)]
+    pub architecture: Option<String>,
+
     /// Language selected for the product.
     #[serde(rename = "lang")]
     pub language: String,
@@ -101,6 +131,10 @@ pub struct PurchasedMarketProduct {
     #[serde(rename = "location")]
     pub location: Option<Location>,
 
+    /// Datacenter of the purchased product, e.g. `FSN1-DC5`.
+    #[serde(default)]
+    pub datacenter: Option<Datacenter>,
+
     /// Model name of the CPU
     pub cpu: String,
 
@@ -109,6 +143,7 @@ pub struct PurchasedMarketProduct {
 
     /// Total amount of memory installed in the server.
     #[serde(deserialize_with = "crate::conversion::gb")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub memory_size: ByteSize,
 
     /// Primary hard drive capacity.
@@ -122,6 +157,7 @@ pub struct PurchasedMarketProduct {
     ///
     /// The HDD size will be 3.84TB, and [`MarketProduct::primary_hdd_count`] will be 6, not 8.
     #[serde(rename = "hdd_size", deserialize_with = "crate::conversion::gb")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub primary_hdd_size: ByteSize,
 
     /// Human-readable summary of installed hardware/features, such as
@@ -193,7 +229,8 @@ mod location_prices {
 }
 
 /// Price information for a single location.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SingleLocationPrice {
     /// Location this price applies to.
     pub location: Location,
@@ -206,7 +243,8 @@ pub struct SingleLocationPrice {
 }
 
 /// Price (both setup and recurring) for a single location.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct LocationPrice {
     /// Monthly price in euros.
     pub recurring: RecurringPrice,
@@ -215,29 +253,38 @@ pub struct LocationPrice {
 }
 
 /// A recurring price point, both excluding and including VAT.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct RecurringPrice {
     /// Monthly price excluding VAT.
+    #[serde(deserialize_with = "crate::conversion::price")]
     pub net: Decimal,
     /// Monthly price including VAT.
+    #[serde(deserialize_with = "crate::conversion::price")]
     pub gross: Decimal,
     /// Hourly price excluding VAT.
+    #[serde(deserialize_with = "crate::conversion::price")]
     pub hourly_net: Decimal,
     /// Hourly price including VAT.
+    #[serde(deserialize_with = "crate::conversion::price")]
     pub hourly_gross: Decimal,
 }
 
 /// A one-time setup price point, both excluding and including VAT.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SetupPrice {
     /// Monthly price excluding VAT.
+    #[serde(deserialize_with = "crate::conversion::price")]
     pub net: Decimal,
     /// Monthly price including VAT.
+    #[serde(deserialize_with = "crate::conversion::price")]
     pub gross: Decimal,
 }
 
 /// Describes an addon which can be purchased.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Addon {
     /// Unique identifier for this addon.
     pub id: AddonId,
@@ -255,12 +302,14 @@ pub struct Addon {
     pub max: u32,
 
     /// Prices for this addon in each location.
+    #[cfg_attr(feature = "schemars", schemars(with = "Vec<SingleLocationPrice>"))]
     #[serde(with = "location_prices")]
     pub prices: HashMap<Location, LocationPrice>,
 }
 
 /// Describes an addon available for purchase for a specific server.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AvailableAddon {
     /// Unique ID for this addon.
     pub id: AddonId,
@@ -275,82 +324,9 @@ pub struct AvailableAddon {
     pub price: SingleLocationPrice,
 }
 
-/// Location, e.g. "FSN1".
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Location(pub String);
-
-impl From<String> for Location {
-    fn from(value: String) -> Self {
-        Location(value)
-    }
-}
-
-impl From<&str> for Location {
-    fn from(value: &str) -> Self {
-        Location(value.to_string())
-    }
-}
-
-impl From<Location> for String {
-    fn from(value: Location) -> Self {
-        value.0
-    }
-}
-
-impl Display for Location {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.0.fmt(f)
-    }
-}
-
-impl PartialEq<str> for Location {
-    fn eq(&self, other: &str) -> bool {
-        self.0.eq(other)
-    }
-}
-
-/// Datacenter within a Location, e.g. "FSN1-DC1".
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Datacenter(pub String);
-
-impl From<String> for Datacenter {
-    fn from(value: String) -> Self {
-        Datacenter(value)
-    }
-}
-
-impl From<&str> for Datacenter {
-    fn from(value: &str) -> Self {
-        Datacenter(value.to_string())
-    }
-}
-
-impl From<Datacenter> for String {
-    fn from(value: Datacenter) -> Self {
-        value.0
-    }
-}
-
-impl From<Datacenter> for Location {
-    fn from(value: Datacenter) -> Location {
-        Location(value.0.split_once('-').unwrap().0.to_string())
-    }
-}
-
-impl Display for Datacenter {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.0.fmt(f)
-    }
-}
-
-impl PartialEq<str> for Datacenter {
-    fn eq(&self, other: &str) -> bool {
-        self.0.eq(other)
-    }
-}
-
 /// Product ID, e.g. "EX44".
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ProductId(pub String);
 
 impl From<String> for ProductId {
@@ -384,12 +360,14 @@ impl PartialEq<str> for ProductId {
 }
 
 /// Describes the purchase of a single standard hetzner product.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ProductTransaction {
     /// Unique transaction ID.
     pub id: TransactionId,
 
     /// Timestamp for the purchase.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     #[serde(with = "time::serde::rfc3339")]
     pub date: OffsetDateTime,
 
@@ -424,8 +402,51 @@ pub struct ProductTransaction {
     pub addons: Vec<String>,
 }
 
+impl Product {
+    /// Parse the [`description`](Product::description) into structured hardware components.
+    pub fn hardware(&self) -> Vec<HardwareComponent> {
+        HardwareComponent::parse_description(&self.description)
+    }
+}
+
+impl PurchasedProduct {
+    /// Parse the [`description`](PurchasedProduct::description) into structured hardware components.
+    pub fn hardware(&self) -> Vec<HardwareComponent> {
+        HardwareComponent::parse_description(&self.description)
+    }
+}
+
+impl MarketProduct {
+    /// Parse the [`description`](MarketProduct::description) into structured hardware components.
+    pub fn hardware(&self) -> Vec<HardwareComponent> {
+        HardwareComponent::parse_description(&self.description)
+    }
+
+    /// Location of the [`datacenter`](MarketProduct::datacenter), if known.
+    pub fn location(&self) -> Option<Location> {
+        self.datacenter.as_ref().map(Datacenter::location)
+    }
+
+    /// Record the current price of the product, as observed at `recorded_at`.
+    pub fn price_point(&self, recorded_at: OffsetDateTime) -> MarketPricePoint {
+        MarketPricePoint {
+            id: self.id,
+            price: self.price.clone(),
+            recorded_at,
+        }
+    }
+}
+
+impl PurchasedMarketProduct {
+    /// Parse the [`description`](PurchasedMarketProduct::description) into structured hardware components.
+    pub fn hardware(&self) -> Vec<HardwareComponent> {
+        HardwareComponent::parse_description(&self.description)
+    }
+}
+
 /// Status of the transaction.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum TransactionStatus {
     /// Transaction completed.
     #[serde(rename = "ready")]
@@ -442,6 +463,7 @@ pub enum TransactionStatus {
 
 /// Transaction ID, e.g. "B20150121-344957-251478".
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct TransactionId(pub String);
 
 impl From<String> for TransactionId {
@@ -475,12 +497,14 @@ impl PartialEq<str> for TransactionId {
 }
 
 /// Describes the purchase of a single Hetzner market (auction) server.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MarketTransaction {
     /// Unique transaction ID.
     pub id: MarketTransactionId,
 
     /// Timestamp for the purchase.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     #[serde(with = "time::serde::rfc3339")]
     pub date: OffsetDateTime,
 
@@ -514,6 +538,7 @@ pub struct MarketTransaction {
 
 /// Market Transaction ID, e.g. "B20150121-344957-251478".
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MarketTransactionId(pub String);
 
 impl From<String> for MarketTransactionId {
@@ -548,6 +573,7 @@ impl PartialEq<str> for MarketTransactionId {
 
 /// Addon Transaction ID, e.g. "B20150121-344957-251478".
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AddonTransactionId(pub String);
 
 impl From<String> for AddonTransactionId {
@@ -581,12 +607,14 @@ impl PartialEq<str> for AddonTransactionId {
 }
 
 /// Describes the purchase of a single addon.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AddonTransaction {
     /// Unique transacton ID.
     pub id: AddonTransactionId,
 
     /// Timestamp for the purchase.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     #[serde(with = "time::serde::rfc3339")]
     pub date: OffsetDateTime,
 
@@ -605,16 +633,127 @@ pub struct AddonTransaction {
 }
 
 /// Resource associated with an addon purchase.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Resource {
-    /// Indicates the type of the resource. e.g. `subnet`
-    pub r#type: String,
-    /// The ID of the resource. e.g. `10.0.0.0`
-    pub id: String,
+    /// Kind of the resource, e.g. [`ResourceKind::Subnet`].
+    #[serde(rename = "type")]
+    pub kind: ResourceKind,
+
+    /// ID of the resource, e.g. `10.0.0.0` for a subnet.
+    pub id: TransactionResourceId,
+}
+
+/// Kind of [`Resource`] associated with an addon purchase.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ResourceKind {
+    /// Subnet, identified by its network address.
+    Subnet,
+
+    /// Single IP address.
+    Ip,
+
+    /// Separate MAC address.
+    Mac,
+
+    /// Any other kind of resource.
+    #[serde(untagged)]
+    Other(String),
+}
+
+/// ID of a [`Resource`], parsed into an address where possible.
+///
+/// Subnets are identified by their network address without a prefix length,
+/// so their IDs are usually [`TransactionResourceId::Ip`].
+///
+/// # Example
+/// ```rust
+/// # use std::net::{IpAddr, Ipv4Addr};
+/// # use hrobot::api::ordering::TransactionResourceId;
+/// let id = TransactionResourceId::parse("10.0.0.0");
+///
+/// assert_eq!(id, TransactionResourceId::Ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0))));
+/// assert_eq!(
+///     TransactionResourceId::parse("2a01:4f8::/64").ip(),
+///     Some("2a01:4f8::".parse().unwrap())
+/// );
+/// assert_eq!(TransactionResourceId::parse("abc").ip(), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(from = "String", into = "String")]
+pub enum TransactionResourceId {
+    /// IP address, or the network address of a subnet.
+    Ip(IpAddr),
+
+    /// Subnet including its prefix length.
+    Subnet(IpNet),
+
+    /// MAC address.
+    Mac(MacAddress),
+
+    /// ID which is not an address, as returned by the API.
+    Other(String),
+}
+
+impl TransactionResourceId {
+    /// Interpret a resource ID as returned by the API.
+    pub fn parse(id: &str) -> TransactionResourceId {
+        if let Ok(ip) = id.parse() {
+            TransactionResourceId::Ip(ip)
+        } else if let Ok(net) = id.parse() {
+            TransactionResourceId::Subnet(net)
+        } else if let Ok(mac) = id.parse() {
+            TransactionResourceId::Mac(mac)
+        } else {
+            TransactionResourceId::Other(id.to_string())
+        }
+    }
+
+    /// IP address of the resource, which is the network address for subnets.
+    pub fn ip(&self) -> Option<IpAddr> {
+        match self {
+            TransactionResourceId::Ip(ip) => Some(*ip),
+            TransactionResourceId::Subnet(net) => Some(net.network()),
+            TransactionResourceId::Mac(_) | TransactionResourceId::Other(_) => None,
+        }
+    }
+}
+
+impl From<&str> for TransactionResourceId {
+    fn from(value: &str) -> Self {
+        TransactionResourceId::parse(value)
+    }
+}
+
+impl From<String> for TransactionResourceId {
+    fn from(value: String) -> Self {
+        TransactionResourceId::parse(&value)
+    }
+}
+
+impl From<TransactionResourceId> for String {
+    fn from(value: TransactionResourceId) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for TransactionResourceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransactionResourceId::Ip(ip) => ip.fmt(f),
+            TransactionResourceId::Subnet(net) => net.fmt(f),
+            TransactionResourceId::Mac(mac) => mac.fmt(f),
+            TransactionResourceId::Other(id) => f.write_str(id),
+        }
+    }
 }
 
 /// Describes a purchased addon as it appears in an [`AddonTransaction`]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PurchasedAddon {
     /// Unique identifier for this product type.
     pub id: AddonId,
@@ -630,6 +769,7 @@ pub struct PurchasedAddon {
 ///
 /// Uniquely identifies an addon.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AddonId(pub String);
 
 impl From<String> for AddonId {
@@ -662,18 +802,110 @@ impl PartialEq<str> for AddonId {
     }
 }
 
+impl PartialEq<AddonKind> for AddonId {
+    fn eq(&self, other: &AddonKind) -> bool {
+        self.0.eq(other.as_str())
+    }
+}
+
+impl From<&AddonId> for AddonId {
+    fn from(value: &AddonId) -> Self {
+        value.clone()
+    }
+}
+
+impl From<AddonKind> for AddonId {
+    fn from(value: AddonKind) -> Self {
+        match value {
+            AddonKind::Other(id) => AddonId(id),
+            known => AddonId(known.as_str().to_string()),
+        }
+    }
+}
+
+impl AddonId {
+    /// Well-known kind of addon identified by this ID.
+    pub fn kind(&self) -> AddonKind {
+        AddonKind::from(self.0.as_str())
+    }
+}
+
+/// Well-known addons, which can be used wherever an [`AddonId`] is expected.
+///
+/// # Example
+/// ```rust
+/// # use hrobot::api::ordering::{AddonId, AddonKind};
+/// let id = AddonId::from(AddonKind::PrimaryIpv4);
+/// assert_eq!(id, AddonId::from("primary_ipv4"));
+/// assert_eq!(AddonId::from("ipv6_subnet").kind(), AddonKind::Other("ipv6_subnet".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AddonKind {
+    /// Primary IPv4 address (`primary_ipv4`).
+    PrimaryIpv4,
+
+    /// Additional IPv4 address (`additional_ipv4`).
+    AdditionalIpv4,
+
+    /// Failover IPv4 address (`failover_ip`).
+    FailoverIp,
+
+    /// IPv4 /29 subnet (`subnet_ipv4_29`).
+    SubnetIpv4_29,
+
+    /// Failover IPv4 /29 subnet (`failover_subnet_ipv4_29`).
+    FailoverSubnetIpv4_29,
+
+    /// Any other addon.
+    Other(String),
+}
+
+impl AddonKind {
+    /// Addon ID as used by the API.
+    pub fn as_str(&self) -> &str {
+        match self {
+            AddonKind::PrimaryIpv4 => "primary_ipv4",
+            AddonKind::AdditionalIpv4 => "additional_ipv4",
+            AddonKind::FailoverIp => "failover_ip",
+            AddonKind::SubnetIpv4_29 => "subnet_ipv4_29",
+            AddonKind::FailoverSubnetIpv4_29 => "failover_subnet_ipv4_29",
+            AddonKind::Other(id) => id,
+        }
+    }
+}
+
+impl From<&str> for AddonKind {
+    fn from(value: &str) -> Self {
+        match value {
+            "primary_ipv4" => AddonKind::PrimaryIpv4,
+            "additional_ipv4" => AddonKind::AdditionalIpv4,
+            "failover_ip" => AddonKind::FailoverIp,
+            "subnet_ipv4_29" => AddonKind::SubnetIpv4_29,
+            "failover_subnet_ipv4_29" => AddonKind::FailoverSubnetIpv4_29,
+            other => AddonKind::Other(other.to_string()),
+        }
+    }
+}
+
+impl Display for AddonKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// SSH Public Key provided as an authorized key when purchasing a server.
 ///
 /// This is just key metadata, it does not contain the key itself. To retrieve the key, see [`AsyncRobot::get_ssh_key`](crate::AsyncRobot::get_ssh_key).
 ///
 /// Similar to the [`SshKeyReference`](crate::api::keys::SshKeyReference), but does not return the time at which the key was created.
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct InitialProductSshKey {
     /// Unique name for the key.
     pub name: String,
 
     /// Fingerprint of the public key.
-    pub fingerprint: String,
+    pub fingerprint: Fingerprint,
 
     /// Key algorithm (ED25519, RSA)
     #[serde(rename = "type")]
@@ -685,7 +917,8 @@ pub struct InitialProductSshKey {
 }
 
 /// SSH Host Key
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct HostKey {
     /// Fingerprint of the public key.
     pub fingerprint: String,
@@ -700,31 +933,41 @@ pub struct HostKey {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 struct InternalMarketProduct {
     pub id: MarketProductId,
     pub name: String,
     pub description: Vec<String>,
-    #[serde(rename = "traffic", deserialize_with = "crate::conversion::traffic")]
-    pub traffic_limit: Option<ByteSize>,
+    #[serde(rename = "traffic")]
+    pub traffic_limit: TrafficQuota,
     #[serde(rename = "dist")]
     pub distributions: Vec<String>,
     #[serde(rename = "lang")]
     pub languages: Vec<String>,
-    pub datacenter: Option<String>,
+    pub datacenter: Option<Datacenter>,
     pub cpu: String,
     pub cpu_benchmark: u32,
     #[serde(deserialize_with = "crate::conversion::gb")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub memory_size: ByteSize,
     #[serde(deserialize_with = "crate::conversion::gb")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub hdd_size: ByteSize,
     pub hdd_text: String,
     pub hdd_count: u8,
+    #[serde(deserialize_with = "crate::conversion::price")]
     pub price: Decimal,
+    #[serde(deserialize_with = "crate::conversion::price")]
     pub price_vat: Decimal,
+    #[serde(deserialize_with = "crate::conversion::price")]
     pub price_setup: Decimal,
+    #[serde(deserialize_with = "crate::conversion::price")]
     pub price_hourly: Decimal,
+    #[serde(deserialize_with = "crate::conversion::price")]
     pub price_hourly_vat: Decimal,
+    #[serde(deserialize_with = "crate::conversion::price")]
     pub price_setup_vat: Decimal,
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub fixed_price: bool,
     pub next_reduce: i64,
     pub next_reduce_date: String,
@@ -732,7 +975,8 @@ struct InternalMarketProduct {
 }
 
 /// Describes a Hetzner market (auction) product.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(from = "InternalMarketProduct")]
 pub struct MarketProduct {
     /// Unique identifier for this market product.
@@ -744,8 +988,8 @@ pub struct MarketProduct {
     /// Human-readable list of features for this product.
     pub description: Vec<String>,
 
-    /// Monthly traffic limitation if any, e.g. `5 TB`.
-    pub traffic_limit: Option<ByteSize>,
+    /// Monthly traffic limitation, e.g. `5 TB`.
+    pub traffic_limit: TrafficQuota,
 
     /// Distribution selected for the purchased product.
     pub distributions: Vec<String>,
@@ -753,8 +997,8 @@ pub struct MarketProduct {
     /// Language selected for the product.
     pub languages: Vec<String>,
 
-    /// Datacenter of the purchased product.
-    pub datacenter: Option<String>,
+    /// Datacenter the product is located in, e.g. `FSN1-DC5`.
+    pub datacenter: Option<Datacenter>,
 
     /// Model name of the CPU
     pub cpu: String,
@@ -763,6 +1007,7 @@ pub struct MarketProduct {
     pub cpu_benchmark: u32,
 
     /// Total amount of memory installed in the server.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub memory_size: ByteSize,
 
     /// Primary hard drive capacity.
@@ -775,6 +1020,7 @@ pub struct MarketProduct {
     /// * 2x SSD SATA 3,84 TB Datacenter
     ///
     /// The HDD size will be 3.84TB, and [`MarketProduct::primary_hdd_count`] will be 6, not 8.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub primary_hdd_size: ByteSize,
 
     /// Human-readable summary of installed hardware/features, such as
@@ -797,12 +1043,14 @@ pub struct MarketProduct {
     pub price: LocationPrice,
 
     /// Indicates that the lowest price point has been reached, and won't be lowered further.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub fixed_price: bool,
 
     /// Time until the price of the product is reduced.
-    pub next_reduce_in: std::time::Duration,
+    pub next_reduce_in: Duration,
 
     /// Timestamp indicating the time at which the product price will be further reduced.
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
     pub next_reduce_at: Option<OffsetDateTime>,
 
     /// List of available addons for the product.
@@ -838,7 +1086,7 @@ impl From<InternalMarketProduct> for MarketProduct {
                     gross: value.price_setup_vat,
                 },
             },
-            next_reduce_in: std::time::Duration::from_secs(value.next_reduce.unsigned_abs()),
+            next_reduce_in: Duration::from_secs(value.next_reduce.unsigned_abs()),
             next_reduce_at: PrimitiveDateTime::parse(
                 &value.next_reduce_date,
                 &time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]:[second]"),
@@ -853,10 +1101,163 @@ impl From<InternalMarketProduct> for MarketProduct {
     }
 }
 
+/// Price of a market (auction) product at a point in time,
+/// as returned by [`AsyncRobot::snapshot_market_prices`](crate::AsyncRobot::snapshot_market_prices).
+///
+/// Since the [`id`](MarketPricePoint::id) of a market product stays the same as
+/// its price is reduced, price points recorded periodically can be grouped by ID
+/// to track the price evolution of each product.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MarketPricePoint {
+    /// Market product the price applies to.
+    pub id: MarketProductId,
+
+    /// Price of the product at the time of recording.
+    pub price: LocationPrice,
+
+    /// Time at which the price was recorded.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    #[serde(with = "time::serde::rfc3339")]
+    pub recorded_at: OffsetDateTime,
+}
+
+impl From<MarketPricePoint> for (MarketProductId, LocationPrice, OffsetDateTime) {
+    fn from(point: MarketPricePoint) -> Self {
+        (point.id, point.price, point.recorded_at)
+    }
+}
+
+/// Criteria for finding market (auction) products, used by
+/// [`AsyncRobot::search_market_products`](crate::AsyncRobot::search_market_products)
+/// and [`AsyncRobot::watch_market`](crate::AsyncRobot::watch_market).
+///
+/// Products must satisfy all configured criteria to match.
+///
+/// # Example
+/// ```rust
+/// # use hrobot::bytesize::ByteSize;
+/// # use hrobot::api::ordering::{Location, MarketQuery};
+/// # use hrobot::rust_decimal::Decimal;
+/// let query = MarketQuery::default()
+///     .with_min_cpu_benchmark(20000)
+///     .with_max_price(Decimal::from(50))
+///     .with_min_memory(ByteSize::gb(64))
+///     .with_location(Location::from("FSN1"))
+///     .with_feature("ECC")
+///     .with_feature("NVMe");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarketQuery {
+    /// Minimum [`MarketProduct::cpu_benchmark`] score.
+    pub min_cpu_benchmark: Option<u32>,
+
+    /// Maximum monthly price in euros, excluding VAT.
+    pub max_price: Option<Decimal>,
+
+    /// Minimum [`MarketProduct::memory_size`].
+    pub min_memory: Option<ByteSize>,
+
+    /// Minimum [`MarketProduct::primary_hdd_size`].
+    pub min_disk_size: Option<ByteSize>,
+
+    /// Minimum [`MarketProduct::primary_hdd_count`].
+    pub min_disk_count: Option<u8>,
+
+    /// Location the product must be located in.
+    pub location: Option<Location>,
+
+    /// Keywords which must all appear in the product's [`features`](MarketProduct::features)
+    /// or [`description`](MarketProduct::description), e.g. `ECC` or `NVMe`.
+    ///
+    /// Keywords are matched case-insensitively.
+    pub features: Vec<String>,
+}
+
+impl MarketQuery {
+    /// Only match products with a CPU benchmark score of at least `score`.
+    pub fn with_min_cpu_benchmark(mut self, score: u32) -> Self {
+        self.min_cpu_benchmark = Some(score);
+        self
+    }
+
+    /// Only match products costing at most `price` euros per month, excluding VAT.
+    pub fn with_max_price(mut self, price: Decimal) -> Self {
+        self.max_price = Some(price);
+        self
+    }
+
+    /// Only match products with at least `memory` installed.
+    pub fn with_min_memory(mut self, memory: ByteSize) -> Self {
+        self.min_memory = Some(memory);
+        self
+    }
+
+    /// Only match products whose primary drives are at least `size` each.
+    pub fn with_min_disk_size(mut self, size: ByteSize) -> Self {
+        self.min_disk_size = Some(size);
+        self
+    }
+
+    /// Only match products with at least `count` primary drives.
+    pub fn with_min_disk_count(mut self, count: u8) -> Self {
+        self.min_disk_count = Some(count);
+        self
+    }
+
+    /// Only match products located in `location`.
+    pub fn with_location(mut self, location: Location) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Only match products mentioning `keyword` in their features or description.
+    pub fn with_feature(mut self, keyword: impl Into<String>) -> Self {
+        self.features.push(keyword.into());
+        self
+    }
+
+    /// Check whether the product satisfies all criteria of the query.
+    pub fn matches(&self, product: &MarketProduct) -> bool {
+        let contains = |keyword: &String| {
+            let keyword = keyword.to_lowercase();
+
+            product.features.to_lowercase().contains(&keyword)
+                || product
+                    .description
+                    .iter()
+                    .any(|line| line.to_lowercase().contains(&keyword))
+        };
+
+        self.min_cpu_benchmark
+            .is_none_or(|score| product.cpu_benchmark >= score)
+            && self
+                .max_price
+                .is_none_or(|price| product.price.recurring.net <= price)
+            && self
+                .min_memory
+                .is_none_or(|memory| product.memory_size >= memory)
+            && self
+                .min_disk_size
+                .is_none_or(|size| product.primary_hdd_size >= size)
+            && self
+                .min_disk_count
+                .is_none_or(|count| product.primary_hdd_count >= count)
+            && self.location.as_ref().is_none_or(|location| {
+                product
+                    .datacenter
+                    .as_ref()
+                    .is_some_and(|datacenter| datacenter.location() == *location)
+            })
+            && self.features.iter().all(contains)
+    }
+}
+
 /// Unique Market Product ID.
 ///
 /// Uniquely identifies a product on the Hetzner (auction) market.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MarketProductId(pub u32);
 
 impl From<u32> for MarketProductId {
@@ -883,17 +1284,150 @@ impl PartialEq<u32> for MarketProductId {
     }
 }
 
+impl PartialEq<MarketProductId> for u32 {
+    fn eq(&self, other: &MarketProductId) -> bool {
+        self.eq(&other.0)
+    }
+}
+
+impl FromStr for MarketProductId {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u32::from_str(s).map(MarketProductId)
+    }
+}
+
 /// Authorization method chosen for the purchase.
 /// Only one can be selected.
 #[derive(Debug, Clone)]
 pub enum AuthorizationMethod {
-    /// List of fingerprints corresponding to ssh keys already
+    /// List of MD5 fingerprints corresponding to ssh keys already
     /// provisioned within the Hetzner Robot system.
-    Keys(Vec<String>),
+    Keys(Vec<Fingerprint>),
     /// Set a root password for the server upon provisioning.
     Password(String),
 }
 
+impl AuthorizationMethod {
+    /// Authorize the given ssh keys, as returned by
+    /// [`AsyncRobot::list_ssh_keys`](crate::AsyncRobot::list_ssh_keys).
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::ordering::AuthorizationMethod;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let keys = robot.list_ssh_keys().await.unwrap();
+    ///
+    /// let auth = AuthorizationMethod::keys(keys.iter().filter(|key| key.name.starts_with("ops-")));
+    /// # }
+    /// ```
+    pub fn keys<'a>(keys: impl IntoIterator<Item = &'a SshKey>) -> Self {
+        AuthorizationMethod::Keys(keys.into_iter().map(|key| key.fingerprint).collect())
+    }
+
+    /// First authorized key fingerprint which is not in the MD5 format used by the API, if any.
+    pub(crate) fn unsupported_fingerprint(&self) -> Option<Fingerprint> {
+        match self {
+            AuthorizationMethod::Keys(keys) => keys.iter().find(|key| !key.is_md5()).copied(),
+            AuthorizationMethod::Password(_) => None,
+        }
+    }
+}
+
+/// Caller-supplied identifier which can be embedded in the comment of an order,
+/// and later used to locate the resulting transaction using
+/// [`AsyncRobot::find_transaction_by_correlation_id`](crate::AsyncRobot::find_transaction_by_correlation_id).
+///
+/// The identifier is embedded as `[correlation-id:<id>]`, with any `]` or `\`
+/// in the identifier escaped by a backslash.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CorrelationId(pub String);
+
+impl CorrelationId {
+    const PREFIX: &'static str = "[correlation-id:";
+    const SUFFIX: &'static str = "]";
+
+    /// Embed the correlation ID into the given comment.
+    pub(crate) fn tag(&self, comment: Option<&str>) -> String {
+        let id = self.0.replace('\\', "\\\\").replace(']', "\\]");
+        let tag = format!("{}{id}{}", Self::PREFIX, Self::SUFFIX);
+
+        match comment {
+            Some(comment) if !comment.is_empty() => format!("{comment} {tag}"),
+            _ => tag,
+        }
+    }
+
+    /// Extract a correlation ID embedded in a comment, if any.
+    pub fn from_comment(comment: &str) -> Option<Self> {
+        let (_, tagged) = comment.split_once(Self::PREFIX)?;
+
+        let mut id = String::new();
+        let mut chars = tagged.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                ']' => return Some(CorrelationId(id)),
+                '\\' => id.push(chars.next()?),
+                c => id.push(c),
+            }
+        }
+
+        None
+    }
+}
+
+impl From<String> for CorrelationId {
+    fn from(value: String) -> Self {
+        CorrelationId(value)
+    }
+}
+
+impl From<&str> for CorrelationId {
+    fn from(value: &str) -> Self {
+        CorrelationId(value.to_string())
+    }
+}
+
+impl Display for CorrelationId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl PartialEq<str> for CorrelationId {
+    fn eq(&self, other: &str) -> bool {
+        self.0.eq(other)
+    }
+}
+
+/// Either a [`ProductTransaction`] or a [`MarketTransaction`].
+#[derive(Debug, Clone)]
+pub enum Transaction {
+    /// Standard product purchase.
+    Product(ProductTransaction),
+    /// Market (auction) server purchase.
+    Market(MarketTransaction),
+}
+
+impl ProductTransaction {
+    /// Correlation ID embedded in the comment of the order, if any.
+    pub fn correlation_id(&self) -> Option<CorrelationId> {
+        CorrelationId::from_comment(self.comment.as_deref()?)
+    }
+}
+
+impl MarketTransaction {
+    /// Correlation ID embedded in the comment of the order, if any.
+    pub fn correlation_id(&self) -> Option<CorrelationId> {
+        CorrelationId::from_comment(self.comment.as_deref()?)
+    }
+}
+
 /// LetMeSpendMyMoneyAlready must be selected for any purchase order to
 /// actually go through, otherwise the "test" flag will be set.
 /// and the API will just simulate a purchase, returning a
@@ -941,6 +1475,17 @@ pub struct ProductOrder {
     pub i_want_to_spend_money_to_purchase_a_server: ImSeriousAboutSpendingMoney,
 }
 
+impl ProductOrder {
+    /// Embed a [`CorrelationId`] in the comment of the order.
+    ///
+    /// Note that comments require manual provisioning, which can increase the
+    /// processing time for the purchase request.
+    pub fn with_correlation_id(mut self, id: &CorrelationId) -> Self {
+        self.comment = Some(id.tag(self.comment.as_deref()));
+        self
+    }
+}
+
 impl UrlEncode for ProductOrder {
     fn encode_into(&self, mut f: crate::urlencode::UrlEncodingBuffer<'_>) {
         f.set("product_id", &self.id);
@@ -984,6 +1529,206 @@ impl UrlEncode for ProductOrder {
     }
 }
 
+/// Describes why a [`ProductOrderBuilder`] rejected a value.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum InvalidProductOrder {
+    /// Product is not available in the chosen location.
+    #[error("product {product} is not available in location {location}, available locations are: {available:?}")]
+    Location {
+        /// Product being ordered.
+        product: ProductId,
+        /// Rejected location.
+        location: Location,
+        /// Locations offered for the product.
+        available: Vec<Location>,
+    },
+
+    /// Distribution is not offered for the product.
+    #[error("distribution {distribution:?} is not offered for product {product}, available distributions are: {available:?}")]
+    Distribution {
+        /// Product being ordered.
+        product: ProductId,
+        /// Rejected distribution.
+        distribution: String,
+        /// Distributions offered for the product.
+        available: Vec<String>,
+    },
+
+    /// Language is not offered for the product.
+    #[error("language {language:?} is not offered for product {product}, available languages are: {available:?}")]
+    Language {
+        /// Product being ordered.
+        product: ProductId,
+        /// Rejected language.
+        language: String,
+        /// Languages offered for the product.
+        available: Vec<String>,
+    },
+
+    /// SSH key fingerprint is not in the MD5 format used by the Hetzner Robot API.
+    #[error("ssh key fingerprint {0} must be in md5 format")]
+    Fingerprint(Fingerprint),
+
+    /// Addon cannot be ordered for the product in the chosen location.
+    #[error("addon {addon} cannot be ordered for product {product} in location {location}")]
+    Addon {
+        /// Product being ordered.
+        product: ProductId,
+        /// Rejected addon.
+        addon: AddonId,
+        /// Location chosen for the order.
+        location: Location,
+    },
+}
+
+/// Builder for a [`ProductOrder`], which only accepts locations, distributions,
+/// languages and addons offered by the [`Product`] it was constructed from.
+///
+/// # Example
+/// ```rust,no_run
+/// # use hrobot::api::ordering::{AuthorizationMethod, Location, ProductId, ProductOrderBuilder};
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let robot = hrobot::AsyncRobot::default();
+/// let product = robot.get_product(&ProductId::from("EX44")).await?;
+///
+/// let order = ProductOrderBuilder::new(
+///     &product,
+///     Location::from("FSN1"),
+///     AuthorizationMethod::Keys(vec!["d7:34:1c:8c:4e:20:e0:1f:07:66:45:d9:97:22:ec:07".parse()?]),
+/// )?
+/// .distribution("Rescue system")?
+/// .language("en")?
+/// .build();
+///
+/// robot.place_product_order(order).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProductOrderBuilder<'a> {
+    product: &'a Product,
+    order: ProductOrder,
+}
+
+impl<'a> ProductOrderBuilder<'a> {
+    /// Start building an order for the product in the given location.
+    ///
+    /// Fails if the product is not available in the location, or if any
+    /// of the authorized key fingerprints are not in MD5 format.
+    pub fn new(
+        product: &'a Product,
+        location: Location,
+        auth: AuthorizationMethod,
+    ) -> Result<Self, InvalidProductOrder> {
+        if let Some(key) = auth.unsupported_fingerprint() {
+            return Err(InvalidProductOrder::Fingerprint(key));
+        }
+
+        if !product.locations.contains(&location) {
+            return Err(InvalidProductOrder::Location {
+                product: product.id.clone(),
+                location,
+                available: product.locations.clone(),
+            });
+        }
+
+        Ok(ProductOrderBuilder {
+            product,
+            order: ProductOrder {
+                id: product.id.clone(),
+                auth,
+                location,
+                distribution: None,
+                language: None,
+                comment: None,
+                addons: Vec::new(),
+                i_want_to_spend_money_to_purchase_a_server: ImSeriousAboutSpendingMoney::default(),
+            },
+        })
+    }
+
+    /// Distribution to install on the server.
+    ///
+    /// Fails if the distribution is not among [`Product::distributions`].
+    pub fn distribution(mut self, distribution: &str) -> Result<Self, InvalidProductOrder> {
+        if !self.product.distributions.iter().any(|d| d == distribution) {
+            return Err(InvalidProductOrder::Distribution {
+                product: self.product.id.clone(),
+                distribution: distribution.to_string(),
+                available: self.product.distributions.clone(),
+            });
+        }
+
+        self.order.distribution = Some(distribution.to_string());
+        Ok(self)
+    }
+
+    /// Language for the installed distribution.
+    ///
+    /// Fails if the language is not among [`Product::languages`].
+    pub fn language(mut self, language: &str) -> Result<Self, InvalidProductOrder> {
+        if !self.product.languages.iter().any(|l| l == language) {
+            return Err(InvalidProductOrder::Language {
+                product: self.product.id.clone(),
+                language: language.to_string(),
+                available: self.product.languages.clone(),
+            });
+        }
+
+        self.order.language = Some(language.to_string());
+        Ok(self)
+    }
+
+    /// Order an addon alongside the product.
+    ///
+    /// Fails if the addon is not among [`Product::orderable_addons`],
+    /// or is not available in the chosen location.
+    pub fn addon(mut self, addon: impl Into<AddonId>) -> Result<Self, InvalidProductOrder> {
+        let addon = addon.into();
+        let available = self.product.orderable_addons.iter().any(|orderable| {
+            orderable.id == addon
+                && orderable
+                    .location
+                    .as_ref()
+                    .is_none_or(|location| location == &self.order.location)
+        });
+
+        if !available {
+            return Err(InvalidProductOrder::Addon {
+                product: self.product.id.clone(),
+                addon,
+                location: self.order.location.clone(),
+            });
+        }
+
+        self.order.addons.push(addon);
+        Ok(self)
+    }
+
+    /// Comment for the order. Note that comments require manual provisioning,
+    /// which can increase the processing time for the purchase request.
+    pub fn comment(mut self, comment: &str) -> Self {
+        self.order.comment = Some(comment.to_string());
+        self
+    }
+
+    /// Set to [`ImSeriousAboutSpendingMoney::LetMeSpendMyMoneyAlready`] to actually
+    /// purchase the server. Orders are only simulated by default.
+    pub fn i_want_to_spend_money_to_purchase_a_server(
+        mut self,
+        confirmation: ImSeriousAboutSpendingMoney,
+    ) -> Self {
+        self.order.i_want_to_spend_money_to_purchase_a_server = confirmation;
+        self
+    }
+
+    /// Construct the [`ProductOrder`].
+    pub fn build(self) -> ProductOrder {
+        self.order
+    }
+}
+
 /// Hetzner Auction market order.
 ///
 /// Note: this is distinct from the [`ProductOrder`] which pertains to
@@ -1016,6 +1761,17 @@ pub struct MarketProductOrder {
     pub i_want_to_spend_money_to_purchase_a_server: ImSeriousAboutSpendingMoney,
 }
 
+impl MarketProductOrder {
+    /// Embed a [`CorrelationId`] in the comment of the order.
+    ///
+    /// Note that comments require manual provisioning, which can increase the
+    /// processing time for the purchase request.
+    pub fn with_correlation_id(mut self, id: &CorrelationId) -> Self {
+        self.comment = Some(id.tag(self.comment.as_deref()));
+        self
+    }
+}
+
 impl UrlEncode for MarketProductOrder {
     fn encode_into(&self, mut f: crate::urlencode::UrlEncodingBuffer<'_>) {
         f.set("product_id", self.id);
@@ -1114,9 +1870,9 @@ mod tests {
             ordering::{
                 AddonId, AddonTransaction, AuthorizationMethod, AvailableAddon,
                 ImSeriousAboutSpendingMoney, MarketProductId, MarketTransaction,
-                ProductTransaction,
+                ProductTransaction, Resource, ResourceKind, TransactionResourceId,
             },
-            wrapper::List,
+            wrapper::{List, Single},
         },
         urlencode::UrlEncode,
     };
@@ -1129,8 +1885,12 @@ mod tests {
         let a = MarketProductOrder {
             id: MarketProductId(100),
             auth: AuthorizationMethod::Keys(vec![
-                String::from("15:28:b0:03:95:f0:77:b3:10:56:15:6b:77:22:a5:aa"),
-                String::from("15:28:b0:03:95:f0:77:b3:10:56:15:6b:77:22:a5:bb"),
+                "15:28:b0:03:95:f0:77:b3:10:56:15:6b:77:22:a5:aa"
+                    .parse()
+                    .unwrap(),
+                "15:28:b0:03:95:f0:77:b3:10:56:15:6b:77:22:a5:bb"
+                    .parse()
+                    .unwrap(),
             ]),
             distribution: Some("Rescue System".to_string()),
             language: Some("en".to_string()),
@@ -1233,6 +1993,108 @@ mod tests {
         let transactions: List<ProductTransaction> = serde_json::from_str(example_data).unwrap();
 
         info!("{transactions:#?}");
+        assert!(transactions.0.iter().all(|transaction| transaction
+            .product
+            .architecture
+            .as_deref()
+            == Some("64")));
+    }
+
+    #[test]
+    #[traced_test]
+    fn deserialize_single_transaction() {
+        // This is the example shown in the API documentation:
+        // <https://robot.hetzner.com/doc/webservice/en.html#get-order-server-transaction-id>
+        let example_data = r#"
+            {
+                "transaction":{
+                    "id":"B20150121-344958-251479",
+                    "date":"2015-01-21T12:54:01+01:00",
+                    "status":"ready",
+                    "server_number":107239,
+                    "server_ip":"188.40.1.1",
+                    "authorized_key":[
+                        {
+                            "key":{
+                                "name":"key1",
+                                "fingerprint":"15:28:b0:03:95:f0:77:b3:10:56:15:6b:77:22:a5:bb",
+                                "type":"ED25519",
+                                "size":256
+                            }
+                        }
+                    ],
+                    "host_key":[
+                        {
+                            "key":{
+                                "fingerprint":"c1:e4:08:73:dd:f7:e9:d1:94:ab:e9:0f:28:b2:d2:ed",
+                                "type":"DSA",
+                                "size":1024
+                            }
+                        }
+                    ],
+                    "comment":null,
+                    "product":{
+                        "id":"EX40",
+                        "name":"Dedicated Root Server EX40",
+                        "description":[
+                            "Intel\u00ae Core\u2122 i7-4770 Quad-Core Haswell",
+                            "32 GB DDR3 RAM",
+                            "2 x 2 TB SATA 6 Gb\/s Enterprise HDD; 7200 rpm(Software-RAID 1)",
+                            "1 Gbit\/s bandwidth"
+                        ],
+                        "traffic":"30 TB",
+                        "dist":"Debian 7.7 minimal",
+                        "@deprecated arch":"64",
+                        "lang":"en",
+                        "location":"FSN1"
+                    },
+                    "addons":[]
+                }
+            }"#;
+
+        let transaction: Single<ProductTransaction> = serde_json::from_str(example_data).unwrap();
+
+        info!("{transaction:#?}");
+        assert_eq!(transaction.0.product.architecture.as_deref(), Some("64"));
+    }
+
+    #[test]
+    #[traced_test]
+    fn deserialize_transaction_with_unknown_keys() {
+        // Neither the deprecated architecture field, nor any keys added
+        // to the API in the future should prevent deserialization.
+        let example_data = r#"
+            {
+                "transaction":{
+                    "id":"B20150121-344957-251478",
+                    "date":"2015-01-21T12:30:43+01:00",
+                    "status":"in process",
+                    "server_number":null,
+                    "server_ip":null,
+                    "authorized_key":[],
+                    "host_key":[],
+                    "comment":null,
+                    "@deprecated foo":true,
+                    "product":{
+                        "id":"VX6",
+                        "name":"vServer VX6",
+                        "description":[],
+                        "traffic":"2 TB",
+                        "dist":"Rescue system",
+                        "lang":"en",
+                        "location":null,
+                        "@deprecated bar":{"baz":[1, 2, 3]},
+                        "unknown":null
+                    },
+                    "addons":[],
+                    "unknown":"value"
+                }
+            }"#;
+
+        let transaction: Single<ProductTransaction> = serde_json::from_str(example_data).unwrap();
+
+        info!("{transaction:#?}");
+        assert_eq!(transaction.0.product.architecture, None);
     }
 
     #[test]
@@ -1338,6 +2200,11 @@ mod tests {
 
         let transactions: List<MarketTransaction> = serde_json::from_str(example_data).unwrap();
         info!("{transactions:#?}");
+        assert!(transactions.0.iter().all(|transaction| transaction
+            .product
+            .architecture
+            .as_deref()
+            == Some("64")));
     }
 
     #[test]
@@ -1412,6 +2279,14 @@ mod tests {
         let transactions: List<AddonTransaction> = serde_json::from_str(example_data).unwrap();
 
         info!("{transactions:#?}");
+
+        assert_eq!(
+            transactions.0[1].resources,
+            [Resource {
+                kind: ResourceKind::Subnet,
+                id: TransactionResourceId::Ip("10.0.0.0".parse().unwrap()),
+            }]
+        );
     }
 
     #[test]
@@ -1463,7 +2338,7 @@ mod tests {
             }
           ]"#;
 
-        let data: List<AvailableAddon> = serde_json::from_str(&example_data).unwrap();
+        let data: List<AvailableAddon> = serde_json::from_str(example_data).unwrap();
 
         info!("{data:#?}");
     }
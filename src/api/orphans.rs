@@ -0,0 +1,318 @@
+//! Detection of resources which are likely forgotten.
+//!
+//! An [`OrphanReport`] lists resources which do not appear to be in use,
+//! as candidates for cleanup. Since the API does not expose actual usage,
+//! every category is a heuristic, and should be reviewed before acting on it.
+
+use futures_util::future;
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+use crate::{
+    error::{ApiError, Error},
+    AsyncRobot,
+};
+
+use super::{
+    bulk::{BulkExecutor, BulkOptions},
+    failover::Failover,
+    firewall::{Firewall, FirewallTemplate},
+    storagebox::{StorageBoxId, Subaccount},
+    vswitch::VSwitch,
+};
+
+impl AsyncRobot {
+    /// Find resources in the account which are likely forgotten, such as failover
+    /// IPs which are not routed anywhere and vSwitches without any servers.
+    ///
+    /// See [`OrphanReport`] for the criteria used for each category. Requests are
+    /// issued according to the given [`BulkOptions`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::{bulk::BulkOptions, orphans::OrphanCriteria};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let report = robot
+    ///     .find_orphans(OrphanCriteria::default(), BulkOptions::default())
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// for vswitch in &report.empty_vswitches {
+    ///     println!("vswitch {} has no servers", vswitch.name);
+    /// }
+    /// # }
+    /// ```
+    pub async fn find_orphans(
+        &self,
+        criteria: OrphanCriteria,
+        options: BulkOptions,
+    ) -> Result<OrphanReport, Error> {
+        let executor = BulkExecutor::for_robot(self, options);
+
+        let (servers, failovers, vswitches, templates, storageboxes) = future::try_join5(
+            executor.run(|| self.list_servers()),
+            executor.run(|| self.list_failover_ips()),
+            executor.run(|| self.list_vswitches()),
+            executor.run(|| self.list_firewall_templates()),
+            executor.run(|| self.list_storageboxes()),
+        )
+        .await?;
+
+        let unrouted_failover_ips = failovers
+            .into_iter()
+            .filter(|failover| failover.server_address.is_none())
+            .collect();
+
+        let empty_vswitches = executor
+            .bounded_try_join_all(vswitches.iter().map(|vswitch| vswitch.id), |vswitch| {
+                self.get_vswitch(vswitch)
+            })
+            .await?
+            .into_iter()
+            .filter(|vswitch| vswitch.servers.is_empty())
+            .collect();
+
+        let firewalls: Vec<Firewall> = executor
+            .bounded_try_join_all(
+                servers.iter().map(|server| server.id),
+                |server| async move {
+                    match self.get_firewall(server).await {
+                        Ok(firewall) => Ok(Some(firewall)),
+                        Err(Error::Api(ApiError::FirewallNotAvailable { .. }, _)) => Ok(None),
+                        Err(err) => Err(err),
+                    }
+                },
+            )
+            .await?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        let unused_firewall_templates = executor
+            .bounded_try_join_all(
+                templates
+                    .iter()
+                    .filter(|template| !template.is_default)
+                    .map(|template| template.id),
+                |template| self.get_firewall_template(template),
+            )
+            .await?
+            .into_iter()
+            .filter(|template| {
+                !firewalls
+                    .iter()
+                    .any(|firewall| template_matches(template, firewall))
+            })
+            .collect();
+
+        let subaccounts = executor
+            .bounded_try_join_all(
+                storageboxes.iter().map(|storagebox| storagebox.id),
+                |storagebox| async move {
+                    let subaccounts = self.list_subaccounts(storagebox).await?;
+                    Ok(subaccounts
+                        .into_iter()
+                        .map(move |subaccount| StorageBoxSubaccount {
+                            storagebox,
+                            subaccount,
+                        }))
+                },
+            )
+            .await?;
+
+        let cutoff = OffsetDateTime::now_utc() - criteria.subaccount_age;
+
+        let stale_subaccounts = subaccounts
+            .into_iter()
+            .flatten()
+            .filter(|entry| {
+                entry.subaccount.comment.trim().is_empty() && entry.subaccount.createtime < cutoff
+            })
+            .collect();
+
+        Ok(OrphanReport {
+            unrouted_failover_ips,
+            empty_vswitches,
+            unused_firewall_templates,
+            stale_subaccounts,
+        })
+    }
+}
+
+/// The API does not report which template a firewall was created from,
+/// so a template is considered in use if any firewall has the same content.
+fn template_matches(template: &FirewallTemplate, firewall: &Firewall) -> bool {
+    template.filter_ipv6 == firewall.filter_ipv6
+        && template.whitelist_hetzner_services == firewall.whitelist_hetzner_services
+        && template.rules == firewall.rules
+}
+
+/// Thresholds used by [`AsyncRobot::find_orphans`].
+#[derive(Debug, Clone)]
+pub struct OrphanCriteria {
+    /// Minimum age of a storagebox sub-account without a comment
+    /// before it is considered stale.
+    pub subaccount_age: Duration,
+}
+
+impl Default for OrphanCriteria {
+    /// Consider sub-accounts without a comment stale after 90 days.
+    fn default() -> Self {
+        OrphanCriteria {
+            subaccount_age: Duration::days(90),
+        }
+    }
+}
+
+impl OrphanCriteria {
+    /// Consider sub-accounts without a comment stale once they are older than `age`.
+    pub fn with_subaccount_age(mut self, age: Duration) -> Self {
+        self.subaccount_age = age;
+        self
+    }
+}
+
+/// Resources which are likely forgotten, as returned by [`AsyncRobot::find_orphans`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct OrphanReport {
+    /// Failover IPs which are not routed to any server.
+    pub unrouted_failover_ips: Vec<Failover>,
+
+    /// vSwitches without any connected servers.
+    pub empty_vswitches: Vec<VSwitch>,
+
+    /// Firewall templates whose rules and settings do not match the firewall of
+    /// any server. The default template is never included, since it applies to
+    /// newly ordered servers.
+    pub unused_firewall_templates: Vec<FirewallTemplate>,
+
+    /// Storagebox sub-accounts without a comment, which are older than
+    /// [`OrphanCriteria::subaccount_age`].
+    pub stale_subaccounts: Vec<StorageBoxSubaccount>,
+}
+
+impl OrphanReport {
+    /// True if no likely forgotten resources were found.
+    pub fn is_empty(&self) -> bool {
+        self.unrouted_failover_ips.is_empty()
+            && self.empty_vswitches.is_empty()
+            && self.unused_firewall_templates.is_empty()
+            && self.stale_subaccounts.is_empty()
+    }
+}
+
+/// Sub-account along with the storagebox it belongs to.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StorageBoxSubaccount {
+    /// Storagebox the sub-account belongs to.
+    pub storagebox: StorageBoxId,
+
+    /// The sub-account itself.
+    pub subaccount: Subaccount,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        api::{bulk::BulkOptions, firewall::TemplateId, vswitch::VSwitchId},
+        mock::{fixtures, MockClient},
+    };
+
+    use super::OrphanCriteria;
+
+    /// Serves an account with one of each kind of orphan, alongside
+    /// resources which are in use.
+    fn account_client() -> MockClient {
+        // Only the template matching the firewall of server 123 is in use.
+        let template = |id, name, filter_ipv6| {
+            fixtures::patch(
+                fixtures::firewall_template(id, name),
+                json!({"filter_ipv6": filter_ipv6}),
+            )
+        };
+
+        MockClient::new()
+            .on("GET", "/server", json!([fixtures::server(123)]))
+            .on("GET", "/firewall/123", fixtures::firewall(123, "active"))
+            .on(
+                "GET",
+                "/failover",
+                json!([
+                    fixtures::failover("2.2.2.2", 123, Some("1.1.1.123")),
+                    fixtures::failover("3.3.3.3", 123, None)
+                ]),
+            )
+            .on("GET", "/vswitch", json!([fixtures::vswitch(1)]))
+            .on("GET", "/vswitch/1", fixtures::vswitch(1))
+            .on(
+                "GET",
+                "/firewall/template",
+                json!([
+                    template(1, "used", false),
+                    template(2, "unused", true),
+                    fixtures::patch(
+                        fixtures::firewall_template(3, "default"),
+                        json!({"is_default": true})
+                    )
+                ]),
+            )
+            .on(
+                "GET",
+                "/firewall/template/1",
+                fixtures::patch(
+                    template(1, "used", false),
+                    json!({"rules": {"input": [], "output": []}}),
+                ),
+            )
+            .on(
+                "GET",
+                "/firewall/template/2",
+                fixtures::patch(
+                    template(2, "unused", true),
+                    json!({"rules": {"input": [], "output": []}}),
+                ),
+            )
+            .on("GET", "/storagebox", json!([fixtures::storagebox(1234)]))
+            .on(
+                "GET",
+                "/storagebox/1234/subaccount",
+                json!([
+                    fixtures::subaccount(1234, "sub1", ""),
+                    fixtures::subaccount(1234, "sub2", "offsite backups")
+                ]),
+            )
+    }
+
+    #[tokio::test]
+    async fn find_orphans() {
+        let robot = account_client().robot();
+
+        let report = robot
+            .find_orphans(OrphanCriteria::default(), BulkOptions::default())
+            .await
+            .unwrap();
+
+        assert!(!report.is_empty());
+
+        assert_eq!(report.unrouted_failover_ips.len(), 1);
+        assert_eq!(report.unrouted_failover_ips[0].ip.to_string(), "3.3.3.3/32");
+
+        assert_eq!(report.empty_vswitches.len(), 1);
+        assert_eq!(report.empty_vswitches[0].id, VSwitchId(1));
+
+        assert_eq!(report.unused_firewall_templates.len(), 1);
+        assert_eq!(report.unused_firewall_templates[0].id, TemplateId(2));
+
+        assert_eq!(report.stale_subaccounts.len(), 1);
+        assert_eq!(
+            report.stale_subaccounts[0].subaccount.username.0,
+            "u1234-sub1"
+        );
+    }
+}
@@ -1,13 +1,17 @@
 //! Reverse DNS structs and implementations.
 
-use std::net::IpAddr;
+use std::{collections::HashMap, net::IpAddr};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{error::Error, AsyncRobot};
+use crate::{
+    error::{ApiError, Error},
+    AsyncRobot,
+};
 
 use super::{
-    wrapper::{Empty, List, Single},
+    bulk::{BulkExecutor, BulkOptions},
+    wrapper::{Empty, List, OrEmpty, Single},
     UnauthenticatedRequest,
 };
 
@@ -60,7 +64,7 @@ impl AsyncRobot {
     /// # }
     /// ```
     pub async fn list_rdns_entries(&self) -> Result<Vec<RdnsEntry>, Error> {
-        Ok(self.go(list_rdns_entries()).await?.0)
+        Ok(self.go(list_rdns_entries()).await.or_empty()?.0)
     }
 
     /// Get Reverse DNS entry for IP address.
@@ -119,12 +123,80 @@ impl AsyncRobot {
         self.go(delete_rdns_entry(ip)).await?.throw_away();
         Ok(())
     }
+
+    /// Create or update Reverse DNS entries for many IP addresses at once.
+    ///
+    /// Each entry is updated, or created if the IP address has no Reverse DNS entry yet.
+    /// Requests are issued according to the given [`BulkOptions`]. If the API rate limit
+    /// is exceeded, the affected entry is retried after waiting long enough
+    /// for the rate limit to allow another request.
+    ///
+    /// Returns the outcome of each individual entry, keyed by IP address. IP addresses
+    /// given more than once are not set at all, and fail with [`Error::DuplicateIp`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::bulk::BulkOptions;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let results = robot.set_rdns_bulk([
+    ///     ("123.123.123.123".parse().unwrap(), "one.example.com"),
+    ///     ("123.123.123.124".parse().unwrap(), "two.example.com"),
+    /// ], BulkOptions::default()).await;
+    ///
+    /// for (ip, result) in results {
+    ///     if let Err(err) = result {
+    ///         println!("failed to set rdns entry for {ip}: {err}");
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub async fn set_rdns_bulk<'a>(
+        &self,
+        entries: impl IntoIterator<Item = (IpAddr, &'a str)>,
+        options: BulkOptions,
+    ) -> HashMap<IpAddr, Result<RdnsEntry, Error>> {
+        let entries: Vec<_> = entries.into_iter().collect();
+
+        let mut occurrences = HashMap::<IpAddr, usize>::new();
+        for (ip, _) in &entries {
+            *occurrences.entry(*ip).or_default() += 1;
+        }
+
+        let (unique, duplicates): (Vec<_>, Vec<_>) = entries
+            .into_iter()
+            .partition(|(ip, _)| occurrences[ip] == 1);
+
+        let results = BulkExecutor::for_robot(self, options)
+            .bounded_join_all(unique.iter().copied(), |(ip, ptr)| async move {
+                match self.update_rdns_entry(ip, ptr).await {
+                    Err(Error::Api(ApiError::RdnsNotFound { .. }, _)) => {
+                        self.create_rdns_entry(ip, ptr).await
+                    }
+                    result => result,
+                }
+            })
+            .await;
+
+        unique
+            .into_iter()
+            .map(|(ip, _)| ip)
+            .zip(results)
+            .chain(
+                duplicates
+                    .into_iter()
+                    .map(|(ip, _)| (ip, Err(Error::DuplicateIp(ip)))),
+            )
+            .collect()
+    }
 }
 
 /// Reverse DNS Entry.
 ///
 /// Maps an IP address to a single domain.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct RdnsEntry {
     /// IP Address this entry represents.
     pub ip: IpAddr,
@@ -132,3 +204,108 @@ pub struct RdnsEntry {
     /// The target domain/record.
     pub ptr: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use hyper::StatusCode;
+
+    use crate::{
+        api::bulk::BulkOptions,
+        error::Error,
+        mock::{fixtures, MockClient, Reply},
+    };
+
+    #[tokio::test]
+    async fn set_rdns_bulk() {
+        // Updates are echoed back, except for the ip 10.0.0.13, which does not exist,
+        // and the ip 10.0.0.14, which has no entry yet, so only creating it succeeds.
+        let client = MockClient::new()
+            .on(
+                "*",
+                "/rdns/10.0.0.13",
+                Reply::error(StatusCode::NOT_FOUND, "IP_NOT_FOUND", "IP not found"),
+            )
+            .on(
+                "POST",
+                "/rdns/10.0.0.14",
+                Reply::error(
+                    StatusCode::NOT_FOUND,
+                    "RDNS_NOT_FOUND",
+                    "RDNS entry not found",
+                ),
+            )
+            .on_with("*", "/rdns/*", |request| {
+                let ip = request.uri().path().rsplit_once('/').unwrap().1;
+                let ptr = request.body().strip_prefix("ptr=").unwrap();
+
+                fixtures::rdns(ip, ptr).into()
+            });
+        let requests = client.requests();
+
+        let results = client
+            .robot()
+            .set_rdns_bulk(
+                (10..20).map(|i| {
+                    (
+                        format!("10.0.0.{i}").parse::<IpAddr>().unwrap(),
+                        "server.example.com",
+                    )
+                }),
+                BulkOptions::default(),
+            )
+            .await;
+
+        assert_eq!(results.len(), 10);
+        assert_eq!(
+            requests
+                .lines()
+                .iter()
+                .filter(|line| line.ends_with("/10.0.0.14"))
+                .collect::<Vec<_>>(),
+            ["POST /rdns/10.0.0.14", "PUT /rdns/10.0.0.14"]
+        );
+
+        for (ip, result) in results {
+            if ip == "10.0.0.13".parse::<IpAddr>().unwrap() {
+                assert!(result.is_err());
+            } else {
+                let entry = result.unwrap();
+                assert_eq!(entry.ip, ip);
+                assert_eq!(entry.ptr, "server.example.com");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn set_rdns_bulk_duplicates() {
+        let client = MockClient::new().on_with("*", "/rdns/*", |request| {
+            let ip = request.uri().path().rsplit_once('/').unwrap().1;
+            let ptr = request.body().strip_prefix("ptr=").unwrap();
+
+            fixtures::rdns(ip, ptr).into()
+        });
+        let requests = client.requests();
+
+        let duplicate = "10.0.0.10".parse::<IpAddr>().unwrap();
+        let results = client
+            .robot()
+            .set_rdns_bulk(
+                [
+                    (duplicate, "one.example.com"),
+                    ("10.0.0.11".parse().unwrap(), "two.example.com"),
+                    (duplicate, "three.example.com"),
+                ],
+                BulkOptions::default(),
+            )
+            .await;
+
+        assert_eq!(results.len(), 2);
+        assert!(matches!(
+            results[&duplicate],
+            Err(Error::DuplicateIp(ip)) if ip == duplicate
+        ));
+        assert_eq!(requests.lines(), ["POST /rdns/10.0.0.11"]);
+    }
+}
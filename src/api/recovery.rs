@@ -0,0 +1,441 @@
+//! Disaster recovery bundle structs and implementation.
+//!
+//! A [`DisasterRecoveryBundle`] captures the recoverable configuration of an
+//! entire Robot account in a single serializable structure, which can be stored
+//! alongside disaster recovery runbooks and later re-applied using
+//! [`AsyncRobot::apply_dr_bundle`].
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::{
+    error::{ApiError, Error},
+    AsyncRobot,
+};
+
+use super::{
+    boot::ActiveConfig,
+    firewall::{FirewallConfig, FirewallTemplate, State},
+    rdns::RdnsEntry,
+    server::ServerId,
+    storagebox::{Accessibility, SnapshotPlan, StorageBoxId},
+    vswitch::{VSwitchId, VlanId},
+};
+
+impl AsyncRobot {
+    /// Export the recoverable configuration of the account into a [`DisasterRecoveryBundle`].
+    ///
+    /// The bundle contains server firewalls, firewall templates, reverse DNS entries,
+    /// vSwitch layouts, storagebox settings and currently active boot configurations.
+    ///
+    /// No secrets such as passwords are included in the bundle.
+    ///
+    /// Note that this issues a large number of requests, proportional to the
+    /// number of resources in the account.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let bundle = robot.export_dr_bundle().await.unwrap();
+    /// println!("{}", serde_json::to_string_pretty(&bundle).unwrap());
+    /// # }
+    /// ```
+    pub async fn export_dr_bundle(&self) -> Result<DisasterRecoveryBundle, Error> {
+        let mut firewalls = Vec::new();
+        let mut boot = Vec::new();
+
+        for server in self.list_servers().await? {
+            match self.get_firewall(server.id).await {
+                Ok(firewall) => {
+                    let mut config = firewall.config();
+
+                    // A firewall which is still processing a previous change
+                    // will end up active, and "in process" can't be applied.
+                    if config.status == State::InProcess {
+                        config.status = State::Active;
+                    }
+
+                    firewalls.push(ServerFirewall {
+                        server: server.id,
+                        config,
+                    });
+                }
+                Err(Error::Api(ApiError::FirewallNotAvailable { .. }, _)) => (),
+                Err(err) => return Err(err),
+            }
+
+            boot.push(ServerBootState {
+                server: server.id,
+                active: self
+                    .get_boot_config(server.id)
+                    .await?
+                    .active()
+                    .as_ref()
+                    .map(BootSystem::from),
+            });
+        }
+
+        let mut firewall_templates = Vec::new();
+        for template in self.list_firewall_templates().await? {
+            firewall_templates.push(self.get_firewall_template(template.id).await?);
+        }
+
+        let mut vswitches = Vec::new();
+        for vswitch in self.list_vswitches().await? {
+            if vswitch.cancelled {
+                continue;
+            }
+
+            let vswitch = self.get_vswitch(vswitch.id).await?;
+
+            vswitches.push(VSwitchLayout {
+                id: vswitch.id,
+                name: vswitch.name,
+                vlan: vswitch.vlan,
+                servers: vswitch
+                    .servers
+                    .into_iter()
+                    .map(|server| server.id)
+                    .collect(),
+            });
+        }
+
+        let mut storageboxes = Vec::new();
+        for storagebox in self.list_storageboxes().await? {
+            if storagebox.cancelled {
+                continue;
+            }
+
+            let storagebox = self.get_storagebox(storagebox.id).await?;
+
+            storageboxes.push(StorageBoxSettings {
+                id: storagebox.id,
+                name: storagebox.name,
+                accessibility: storagebox.accessibility,
+                snapshot_directory: storagebox.snapshot_directory,
+                snapshot_plan: self.get_snapshot_plan(storagebox.id).await?,
+            });
+        }
+
+        Ok(DisasterRecoveryBundle {
+            created_at: OffsetDateTime::now_utc(),
+            firewalls,
+            firewall_templates,
+            rdns: self.list_rdns_entries().await?,
+            vswitches,
+            storageboxes,
+            boot,
+        })
+    }
+
+    /// Re-apply the recoverable parts of a [`DisasterRecoveryBundle`].
+    ///
+    /// * Firewall templates are updated, or re-created if they no longer exist,
+    ///   in which case they will be assigned a new [`TemplateId`](crate::api::firewall::TemplateId).
+    /// * Server firewalls are overwritten with the configuration from the bundle.
+    /// * Reverse DNS entries are updated, or created if missing.
+    /// * vSwitches are renamed and have their VLAN ID updated, or are re-created
+    ///   if they no longer exist. Servers missing from the vSwitch are connected,
+    ///   but servers not present in the bundle are left untouched.
+    /// * Storageboxes have their name, accessibility, snapshot directory and
+    ///   snapshot plan restored.
+    ///
+    /// Boot configurations are *not* re-applied, since activating them requires
+    /// a reset of the server and produces new credentials.
+    ///
+    /// Application stops at the first error encountered.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::recovery::DisasterRecoveryBundle;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let bundle: DisasterRecoveryBundle = serde_json::from_str(
+    ///     &std::fs::read_to_string("bundle.json").unwrap()
+    /// ).unwrap();
+    ///
+    /// robot.apply_dr_bundle(&bundle).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn apply_dr_bundle(&self, bundle: &DisasterRecoveryBundle) -> Result<(), Error> {
+        for template in &bundle.firewall_templates {
+            let config = template.config();
+
+            match self
+                .update_firewall_template(template.id, config.clone())
+                .await
+            {
+                Ok(_) => (),
+                Err(Error::Api(ApiError::FirewallTemplateNotFound { .. }, _)) => {
+                    let _ = self.create_firewall_template(config).await?;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        for firewall in &bundle.firewalls {
+            let _ = self
+                .set_firewall_config(firewall.server, &firewall.config)
+                .await?;
+        }
+
+        for entry in &bundle.rdns {
+            match self.update_rdns_entry(entry.ip, &entry.ptr).await {
+                Ok(_) => (),
+                Err(Error::Api(ApiError::RdnsNotFound { .. }, _)) => {
+                    let _ = self.create_rdns_entry(entry.ip, &entry.ptr).await?;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        for layout in &bundle.vswitches {
+            let (id, connected) = match self.get_vswitch(layout.id).await {
+                Ok(vswitch) if !vswitch.cancelled => {
+                    if vswitch.name != layout.name || vswitch.vlan != layout.vlan {
+                        self.update_vswitch(vswitch.id, &layout.name, layout.vlan)
+                            .await?;
+                    }
+
+                    (
+                        vswitch.id,
+                        vswitch
+                            .servers
+                            .into_iter()
+                            .map(|server| server.id)
+                            .collect(),
+                    )
+                }
+                Ok(_) | Err(Error::Api(ApiError::NotFound { .. }, _)) => (
+                    self.create_vswitch(&layout.name, layout.vlan).await?.id,
+                    Vec::new(),
+                ),
+                Err(err) => return Err(err),
+            };
+
+            let missing: Vec<_> = layout
+                .servers
+                .iter()
+                .filter(|server| !connected.contains(server))
+                .copied()
+                .collect();
+
+            if !missing.is_empty() {
+                self.connect_vswitch_servers(id, &missing).await?;
+            }
+        }
+
+        for storagebox in &bundle.storageboxes {
+            let _ = self
+                .rename_storagebox(storagebox.id, &storagebox.name)
+                .await?;
+
+            let _ = self
+                .configure_storagebox_accessibility(storagebox.id, storagebox.accessibility.clone())
+                .await?;
+
+            let _ = if storagebox.snapshot_directory {
+                self.enable_storagebox_snapshot_directory(storagebox.id)
+                    .await?
+            } else {
+                self.disable_storagebox_snapshot_directory(storagebox.id)
+                    .await?
+            };
+
+            let _ = self
+                .update_snapshot_plan(storagebox.id, storagebox.snapshot_plan.clone())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Snapshot of the recoverable configuration of an entire Robot account.
+///
+/// Produced by [`AsyncRobot::export_dr_bundle`] and re-applied using
+/// [`AsyncRobot::apply_dr_bundle`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DisasterRecoveryBundle {
+    /// Point in time at which the bundle was exported.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+
+    /// Firewall configuration of every server which supports a firewall.
+    pub firewalls: Vec<ServerFirewall>,
+
+    /// All firewall templates, including their rules.
+    pub firewall_templates: Vec<FirewallTemplate>,
+
+    /// All reverse DNS entries.
+    pub rdns: Vec<RdnsEntry>,
+
+    /// Layout of all active vSwitches.
+    pub vswitches: Vec<VSwitchLayout>,
+
+    /// Settings of all active storageboxes.
+    pub storageboxes: Vec<StorageBoxSettings>,
+
+    /// Currently active boot configuration of each server.
+    ///
+    /// This is for documentation purposes only, and is not re-applied.
+    pub boot: Vec<ServerBootState>,
+}
+
+/// Firewall configuration of a single server.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ServerFirewall {
+    /// Server which the firewall applies to.
+    pub server: ServerId,
+
+    /// Firewall configuration of the server.
+    pub config: FirewallConfig,
+}
+
+/// Layout of a single vSwitch.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct VSwitchLayout {
+    /// Unique vSwitch ID.
+    pub id: VSwitchId,
+
+    /// Name of the vSwitch.
+    pub name: String,
+
+    /// VLAN ID of the vSwitch.
+    pub vlan: VlanId,
+
+    /// Servers connected to the vSwitch.
+    pub servers: Vec<ServerId>,
+}
+
+/// Recoverable settings of a single storagebox.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StorageBoxSettings {
+    /// Unique storagebox ID.
+    pub id: StorageBoxId,
+
+    /// Human-readable name of the storagebox.
+    pub name: String,
+
+    /// Services through which the storagebox is accessible.
+    pub accessibility: Accessibility,
+
+    /// Whether the snapshot directory is visible.
+    pub snapshot_directory: bool,
+
+    /// Snapshot plan of the storagebox.
+    pub snapshot_plan: SnapshotPlan,
+}
+
+/// Boot configuration system active for a single server, if any.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ServerBootState {
+    /// Server which the boot configuration applies to.
+    pub server: ServerId,
+
+    /// Currently active boot configuration system, if any.
+    pub active: Option<BootSystem>,
+}
+
+/// Boot configuration system.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum BootSystem {
+    /// Rescue system.
+    Rescue,
+    /// Linux installation.
+    Linux,
+    /// VNC installation.
+    Vnc,
+    /// Windows installation.
+    Windows,
+    /// Plesk installation.
+    Plesk,
+    /// CPanel installation.
+    Cpanel,
+}
+
+impl From<&ActiveConfig> for BootSystem {
+    fn from(value: &ActiveConfig) -> Self {
+        match value {
+            ActiveConfig::Rescue(_) => BootSystem::Rescue,
+            ActiveConfig::Linux(_) => BootSystem::Linux,
+            ActiveConfig::Vnc(_) => BootSystem::Vnc,
+            ActiveConfig::Windows(_) => BootSystem::Windows,
+            ActiveConfig::Plesk(_) => BootSystem::Plesk,
+            ActiveConfig::CPanel(_) => BootSystem::Cpanel,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::api::{
+        firewall::{FirewallConfig, Ipv4Filter, Rule, Rules, State},
+        server::ServerId,
+        vswitch::{VSwitchId, VlanId},
+    };
+
+    use super::{
+        BootSystem, DisasterRecoveryBundle, ServerBootState, ServerFirewall, VSwitchLayout,
+    };
+
+    #[test]
+    fn bundle_roundtrip() {
+        let bundle = DisasterRecoveryBundle {
+            created_at: datetime!(2024-05-01 12:33:00 UTC),
+            firewalls: vec![ServerFirewall {
+                server: ServerId(1234567),
+                config: FirewallConfig {
+                    status: State::Active,
+                    filter_ipv6: false,
+                    whitelist_hetzner_services: true,
+                    rules: Rules {
+                        ingress: vec![
+                            Rule::accept("Allow SSH").matching(Ipv4Filter::tcp(None).to_port(22))
+                        ],
+                        egress: vec![Rule::accept("Allow all")],
+                    },
+                },
+            }],
+            firewall_templates: vec![],
+            rdns: vec![],
+            vswitches: vec![VSwitchLayout {
+                id: VSwitchId(12345),
+                name: "vswitch".to_string(),
+                vlan: VlanId::MIN,
+                servers: vec![ServerId(1234567)],
+            }],
+            storageboxes: vec![],
+            boot: vec![ServerBootState {
+                server: ServerId(1234567),
+                active: Some(BootSystem::Rescue),
+            }],
+        };
+
+        let serialized = serde_json::to_string(&bundle).unwrap();
+        let deserialized: DisasterRecoveryBundle = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(deserialized.created_at, bundle.created_at);
+        assert_eq!(
+            deserialized.firewalls[0].config.rules,
+            bundle.firewalls[0].config.rules
+        );
+        assert_eq!(deserialized.vswitches, bundle.vswitches);
+        assert_eq!(deserialized.boot, bundle.boot);
+    }
+}
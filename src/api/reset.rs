@@ -7,15 +7,17 @@ use crate::{error::Error, AsyncRobot};
 
 use super::{
     server::ServerId,
-    wrapper::{List, Single},
+    wrapper::{List, OrEmpty, Single},
     UnauthenticatedRequest,
 };
 
-fn list_reset_options() -> UnauthenticatedRequest<List<ResetOptions>> {
+fn list_reset_options() -> UnauthenticatedRequest<List<ServerResetOptions>> {
     UnauthenticatedRequest::from("https://robot-ws.your-server.de/reset")
 }
 
-fn get_reset_options(server_number: ServerId) -> UnauthenticatedRequest<Single<ResetOptions>> {
+fn get_reset_options(
+    server_number: ServerId,
+) -> UnauthenticatedRequest<Single<ServerResetOptions>> {
     UnauthenticatedRequest::from(&format!(
         "https://robot-ws.your-server.de/reset/{server_number}"
     ))
@@ -46,7 +48,8 @@ impl AsyncRobot {
     pub async fn list_reset_options(&self) -> Result<HashMap<ServerId, Vec<Reset>>, Error> {
         Ok(self
             .go(list_reset_options())
-            .await?
+            .await
+            .or_empty()?
             .0
             .into_iter()
             .map(|option| (option.server_number, option.options))
@@ -64,12 +67,19 @@ impl AsyncRobot {
     /// robot.get_reset_options(ServerId(1234567)).await.unwrap();
     /// # }
     /// ```
-    pub async fn get_reset_options(&self, server_number: ServerId) -> Result<Vec<Reset>, Error> {
+    pub async fn get_reset_options(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<Vec<Reset>, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_reset_options(server_number)).await?.0.options)
     }
 
     /// Trigger a reset for the server.
     ///
+    /// Use [`AsyncRobot::trigger_reset_with`] to verify that the server
+    /// supports the reset type before triggering it.
+    ///
     /// # Example
     /// ```rust,no_run
     /// # use hrobot::api::server::ServerId;
@@ -82,11 +92,88 @@ impl AsyncRobot {
     /// ```
     pub async fn trigger_reset(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
         reset: Reset,
     ) -> Result<Reset, Error> {
+        let server_number = server_number.into();
         Ok(self.go(trigger_reset(server_number, reset)?).await?.0.reset)
     }
+
+    /// Trigger a reset for the server, as configured by `options`.
+    ///
+    /// With [validation](ResetOptions::with_validation) enabled, the reset options of the
+    /// server are retrieved first, and resets not supported by the server fail with
+    /// [`Error::ResetUnsupported`] listing the supported reset types, instead of being
+    /// rejected by the API with a less descriptive error.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::server::ServerId;
+    /// # use hrobot::api::reset::{Reset, ResetOptions};
+    /// # use hrobot::error::Error;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// match robot.trigger_reset_with(
+    ///     ServerId(1234567),
+    ///     Reset::Hardware,
+    ///     ResetOptions::default().with_validation(true),
+    /// ).await {
+    ///     Err(Error::ResetUnsupported { supported, .. }) => {
+    ///         println!("server only supports {supported:?}");
+    ///     }
+    ///     result => { result.unwrap(); }
+    /// }
+    /// # }
+    /// ```
+    pub async fn trigger_reset_with(
+        &self,
+        server_number: impl Into<ServerId>,
+        reset: Reset,
+        options: ResetOptions,
+    ) -> Result<Reset, Error> {
+        let server_number = server_number.into();
+
+        if options.validate {
+            let supported = self.get_reset_options(server_number).await?;
+            ensure_supported(&reset, supported)?;
+        }
+
+        self.trigger_reset(server_number, reset).await
+    }
+}
+
+/// Fail with [`Error::ResetUnsupported`] unless `reset` is among the `supported` resets.
+pub(crate) fn ensure_supported(reset: &Reset, supported: Vec<Reset>) -> Result<(), Error> {
+    if supported.contains(reset) {
+        Ok(())
+    } else {
+        Err(Error::ResetUnsupported {
+            reset: reset.clone(),
+            supported,
+        })
+    }
+}
+
+/// Configures how [`AsyncRobot::trigger_reset_with`] triggers a reset.
+///
+/// # Example
+/// ```rust
+/// # use hrobot::api::reset::ResetOptions;
+/// let options = ResetOptions::default().with_validation(true);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ResetOptions {
+    /// Verify that the server supports the reset type before triggering it.
+    pub validate: bool,
+}
+
+impl ResetOptions {
+    /// Verify that the server supports the reset type before triggering it.
+    pub fn with_validation(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -96,14 +183,15 @@ struct ExecuteReset {
 }
 
 #[derive(Serialize, Deserialize)]
-struct ResetOptions {
+struct ServerResetOptions {
     server_number: ServerId,
     #[serde(rename = "type")]
     options: Vec<Reset>,
 }
 
 /// Kind of reset to perform.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Reset {
     /// Request a manual power cycle, by Hetzner staff.
     ///
@@ -168,3 +256,69 @@ pub enum Reset {
     #[serde(untagged)]
     Other(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        api::server::ServerId,
+        error::Error,
+        mock::{fixtures, MockClient},
+    };
+
+    use super::{Reset, ResetOptions};
+
+    #[tokio::test]
+    async fn validated_reset() {
+        // The server only supports software resets.
+        let client = MockClient::new()
+            .on(
+                "GET",
+                "/reset/321",
+                fixtures::reset(321, json!(["sw", "man"])),
+            )
+            .on("POST", "/reset/321", fixtures::reset(321, json!("sw")));
+        let requests = client.requests();
+        let robot = client.robot();
+        let validated = ResetOptions::default().with_validation(true);
+
+        let err = robot
+            .trigger_reset_with(ServerId(321), Reset::Hardware, validated.clone())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            &err,
+            Error::ResetUnsupported { reset: Reset::Hardware, supported }
+                if supported == &[Reset::Software, Reset::Manual]
+        ));
+        assert_eq!(requests.lines(), ["GET /reset/321"]);
+
+        assert_eq!(
+            robot
+                .trigger_reset_with(ServerId(321), Reset::Software, validated)
+                .await
+                .unwrap(),
+            Reset::Software
+        );
+        assert_eq!(
+            requests.lines(),
+            ["GET /reset/321", "GET /reset/321", "POST /reset/321"]
+        );
+
+        // Without validation, the reset is sent as-is.
+        let _ = robot
+            .trigger_reset_with(ServerId(321), Reset::Hardware, ResetOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            requests.lines(),
+            [
+                "GET /reset/321",
+                "GET /reset/321",
+                "POST /reset/321",
+                "POST /reset/321"
+            ]
+        );
+    }
+}
@@ -0,0 +1,240 @@
+//! Structured hardware descriptions.
+//!
+//! The Robot API does not expose hardware health data such as SMART summaries
+//! or drive serials, but products and market products do come with a
+//! human-readable description of their hardware, e.g. `"2 x 2 TB SATA HDD"`.
+//! This module parses those descriptions into [`HardwareComponent`]s on a
+//! best-effort basis.
+
+use std::str::FromStr;
+
+use bytesize::ByteSize;
+
+/// Type of storage drive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DriveKind {
+    /// Spinning hard disk drive.
+    Hdd,
+    /// SATA or SAS solid state drive.
+    Ssd,
+    /// NVMe solid state drive.
+    Nvme,
+}
+
+/// Hardware component parsed from a single line of a product description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HardwareComponent {
+    /// Processor.
+    Cpu {
+        /// Model of the CPU, e.g. `Intel Core i7 980x`.
+        model: String,
+    },
+
+    /// Memory modules.
+    Memory {
+        /// Number of modules.
+        quantity: u8,
+        /// Capacity of each module.
+        capacity: ByteSize,
+        /// Original description line.
+        description: String,
+    },
+
+    /// Storage drives.
+    Drive {
+        /// Number of drives.
+        quantity: u8,
+        /// Capacity of each drive.
+        capacity: ByteSize,
+        /// Type of drive.
+        kind: DriveKind,
+        /// Original description line.
+        description: String,
+    },
+
+    /// Description line which could not be identified as any of the above,
+    /// e.g. `1 Gbit/s bandwidth`.
+    Other(String),
+}
+
+impl HardwareComponent {
+    /// Parse a single line of a product description.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use hrobot::api::server::hardware::{DriveKind, HardwareComponent};
+    /// # use hrobot::bytesize::ByteSize;
+    /// assert_eq!(
+    ///     HardwareComponent::parse("2x SSD 120 GB SATA"),
+    ///     HardwareComponent::Drive {
+    ///         quantity: 2,
+    ///         capacity: ByteSize::gb(120),
+    ///         kind: DriveKind::Ssd,
+    ///         description: "2x SSD 120 GB SATA".to_string(),
+    ///     }
+    /// );
+    /// ```
+    pub fn parse(line: &str) -> Self {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let lowercase = line.to_lowercase();
+
+        let (quantity, rest) = quantity(&tokens);
+
+        let Some(capacity) = capacity(rest) else {
+            return if is_cpu(&lowercase) {
+                HardwareComponent::Cpu {
+                    model: line.trim().to_string(),
+                }
+            } else {
+                HardwareComponent::Other(line.to_string())
+            };
+        };
+
+        let description = line.to_string();
+
+        if lowercase.contains("ram") {
+            HardwareComponent::Memory {
+                quantity,
+                capacity,
+                description,
+            }
+        } else if let Some(kind) = drive_kind(&lowercase) {
+            HardwareComponent::Drive {
+                quantity,
+                capacity,
+                kind,
+                description,
+            }
+        } else {
+            HardwareComponent::Other(description)
+        }
+    }
+
+    /// Parse every line of a product description.
+    pub fn parse_description<S: AsRef<str>>(description: &[S]) -> Vec<Self> {
+        description
+            .iter()
+            .map(|line| HardwareComponent::parse(line.as_ref()))
+            .collect()
+    }
+}
+
+/// Extract a leading quantity such as `2x` or `2 x`, defaulting to 1.
+fn quantity<'a, 'b>(tokens: &'a [&'b str]) -> (u8, &'a [&'b str]) {
+    match tokens {
+        [count, "x", rest @ ..] => match count.parse() {
+            Ok(count) => (count, rest),
+            Err(_) => (1, tokens),
+        },
+        [count, rest @ ..] => match count.strip_suffix('x').map(str::parse) {
+            Some(Ok(count)) => (count, rest),
+            _ => (1, tokens),
+        },
+        [] => (1, tokens),
+    }
+}
+
+/// Find the first `<number> <unit>` pair, e.g. `1,5 TB`.
+fn capacity(tokens: &[&str]) -> Option<ByteSize> {
+    tokens.windows(2).find_map(|pair| {
+        let number = pair[0].replace(',', ".");
+        let unit = pair[1];
+
+        if number.parse::<f64>().is_err() || !matches!(unit, "MB" | "GB" | "TB" | "PB") {
+            return None;
+        }
+
+        ByteSize::from_str(&format!("{number} {unit}")).ok()
+    })
+}
+
+fn drive_kind(lowercase: &str) -> Option<DriveKind> {
+    if lowercase.contains("nvme") {
+        Some(DriveKind::Nvme)
+    } else if lowercase.contains("ssd") {
+        Some(DriveKind::Ssd)
+    } else if lowercase.contains("hdd") {
+        Some(DriveKind::Hdd)
+    } else {
+        None
+    }
+}
+
+fn is_cpu(lowercase: &str) -> bool {
+    ["cpu", "intel", "amd", "xeon", "ryzen", "epyc", "opteron"]
+        .iter()
+        .any(|keyword| lowercase.contains(keyword))
+}
+
+#[cfg(test)]
+mod tests {
+    use bytesize::ByteSize;
+
+    use super::{DriveKind, HardwareComponent};
+
+    #[test]
+    fn parse_product_descriptions() {
+        assert_eq!(
+            HardwareComponent::parse_description(&[
+                "Intel\u{ae} Core\u{2122} i7-4770 Quad-Core Haswell",
+                "32 GB DDR3 RAM",
+                "2 x 2 TB SATA 6 Gb/s Enterprise HDD; 7200 rpm(Software-RAID 1)",
+                "1 Gbit/s bandwidth",
+            ]),
+            vec![
+                HardwareComponent::Cpu {
+                    model: "Intel\u{ae} Core\u{2122} i7-4770 Quad-Core Haswell".to_string()
+                },
+                HardwareComponent::Memory {
+                    quantity: 1,
+                    capacity: ByteSize::gb(32),
+                    description: "32 GB DDR3 RAM".to_string(),
+                },
+                HardwareComponent::Drive {
+                    quantity: 2,
+                    capacity: ByteSize::tb(2),
+                    kind: DriveKind::Hdd,
+                    description: "2 x 2 TB SATA 6 Gb/s Enterprise HDD; 7200 rpm(Software-RAID 1)"
+                        .to_string(),
+                },
+                HardwareComponent::Other("1 Gbit/s bandwidth".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_market_descriptions() {
+        assert_eq!(
+            HardwareComponent::parse_description(&[
+                "Single-Core CPU",
+                "6x RAM 4096 MB DDR3",
+                "7x HDD 1,5 TB SATA",
+                "2 x 512 GB NVMe SSD",
+                "No telephone support",
+            ]),
+            vec![
+                HardwareComponent::Cpu {
+                    model: "Single-Core CPU".to_string()
+                },
+                HardwareComponent::Memory {
+                    quantity: 6,
+                    capacity: ByteSize::mb(4096),
+                    description: "6x RAM 4096 MB DDR3".to_string(),
+                },
+                HardwareComponent::Drive {
+                    quantity: 7,
+                    capacity: ByteSize::gb(1500),
+                    kind: DriveKind::Hdd,
+                    description: "7x HDD 1,5 TB SATA".to_string(),
+                },
+                HardwareComponent::Drive {
+                    quantity: 2,
+                    capacity: ByteSize::gb(512),
+                    kind: DriveKind::Nvme,
+                    description: "2 x 512 GB NVMe SSD".to_string(),
+                },
+                HardwareComponent::Other("No telephone support".to_string()),
+            ]
+        );
+    }
+}
@@ -1,10 +1,11 @@
 //! Server structs and implementations.
 
+pub mod hardware;
 mod models;
 
 use crate::{
-    api::wrapper::{List, Single},
-    error::Error,
+    api::wrapper::{List, OrEmpty, Single},
+    error::{ApiError, Error},
     AsyncRobot,
 };
 use hyper::Uri;
@@ -60,6 +61,23 @@ fn cancel_server(
     .with_body(cancellation)
 }
 
+fn withdraw_order(
+    server_number: ServerId,
+    reason: Option<&str>,
+) -> Result<UnauthenticatedRequest<Single<Reversal>>, serde_html_form::ser::Error> {
+    #[derive(Serialize)]
+    struct WithdrawOrderRequest<'a> {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub reason: Option<&'a str>,
+    }
+
+    UnauthenticatedRequest::from(&format!(
+        "https://robot-ws.your-server.de/server/{server_number}/reversal"
+    ))
+    .with_method("POST")
+    .with_body(WithdrawOrderRequest { reason })
+}
+
 fn withdraw_server_cancellation(server_number: ServerId) -> UnauthenticatedRequest<Empty> {
     UnauthenticatedRequest::from(&format!(
         "https://robot-ws.your-server.de/server/{server_number}/cancellation"
@@ -83,7 +101,34 @@ impl AsyncRobot {
     /// # }
     /// ```
     pub async fn list_servers(&self) -> Result<Vec<Server>, Error> {
-        Ok(self.go(list_servers()).await?.0)
+        Ok(self.go(list_servers()).await.or_empty()?.0)
+    }
+
+    /// List owned servers matching the query.
+    ///
+    /// The server listing does not support any search parameters, so all
+    /// servers are fetched and filtered according to [`ServerQuery::matches`].
+    /// With the `cache` feature enabled, `CachedRobot::search_servers` avoids
+    /// transferring the full server list for every lookup.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::server::ServerQuery;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let query = ServerQuery::default().with_name("web-1");
+    ///
+    /// for server in robot.search_servers(&query).await.unwrap() {
+    ///     println!("{}: {}", server.id, server.name);
+    /// }
+    /// # }
+    /// ```
+    pub async fn search_servers(&self, query: &ServerQuery) -> Result<Vec<Server>, Error> {
+        let mut servers = self.list_servers().await?;
+        servers.retain(|server| query.matches(server));
+        Ok(servers)
     }
 
     /// Retrieve complete information about a specific [`Server`].
@@ -99,7 +144,8 @@ impl AsyncRobot {
     /// println!("Name: {}", server.name);
     /// # }
     /// ```
-    pub async fn get_server(&self, server_number: ServerId) -> Result<Server, Error> {
+    pub async fn get_server(&self, server_number: impl Into<ServerId>) -> Result<Server, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_server(server_number)).await?.0)
     }
 
@@ -116,9 +162,10 @@ impl AsyncRobot {
     /// ```
     pub async fn rename_server(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
         name: &str,
     ) -> Result<Server, Error> {
+        let server_number = server_number.into();
         Ok(self.go(rename_server(server_number, name)?).await?.0)
     }
 
@@ -140,8 +187,9 @@ impl AsyncRobot {
     /// ```
     pub async fn get_server_cancellation(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
     ) -> Result<Cancellation, Error> {
+        let server_number = server_number.into();
         Ok(self.go(get_server_cancellation(server_number)).await?.0)
     }
 
@@ -163,9 +211,10 @@ impl AsyncRobot {
     /// ```
     pub async fn cancel_server(
         &self,
-        server_number: ServerId,
+        server_number: impl Into<ServerId>,
         cancellation: Cancel,
     ) -> Result<Cancelled, Error> {
+        let server_number = server_number.into();
         Ok(self
             .go(cancel_server(server_number, cancellation)?)
             .await?
@@ -183,11 +232,123 @@ impl AsyncRobot {
     /// robot.withdraw_server_cancellation(ServerId(1234567)).await.unwrap();
     /// # }
     /// ```
-    pub async fn withdraw_server_cancellation(&self, server_number: ServerId) -> Result<(), Error> {
+    pub async fn withdraw_server_cancellation(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<(), Error> {
+        let server_number = server_number.into();
         self.go(withdraw_server_cancellation(server_number))
             .await?
             .throw_away();
 
         Ok(())
     }
+
+    /// Withdraw (reverse) the order of a server, optionally giving a reason.
+    ///
+    /// Applies to both standard and market (auction) purchases, as long as the
+    /// order is still eligible for withdrawal. If it is not, the server is
+    /// retrieved to determine why, and [`Error::ReversalNotPossible`] is returned.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::server::{ReversalRefusal, ServerId};
+    /// # use hrobot::error::Error;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// match robot.withdraw_order(ServerId(1234567), Some("Ordered by mistake")).await {
+    ///     Ok(reversal) => println!("withdrew order of {}", reversal.id),
+    ///     Err(Error::ReversalNotPossible { reason: ReversalRefusal::AlreadyCancelled, .. }) => {
+    ///         println!("server is already cancelled");
+    ///     }
+    ///     Err(error) => panic!("{error}"),
+    /// }
+    /// # }
+    /// ```
+    pub async fn withdraw_order(
+        &self,
+        server_number: impl Into<ServerId>,
+        reason: Option<&str>,
+    ) -> Result<Reversal, Error> {
+        let server_number = server_number.into();
+
+        match self.go(withdraw_order(server_number, reason)?).await {
+            Ok(reversal) => Ok(reversal.0),
+            Err(Error::Api(ApiError::ServerReversalNotPossible { message }, _)) => {
+                let reason = if self.get_server(server_number).await?.cancelled {
+                    ReversalRefusal::AlreadyCancelled
+                } else {
+                    ReversalRefusal::NotEligible(message)
+                };
+
+                Err(Error::ReversalNotPossible {
+                    server: server_number,
+                    reason,
+                })
+            }
+            Err(error) => Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::StatusCode;
+    use serde_json::json;
+
+    use crate::{
+        api::server::{ReversalRefusal, ServerId},
+        error::Error,
+        mock::{fixtures, MockClient, Reply},
+    };
+
+    #[tokio::test]
+    async fn withdraw_order() {
+        // Servers 1 (active) and 2 (cancelled) both refuse withdrawal,
+        // while the order of server 3 is withdrawn successfully.
+        let refusal = Reply::error(
+            StatusCode::CONFLICT,
+            "SERVER_REVERSAL_NOT_POSSIBLE",
+            "Order withdrawal period expired",
+        );
+        let robot = MockClient::new()
+            .on("POST", "/server/1/reversal", refusal.clone())
+            .on("POST", "/server/2/reversal", refusal)
+            .on(
+                "POST",
+                "/server/3/reversal",
+                json!({"reversal": {
+                    "server_ip": "1.1.1.3",
+                    "server_ipv6_net": "2a01:4f8:1::",
+                    "server_number": 3
+                }}),
+            )
+            .on("GET", "/server/1", fixtures::server(1))
+            .on(
+                "GET",
+                "/server/2",
+                fixtures::patch(fixtures::server(2), json!({"cancelled": true})),
+            )
+            .robot();
+
+        let reversal = robot.withdraw_order(ServerId(3), None).await.unwrap();
+        assert_eq!(reversal.id, ServerId(3));
+
+        match robot.withdraw_order(ServerId(1), Some("Mistake")).await {
+            Err(Error::ReversalNotPossible {
+                server: ServerId(1),
+                reason: ReversalRefusal::NotEligible(message),
+            }) => assert_eq!(message, "Order withdrawal period expired"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        assert!(matches!(
+            robot.withdraw_order(ServerId(2), None).await,
+            Err(Error::ReversalNotPossible {
+                server: ServerId(2),
+                reason: ReversalRefusal::AlreadyCancelled,
+            })
+        ));
+    }
 }
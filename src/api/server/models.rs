@@ -1,11 +1,14 @@
-use bytesize::ByteSize;
 use serde::{Deserialize, Serialize};
 use std::{
     fmt::Display,
     net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    num::ParseIntError,
+    str::FromStr,
 };
 use time::Date;
 
+use crate::api::traffic::TrafficQuota;
+
 /// Unique Server ID.
 ///
 /// Simple wrapper around a u32, to avoid confusion with for example [`TemplateId`](crate::api::firewall::TemplateId)
@@ -14,7 +17,37 @@ use time::Date;
 /// Using a plain integer means it isn't clear what the argument is, is it a counter of my servers, where the argument
 /// is in range `0..N` where `N` is the number of dedicated servers in my account, or is it a limiter, like get first `N`
 /// servers, for example.
+///
+/// Client methods accept anything which converts into a [`ServerId`], so plain
+/// integers work at call sites, while the returned models keep using the newtype:
+///
+/// ```rust,no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// let robot = hrobot::AsyncRobot::default();
+/// let server = robot.get_server(1234567).await.unwrap();
+/// let firewall = robot.get_firewall(server.id).await.unwrap();
+/// # }
+/// ```
+///
+/// Servers themselves also convert into their [`ServerId`], so a [`Server`]
+/// can be passed directly to any method taking an `impl Into<ServerId>`:
+///
+/// ```rust,no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// let robot = hrobot::AsyncRobot::default();
+/// for server in robot.list_servers().await.unwrap() {
+///     let firewall = robot.get_firewall(&server).await.unwrap();
+/// }
+/// # }
+/// ```
+///
+/// Since `u32` is the only integer type converting into a [`ServerId`], adding
+/// conversions from other integer types would break type inference for integer
+/// literals, and is intentionally avoided.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ServerId(pub u32);
 
 impl From<u32> for ServerId {
@@ -23,6 +56,24 @@ impl From<u32> for ServerId {
     }
 }
 
+impl From<&ServerId> for ServerId {
+    fn from(value: &ServerId) -> Self {
+        *value
+    }
+}
+
+impl From<&Server> for ServerId {
+    fn from(server: &Server) -> Self {
+        server.id
+    }
+}
+
+impl From<Server> for ServerId {
+    fn from(server: Server) -> Self {
+        server.id
+    }
+}
+
 impl From<ServerId> for u32 {
     fn from(value: ServerId) -> Self {
         value.0
@@ -41,8 +92,23 @@ impl PartialEq<u32> for ServerId {
     }
 }
 
+impl PartialEq<ServerId> for u32 {
+    fn eq(&self, other: &ServerId) -> bool {
+        self.eq(&other.0)
+    }
+}
+
+impl FromStr for ServerId {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u32::from_str(s).map(ServerId)
+    }
+}
+
 /// Indicates the status of a server.
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Status {
     /// Server is ready for use.
     #[serde(rename = "ready")]
@@ -53,7 +119,8 @@ pub enum Status {
 }
 
 /// Reference to a Subnet.
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SubnetReference {
     /// Subnet Address
     #[serde(rename = "ip")]
@@ -64,30 +131,39 @@ pub struct SubnetReference {
 }
 
 /// Flags describe availability of a service or add-on for the server.
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ServerFlags {
     /// Server reset is available.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub reset: bool,
 
     /// Server rescue is available.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub rescue: bool,
 
     /// VNC installation is available.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub vnc: bool,
 
     /// Windows installation is available
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub windows: bool,
 
     /// Plesk installation is available
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub plesk: bool,
 
     /// CPanel installation is available.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub cpanel: bool,
 
     /// Wake-on-LAN is available.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub wol: bool,
 
     /// Hot-swap is available.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub hot_swap: bool,
 
     /// StorageBox this server is linked with (if any).
@@ -95,7 +171,8 @@ pub struct ServerFlags {
 }
 
 /// Describes a Hetzner Dedicated Server instance.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Server {
     /// *Primary* IPv4 address.
     ///
@@ -123,20 +200,23 @@ pub struct Server {
     /// Datacenter in which the sever is located. e.g. `FSN1-DC14` for Datacenter-14 at Data Center Park Falkenstein.
     ///
     /// See [here](https://www.hetzner.com/unternehmen/rechenzentrum) for a list of datacenters.
-    pub dc: String,
+    pub dc: Datacenter,
 
-    /// Monthly traffic limitation if any, e.g. `5 TB`.
-    #[serde(rename = "traffic", deserialize_with = "crate::conversion::traffic")]
-    pub traffic_limit: Option<ByteSize>,
+    /// Monthly traffic limitation, e.g. `5 TB`.
+    #[serde(rename = "traffic")]
+    pub traffic_limit: TrafficQuota,
 
     /// Current status of the server.
     pub status: Status,
 
     /// True if server has been cancelled.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub cancelled: bool,
 
-    /// Server has been paid for until this date. Format is `YYYY-MM-DD`.
-    pub paid_until: String,
+    /// Server has been paid for until this date.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    #[serde(with = "crate::conversion::date")]
+    pub paid_until: Date,
 
     /// IP Addresses associated with this server.
     ///
@@ -150,7 +230,11 @@ pub struct Server {
     pub ips: Vec<String>,
 
     /// Subnets associated with this server.
-    #[serde(rename = "subnet", default)]
+    #[serde(
+        rename = "subnet",
+        default,
+        deserialize_with = "crate::conversion::deserialize_null_default"
+    )]
     pub subnets: Vec<SubnetReference>,
 
     /// Server flags indicating availability of extra services.
@@ -162,11 +246,173 @@ pub struct Server {
     pub availability: Option<ServerFlags>,
 }
 
+impl AsRef<ServerId> for Server {
+    fn as_ref(&self) -> &ServerId {
+        &self.id
+    }
+}
+
+impl Server {
+    /// Location of the server's [`Datacenter`], e.g. `FSN1`.
+    pub fn location(&self) -> Location {
+        self.dc.location()
+    }
+
+    /// Primary IP address of the server.
+    ///
+    /// This is the primary [IPv4 address](Server::ipv4) if the server has one,
+    /// since IPv4 is an optional addon, falling back to the
+    /// [primary IPv6 address](Server::primary_ipv6) otherwise.
+    pub fn primary_ip(&self) -> IpAddr {
+        self.ipv4
+            .map(IpAddr::V4)
+            .unwrap_or_else(|| IpAddr::V6(self.primary_ipv6()))
+    }
+
+    /// Primary IPv6 address of the server within its [IPv6 prefix](Server::ipv6_net),
+    /// e.g. `2a01:4f8:111:4221::2` for the prefix `2a01:4f8:111:4221::`.
+    ///
+    /// This is the address configured by Hetzner's installimage and rescue system.
+    pub fn primary_ipv6(&self) -> Ipv6Addr {
+        Ipv6Addr::from(u128::from(self.ipv6_net) | 2)
+    }
+
+    /// Default IPv6 gateway of the server, which is always the link-local address `fe80::1`.
+    pub fn ipv6_gateway(&self) -> Ipv6Addr {
+        Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)
+    }
+
+    /// Compare the content of two server descriptions, ignoring volatile fields.
+    ///
+    /// Unlike [`PartialEq`], this ignores [`status`](Server::status) and
+    /// [`paid_until`](Server::paid_until), and only compares
+    /// [`availability`](Server::availability) if it is present on both sides,
+    /// since it is omitted when listing servers. This makes it suitable
+    /// for detecting actual changes when polling.
+    pub fn content_eq(&self, other: &Server) -> bool {
+        self.ipv4 == other.ipv4
+            && self.ipv6_net == other.ipv6_net
+            && self.id == other.id
+            && self.name == other.name
+            && self.product == other.product
+            && self.dc == other.dc
+            && self.traffic_limit == other.traffic_limit
+            && self.cancelled == other.cancelled
+            && self.ips == other.ips
+            && self.subnets == other.subnets
+            && match (&self.availability, &other.availability) {
+                (Some(ours), Some(theirs)) => ours == theirs,
+                _ => true,
+            }
+    }
+}
+
+/// Criteria for finding servers using [`AsyncRobot::search_servers`](crate::AsyncRobot::search_servers).
+///
+/// Every criterion is optional, and a server must satisfy all of the ones given.
+///
+/// # Example
+/// ```rust
+/// # use hrobot::api::server::{Location, ServerQuery};
+/// let query = ServerQuery::default()
+///     .with_name("web-1")
+///     .with_location(Location::from("FSN1"))
+///     .with_cancelled(false);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerQuery {
+    /// Exact [`Server::name`].
+    pub name: Option<String>,
+
+    /// Exact [`Server::product`], e.g. `AX41-NVME`.
+    pub product: Option<String>,
+
+    /// Location the server must be located in, e.g. `FSN1`.
+    pub location: Option<Location>,
+
+    /// [`Server::status`] of the server.
+    pub status: Option<Status>,
+
+    /// Whether the server must be [cancelled](Server::cancelled) or not.
+    pub cancelled: Option<bool>,
+
+    /// IP address which must be the server's primary IPv4 address,
+    /// or one of its [`ips`](Server::ips).
+    pub ip: Option<IpAddr>,
+}
+
+impl ServerQuery {
+    /// Only match servers named exactly `name`.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Only match servers of the product `product`.
+    pub fn with_product(mut self, product: impl Into<String>) -> Self {
+        self.product = Some(product.into());
+        self
+    }
+
+    /// Only match servers located in `location`.
+    pub fn with_location(mut self, location: Location) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Only match servers with the status `status`.
+    pub fn with_status(mut self, status: Status) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Only match servers which are cancelled, or which are not.
+    pub fn with_cancelled(mut self, cancelled: bool) -> Self {
+        self.cancelled = Some(cancelled);
+        self
+    }
+
+    /// Only match servers with the IP address `ip`.
+    pub fn with_ip(mut self, ip: IpAddr) -> Self {
+        self.ip = Some(ip);
+        self
+    }
+
+    /// Check whether the server satisfies all criteria of the query.
+    pub fn matches(&self, server: &Server) -> bool {
+        self.name.as_ref().is_none_or(|name| server.name == *name)
+            && self
+                .product
+                .as_ref()
+                .is_none_or(|product| server.product == *product)
+            && self
+                .location
+                .as_ref()
+                .is_none_or(|location| server.location() == *location)
+            && self
+                .status
+                .as_ref()
+                .is_none_or(|status| server.status == *status)
+            && self
+                .cancelled
+                .is_none_or(|cancelled| server.cancelled == cancelled)
+            && self.ip.is_none_or(|ip| {
+                server.ipv4.map(IpAddr::V4) == Some(ip)
+                    || server
+                        .ips
+                        .iter()
+                        .any(|address| address.parse::<IpAddr>() == Ok(ip))
+            })
+    }
+}
+
 /// Describes the terms under which a server was cancelled.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Cancelled {
     /// Date on which the cancellation will take effect.
-    #[serde(rename = "cancellation_date")]
+    #[serde(rename = "cancellation_date", with = "crate::conversion::date")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub date: Date,
 
     /// Reason for the cancellation.
@@ -174,6 +420,7 @@ pub struct Cancelled {
     pub reason: Option<String>,
 
     /// Indicates if the server location will be reserved after server cancellation.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub reserved: bool,
 }
 
@@ -212,16 +459,59 @@ impl From<Cancel> for InternalCancel {
     }
 }
 
+/// Withdrawn (reversed) server order, as returned by
+/// [`AsyncRobot::withdraw_order`](crate::AsyncRobot::withdraw_order).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Reversal {
+    /// Primary IPv4 address of the server, if any.
+    #[serde(rename = "server_ip")]
+    pub ipv4: Option<Ipv4Addr>,
+
+    /// Primary IPv6 prefix of the server.
+    #[serde(rename = "server_ipv6_net")]
+    pub ipv6_net: Option<Ipv6Addr>,
+
+    /// ID of the server whose order was withdrawn.
+    #[serde(rename = "server_number")]
+    pub id: ServerId,
+}
+
+/// Reason a server order cannot be withdrawn, see [`Error::ReversalNotPossible`](crate::error::Error::ReversalNotPossible).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ReversalRefusal {
+    /// The server has already been cancelled, so there is no order left to withdraw.
+    AlreadyCancelled,
+
+    /// The order is not eligible for withdrawal, for example because the withdrawal
+    /// period has passed. Contains the message returned by the API.
+    NotEligible(String),
+}
+
+impl Display for ReversalRefusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReversalRefusal::AlreadyCancelled => f.write_str("server has already been cancelled"),
+            ReversalRefusal::NotEligible(message) => f.write_str(message),
+        }
+    }
+}
+
 /// Describes possibility of cancellation for a server.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Cancellable {
     /// Earliest date at which it is possible to
     /// cancel the server.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    #[serde(with = "crate::conversion::date")]
     pub earliest_cancellation_date: Date,
 
     /// Indicates whether the current server location
     /// is eligible for reservation after server
     /// cancellation
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub reservation_possible: bool,
 
     /// List of possible reasons for cancellations.
@@ -230,7 +520,8 @@ pub struct Cancellable {
 }
 
 /// Indicates the cancellation status of the server.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Cancellation {
     /// Server has been cancelled.
@@ -239,6 +530,98 @@ pub enum Cancellation {
     Cancellable(Cancellable),
 }
 
+/// Location, e.g. "FSN1".
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Location(pub String);
+
+impl From<String> for Location {
+    fn from(value: String) -> Self {
+        Location(value)
+    }
+}
+
+impl From<&str> for Location {
+    fn from(value: &str) -> Self {
+        Location(value.to_string())
+    }
+}
+
+impl From<Location> for String {
+    fn from(value: Location) -> Self {
+        value.0
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl PartialEq<str> for Location {
+    fn eq(&self, other: &str) -> bool {
+        self.0.eq(other)
+    }
+}
+
+/// Datacenter within a Location, e.g. "FSN1-DC1".
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Datacenter(pub String);
+
+impl From<String> for Datacenter {
+    fn from(value: String) -> Self {
+        Datacenter(value)
+    }
+}
+
+impl From<&str> for Datacenter {
+    fn from(value: &str) -> Self {
+        Datacenter(value.to_string())
+    }
+}
+
+impl From<Datacenter> for String {
+    fn from(value: Datacenter) -> Self {
+        value.0
+    }
+}
+
+impl Datacenter {
+    /// Location of the datacenter, e.g. `FSN1` for `FSN1-DC14`.
+    ///
+    /// If the datacenter does not follow the `<location>-<datacenter>`
+    /// naming scheme, the entire name is used as the location.
+    pub fn location(&self) -> Location {
+        Location(
+            self.0
+                .split_once('-')
+                .map(|(location, _)| location)
+                .unwrap_or(&self.0)
+                .to_string(),
+        )
+    }
+}
+
+impl From<Datacenter> for Location {
+    fn from(value: Datacenter) -> Location {
+        value.location()
+    }
+}
+
+impl Display for Datacenter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl PartialEq<str> for Datacenter {
+    fn eq(&self, other: &str) -> bool {
+        self.0.eq(other)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::api::server::ServerId;
@@ -249,5 +632,272 @@ mod tests {
 
         assert_eq!(u32::from(ServerId(10)), 10);
         assert_eq!(ServerId(10), 10);
+        assert_eq!(10, ServerId(10));
+
+        assert_eq!("10".parse::<ServerId>(), Ok(ServerId(10)));
+        assert!("ten".parse::<ServerId>().is_err());
+        assert_eq!(
+            ServerId(10).to_string().parse::<ServerId>(),
+            Ok(ServerId(10))
+        );
+    }
+
+    #[test]
+    fn server_content_eq() {
+        use crate::api::server::{Server, Status};
+
+        let json = r#"{
+            "server_ip": "123.123.123.123",
+            "server_ipv6_net": "2a01:f48:111:4221::",
+            "server_number": 321,
+            "server_name": "server1",
+            "product": "DS 3000",
+            "dc": "NBG1-DC1",
+            "traffic": "5 TB",
+            "status": "ready",
+            "cancelled": false,
+            "paid_until": "2010-09-02",
+            "ip": ["123.123.123.123"],
+            "subnet": [{"ip": "2a01:4f8:111:4221::", "mask": "64"}]
+        }"#;
+
+        let server: Server = serde_json::from_str(json).unwrap();
+
+        let mut polled: Server = serde_json::from_str(json).unwrap();
+        polled.status = Status::InProgress;
+        polled.paid_until = time::macros::date!(2010 - 10 - 02);
+
+        assert_ne!(server, polled);
+        assert!(server.content_eq(&polled));
+
+        polled.name = "server2".to_string();
+        assert!(!server.content_eq(&polled));
+    }
+
+    #[test]
+    fn server_query() {
+        use std::net::{IpAddr, Ipv4Addr};
+
+        use crate::api::server::{Location, Server, ServerQuery, Status};
+
+        let server: Server = serde_json::from_str(
+            r#"{
+                "server_ip": "123.123.123.123",
+                "server_ipv6_net": "2a01:f48:111:4221::",
+                "server_number": 321,
+                "server_name": "web-1",
+                "product": "AX41-NVME",
+                "dc": "FSN1-DC14",
+                "traffic": "unlimited",
+                "status": "ready",
+                "cancelled": false,
+                "paid_until": "2010-09-02",
+                "ip": ["123.123.123.123", "123.123.123.124"],
+                "subnet": []
+            }"#,
+        )
+        .unwrap();
+
+        assert!(ServerQuery::default().matches(&server));
+        assert!(ServerQuery::default()
+            .with_name("web-1")
+            .with_product("AX41-NVME")
+            .with_location(Location::from("FSN1"))
+            .with_status(Status::Ready)
+            .with_cancelled(false)
+            .with_ip(IpAddr::V4(Ipv4Addr::new(123, 123, 123, 124)))
+            .matches(&server));
+
+        assert!(!ServerQuery::default().with_name("web").matches(&server));
+        assert!(!ServerQuery::default()
+            .with_location(Location::from("NBG1"))
+            .matches(&server));
+        assert!(!ServerQuery::default().with_cancelled(true).matches(&server));
+        assert!(!ServerQuery::default()
+            .with_ip(IpAddr::V4(Ipv4Addr::new(123, 123, 123, 125)))
+            .matches(&server));
+    }
+
+    #[test]
+    fn server_into_server_id() {
+        use crate::api::server::Server;
+
+        fn id(server: impl Into<ServerId>) -> ServerId {
+            server.into()
+        }
+
+        let server: Server = serde_json::from_str(
+            r#"{
+                "server_ip": "123.123.123.123",
+                "server_ipv6_net": "2a01:4f8:111:4221::",
+                "server_number": 321,
+                "server_name": "server1",
+                "product": "DS 3000",
+                "dc": "NBG1-DC1",
+                "traffic": "5 TB",
+                "status": "ready",
+                "cancelled": false,
+                "paid_until": "2010-09-02",
+                "ip": ["123.123.123.123"],
+                "subnet": null
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(id(321), ServerId(321));
+        assert_eq!(
+            [ServerId(321)].iter().map(id).collect::<Vec<_>>(),
+            [ServerId(321)]
+        );
+        assert_eq!(id(&server), ServerId(321));
+        assert_eq!(server.as_ref(), &ServerId(321));
+        assert_eq!(id(server), ServerId(321));
+    }
+
+    #[test]
+    fn deserialize_cancellation() {
+        use time::macros::date;
+
+        use crate::api::{
+            server::{Cancellation, Server},
+            wrapper::Single,
+        };
+
+        let cancellable: Single<Cancellation> = serde_json::from_str(
+            r#"{
+                "cancellation": {
+                    "server_ip": "123.123.123.123",
+                    "server_ipv6_net": "2a01:4f8:111:4221::",
+                    "server_number": 321,
+                    "server_name": "server1",
+                    "earliest_cancellation_date": "2014-04-15",
+                    "cancelled": false,
+                    "reservation_possible": true,
+                    "reserved": false,
+                    "cancellation_date": null,
+                    "cancellation_reason": [
+                        "Upgrade to a new server",
+                        "Dissatisfied with the hardware"
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        match cancellable.0 {
+            Cancellation::Cancellable(cancellable) => {
+                assert_eq!(
+                    cancellable.earliest_cancellation_date,
+                    date!(2014 - 04 - 15)
+                );
+                assert!(cancellable.reservation_possible);
+            }
+            other => panic!("expected cancellable server, got {other:?}"),
+        }
+
+        let cancelled: Single<Cancellation> = serde_json::from_str(
+            r#"{
+                "cancellation": {
+                    "server_ip": "123.123.123.123",
+                    "server_ipv6_net": "2a01:4f8:111:4221::",
+                    "server_number": 321,
+                    "server_name": "server1",
+                    "earliest_cancellation_date": "2014-04-15",
+                    "cancelled": true,
+                    "reservation_possible": false,
+                    "reserved": false,
+                    "cancellation_date": "2014-04-15 00:00:00",
+                    "cancellation_reason": "Upgrade to a new server"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            cancelled.0,
+            Cancellation::Cancelled(super::Cancelled {
+                date: date!(2014 - 04 - 15),
+                reason: Some("Upgrade to a new server".to_string()),
+                reserved: false,
+            })
+        );
+
+        let server: Server = serde_json::from_str(
+            r#"{
+                "server_ip": "123.123.123.123",
+                "server_ipv6_net": "2a01:4f8:111:4221::",
+                "server_number": 321,
+                "server_name": "server1",
+                "product": "DS 3000",
+                "dc": "NBG1-DC1",
+                "traffic": "5 TB",
+                "status": "ready",
+                "cancelled": false,
+                "paid_until": "2010-09-02T00:00:00+02:00",
+                "ip": ["123.123.123.123"],
+                "subnet": null
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(server.paid_until, date!(2010 - 09 - 02));
+    }
+
+    #[test]
+    fn server_without_ipv4() {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+        use crate::api::server::Server;
+
+        let json = r#"{
+            "server_ip": null,
+            "server_ipv6_net": "2a01:4f8:111:4221::",
+            "server_number": 321,
+            "server_name": "server1",
+            "product": "AX41-NVMe",
+            "dc": "FSN1-DC14",
+            "traffic": "unlimited",
+            "status": "ready",
+            "cancelled": false,
+            "paid_until": "2010-09-02",
+            "ip": null,
+            "subnet": null
+        }"#;
+
+        let mut server: Server = serde_json::from_str(json).unwrap();
+
+        assert!(server.ips.is_empty());
+        assert_eq!(
+            server.primary_ip(),
+            "2a01:4f8:111:4221::2".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(
+            server.ipv6_gateway(),
+            "fe80::1".parse::<Ipv6Addr>().unwrap()
+        );
+
+        server.ipv4 = Some(Ipv4Addr::new(123, 123, 123, 123));
+        assert_eq!(
+            server.primary_ip(),
+            IpAddr::V4(Ipv4Addr::new(123, 123, 123, 123))
+        );
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn server_json_schema() {
+        use crate::api::server::Server;
+
+        let schema = serde_json::to_value(schemars::schema_for!(Server)).unwrap();
+
+        assert_eq!(schema["properties"]["server_ip"]["type"][0], "string");
+        assert_eq!(
+            schema["properties"]["server_number"]["$ref"],
+            "#/$defs/ServerId"
+        );
+        assert_eq!(schema["$defs"]["ServerId"]["type"], "integer");
+        assert!(schema["required"]
+            .as_array()
+            .unwrap()
+            .contains(&"server_name".into()));
     }
 }
@@ -0,0 +1,230 @@
+//! Connection strings for common backup tools targeting a storagebox.
+//!
+//! See <https://docs.hetzner.com/robot/storage-box/access/access-ssh-rsync-borg>
+//! for the underlying conventions, such as the storagebox SSH port being `23`.
+
+use std::fmt::Display;
+
+use super::{StorageBox, Subaccount};
+
+/// SSH port used by storageboxes for SFTP, rsync, borg and restic access.
+///
+/// Port `22` only offers SFTP/SCP without the extended command set.
+pub const STORAGEBOX_SSH_PORT: u16 = 23;
+
+/// SSH endpoint of a storagebox or sub-account, from which connection
+/// strings for backup tools can be derived.
+///
+/// Requires SSH access to be enabled, see [`Accessibility::ssh`](super::Accessibility::ssh).
+///
+/// # Example
+/// ```rust
+/// # use hrobot::api::storagebox::BackupTarget;
+/// let target = BackupTarget::new("u123456", "u123456.your-storagebox.de");
+///
+/// assert_eq!(
+///     target.borg("backups/web").to_string(),
+///     "ssh://u123456@u123456.your-storagebox.de:23/./backups/web"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BackupTarget {
+    /// Username to log in as, either the storagebox login or a sub-account username.
+    pub username: String,
+
+    /// Hostname of the storagebox, e.g. `u123456.your-storagebox.de`.
+    pub host: String,
+
+    /// SSH port, [`STORAGEBOX_SSH_PORT`] unless overridden.
+    pub port: u16,
+}
+
+impl BackupTarget {
+    /// Construct a target for the given username and host, using the [`STORAGEBOX_SSH_PORT`].
+    pub fn new(username: impl Into<String>, host: impl Into<String>) -> Self {
+        BackupTarget {
+            username: username.into(),
+            host: host.into(),
+            port: STORAGEBOX_SSH_PORT,
+        }
+    }
+
+    /// Borg repository at `path`, relative to the home directory.
+    pub fn borg(&self, path: impl Into<String>) -> BorgRepository {
+        BorgRepository {
+            target: self.clone(),
+            path: relative(path.into()),
+        }
+    }
+
+    /// Restic SFTP repository at `path`, relative to the home directory.
+    pub fn restic(&self, path: impl Into<String>) -> ResticRepository {
+        ResticRepository {
+            target: self.clone(),
+            path: relative(path.into()),
+        }
+    }
+
+    /// Rclone SFTP remote named `name`.
+    pub fn rclone(&self, name: impl Into<String>) -> RcloneRemote {
+        RcloneRemote {
+            target: self.clone(),
+            name: name.into(),
+        }
+    }
+
+    /// Shell command installing the local public key at `public_key_path`
+    /// as an authorized key for this target.
+    ///
+    /// Must be run once (with password authentication) before key-based
+    /// access by any of the backup tools is possible.
+    pub fn install_ssh_key_command(&self, public_key_path: &str) -> String {
+        format!(
+            "cat {public_key_path} | ssh -p {} {}@{} install-ssh-key",
+            self.port, self.username, self.host
+        )
+    }
+}
+
+/// Strip leading slashes, since all paths are relative to the home directory.
+fn relative(path: String) -> String {
+    path.trim_start_matches('/').to_string()
+}
+
+impl StorageBox {
+    /// SSH endpoint of the storagebox's main account, for use with backup tools.
+    pub fn backup_target(&self) -> BackupTarget {
+        BackupTarget::new(&self.login, &self.server)
+    }
+}
+
+impl Subaccount {
+    /// SSH endpoint of the sub-account, for use with backup tools.
+    ///
+    /// Paths are relative to the sub-account's [home directory](Subaccount::homedirectory).
+    pub fn backup_target(&self) -> BackupTarget {
+        BackupTarget::new(self.username.0.clone(), &self.server)
+    }
+}
+
+/// Borg repository located on a storagebox.
+///
+/// Displays as the repository URL, e.g. `ssh://u123456@u123456.your-storagebox.de:23/./backups`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BorgRepository {
+    /// Storagebox hosting the repository.
+    pub target: BackupTarget,
+
+    /// Path of the repository, relative to the home directory.
+    pub path: String,
+}
+
+impl BorgRepository {
+    /// Command initializing the repository with `repokey` encryption.
+    pub fn init_command(&self) -> String {
+        format!("borg init --encryption=repokey {self}")
+    }
+}
+
+impl Display for BorgRepository {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ssh://{}@{}:{}/./{}",
+            self.target.username, self.target.host, self.target.port, self.path
+        )
+    }
+}
+
+/// Restic repository located on a storagebox, accessed over SFTP.
+///
+/// Displays as the repository URL, e.g. `sftp://u123456@u123456.your-storagebox.de:23/backups`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResticRepository {
+    /// Storagebox hosting the repository.
+    pub target: BackupTarget,
+
+    /// Path of the repository, relative to the home directory.
+    pub path: String,
+}
+
+impl ResticRepository {
+    /// Command initializing the repository.
+    pub fn init_command(&self) -> String {
+        format!("restic -r {self} init")
+    }
+}
+
+impl Display for ResticRepository {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "sftp://{}@{}:{}/{}",
+            self.target.username, self.target.host, self.target.port, self.path
+        )
+    }
+}
+
+/// Rclone SFTP remote for a storagebox.
+///
+/// Displays as the `rclone.conf` stanza defining the remote. Authentication
+/// is left to the SSH agent, or can be configured with `rclone config`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RcloneRemote {
+    /// Storagebox the remote points to.
+    pub target: BackupTarget,
+
+    /// Name of the remote, as used in `rclone` paths like `name:backups`.
+    pub name: String,
+}
+
+impl Display for RcloneRemote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "[{}]", self.name)?;
+        writeln!(f, "type = sftp")?;
+        writeln!(f, "host = {}", self.target.host)?;
+        writeln!(f, "user = {}", self.target.username)?;
+        writeln!(f, "port = {}", self.target.port)?;
+        write!(f, "shell_type = unix")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BackupTarget;
+
+    #[test]
+    fn connection_strings() {
+        let target = BackupTarget::new("u123456-sub1", "u123456-sub1.your-storagebox.de");
+
+        let borg = target.borg("/borg/web");
+        assert_eq!(
+            borg.to_string(),
+            "ssh://u123456-sub1@u123456-sub1.your-storagebox.de:23/./borg/web"
+        );
+        assert_eq!(
+            borg.init_command(),
+            "borg init --encryption=repokey ssh://u123456-sub1@u123456-sub1.your-storagebox.de:23/./borg/web"
+        );
+
+        assert_eq!(
+            target.restic("restic").to_string(),
+            "sftp://u123456-sub1@u123456-sub1.your-storagebox.de:23/restic"
+        );
+
+        assert_eq!(
+            target.rclone("storagebox").to_string(),
+            "[storagebox]\n\
+            type = sftp\n\
+            host = u123456-sub1.your-storagebox.de\n\
+            user = u123456-sub1\n\
+            port = 23\n\
+            shell_type = unix"
+        );
+
+        assert_eq!(
+            target.install_ssh_key_command("~/.ssh/id_ed25519.pub"),
+            "cat ~/.ssh/id_ed25519.pub | ssh -p 23 u123456-sub1@u123456-sub1.your-storagebox.de install-ssh-key"
+        );
+    }
+}
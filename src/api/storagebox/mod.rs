@@ -2,13 +2,19 @@
 use crate::{error::Error, AsyncRobot};
 
 use super::{
-    wrapper::{Empty, List, Single},
+    wrapper::{Empty, List, OrEmpty, Single},
     UnauthenticatedRequest,
 };
 
+mod backup;
 mod models;
+mod retention;
+mod revert;
+pub use backup::*;
 pub use models::*;
-use serde::Serialize;
+pub use retention::*;
+pub use revert::*;
+use serde::{Deserialize, Serialize};
 
 fn list_storageboxes() -> UnauthenticatedRequest<List<StorageBoxReference>> {
     UnauthenticatedRequest::from("https://robot-ws.your-server.de/storagebox")
@@ -20,6 +26,21 @@ fn get_storagebox(storagebox: StorageBoxId) -> UnauthenticatedRequest<Single<Sto
     ))
 }
 
+/// Only the disk fields of a storagebox.
+#[derive(Debug, Deserialize)]
+struct StorageBoxUsage {
+    #[serde(flatten)]
+    disk: Disk,
+}
+
+fn get_storagebox_usage(
+    storagebox: StorageBoxId,
+) -> UnauthenticatedRequest<Single<StorageBoxUsage>> {
+    UnauthenticatedRequest::from(&format!(
+        "https://robot-ws.your-server.de/storagebox/{storagebox}"
+    ))
+}
+
 fn reset_password(storagebox: StorageBoxId) -> UnauthenticatedRequest<Single<String>> {
     UnauthenticatedRequest::from(&format!(
         "https://robot-ws.your-server.de/storagebox/{storagebox}/password"
@@ -80,7 +101,10 @@ fn create_snapshot(storagebox: StorageBoxId) -> UnauthenticatedRequest<Single<Cr
     .with_method("POST")
 }
 
-fn delete_snapshot(storagebox: StorageBoxId, snapshot_name: &str) -> UnauthenticatedRequest<Empty> {
+fn delete_snapshot(
+    storagebox: StorageBoxId,
+    snapshot_name: SnapshotName,
+) -> UnauthenticatedRequest<Empty> {
     UnauthenticatedRequest::from(&format!(
         "https://robot-ws.your-server.de/storagebox/{storagebox}/snapshot/{snapshot_name}"
     ))
@@ -89,7 +113,7 @@ fn delete_snapshot(storagebox: StorageBoxId, snapshot_name: &str) -> Unauthentic
 
 fn revert_to_snapshot(
     storagebox: StorageBoxId,
-    snapshot_name: &str,
+    snapshot_name: SnapshotName,
 ) -> UnauthenticatedRequest<Empty> {
     UnauthenticatedRequest::from(&format!(
         "https://robot-ws.your-server.de/storagebox/{storagebox}/snapshot/{snapshot_name}"
@@ -100,7 +124,7 @@ fn revert_to_snapshot(
 
 fn change_snapshot_comment(
     storagebox: StorageBoxId,
-    snapshot_name: &str,
+    snapshot_name: SnapshotName,
     comment: &str,
 ) -> Result<UnauthenticatedRequest<Empty>, serde_html_form::ser::Error> {
     #[derive(Serialize)]
@@ -162,7 +186,7 @@ fn create_subaccount(
     storagebox: StorageBoxId,
     home_directory: &str,
     accessibility: Accessibility,
-    read_only: Permission,
+    permissions: SubaccountPermissions,
     comment: Option<&str>,
 ) -> Result<UnauthenticatedRequest<Single<CreatedSubaccount>>, serde_html_form::ser::Error> {
     UnauthenticatedRequest::from(&format!(
@@ -174,7 +198,7 @@ fn create_subaccount(
         samba: Some(accessibility.samba),
         ssh: Some(accessibility.ssh),
         webdav: Some(accessibility.webdav),
-        readonly: Some(read_only),
+        readonly: Some(permissions.readonly),
         comment,
     })
 }
@@ -184,7 +208,7 @@ fn update_subaccount(
     subaccount: &SubaccountId,
     home_directory: Option<&str>,
     accessibility: Option<&Accessibility>,
-    read_only: Option<Permission>,
+    permissions: Option<SubaccountPermissions>,
     comment: Option<&str>,
 ) -> Result<UnauthenticatedRequest<Empty>, serde_html_form::ser::Error> {
     UnauthenticatedRequest::from(&format!(
@@ -196,11 +220,24 @@ fn update_subaccount(
         samba: accessibility.map(|a| a.samba),
         ssh: accessibility.map(|a| a.ssh),
         webdav: accessibility.map(|a| a.webdav),
-        readonly: read_only,
+        readonly: permissions.map(|permissions| permissions.readonly),
         comment,
     })
 }
 
+fn toggle_subaccount_service(
+    storagebox: StorageBoxId,
+    subaccount: &SubaccountId,
+    service: &str,
+    enabled: bool,
+) -> UnauthenticatedRequest<Empty> {
+    UnauthenticatedRequest::from(&format!(
+        "https://robot-ws.your-server.de/storagebox/{storagebox}/subaccount/{subaccount}"
+    ))
+    .with_method("PUT")
+    .with_serialized_body(format!("{service}={enabled}"))
+}
+
 fn delete_subaccount(
     storagebox: StorageBoxId,
     subaccount: SubaccountId,
@@ -237,7 +274,7 @@ impl AsyncRobot {
     /// # }
     /// ```
     pub async fn list_storageboxes(&self) -> Result<Vec<StorageBoxReference>, Error> {
-        Ok(self.go(list_storageboxes()).await?.0)
+        Ok(self.go(list_storageboxes()).await.or_empty()?.0)
     }
 
     /// Get a single storagebox.
@@ -252,10 +289,35 @@ impl AsyncRobot {
     /// robot.get_storagebox(StorageBoxId(1234)).await.unwrap();
     /// # }
     /// ```
-    pub async fn get_storagebox(&self, id: StorageBoxId) -> Result<StorageBox, Error> {
+    pub async fn get_storagebox(&self, id: impl Into<StorageBoxId>) -> Result<StorageBox, Error> {
+        let id = id.into();
         Ok(self.go(get_storagebox(id)).await?.0)
     }
 
+    /// Get the disk usage and quota of a single storagebox.
+    ///
+    /// The API has no dedicated endpoint for this, so the full storagebox is
+    /// still transferred, but only the disk fields are deserialized.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::storagebox::StorageBoxId;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let usage = robot.get_storagebox_usage(StorageBoxId(1234)).await.unwrap();
+    ///
+    /// if usage.usage_ratio() > 0.9 {
+    ///     println!("storagebox is {} of {} full", usage.total, usage.quota);
+    /// }
+    /// # }
+    /// ```
+    pub async fn get_storagebox_usage(&self, id: impl Into<StorageBoxId>) -> Result<Disk, Error> {
+        let id = id.into();
+        Ok(self.go(get_storagebox_usage(id)).await?.0.disk)
+    }
+
     /// Rename storagebox.
     ///
     /// # Example
@@ -270,9 +332,10 @@ impl AsyncRobot {
     /// ```
     pub async fn rename_storagebox(
         &self,
-        id: StorageBoxId,
+        id: impl Into<StorageBoxId>,
         name: &str,
     ) -> Result<StorageBox, Error> {
+        let id = id.into();
         Ok(self.go(rename_storagebox(id, name)?).await?.0)
     }
 
@@ -295,9 +358,10 @@ impl AsyncRobot {
     /// ```
     pub async fn configure_storagebox_accessibility(
         &self,
-        id: StorageBoxId,
+        id: impl Into<StorageBoxId>,
         accessibility: Accessibility,
     ) -> Result<StorageBox, Error> {
+        let id = id.into();
         Ok(self
             .go(configure_accessibility(id, accessibility)?)
             .await?
@@ -316,7 +380,11 @@ impl AsyncRobot {
     /// robot.enable_storagebox_samba(StorageBoxId(1234)).await.unwrap();
     /// # }
     /// ```
-    pub async fn enable_storagebox_samba(&self, id: StorageBoxId) -> Result<StorageBox, Error> {
+    pub async fn enable_storagebox_samba(
+        &self,
+        id: impl Into<StorageBoxId>,
+    ) -> Result<StorageBox, Error> {
+        let id = id.into();
         Ok(self.go(toggle_service(id, "samba", true)).await?.0)
     }
 
@@ -332,7 +400,11 @@ impl AsyncRobot {
     /// robot.disable_storagebox_samba(StorageBoxId(1234)).await.unwrap();
     /// # }
     /// ```
-    pub async fn disable_storagebox_samba(&self, id: StorageBoxId) -> Result<StorageBox, Error> {
+    pub async fn disable_storagebox_samba(
+        &self,
+        id: impl Into<StorageBoxId>,
+    ) -> Result<StorageBox, Error> {
+        let id = id.into();
         Ok(self.go(toggle_service(id, "samba", false)).await?.0)
     }
 
@@ -348,7 +420,11 @@ impl AsyncRobot {
     /// robot.enable_storagebox_webdav(StorageBoxId(1234)).await.unwrap();
     /// # }
     /// ```
-    pub async fn enable_storagebox_webdav(&self, id: StorageBoxId) -> Result<StorageBox, Error> {
+    pub async fn enable_storagebox_webdav(
+        &self,
+        id: impl Into<StorageBoxId>,
+    ) -> Result<StorageBox, Error> {
+        let id = id.into();
         Ok(self.go(toggle_service(id, "webdav", true)).await?.0)
     }
 
@@ -364,7 +440,11 @@ impl AsyncRobot {
     /// robot.disable_storagebox_webdav(StorageBoxId(1234)).await.unwrap();
     /// # }
     /// ```
-    pub async fn disable_storagebox_webdav(&self, id: StorageBoxId) -> Result<StorageBox, Error> {
+    pub async fn disable_storagebox_webdav(
+        &self,
+        id: impl Into<StorageBoxId>,
+    ) -> Result<StorageBox, Error> {
+        let id = id.into();
         Ok(self.go(toggle_service(id, "webdav", false)).await?.0)
     }
 
@@ -380,7 +460,11 @@ impl AsyncRobot {
     /// robot.enable_storagebox_ssh(StorageBoxId(1234)).await.unwrap();
     /// # }
     /// ```
-    pub async fn enable_storagebox_ssh(&self, id: StorageBoxId) -> Result<StorageBox, Error> {
+    pub async fn enable_storagebox_ssh(
+        &self,
+        id: impl Into<StorageBoxId>,
+    ) -> Result<StorageBox, Error> {
+        let id = id.into();
         Ok(self.go(toggle_service(id, "ssh", true)).await?.0)
     }
 
@@ -396,7 +480,11 @@ impl AsyncRobot {
     /// robot.disable_storagebox_ssh(StorageBoxId(1234)).await.unwrap();
     /// # }
     /// ```
-    pub async fn disable_storagebox_ssh(&self, id: StorageBoxId) -> Result<StorageBox, Error> {
+    pub async fn disable_storagebox_ssh(
+        &self,
+        id: impl Into<StorageBoxId>,
+    ) -> Result<StorageBox, Error> {
+        let id = id.into();
         Ok(self.go(toggle_service(id, "ssh", false)).await?.0)
     }
 
@@ -419,8 +507,9 @@ impl AsyncRobot {
     /// ```
     pub async fn enable_storagebox_external_reachability(
         &self,
-        id: StorageBoxId,
+        id: impl Into<StorageBoxId>,
     ) -> Result<StorageBox, Error> {
+        let id = id.into();
         Ok(self
             .go(toggle_service(id, "external_reachability", true))
             .await?
@@ -446,8 +535,9 @@ impl AsyncRobot {
     /// ```
     pub async fn disable_storagebox_external_reachability(
         &self,
-        id: StorageBoxId,
+        id: impl Into<StorageBoxId>,
     ) -> Result<StorageBox, Error> {
+        let id = id.into();
         Ok(self
             .go(toggle_service(id, "external_reachability", false))
             .await?
@@ -473,8 +563,9 @@ impl AsyncRobot {
     /// ```
     pub async fn enable_storagebox_snapshot_directory(
         &self,
-        id: StorageBoxId,
+        id: impl Into<StorageBoxId>,
     ) -> Result<StorageBox, Error> {
+        let id = id.into();
         Ok(self.go(toggle_service(id, "zfs", true)).await?.0)
     }
 
@@ -497,8 +588,9 @@ impl AsyncRobot {
     /// ```
     pub async fn disable_storagebox_snapshot_directory(
         &self,
-        id: StorageBoxId,
+        id: impl Into<StorageBoxId>,
     ) -> Result<StorageBox, Error> {
+        let id = id.into();
         Ok(self.go(toggle_service(id, "zfs", false)).await?.0)
     }
 
@@ -514,12 +606,19 @@ impl AsyncRobot {
     /// robot.reset_storagebox_password(StorageBoxId(1234)).await.unwrap();
     /// # }
     /// ```
-    pub async fn reset_storagebox_password(&self, id: StorageBoxId) -> Result<String, Error> {
+    pub async fn reset_storagebox_password(
+        &self,
+        id: impl Into<StorageBoxId>,
+    ) -> Result<String, Error> {
+        let id = id.into();
         Ok(self.go(reset_password(id)).await?.0)
     }
 
     /// List snapshots for storagebox.
     ///
+    /// Snapshots are returned in the order given by the API. Since [`Snapshot`]
+    /// is ordered by timestamp, sorting the result orders them oldest first.
+    ///
     /// # Example
     /// ```rust,no_run
     /// # use hrobot::api::storagebox::StorageBoxId;
@@ -530,10 +629,70 @@ impl AsyncRobot {
     /// robot.list_snapshots(StorageBoxId(1234)).await.unwrap();
     /// # }
     /// ```
-    pub async fn list_snapshots(&self, id: StorageBoxId) -> Result<Vec<Snapshot>, Error> {
+    pub async fn list_snapshots(
+        &self,
+        id: impl Into<StorageBoxId>,
+    ) -> Result<Vec<Snapshot>, Error> {
+        let id = id.into();
         Ok(self.go(list_snapshots(id)).await?.0)
     }
 
+    /// Get the most recently taken snapshot of the storagebox, if any.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::storagebox::StorageBoxId;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// if let Some(snapshot) = robot.get_latest_snapshot(StorageBoxId(1234)).await.unwrap() {
+    ///     println!("latest snapshot was taken at {}", snapshot.timestamp);
+    /// }
+    /// # }
+    /// ```
+    pub async fn get_latest_snapshot(
+        &self,
+        id: impl Into<StorageBoxId>,
+    ) -> Result<Option<Snapshot>, Error> {
+        Ok(self.list_snapshots(id).await?.into_iter().max())
+    }
+
+    /// List snapshots of the storagebox taken more than `age` ago, oldest first.
+    ///
+    /// Useful for enforcing retention policies.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::api::storagebox::StorageBoxId;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let month = Duration::from_secs(30 * 24 * 60 * 60);
+    ///
+    /// for snapshot in robot.snapshots_older_than(StorageBoxId(1234), month).await.unwrap() {
+    ///     robot.delete_snapshot(StorageBoxId(1234), snapshot.name).await.unwrap();
+    /// }
+    /// # }
+    /// ```
+    pub async fn snapshots_older_than(
+        &self,
+        id: impl Into<StorageBoxId>,
+        age: std::time::Duration,
+    ) -> Result<Vec<Snapshot>, Error> {
+        let mut snapshots: Vec<_> = self
+            .list_snapshots(id)
+            .await?
+            .into_iter()
+            .filter(|snapshot| snapshot.is_older_than(age))
+            .collect();
+
+        snapshots.sort();
+        Ok(snapshots)
+    }
+
     /// Create a new snapshot of the storagebox.
     ///
     /// # Example
@@ -546,7 +705,11 @@ impl AsyncRobot {
     /// robot.create_snapshot(StorageBoxId(1234)).await.unwrap();
     /// # }
     /// ```
-    pub async fn create_snapshot(&self, id: StorageBoxId) -> Result<CreatedSnapshot, Error> {
+    pub async fn create_snapshot(
+        &self,
+        id: impl Into<StorageBoxId>,
+    ) -> Result<CreatedSnapshot, Error> {
+        let id = id.into();
         Ok(self.go(create_snapshot(id)).await?.0)
     }
 
@@ -557,26 +720,28 @@ impl AsyncRobot {
     /// for deletion, is to first retrieve it, and use its name from there.
     ///
     /// If you otherwise know the timestamp, but not the name of the snapshot,
-    /// you can format it as "YYYY-MM-DDThh-mm-ss", with an assumed UTC timezone.
+    /// you can convert the timestamp into a [`SnapshotName`] directly.
     ///
     /// # Example
     /// ```rust,no_run
-    /// # use hrobot::api::storagebox::StorageBoxId;
+    /// # use hrobot::api::storagebox::{SnapshotName, StorageBoxId};
     /// # #[tokio::main]
     /// # async fn main() {
     /// # let _ = dotenvy::dotenv().ok();
     /// let robot = hrobot::AsyncRobot::default();
     /// robot.delete_snapshot(
     ///     StorageBoxId(1234),
-    ///     "2015-12-21T13-13-03"
+    ///     "2015-12-21T13-13-03".parse::<SnapshotName>().unwrap()
     /// ).await.unwrap();
     /// # }
     /// ```
     pub async fn delete_snapshot(
         &self,
-        id: StorageBoxId,
-        snapshot_name: &str,
+        id: impl Into<StorageBoxId>,
+        snapshot_name: impl Into<SnapshotName>,
     ) -> Result<(), Error> {
+        let id = id.into();
+        let snapshot_name = snapshot_name.into();
         self.go(delete_snapshot(id, snapshot_name))
             .await?
             .throw_away();
@@ -585,24 +750,31 @@ impl AsyncRobot {
 
     /// Revert storagebox to a snapshot.
     ///
+    /// **Unguarded:** this immediately and irreversibly discards all changes
+    /// made since the snapshot was taken. Prefer [`AsyncRobot::plan_snapshot_revert`]
+    /// followed by [`AsyncRobot::confirm_revert`], which reports what would be lost
+    /// and refuses to act on outdated information.
+    ///
     /// # Example
     /// ```rust,no_run
-    /// # use hrobot::api::storagebox::StorageBoxId;
+    /// # use hrobot::api::storagebox::{SnapshotName, StorageBoxId};
     /// # #[tokio::main]
     /// # async fn main() {
     /// # let _ = dotenvy::dotenv().ok();
     /// let robot = hrobot::AsyncRobot::default();
     /// robot.revert_to_snapshot(
     ///     StorageBoxId(1234),
-    ///     "2015-12-21T13-13-03"
+    ///     "2015-12-21T13-13-03".parse::<SnapshotName>().unwrap()
     /// ).await.unwrap();
     /// # }
     /// ```
     pub async fn revert_to_snapshot(
         &self,
-        id: StorageBoxId,
-        snapshot_name: &str,
+        id: impl Into<StorageBoxId>,
+        snapshot_name: impl Into<SnapshotName>,
     ) -> Result<(), Error> {
+        let id = id.into();
+        let snapshot_name = snapshot_name.into();
         self.go(revert_to_snapshot(id, snapshot_name))
             .await?
             .throw_away();
@@ -613,24 +785,26 @@ impl AsyncRobot {
     ///
     /// # Example
     /// ```rust,no_run
-    /// # use hrobot::api::storagebox::StorageBoxId;
+    /// # use hrobot::api::storagebox::{SnapshotName, StorageBoxId};
     /// # #[tokio::main]
     /// # async fn main() {
     /// # let _ = dotenvy::dotenv().ok();
     /// let robot = hrobot::AsyncRobot::default();
     /// robot.change_snapshot_comment(
     ///     StorageBoxId(1234),
-    ///     "2015-12-21T13-13-03",
+    ///     "2015-12-21T13-13-03".parse::<SnapshotName>().unwrap(),
     ///     "Last backup before upgrade to 2.0"
     /// ).await.unwrap();
     /// # }
     /// ```
     pub async fn change_snapshot_comment(
         &self,
-        id: StorageBoxId,
-        snapshot_name: &str,
+        id: impl Into<StorageBoxId>,
+        snapshot_name: impl Into<SnapshotName>,
         comment: &str,
     ) -> Result<(), Error> {
+        let id = id.into();
+        let snapshot_name = snapshot_name.into();
         self.go(change_snapshot_comment(id, snapshot_name, comment)?)
             .await?
             .throw_away();
@@ -649,7 +823,11 @@ impl AsyncRobot {
     /// robot.get_snapshot_plan(StorageBoxId(1234)).await.unwrap();
     /// # }
     /// ```
-    pub async fn get_snapshot_plan(&self, id: StorageBoxId) -> Result<SnapshotPlan, Error> {
+    pub async fn get_snapshot_plan(
+        &self,
+        id: impl Into<StorageBoxId>,
+    ) -> Result<SnapshotPlan, Error> {
+        let id = id.into();
         Ok(self.go(get_snapshot_plan(id)).await?.0)
     }
 
@@ -671,9 +849,10 @@ impl AsyncRobot {
     /// ```
     pub async fn update_snapshot_plan(
         &self,
-        id: StorageBoxId,
+        id: impl Into<StorageBoxId>,
         plan: SnapshotPlan,
     ) -> Result<SnapshotPlan, Error> {
+        let id = id.into();
         Ok(self.go(update_snapshot_plan(id, plan)?).await?.0)
     }
 
@@ -689,10 +868,51 @@ impl AsyncRobot {
     /// robot.list_subaccounts(StorageBoxId(1234)).await.unwrap();
     /// # }
     /// ```
-    pub async fn list_subaccounts(&self, id: StorageBoxId) -> Result<Vec<Subaccount>, Error> {
+    pub async fn list_subaccounts(
+        &self,
+        id: impl Into<StorageBoxId>,
+    ) -> Result<Vec<Subaccount>, Error> {
+        let id = id.into();
         Ok(self.go(list_subaccounts(id)).await?.0)
     }
 
+    /// List sub-accounts for storagebox which were created more than `older_than` ago,
+    /// oldest first.
+    ///
+    /// The API reports no login or modification times for sub-accounts, so creation
+    /// time is the only indication of age available. Useful for finding backup
+    /// credentials which are due for rotation.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::api::storagebox::StorageBoxId;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let year = Duration::from_secs(365 * 24 * 60 * 60);
+    /// for subaccount in robot.list_stale_subaccounts(StorageBoxId(1234), year).await.unwrap() {
+    ///     println!("{} is {} days old", subaccount.username, subaccount.age().whole_days());
+    /// }
+    /// # }
+    /// ```
+    pub async fn list_stale_subaccounts(
+        &self,
+        id: impl Into<StorageBoxId>,
+        older_than: std::time::Duration,
+    ) -> Result<Vec<Subaccount>, Error> {
+        let mut subaccounts: Vec<_> = self
+            .list_subaccounts(id)
+            .await?
+            .into_iter()
+            .filter(|subaccount| subaccount.is_older_than(older_than))
+            .collect();
+
+        subaccounts.sort_by_key(|subaccount| subaccount.createtime);
+        Ok(subaccounts)
+    }
+
     /// Create sub-account.
     ///
     /// # Example
@@ -713,18 +933,19 @@ impl AsyncRobot {
     /// ```
     pub async fn create_subaccount(
         &self,
-        storagebox: StorageBoxId,
+        storagebox: impl Into<StorageBoxId>,
         home_directory: &str,
         accessibility: Accessibility,
-        permissions: Permission,
+        permissions: impl Into<SubaccountPermissions>,
         comment: Option<&str>,
     ) -> Result<CreatedSubaccount, Error> {
+        let storagebox = storagebox.into();
         Ok(self
             .go(create_subaccount(
                 storagebox,
                 home_directory,
                 accessibility,
-                permissions,
+                permissions.into(),
                 comment,
             )?)
             .await?
@@ -749,10 +970,11 @@ impl AsyncRobot {
     /// ```
     pub async fn set_subaccount_home_directory(
         &self,
-        storagebox: StorageBoxId,
+        storagebox: impl Into<StorageBoxId>,
         subaccount: &SubaccountId,
         home_directory: &str,
     ) -> Result<(), Error> {
+        let storagebox = storagebox.into();
         self.go(update_subaccount(
             storagebox,
             subaccount,
@@ -766,7 +988,9 @@ impl AsyncRobot {
         Ok(())
     }
 
-    /// Change sub-account comment/description.
+    /// Update sub-account home directory, accessibility, permissions and comment/description.
+    ///
+    /// Any field set to `None` is left unchanged.
     ///
     /// # Example
     /// ```rust,no_run
@@ -778,26 +1002,27 @@ impl AsyncRobot {
     /// robot.update_subaccount(
     ///     StorageBoxId(1234),
     ///     &SubaccountId("u1234-sub1".to_string()),
-    ///     "/new/home/dir",
+    ///     None, // Keep old home directory
     ///     None, // Keep old accessibility options
-    ///     Some(Permission::ReadWrite),
+    ///     Some(Permission::ReadWrite.into()),
     ///     Some("Sub-account used for accessing backups")
     /// ).await.unwrap();
     /// # }
     /// ```
     pub async fn update_subaccount(
         &self,
-        storagebox: StorageBoxId,
+        storagebox: impl Into<StorageBoxId>,
         subaccount: &SubaccountId,
-        home_directory: &str,
+        home_directory: Option<&str>,
         accessibility: Option<&Accessibility>,
-        permissions: Option<Permission>,
+        permissions: Option<SubaccountPermissions>,
         comment: Option<&str>,
     ) -> Result<(), Error> {
+        let storagebox = storagebox.into();
         self.go(update_subaccount(
             storagebox,
             subaccount,
-            Some(home_directory),
+            home_directory,
             accessibility,
             permissions,
             comment,
@@ -807,6 +1032,279 @@ impl AsyncRobot {
         Ok(())
     }
 
+    /// Enable Samba (SMB) access to the storagebox for the sub-account.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::storagebox::{StorageBoxId, SubaccountId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// robot.enable_subaccount_samba(
+    ///     StorageBoxId(1234),
+    ///     &SubaccountId("u1234-sub1".to_string()),
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn enable_subaccount_samba(
+        &self,
+        storagebox: impl Into<StorageBoxId>,
+        subaccount: &SubaccountId,
+    ) -> Result<(), Error> {
+        let storagebox = storagebox.into();
+        self.go(toggle_subaccount_service(
+            storagebox, subaccount, "samba", true,
+        ))
+        .await?
+        .throw_away();
+        Ok(())
+    }
+
+    /// Disable Samba (SMB) access to the storagebox for the sub-account.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::storagebox::{StorageBoxId, SubaccountId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// robot.disable_subaccount_samba(
+    ///     StorageBoxId(1234),
+    ///     &SubaccountId("u1234-sub1".to_string()),
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn disable_subaccount_samba(
+        &self,
+        storagebox: impl Into<StorageBoxId>,
+        subaccount: &SubaccountId,
+    ) -> Result<(), Error> {
+        let storagebox = storagebox.into();
+        self.go(toggle_subaccount_service(
+            storagebox, subaccount, "samba", false,
+        ))
+        .await?
+        .throw_away();
+        Ok(())
+    }
+
+    /// Enable WebDAV access to the storagebox for the sub-account.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::storagebox::{StorageBoxId, SubaccountId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// robot.enable_subaccount_webdav(
+    ///     StorageBoxId(1234),
+    ///     &SubaccountId("u1234-sub1".to_string()),
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn enable_subaccount_webdav(
+        &self,
+        storagebox: impl Into<StorageBoxId>,
+        subaccount: &SubaccountId,
+    ) -> Result<(), Error> {
+        let storagebox = storagebox.into();
+        self.go(toggle_subaccount_service(
+            storagebox, subaccount, "webdav", true,
+        ))
+        .await?
+        .throw_away();
+        Ok(())
+    }
+
+    /// Disable WebDAV access to the storagebox for the sub-account.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::storagebox::{StorageBoxId, SubaccountId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// robot.disable_subaccount_webdav(
+    ///     StorageBoxId(1234),
+    ///     &SubaccountId("u1234-sub1".to_string()),
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn disable_subaccount_webdav(
+        &self,
+        storagebox: impl Into<StorageBoxId>,
+        subaccount: &SubaccountId,
+    ) -> Result<(), Error> {
+        let storagebox = storagebox.into();
+        self.go(toggle_subaccount_service(
+            storagebox, subaccount, "webdav", false,
+        ))
+        .await?
+        .throw_away();
+        Ok(())
+    }
+
+    /// Enable SSH access to the storagebox for the sub-account.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::storagebox::{StorageBoxId, SubaccountId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// robot.enable_subaccount_ssh(
+    ///     StorageBoxId(1234),
+    ///     &SubaccountId("u1234-sub1".to_string()),
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn enable_subaccount_ssh(
+        &self,
+        storagebox: impl Into<StorageBoxId>,
+        subaccount: &SubaccountId,
+    ) -> Result<(), Error> {
+        let storagebox = storagebox.into();
+        self.go(toggle_subaccount_service(
+            storagebox, subaccount, "ssh", true,
+        ))
+        .await?
+        .throw_away();
+        Ok(())
+    }
+
+    /// Disable SSH access to the storagebox for the sub-account.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::storagebox::{StorageBoxId, SubaccountId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// robot.disable_subaccount_ssh(
+    ///     StorageBoxId(1234),
+    ///     &SubaccountId("u1234-sub1".to_string()),
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn disable_subaccount_ssh(
+        &self,
+        storagebox: impl Into<StorageBoxId>,
+        subaccount: &SubaccountId,
+    ) -> Result<(), Error> {
+        let storagebox = storagebox.into();
+        self.go(toggle_subaccount_service(
+            storagebox, subaccount, "ssh", false,
+        ))
+        .await?
+        .throw_away();
+        Ok(())
+    }
+
+    /// Enable external reachability for the sub-account.
+    ///
+    /// See [`AsyncRobot::enable_storagebox_external_reachability`] for details.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::storagebox::{StorageBoxId, SubaccountId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// robot.enable_subaccount_external_reachability(
+    ///     StorageBoxId(1234),
+    ///     &SubaccountId("u1234-sub1".to_string()),
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn enable_subaccount_external_reachability(
+        &self,
+        storagebox: impl Into<StorageBoxId>,
+        subaccount: &SubaccountId,
+    ) -> Result<(), Error> {
+        let storagebox = storagebox.into();
+        self.go(toggle_subaccount_service(
+            storagebox,
+            subaccount,
+            "external_reachability",
+            true,
+        ))
+        .await?
+        .throw_away();
+        Ok(())
+    }
+
+    /// Disable external reachability for the sub-account.
+    ///
+    /// See [`AsyncRobot::enable_storagebox_external_reachability`] for details.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::storagebox::{StorageBoxId, SubaccountId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// robot.disable_subaccount_external_reachability(
+    ///     StorageBoxId(1234),
+    ///     &SubaccountId("u1234-sub1".to_string()),
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn disable_subaccount_external_reachability(
+        &self,
+        storagebox: impl Into<StorageBoxId>,
+        subaccount: &SubaccountId,
+    ) -> Result<(), Error> {
+        let storagebox = storagebox.into();
+        self.go(toggle_subaccount_service(
+            storagebox,
+            subaccount,
+            "external_reachability",
+            false,
+        ))
+        .await?
+        .throw_away();
+        Ok(())
+    }
+
+    /// Restrict the sub-account to read-only access, or grant it read-write access.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::storagebox::{StorageBoxId, SubaccountId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// robot.set_subaccount_readonly(
+    ///     StorageBoxId(1234),
+    ///     &SubaccountId("u1234-sub1".to_string()),
+    ///     true,
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn set_subaccount_readonly(
+        &self,
+        storagebox: impl Into<StorageBoxId>,
+        subaccount: &SubaccountId,
+        read_only: bool,
+    ) -> Result<(), Error> {
+        let storagebox = storagebox.into();
+        self.go(toggle_subaccount_service(
+            storagebox, subaccount, "readonly", read_only,
+        ))
+        .await?
+        .throw_away();
+        Ok(())
+    }
+
     /// Reset sub-account password.
     ///
     /// # Example
@@ -826,9 +1324,10 @@ impl AsyncRobot {
     /// ```
     pub async fn reset_subaccount_password(
         &self,
-        storagebox: StorageBoxId,
+        storagebox: impl Into<StorageBoxId>,
         subaccount: &SubaccountId,
     ) -> Result<String, Error> {
+        let storagebox = storagebox.into();
         Ok(self
             .go(reset_subaccount_password(storagebox, subaccount))
             .await?
@@ -852,12 +1351,62 @@ impl AsyncRobot {
     /// ```
     pub async fn delete_subaccount(
         &self,
-        storagebox: StorageBoxId,
+        storagebox: impl Into<StorageBoxId>,
         subaccount: SubaccountId,
     ) -> Result<(), Error> {
+        let storagebox = storagebox.into();
         self.go(delete_subaccount(storagebox, subaccount))
             .await?
             .throw_away();
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bytesize::ByteSize;
+
+    use crate::mock::{fixtures, MockClient};
+
+    use super::{update_subaccount, Permission, StorageBoxId, SubaccountId};
+
+    #[tokio::test]
+    async fn storagebox_usage() {
+        let robot = MockClient::new()
+            .on("GET", "/storagebox/1234", fixtures::storagebox(1234))
+            .robot();
+
+        let usage = robot
+            .get_storagebox_usage(StorageBoxId(1234))
+            .await
+            .unwrap();
+        assert_eq!(usage.quota, ByteSize::mib(10240000));
+        assert_eq!(usage.snapshots, ByteSize::mib(512000));
+        assert_eq!(usage.usage_ratio(), 0.25);
+
+        let storagebox = robot
+            .strict_models(true)
+            .get_storagebox(StorageBoxId(1234))
+            .await
+            .unwrap();
+        assert_eq!(storagebox.usage_ratio(), 0.25);
+    }
+
+    #[test]
+    fn partial_subaccount_update_omits_home_directory() {
+        let request = update_subaccount(
+            StorageBoxId(1234),
+            &SubaccountId("u1234-sub1".to_string()),
+            None,
+            None,
+            Some(Permission::ReadWrite.into()),
+            Some("backups"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            request.body.as_deref(),
+            Some("readonly=false&comment=backups")
+        );
+    }
+}
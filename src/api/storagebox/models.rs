@@ -1,10 +1,13 @@
-use std::fmt::Display;
+use std::{cmp::Ordering, fmt::Display, num::ParseIntError, str::FromStr, time::Duration};
 
 use bytesize::ByteSize;
 use serde::{Deserialize, Serialize};
-use time::{Date, OffsetDateTime, Weekday};
+use time::{
+    format_description::FormatItem, macros::format_description, Date, OffsetDateTime,
+    PrimitiveDateTime, Weekday,
+};
 
-use crate::api::server::ServerId;
+use crate::api::server::{Location, ServerId};
 
 /// Unique StorageBox ID.
 ///
@@ -15,6 +18,7 @@ use crate::api::server::ServerId;
 /// is in range `0..N` where `N` is the number of storageboxes in my account, or is it a limiter, like get first `N`
 /// storageboxes, for example.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct StorageBoxId(pub u32);
 
 impl From<u32> for StorageBoxId {
@@ -41,10 +45,25 @@ impl PartialEq<u32> for StorageBoxId {
     }
 }
 
+impl PartialEq<StorageBoxId> for u32 {
+    fn eq(&self, other: &StorageBoxId) -> bool {
+        self.eq(&other.0)
+    }
+}
+
+impl FromStr for StorageBoxId {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u32::from_str(s).map(StorageBoxId)
+    }
+}
+
 /// Reference to a storagebox.
 ///
 /// Does not contain disk, access or reachability information.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct StorageBoxReference {
     /// Unique ID for this storagebox.
     pub id: StorageBoxId,
@@ -59,23 +78,28 @@ pub struct StorageBoxReference {
     pub product: String,
 
     /// Indicates whether the storagebox has been cancelled.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub cancelled: bool,
 
     /// Indicates if the storagebox is locked.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub locked: bool,
 
     /// Datacenter location of the storagebox, e.g. `FSN1`.
-    pub location: String,
+    pub location: Location,
 
     /// Server which this storagebox is linked to.
     pub linked_server: Option<ServerId>,
 
     /// Date until which this storagebox has been paid for.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    #[serde(with = "crate::conversion::date")]
     pub paid_until: Date,
 }
 
 /// Storage Box
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct StorageBox {
     /// Unique ID for this storagebox.
     pub id: StorageBoxId,
@@ -90,18 +114,25 @@ pub struct StorageBox {
     pub product: String,
 
     /// Indicates whether the storagebox has been cancelled.
+    ///
+    /// Storageboxes can only be cancelled through the Robot UI,
+    /// since the API has no cancellation endpoint for them.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub cancelled: bool,
 
     /// Indicates if the storagebox is locked.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub locked: bool,
 
     /// Datacenter location of the storagebox, e.g. `FSN1`.
-    pub location: String,
+    pub location: Location,
 
     /// Server which this storagebox is linked to.
     pub linked_server: Option<ServerId>,
 
     /// Date until which this storagebox has been paid for.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    #[serde(with = "crate::conversion::date")]
     pub paid_until: Date,
 
     /// Servername through which the storagebox can be accessed.
@@ -118,7 +149,10 @@ pub struct StorageBox {
     /// data can be copied.
     ///
     /// See more at: <https://docs.hetzner.com/robot/storage-box/snapshots/>
-    #[serde(rename = "zfs")]
+    #[serde(
+        rename = "zfs",
+        deserialize_with = "crate::conversion::permissive_bool"
+    )]
     pub snapshot_directory: bool,
 
     /// Accessibility.
@@ -126,87 +160,280 @@ pub struct StorageBox {
     pub accessibility: Accessibility,
 }
 
+impl StorageBox {
+    /// Fraction of the quota in use, including snapshots.
+    ///
+    /// See [`Disk::usage_ratio`].
+    pub fn usage_ratio(&self) -> f64 {
+        self.disk.usage_ratio()
+    }
+}
+
 /// Disk usage and quota information for a storagebox.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Disk {
     /// Storage quota.
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
     #[serde(rename = "disk_quota", with = "crate::conversion::mib")]
     pub quota: ByteSize,
 
     /// Storage usage in total (combined data & snapshots).
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
     #[serde(rename = "disk_usage", with = "crate::conversion::mib")]
     pub total: ByteSize,
 
     /// Storage used by data.
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
     #[serde(rename = "disk_usage_data", with = "crate::conversion::mib")]
     pub data: ByteSize,
 
     /// Storage used by snapshots.
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
     #[serde(rename = "disk_usage_snapshots", with = "crate::conversion::mib")]
     pub snapshots: ByteSize,
 }
 
+impl Disk {
+    /// Fraction of the quota in use, including snapshots, e.g. `0.25` for a quarter.
+    ///
+    /// Returns `0.0` if the quota is zero.
+    pub fn usage_ratio(&self) -> f64 {
+        if self.quota.as_u64() == 0 {
+            return 0.0;
+        }
+
+        self.total.as_u64() as f64 / self.quota.as_u64() as f64
+    }
+}
+
 /// Accessibility covers the availability of different services one might
 /// use to access the storagebox.
-#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Accessibility {
     /// Indicates whether the storagebox is accessible via WebDAV.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub webdav: bool,
 
     /// Indicates whether the storagebox is available over Samba.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub samba: bool,
 
     /// Indicates whether the storagebox is accessible via SSH.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub ssh: bool,
 
     /// Indicates whether the server is externally reachable.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub external_reachability: bool,
 }
 
 /// A snapshot is a point-in-time backup of the storagebox, which can be
 /// used to restore the storagebox to the captured state..
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Snapshot {
     /// Name of the snapshot.
-    pub name: String,
+    pub name: SnapshotName,
 
     /// Point in time at which the snapshot was taken.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     #[serde(with = "time::serde::rfc3339")]
     pub timestamp: OffsetDateTime,
 
     /// Size of the snapshot.
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
     #[serde(with = "crate::conversion::mib")]
     pub size: ByteSize,
 
     /// Size of the filesystem.
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
     #[serde(with = "crate::conversion::mib")]
     pub filesystem_size: ByteSize,
 
     /// Indicates whether the snapshot was produced by an
     /// automatic or manual process.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub automatic: bool,
 
     /// Optional comment associated with the snapshot.
     pub comment: String,
 }
 
+impl Snapshot {
+    /// Returns true if the snapshot was taken more than `age` ago.
+    ///
+    /// Ages reaching back further than [`OffsetDateTime`] can represent are never exceeded.
+    pub fn is_older_than(&self, age: Duration) -> bool {
+        cutoff(age).is_some_and(|cutoff| self.timestamp < cutoff)
+    }
+}
+
+/// Point in time `age` ago, if representable.
+fn cutoff(age: Duration) -> Option<OffsetDateTime> {
+    time::Duration::try_from(age)
+        .ok()
+        .and_then(|age| OffsetDateTime::now_utc().checked_sub(age))
+}
+
+/// Snapshots are ordered by the time at which they were taken, oldest first.
+impl Ord for Snapshot {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.timestamp
+            .cmp(&other.timestamp)
+            .then_with(|| self.name.cmp(&other.name))
+            .then_with(|| self.size.cmp(&other.size))
+            .then_with(|| self.filesystem_size.cmp(&other.filesystem_size))
+            .then_with(|| self.automatic.cmp(&other.automatic))
+            .then_with(|| self.comment.cmp(&other.comment))
+    }
+}
+
+impl PartialOrd for Snapshot {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 /// Short summary of the newly created snapshot.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct CreatedSnapshot {
     /// Name of the snapshot.
-    pub name: String,
+    pub name: SnapshotName,
 
     /// Point in time at which the snapshot was taken.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     #[serde(with = "time::serde::rfc3339")]
     pub timestamp: OffsetDateTime,
 
     /// Size of the snapshot.
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
     #[serde(with = "crate::conversion::mib")]
     pub size: ByteSize,
 }
 
+/// Format of snapshot names, e.g. `2015-12-21T13-13-03`.
+const SNAPSHOT_NAME_FORMAT: &[FormatItem<'static>] =
+    format_description!("[year]-[month]-[day]T[hour]-[minute]-[second]");
+
+/// Name of a storagebox [`Snapshot`].
+///
+/// Snapshots are named after the point in time at which they were taken,
+/// formatted as `YYYY-MM-DDThh-mm-ss` with an implicit timezone of UTC,
+/// e.g. `2015-12-21T13-13-03`.
+///
+/// # Example
+/// ```rust
+/// # use hrobot::api::storagebox::SnapshotName;
+/// # use time::macros::datetime;
+/// let name = SnapshotName::try_from("2015-12-21T13-13-03").unwrap();
+/// assert_eq!(name.timestamp(), datetime!(2015-12-21 13:13:03 UTC));
+///
+/// let name = SnapshotName::from(datetime!(2015-12-21 14:13:03 +01:00));
+/// assert_eq!(name.to_string(), "2015-12-21T13-13-03");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SnapshotName(OffsetDateTime);
+
+impl SnapshotName {
+    /// Point in time at which the snapshot was taken, in UTC.
+    pub fn timestamp(&self) -> OffsetDateTime {
+        self.0
+    }
+}
+
+/// Snapshot name is not of the form `YYYY-MM-DDThh-mm-ss`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("snapshot name {0:?} is not of the form YYYY-MM-DDThh-mm-ss")]
+pub struct InvalidSnapshotName(pub String);
+
+impl From<OffsetDateTime> for SnapshotName {
+    /// Name of the snapshot taken at `timestamp`, truncated to whole seconds.
+    fn from(timestamp: OffsetDateTime) -> Self {
+        let timestamp = timestamp.to_offset(time::UtcOffset::UTC);
+        SnapshotName(timestamp.replace_nanosecond(0).unwrap_or(timestamp))
+    }
+}
+
+impl From<SnapshotName> for OffsetDateTime {
+    fn from(value: SnapshotName) -> Self {
+        value.0
+    }
+}
+
+impl TryFrom<&str> for SnapshotName {
+    type Error = InvalidSnapshotName;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        PrimitiveDateTime::parse(value, SNAPSHOT_NAME_FORMAT)
+            .map(|timestamp| SnapshotName(timestamp.assume_utc()))
+            .map_err(|_| InvalidSnapshotName(value.to_string()))
+    }
+}
+
+impl FromStr for SnapshotName {
+    type Err = InvalidSnapshotName;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SnapshotName::try_from(s)
+    }
+}
+
+impl Display for SnapshotName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = self
+            .0
+            .format(SNAPSHOT_NAME_FORMAT)
+            .map_err(|_| std::fmt::Error)?;
+
+        f.write_str(&name)
+    }
+}
+
+impl PartialEq<str> for SnapshotName {
+    fn eq(&self, other: &str) -> bool {
+        SnapshotName::try_from(other).is_ok_and(|other| *self == other)
+    }
+}
+
+impl Serialize for SnapshotName {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SnapshotName {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+        SnapshotName::try_from(name.as_str()).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for SnapshotName {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "SnapshotName".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "pattern": "^[0-9]{4}-[0-9]{2}-[0-9]{2}T[0-9]{2}-[0-9]{2}-[0-9]{2}$"
+        })
+    }
+}
+
 /// Snapshot plans periodically take snapshots of the underlying storagebox.
-#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SnapshotPlan {
     /// Indicates whether the snapshot plan is enabled or not.
     pub status: PlanStatus,
@@ -220,6 +447,7 @@ pub struct SnapshotPlan {
     pub hour: u8,
 
     /// Day of week on which to take snapshot.
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<u8>"))]
     #[serde(
         default,
         with = "crate::conversion::weekday_plus_one",
@@ -284,10 +512,130 @@ impl SnapshotPlan {
         self.max_snapshots = Some(max_snapshots);
         self
     }
+
+    /// Check that the time, day of month and snapshot limit of the plan are within bounds.
+    pub fn validate(&self) -> Result<(), InvalidSnapshotPlan> {
+        if self.hour > 23 {
+            return Err(InvalidSnapshotPlan::Hour(self.hour));
+        }
+
+        if self.minute > 59 {
+            return Err(InvalidSnapshotPlan::Minute(self.minute));
+        }
+
+        if self.day_of_week.is_some() && self.day_of_month.is_some() {
+            return Err(InvalidSnapshotPlan::ConflictingDays);
+        }
+
+        if let Some(day) = self.day_of_month {
+            if !(1..=31).contains(&day) {
+                return Err(InvalidSnapshotPlan::DayOfMonth(day));
+            }
+        }
+
+        if let Some(max_snapshots) = self.max_snapshots {
+            if !(1..=10).contains(&max_snapshots) {
+                return Err(InvalidSnapshotPlan::MaxSnapshots(max_snapshots));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Describes why a [`SnapshotPlan`] is invalid.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum InvalidSnapshotPlan {
+    /// Hour is outside of the range `0..=23`.
+    #[error("hour must be between 0 and 23, but was {0}")]
+    Hour(u8),
+
+    /// Minute is outside of the range `0..=59`.
+    #[error("minute must be between 0 and 59, but was {0}")]
+    Minute(u8),
+
+    /// Day of month is outside of the range `1..=31`.
+    #[error("day of month must be between 1 and 31, but was {0}")]
+    DayOfMonth(u8),
+
+    /// Both day of week and day of month are set.
+    #[error("a snapshot plan can be either weekly or monthly, not both")]
+    ConflictingDays,
+
+    /// Maximum number of snapshots is outside of the range `1..=10`.
+    #[error("maximum number of snapshots must be between 1 and 10, but was {0}")]
+    MaxSnapshots(u8),
+}
+
+/// Builder for constructing a validated [`SnapshotPlan`].
+///
+/// Snapshots are taken at midnight, unless a different time is set using [`SnapshotPlanBuilder::at`].
+///
+/// # Example
+/// ```rust
+/// # use hrobot::api::storagebox::SnapshotPlanBuilder;
+/// # use hrobot::time::Weekday;
+/// let plan = SnapshotPlanBuilder::weekly(Weekday::Monday)
+///     .at(10, 30)
+///     .max_snapshots(5)
+///     .build()
+///     .unwrap();
+///
+/// assert!(SnapshotPlanBuilder::monthly(32).build().is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SnapshotPlanBuilder {
+    plan: SnapshotPlan,
+}
+
+impl SnapshotPlanBuilder {
+    /// Daily snapshots.
+    pub fn daily() -> Self {
+        SnapshotPlanBuilder {
+            plan: SnapshotPlan::daily(0, 0),
+        }
+    }
+
+    /// Weekly snapshots taken on the given day of the week.
+    pub fn weekly(day: Weekday) -> Self {
+        SnapshotPlanBuilder {
+            plan: SnapshotPlan::weekly(day, 0, 0),
+        }
+    }
+
+    /// Monthly snapshots taken on the given 1-indexed day of the month.
+    pub fn monthly(day: u8) -> Self {
+        SnapshotPlanBuilder {
+            plan: SnapshotPlan::monthly(day, 0, 0),
+        }
+    }
+
+    /// Time of day at which to take the snapshot.
+    pub fn at(mut self, hour: u8, minute: u8) -> Self {
+        self.plan.hour = hour;
+        self.plan.minute = minute;
+        self
+    }
+
+    /// Limit the maximum number of snapshots to keep.
+    ///
+    /// Stand-alone storageboxes are limited to 10 snapshots, while linked
+    /// storageboxes are limited to only 2.
+    pub fn max_snapshots(mut self, max_snapshots: u8) -> Self {
+        self.plan.max_snapshots = Some(max_snapshots);
+        self
+    }
+
+    /// Validate and construct the [`SnapshotPlan`].
+    pub fn build(self) -> Result<SnapshotPlan, InvalidSnapshotPlan> {
+        self.plan.validate()?;
+        Ok(self.plan)
+    }
 }
 
 /// Indicates whether the snapshot plan is enabled or not.
-#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum PlanStatus {
     /// Plan is enabled.
@@ -298,7 +646,8 @@ pub enum PlanStatus {
 }
 
 /// Describes a sub-account for the storabox.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Subaccount {
     /// Username for the sub-account.
     pub username: SubaccountId,
@@ -316,19 +665,36 @@ pub struct Subaccount {
     #[serde(flatten)]
     pub accessibility: Accessibility,
 
-    /// Indicates whether the sub-account is limited to read-only access.
-    pub readonly: Permission,
+    /// Permissions of the sub-account within its home directory.
+    #[serde(flatten)]
+    pub permissions: SubaccountPermissions,
 
     /// Timestamp at which the sub-account was created.
     #[serde(deserialize_with = "crate::conversion::assume_berlin_timezone")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub createtime: OffsetDateTime,
 
     /// Comment or description associated with the sub-account
     pub comment: String,
 }
 
+impl Subaccount {
+    /// Time elapsed since the sub-account was created.
+    pub fn age(&self) -> time::Duration {
+        OffsetDateTime::now_utc() - self.createtime
+    }
+
+    /// Returns true if the sub-account was created more than `age` ago.
+    ///
+    /// Ages reaching back further than [`OffsetDateTime`] can represent are never exceeded.
+    pub fn is_older_than(&self, age: Duration) -> bool {
+        cutoff(age).is_some_and(|cutoff| self.createtime < cutoff)
+    }
+}
+
 /// Describes a sub-account for the storabox.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct CreatedSubaccount {
     /// Username for the sub-account.
     pub username: SubaccountId,
@@ -348,6 +714,7 @@ pub struct CreatedSubaccount {
 
 /// Unique Storagebox sub-account ID.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SubaccountId(pub String);
 
 impl From<String> for SubaccountId {
@@ -381,7 +748,7 @@ impl PartialEq<str> for SubaccountId {
 }
 
 /// Permissions of the sub-account.
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Permission {
     /// Subaccount is only able to read from the storagebox/directory
     #[default]
@@ -407,18 +774,65 @@ impl<'de> Deserialize<'de> for Permission {
     where
         D: serde::Deserializer<'de>,
     {
-        Ok(match bool::deserialize(deserializer)? {
+        Ok(match crate::conversion::permissive_bool(deserializer)? {
             true => Permission::ReadOnly,
             false => Permission::ReadWrite,
         })
     }
 }
 
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Permission {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Permission".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Whether the sub-account is read-only.",
+            "type": "boolean"
+        })
+    }
+}
+
+/// Permissions of a sub-account within its home directory.
+///
+/// The API currently only distinguishes read-only from read-write access,
+/// but this struct is non-exhaustive so finer-grained permissions can be added
+/// without breaking changes. Construct it from a [`Permission`].
+///
+/// # Example
+/// ```rust
+/// # use hrobot::api::storagebox::{Permission, SubaccountPermissions};
+/// let permissions = SubaccountPermissions::from(Permission::ReadWrite);
+/// assert_eq!(permissions.readonly, Permission::ReadWrite);
+/// ```
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct SubaccountPermissions {
+    /// Whether the sub-account is limited to read-only access.
+    pub readonly: Permission,
+}
+
+impl SubaccountPermissions {
+    /// Returns true if the sub-account can create, modify and delete files.
+    pub fn can_write(&self) -> bool {
+        self.readonly == Permission::ReadWrite
+    }
+}
+
+impl From<Permission> for SubaccountPermissions {
+    fn from(readonly: Permission) -> Self {
+        SubaccountPermissions { readonly }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::api::storagebox::{StorageBoxId, SubaccountId};
+    use crate::api::storagebox::{Snapshot, SnapshotName, StorageBoxId, SubaccountId};
 
-    use super::Permission;
+    use super::{Permission, StorageBoxReference};
 
     #[test]
     fn storagebox_id() {
@@ -438,6 +852,47 @@ mod tests {
         assert_eq!(&SubaccountId("sub-2".to_string()), "sub-2")
     }
 
+    #[test]
+    fn deserialize_boolean_representations() {
+        let storageboxes: Vec<StorageBoxReference> = serde_json::from_str(
+            r#"[
+                {
+                    "id": 123456,
+                    "login": "u12345",
+                    "name": "Backup Server 1",
+                    "product": "BX60",
+                    "cancelled": "false",
+                    "locked": 0,
+                    "location": "FSN1",
+                    "linked_server": null,
+                    "paid_until": "2015-10-23"
+                },
+                {
+                    "id": 123457,
+                    "login": "u12346",
+                    "name": "Backup Server 2",
+                    "product": "BX60",
+                    "cancelled": 1,
+                    "locked": "true",
+                    "location": "FSN1",
+                    "linked_server": null,
+                    "paid_until": "2015-10-23"
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        assert!(!storageboxes[0].cancelled);
+        assert!(!storageboxes[0].locked);
+        assert!(storageboxes[1].cancelled);
+        assert!(storageboxes[1].locked);
+
+        assert_eq!(
+            serde_json::from_str::<Permission>("1").unwrap(),
+            Permission::ReadOnly
+        );
+    }
+
     #[test]
     fn permission_deserialization() {
         let permission = Permission::ReadOnly;
@@ -456,4 +911,182 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn snapshot_plan_builder() {
+        use time::Weekday;
+
+        use crate::api::storagebox::{InvalidSnapshotPlan, SnapshotPlan, SnapshotPlanBuilder};
+
+        assert_eq!(
+            SnapshotPlanBuilder::daily().at(4, 15).build(),
+            Ok(SnapshotPlan::daily(4, 15))
+        );
+
+        assert_eq!(
+            SnapshotPlanBuilder::weekly(Weekday::Friday)
+                .at(23, 59)
+                .max_snapshots(2)
+                .build(),
+            Ok(SnapshotPlan::weekly(Weekday::Friday, 23, 59).with_limit(2))
+        );
+
+        assert_eq!(
+            SnapshotPlanBuilder::monthly(31).build(),
+            Ok(SnapshotPlan::monthly(31, 0, 0))
+        );
+
+        assert_eq!(
+            SnapshotPlanBuilder::daily().at(24, 0).build(),
+            Err(InvalidSnapshotPlan::Hour(24))
+        );
+
+        assert_eq!(
+            SnapshotPlanBuilder::daily().at(0, 60).build(),
+            Err(InvalidSnapshotPlan::Minute(60))
+        );
+
+        assert_eq!(
+            SnapshotPlanBuilder::monthly(0).build(),
+            Err(InvalidSnapshotPlan::DayOfMonth(0))
+        );
+
+        assert_eq!(
+            SnapshotPlanBuilder::daily().max_snapshots(0).build(),
+            Err(InvalidSnapshotPlan::MaxSnapshots(0))
+        );
+
+        let mut plan = SnapshotPlan::weekly(Weekday::Monday, 0, 0);
+        plan.day_of_month = Some(1);
+        assert_eq!(plan.validate(), Err(InvalidSnapshotPlan::ConflictingDays));
+    }
+
+    #[test]
+    fn snapshot_name() {
+        use time::macros::datetime;
+
+        let name = SnapshotName::try_from("2015-12-21T13-13-03").unwrap();
+        assert_eq!(name.timestamp(), datetime!(2015-12-21 13:13:03 UTC));
+        assert_eq!(name.to_string(), "2015-12-21T13-13-03");
+        assert_eq!(name, *"2015-12-21T13-13-03");
+
+        assert_eq!(
+            SnapshotName::from(datetime!(2015-12-21 14:13:03.5 +01:00)),
+            name
+        );
+
+        assert!("2015-12-21T13:13:03".parse::<SnapshotName>().is_err());
+        assert!(SnapshotName::try_from("snapshot").is_err());
+
+        assert_eq!(
+            serde_json::from_str::<SnapshotName>(r#""2015-12-21T13-13-03""#).unwrap(),
+            name
+        );
+        assert_eq!(
+            serde_json::to_string(&name).unwrap(),
+            r#""2015-12-21T13-13-03""#
+        );
+    }
+
+    #[test]
+    fn snapshot_ordering() {
+        use std::time::Duration;
+
+        use bytesize::ByteSize;
+        use time::OffsetDateTime;
+
+        let snapshot = |timestamp: OffsetDateTime| Snapshot {
+            name: SnapshotName::from(timestamp),
+            timestamp,
+            size: ByteSize::mib(1),
+            filesystem_size: ByteSize::mib(10),
+            automatic: true,
+            comment: String::new(),
+        };
+
+        let now = OffsetDateTime::now_utc();
+        let mut snapshots = [
+            snapshot(now - Duration::from_secs(3600)),
+            snapshot(now - Duration::from_secs(7 * 86400)),
+            snapshot(now - Duration::from_secs(86400)),
+        ];
+
+        snapshots.sort();
+        assert!(snapshots
+            .windows(2)
+            .all(|pair| pair[0].timestamp < pair[1].timestamp));
+        assert_eq!(snapshots.iter().max(), snapshots.last());
+
+        let old: Vec<_> = snapshots
+            .iter()
+            .filter(|snapshot| snapshot.is_older_than(Duration::from_secs(2 * 86400)))
+            .collect();
+        assert_eq!(old, [&snapshots[0]]);
+
+        assert!(!snapshots[0].is_older_than(Duration::MAX));
+        assert!(!snapshots[0].is_older_than(Duration::from_secs(u64::from(u32::MAX) * 86400)));
+    }
+
+    #[test]
+    fn subaccount_age() {
+        use std::time::Duration;
+
+        use time::macros::datetime;
+
+        use crate::api::storagebox::Subaccount;
+
+        let subaccount: Subaccount = serde_json::from_str(
+            r#"{
+                "username": "u1234-sub1",
+                "accountid": "u1234",
+                "server": "u1234-sub1.your-storagebox.de",
+                "homedirectory": "backups",
+                "samba": false,
+                "ssh": true,
+                "external_reachability": false,
+                "webdav": false,
+                "readonly": false,
+                "createtime": "2017-05-24 12:00:00",
+                "comment": ""
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(subaccount.createtime, datetime!(2017-05-24 10:00:00 UTC));
+        assert!(subaccount.age().whole_days() > 365);
+        assert!(subaccount.is_older_than(Duration::from_secs(365 * 86400)));
+        assert!(!subaccount.is_older_than(Duration::from_secs(365 * 86400 * 1000)));
+        assert!(!subaccount.is_older_than(Duration::MAX));
+    }
+
+    #[test]
+    fn subaccount_permissions() {
+        use crate::api::storagebox::{Subaccount, SubaccountPermissions};
+
+        let subaccount: Subaccount = serde_json::from_str(
+            r#"{
+                "username": "u1234-sub1",
+                "accountid": "u1234",
+                "server": "u1234-sub1.your-storagebox.de",
+                "homedirectory": "backups",
+                "samba": false,
+                "ssh": true,
+                "external_reachability": false,
+                "webdav": false,
+                "readonly": "true",
+                "createtime": "2017-05-24 12:00:00",
+                "comment": ""
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            subaccount.permissions,
+            SubaccountPermissions::from(Permission::ReadOnly)
+        );
+        assert!(!subaccount.permissions.can_write());
+
+        let serialized = serde_json::to_value(&subaccount).unwrap();
+        assert_eq!(serialized["readonly"], serde_json::Value::Bool(true));
+    }
 }
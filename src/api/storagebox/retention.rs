@@ -0,0 +1,382 @@
+//! Retention policies for storagebox snapshots.
+
+use std::collections::HashSet;
+
+use time::OffsetDateTime;
+
+use crate::{error::Error, AsyncRobot};
+
+use super::{Snapshot, SnapshotName, StorageBoxId};
+
+/// Identifies the day, week or month a timestamp falls within.
+type Period = fn(OffsetDateTime) -> i32;
+
+/// Policy describing which snapshots of a storagebox to keep.
+///
+/// Snapshots matched by any of the rules are kept, all others are expired.
+/// For the daily, weekly and monthly rules, the newest snapshot within each of
+/// the most recent days, weeks or months which have snapshots is kept.
+/// Periods are determined using the UTC timestamp of the snapshot,
+/// and weeks follow ISO 8601.
+///
+/// A policy without any rules keeps all snapshots.
+///
+/// # Example
+/// ```rust
+/// # use hrobot::api::storagebox::SnapshotRetention;
+/// let retention = SnapshotRetention::default()
+///     .keep_last(3)
+///     .keep_daily(7)
+///     .keep_weekly(4)
+///     .keep_monthly(12);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SnapshotRetention {
+    /// Number of most recent snapshots to keep.
+    pub last: usize,
+
+    /// Number of days for which to keep the newest snapshot.
+    pub daily: usize,
+
+    /// Number of weeks for which to keep the newest snapshot.
+    pub weekly: usize,
+
+    /// Number of months for which to keep the newest snapshot.
+    pub monthly: usize,
+}
+
+impl SnapshotRetention {
+    /// Keep the `count` most recent snapshots.
+    pub fn keep_last(mut self, count: usize) -> Self {
+        self.last = count;
+        self
+    }
+
+    /// Keep the newest snapshot of each of the last `count` days with snapshots.
+    pub fn keep_daily(mut self, count: usize) -> Self {
+        self.daily = count;
+        self
+    }
+
+    /// Keep the newest snapshot of each of the last `count` weeks with snapshots.
+    pub fn keep_weekly(mut self, count: usize) -> Self {
+        self.weekly = count;
+        self
+    }
+
+    /// Keep the newest snapshot of each of the last `count` months with snapshots.
+    pub fn keep_monthly(mut self, count: usize) -> Self {
+        self.monthly = count;
+        self
+    }
+
+    /// Returns true if the policy has no rules, and therefore keeps everything.
+    pub fn is_empty(&self) -> bool {
+        self.last == 0 && self.daily == 0 && self.weekly == 0 && self.monthly == 0
+    }
+
+    /// Snapshots which are not kept by the policy, oldest first.
+    pub fn expired(&self, snapshots: &[Snapshot]) -> Vec<Snapshot> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let mut newest_first: Vec<_> = snapshots.iter().collect();
+        newest_first.sort_by(|a, b| b.cmp(a));
+
+        let mut kept: HashSet<SnapshotName> = newest_first
+            .iter()
+            .take(self.last)
+            .map(|snapshot| snapshot.name)
+            .collect();
+
+        let rules: [(usize, Period); 3] = [
+            (self.daily, |at| at.year() * 1000 + i32::from(at.ordinal())),
+            (self.weekly, |at| {
+                let (year, week, _) = at.to_iso_week_date();
+                year * 100 + i32::from(week)
+            }),
+            (self.monthly, |at| {
+                at.year() * 100 + i32::from(u8::from(at.month()))
+            }),
+        ];
+
+        for (count, period) in rules {
+            let mut previous = None;
+            let mut periods = 0;
+
+            for snapshot in &newest_first {
+                if periods == count {
+                    break;
+                }
+
+                let current = period(snapshot.timestamp.to_offset(time::UtcOffset::UTC));
+                if previous != Some(current) {
+                    previous = Some(current);
+                    periods += 1;
+                    let _ = kept.insert(snapshot.name);
+                }
+            }
+        }
+
+        let mut expired: Vec<_> = snapshots
+            .iter()
+            .filter(|snapshot| !kept.contains(&snapshot.name))
+            .cloned()
+            .collect();
+
+        expired.sort();
+        expired
+    }
+}
+
+/// Outcome of enforcing a [`SnapshotRetention`] policy.
+#[derive(Debug)]
+pub struct RetentionReport {
+    /// Storagebox the policy was enforced on.
+    pub storagebox: StorageBoxId,
+
+    /// Snapshots which were deleted, oldest first.
+    pub deleted: Vec<SnapshotName>,
+
+    /// Expired snapshots which could not be deleted, oldest first.
+    pub failed: Vec<(SnapshotName, Error)>,
+
+    /// Snapshots which were kept by the policy, oldest first.
+    pub kept: Vec<SnapshotName>,
+}
+
+impl RetentionReport {
+    /// Returns true if every expired snapshot was deleted.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+impl AsyncRobot {
+    /// Delete all snapshots of the storagebox which are not kept by the retention policy.
+    ///
+    /// Snapshots are deleted oldest first. Failing to delete a snapshot does not stop
+    /// enforcement, instead the failure is recorded in [`RetentionReport::failed`].
+    /// Use [`SnapshotRetention::expired`] to preview which snapshots would be deleted.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::storagebox::{SnapshotRetention, StorageBoxId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let retention = SnapshotRetention::default().keep_daily(7).keep_weekly(4);
+    ///
+    /// let report = robot
+    ///     .enforce_snapshot_retention(StorageBoxId(1234), &retention)
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// println!("deleted {} snapshots", report.deleted.len());
+    /// for (name, error) in &report.failed {
+    ///     eprintln!("failed to delete {name}: {error}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn enforce_snapshot_retention(
+        &self,
+        id: impl Into<StorageBoxId>,
+        retention: &SnapshotRetention,
+    ) -> Result<RetentionReport, Error> {
+        let id = id.into();
+
+        let mut snapshots = self.list_snapshots(id).await?;
+        snapshots.sort();
+
+        let expired = retention.expired(&snapshots);
+
+        let mut deleted = Vec::with_capacity(expired.len());
+        let mut failed = Vec::new();
+        for snapshot in &expired {
+            match self.delete_snapshot(id, snapshot.name).await {
+                Ok(()) => deleted.push(snapshot.name),
+                Err(error) => failed.push((snapshot.name, error)),
+            }
+        }
+
+        Ok(RetentionReport {
+            storagebox: id,
+            kept: snapshots
+                .iter()
+                .map(|snapshot| snapshot.name)
+                .filter(|name| !expired.iter().any(|snapshot| &snapshot.name == name))
+                .collect(),
+            deleted,
+            failed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytesize::ByteSize;
+    use hyper::StatusCode;
+    use serde_json::json;
+    use time::{macros::datetime, OffsetDateTime};
+
+    use crate::{
+        api::storagebox::{Snapshot, SnapshotName, SnapshotRetention, StorageBoxId},
+        error::{ApiError, Error},
+        mock::{fixtures, MockClient, Reply},
+    };
+
+    fn snapshot(timestamp: OffsetDateTime) -> Snapshot {
+        Snapshot {
+            name: SnapshotName::from(timestamp),
+            timestamp,
+            size: ByteSize::mib(1),
+            filesystem_size: ByteSize::mib(10),
+            automatic: true,
+            comment: String::new(),
+        }
+    }
+
+    fn names(snapshots: &[Snapshot]) -> Vec<String> {
+        snapshots
+            .iter()
+            .map(|snapshot| snapshot.name.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn expired_snapshots() {
+        let snapshots = [
+            snapshot(datetime!(2024-01-15 03:00 UTC)),
+            snapshot(datetime!(2024-02-20 03:00 UTC)),
+            snapshot(datetime!(2024-03-04 03:00 UTC)),
+            snapshot(datetime!(2024-03-05 03:00 UTC)),
+            snapshot(datetime!(2024-03-06 03:00 UTC)),
+            snapshot(datetime!(2024-03-06 15:00 UTC)),
+        ];
+
+        assert!(SnapshotRetention::default().expired(&snapshots).is_empty());
+
+        assert_eq!(
+            names(
+                &SnapshotRetention::default()
+                    .keep_last(2)
+                    .expired(&snapshots)
+            ),
+            [
+                "2024-01-15T03-00-00",
+                "2024-02-20T03-00-00",
+                "2024-03-04T03-00-00",
+                "2024-03-05T03-00-00"
+            ]
+        );
+
+        assert_eq!(
+            names(
+                &SnapshotRetention::default()
+                    .keep_daily(2)
+                    .expired(&snapshots)
+            ),
+            [
+                "2024-01-15T03-00-00",
+                "2024-02-20T03-00-00",
+                "2024-03-04T03-00-00",
+                "2024-03-06T03-00-00"
+            ]
+        );
+
+        // 2024-03-04 is a monday, so 03-04 through 03-06 share a week.
+        assert_eq!(
+            names(
+                &SnapshotRetention::default()
+                    .keep_weekly(2)
+                    .expired(&snapshots)
+            ),
+            [
+                "2024-01-15T03-00-00",
+                "2024-03-04T03-00-00",
+                "2024-03-05T03-00-00",
+                "2024-03-06T03-00-00"
+            ]
+        );
+
+        assert_eq!(
+            names(
+                &SnapshotRetention::default()
+                    .keep_last(1)
+                    .keep_monthly(12)
+                    .expired(&snapshots)
+            ),
+            [
+                "2024-03-04T03-00-00",
+                "2024-03-05T03-00-00",
+                "2024-03-06T03-00-00"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn enforce_retention() {
+        let client = MockClient::new()
+            .on(
+                "GET",
+                "/storagebox/1234/snapshot",
+                json!([
+                    fixtures::snapshot("2015-12-21T13:13:03", 12345),
+                    fixtures::snapshot("2015-12-22T13:13:03", 12400),
+                    fixtures::snapshot("2015-12-20T13:13:03", 12000),
+                    fixtures::snapshot("2015-12-19T13:13:03", 11000)
+                ]),
+            )
+            .on(
+                "DELETE",
+                "/storagebox/1234/snapshot/2015-12-19T13-13-03",
+                Reply::error(
+                    StatusCode::NOT_FOUND,
+                    "SNAPSHOT_NOT_FOUND",
+                    "Snapshot not found",
+                ),
+            )
+            .on("DELETE", "/storagebox/1234/snapshot/*", "");
+        let requests = client.requests();
+        let robot = client.robot();
+
+        let report = robot
+            .enforce_snapshot_retention(
+                StorageBoxId(1234),
+                &SnapshotRetention::default().keep_last(1),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(report.storagebox, StorageBoxId(1234));
+        assert_eq!(
+            report.deleted,
+            [
+                SnapshotName::try_from("2015-12-20T13-13-03").unwrap(),
+                SnapshotName::try_from("2015-12-21T13-13-03").unwrap(),
+            ]
+        );
+        assert_eq!(
+            report.kept,
+            [SnapshotName::try_from("2015-12-22T13-13-03").unwrap()]
+        );
+
+        // Failing deletions are reported without stopping enforcement.
+        assert!(!report.is_complete());
+        assert!(matches!(
+            &report.failed[..],
+            [(name, Error::Api(ApiError::SnapshotNotFound { .. }, _))]
+                if name == &SnapshotName::try_from("2015-12-19T13-13-03").unwrap()
+        ));
+        assert_eq!(
+            requests.lines()[1..],
+            [
+                "DELETE /storagebox/1234/snapshot/2015-12-19T13-13-03",
+                "DELETE /storagebox/1234/snapshot/2015-12-20T13-13-03",
+                "DELETE /storagebox/1234/snapshot/2015-12-21T13-13-03"
+            ]
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Two-step, confirmed snapshot reverts.
+
+use std::time::Duration;
+
+use bytesize::ByteSize;
+use time::OffsetDateTime;
+use tokio::time::Instant;
+
+use crate::{error::Error, AsyncRobot};
+
+use super::{Snapshot, SnapshotName, StorageBoxId};
+
+/// Description of the consequences of reverting a storagebox to a snapshot.
+///
+/// Produced by [`AsyncRobot::plan_snapshot_revert`], and consumed by
+/// [`AsyncRobot::confirm_revert`]. The plan itself acts as the confirmation
+/// token: it can only be obtained by planning the revert, and is only
+/// valid for [`RevertPlan::VALIDITY`]. It cannot be cloned, so each plan
+/// can be confirmed at most once.
+#[derive(Debug)]
+pub struct RevertPlan {
+    /// Storagebox which would be reverted.
+    pub storagebox: StorageBoxId,
+
+    /// Snapshot the storagebox would be reverted to.
+    pub snapshot: Snapshot,
+
+    /// Time elapsed since the snapshot was taken,
+    /// as of when the plan was made.
+    pub age: time::Duration,
+
+    /// Storage currently used by data.
+    pub current_data: ByteSize,
+
+    /// Estimate of the data which would be lost.
+    ///
+    /// Calculated as the growth in data usage since the snapshot was taken.
+    /// Changes which did not increase usage, such as modified or replaced
+    /// files, are not reflected, so the actual loss may be larger.
+    pub estimated_loss: ByteSize,
+
+    /// Snapshots taken after [`snapshot`](RevertPlan::snapshot).
+    ///
+    /// These capture the state which would be lost, and may be
+    /// deleted as part of the revert.
+    pub newer_snapshots: Vec<SnapshotName>,
+
+    planned_at: Instant,
+}
+
+impl RevertPlan {
+    /// Duration after which a plan can no longer be confirmed.
+    pub const VALIDITY: Duration = Duration::from_secs(15 * 60);
+}
+
+impl AsyncRobot {
+    /// Plan reverting the storagebox to the snapshot, without modifying anything.
+    ///
+    /// Returns a [`RevertPlan`] describing the age of the snapshot and an estimate of
+    /// the data which would be lost, which must be passed to [`AsyncRobot::confirm_revert`]
+    /// to actually perform the revert.
+    ///
+    /// Fails with [`Error::UnknownSnapshot`] if the storagebox has no such snapshot.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::storagebox::{SnapshotName, StorageBoxId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let _ = dotenvy::dotenv().ok();
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let plan = robot.plan_snapshot_revert(
+    ///     StorageBoxId(1234),
+    ///     "2015-12-21T13-13-03".parse::<SnapshotName>().unwrap()
+    /// ).await.unwrap();
+    ///
+    /// println!(
+    ///     "Reverting to a snapshot taken {} hours ago loses at least {}",
+    ///     plan.age.whole_hours(),
+    ///     plan.estimated_loss,
+    /// );
+    ///
+    /// robot.confirm_revert(plan).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn plan_snapshot_revert(
+        &self,
+        id: impl Into<StorageBoxId>,
+        snapshot_name: impl Into<SnapshotName>,
+    ) -> Result<RevertPlan, Error> {
+        let id = id.into();
+        let snapshot_name = snapshot_name.into();
+
+        let snapshots = self.list_snapshots(id).await?;
+        let Some(snapshot) = snapshots
+            .iter()
+            .find(|snapshot| snapshot.name == snapshot_name)
+            .cloned()
+        else {
+            return Err(Error::UnknownSnapshot(snapshot_name));
+        };
+
+        let disk = self.get_storagebox_usage(id).await?;
+
+        Ok(RevertPlan {
+            storagebox: id,
+            age: OffsetDateTime::now_utc() - snapshot.timestamp,
+            current_data: disk.data,
+            estimated_loss: ByteSize::b(
+                disk.data
+                    .as_u64()
+                    .saturating_sub(snapshot.filesystem_size.as_u64()),
+            ),
+            newer_snapshots: newer_snapshots(&snapshots, &snapshot),
+            snapshot,
+            planned_at: Instant::now(),
+        })
+    }
+
+    /// Revert the storagebox to the snapshot, as described by the plan.
+    ///
+    /// Fails with [`Error::StaleRevertPlan`] without reverting anything if the plan
+    /// is older than [`RevertPlan::VALIDITY`], if the snapshot no longer exists, or
+    /// if snapshots were created or deleted since the plan was made.
+    ///
+    /// See [`AsyncRobot::plan_snapshot_revert`] for an example.
+    pub async fn confirm_revert(&self, plan: RevertPlan) -> Result<(), Error> {
+        if plan.planned_at.elapsed() > RevertPlan::VALIDITY {
+            return Err(Error::StaleRevertPlan(format!(
+                "plan is older than {} minutes",
+                RevertPlan::VALIDITY.as_secs() / 60
+            )));
+        }
+
+        let snapshots = self.list_snapshots(plan.storagebox).await?;
+        if !snapshots
+            .iter()
+            .any(|snapshot| snapshot.name == plan.snapshot.name)
+        {
+            return Err(Error::StaleRevertPlan(format!(
+                "snapshot {} no longer exists",
+                plan.snapshot.name
+            )));
+        }
+
+        if newer_snapshots(&snapshots, &plan.snapshot) != plan.newer_snapshots {
+            return Err(Error::StaleRevertPlan(
+                "snapshots were created or deleted since the plan was made".to_string(),
+            ));
+        }
+
+        self.revert_to_snapshot(plan.storagebox, plan.snapshot.name)
+            .await
+    }
+}
+
+/// Names of the snapshots taken after `snapshot`, oldest first.
+fn newer_snapshots(snapshots: &[Snapshot], snapshot: &Snapshot) -> Vec<SnapshotName> {
+    let mut newer: Vec<_> = snapshots
+        .iter()
+        .filter(|other| other.timestamp > snapshot.timestamp)
+        .map(|other| other.name)
+        .collect();
+
+    newer.sort();
+    newer
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        api::storagebox::{RevertPlan, SnapshotName, StorageBoxId},
+        error::Error,
+        mock::{fixtures, MockClient},
+    };
+
+    #[tokio::test(start_paused = true)]
+    async fn confirmed_revert() {
+        let client = MockClient::new()
+            .on(
+                "GET",
+                "/storagebox/1234/snapshot",
+                json!([
+                    fixtures::snapshot("2015-12-21T13:13:03", 12345),
+                    fixtures::snapshot("2015-12-22T13:13:03", 12400)
+                ]),
+            )
+            .on(
+                "GET",
+                "/storagebox/1234",
+                fixtures::patch(
+                    fixtures::storagebox(1234),
+                    json!({
+                        "disk_quota": 1000000,
+                        "disk_usage": 13000,
+                        "disk_usage_data": 12445,
+                        "disk_usage_snapshots": 555
+                    }),
+                ),
+            )
+            .on("POST", "/storagebox/1234/snapshot/*", "");
+        let requests = client.requests();
+        let robot = client.robot();
+        let name = SnapshotName::try_from("2015-12-21T13-13-03").unwrap();
+
+        let plan = robot
+            .plan_snapshot_revert(StorageBoxId(1234), name)
+            .await
+            .unwrap();
+
+        assert_eq!(plan.snapshot.name, name);
+        assert_eq!(plan.estimated_loss.as_u64(), 100 * 1024 * 1024);
+        assert_eq!(
+            plan.newer_snapshots,
+            ["2015-12-22T13-13-03".parse::<SnapshotName>().unwrap()]
+        );
+        assert!(plan.age.whole_days() > 365);
+
+        robot.confirm_revert(plan).await.unwrap();
+        assert_eq!(requests.bodies(), ["revert=true"]);
+
+        let plan = robot
+            .plan_snapshot_revert(StorageBoxId(1234), name)
+            .await
+            .unwrap();
+
+        tokio::time::advance(RevertPlan::VALIDITY * 2).await;
+        assert!(matches!(
+            robot.confirm_revert(plan).await,
+            Err(Error::StaleRevertPlan(_))
+        ));
+        assert_eq!(requests.bodies().len(), 1);
+
+        assert!(matches!(
+            robot
+                .plan_snapshot_revert(1234, "2015-12-20T13-13-03".parse::<SnapshotName>().unwrap())
+                .await,
+            Err(Error::UnknownSnapshot(_))
+        ));
+    }
+}
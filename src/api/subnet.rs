@@ -5,16 +5,17 @@ use std::{
     net::{IpAddr, Ipv4Addr},
 };
 
-use ipnet::IpNet;
+use ipnet::{IpNet, Ipv4Net};
 use serde::{Deserialize, Serialize};
 use time::Date;
 
 use crate::{error::Error, AsyncRobot};
 
 use super::{
-    ip::{ExecutedMacRemoval, InternalMac, InternalTrafficWarnings, TrafficWarnings},
+    ip::{ExecutedMacRemoval, InternalMac, InternalTrafficWarnings, MacAddress, TrafficWarnings},
+    rdns::RdnsEntry,
     server::ServerId,
-    wrapper::{List, Single},
+    wrapper::{List, OrEmpty, Single},
     UnauthenticatedRequest,
 };
 
@@ -29,7 +30,7 @@ fn get_subnet(ip: IpAddr) -> UnauthenticatedRequest<Single<InternalSubnet>> {
 fn enable_traffic_warnings(
     ip: IpAddr,
     traffic_warnings: Option<TrafficWarnings>,
-) -> Result<UnauthenticatedRequest<Single<Subnet>>, serde_html_form::ser::Error> {
+) -> Result<UnauthenticatedRequest<Single<InternalSubnet>>, serde_html_form::ser::Error> {
     let request =
         UnauthenticatedRequest::from(&format!("https://robot-ws.your-server.de/subnet/{ip}"))
             .with_method("POST");
@@ -41,7 +42,7 @@ fn enable_traffic_warnings(
     }
 }
 
-fn disable_traffic_warnings(ip: IpAddr) -> UnauthenticatedRequest<Single<Subnet>> {
+fn disable_traffic_warnings(ip: IpAddr) -> UnauthenticatedRequest<Single<InternalSubnet>> {
     UnauthenticatedRequest::from(&format!("https://robot-ws.your-server.de/subnet/{ip}"))
         .with_method("POST")
         .with_serialized_body("traffic_warnings=false".to_string())
@@ -96,8 +97,11 @@ impl AsyncRobot {
     pub async fn list_subnets(&self) -> Result<HashMap<ServerId, Vec<Subnet>>, Error> {
         let mut subnets: HashMap<ServerId, Vec<Subnet>> = HashMap::new();
 
-        for ip in self.go(list_subnets()).await?.0 {
-            subnets.entry(ip.server_number).or_default().push(ip.into());
+        for ip in self.go(list_subnets()).await.or_empty()?.0 {
+            subnets
+                .entry(ip.server_number)
+                .or_default()
+                .push(ip.try_into()?);
         }
 
         Ok(subnets)
@@ -114,7 +118,7 @@ impl AsyncRobot {
     /// # }
     /// ```
     pub async fn get_subnet(&self, subnet_addr: IpAddr) -> Result<Subnet, Error> {
-        Ok(self.go(get_subnet(subnet_addr)).await?.0.into())
+        self.go(get_subnet(subnet_addr)).await?.0.try_into()
     }
 
     /// Enable traffic warnings for the subnet, optionally overriding
@@ -127,7 +131,7 @@ impl AsyncRobot {
     /// # #[tokio::main]
     /// # async fn main() {
     /// let robot = hrobot::AsyncRobot::default();
-    /// robot.enable_ip_traffic_warnings(
+    /// robot.enable_subnet_traffic_warnings(
     ///     "2a01:4f8:123:123::".parse().unwrap(),
     ///     Some(TrafficWarnings {
     ///         hourly:  ByteSize::mb(200),
@@ -142,10 +146,10 @@ impl AsyncRobot {
         ip: IpAddr,
         traffic_warnings: Option<TrafficWarnings>,
     ) -> Result<Subnet, Error> {
-        Ok(self
-            .go(enable_traffic_warnings(ip, traffic_warnings)?)
+        self.go(enable_traffic_warnings(ip, traffic_warnings)?)
             .await?
-            .0)
+            .0
+            .try_into()
     }
 
     /// Disable traffic warnings for the subnet.
@@ -159,7 +163,7 @@ impl AsyncRobot {
     /// # }
     /// ```
     pub async fn disable_subnet_traffic_warnings(&self, ip: IpAddr) -> Result<Subnet, Error> {
-        Ok(self.go(disable_traffic_warnings(ip)).await?.0)
+        self.go(disable_traffic_warnings(ip)).await?.0.try_into()
     }
 
     /// Get the separate MAC address for this subnet.
@@ -172,7 +176,7 @@ impl AsyncRobot {
     /// robot.get_subnet_separate_mac("2a01:4f8:123:123::".parse().unwrap()).await.unwrap();
     /// # }
     /// ```
-    pub async fn get_subnet_separate_mac(&self, ip: IpAddr) -> Result<String, Error> {
+    pub async fn get_subnet_separate_mac(&self, ip: IpAddr) -> Result<MacAddress, Error> {
         Ok(self.go(get_separate_mac(ip)).await?.0.mac)
     }
 
@@ -186,7 +190,7 @@ impl AsyncRobot {
     /// robot.generate_subnet_separate_mac("2a01:4f8:123:123::".parse().unwrap()).await.unwrap();
     /// # }
     /// ```
-    pub async fn generate_subnet_separate_mac(&self, ip: IpAddr) -> Result<String, Error> {
+    pub async fn generate_subnet_separate_mac(&self, ip: IpAddr) -> Result<MacAddress, Error> {
         Ok(self.go(generate_separate_mac(ip)).await?.0.mac)
     }
 
@@ -266,30 +270,35 @@ struct InternalSubnet {
     pub ip: IpAddr,
     pub mask: u8,
     pub gateway: IpAddr,
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub locked: bool,
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub failover: bool,
     #[serde(flatten)]
     pub traffic_warnings: Option<TrafficWarnings>,
 }
 
-impl From<InternalSubnet> for Subnet {
-    fn from(value: InternalSubnet) -> Self {
-        Subnet {
-            // UNWRAP: Assume prefix lengths given by Hetzner are valid.
-            ip: IpNet::new(value.ip, value.mask).unwrap(),
+impl TryFrom<InternalSubnet> for Subnet {
+    type Error = Error;
+
+    fn try_from(value: InternalSubnet) -> Result<Self, Error> {
+        Ok(Subnet {
+            ip: crate::conversion::network(value.ip, value.mask)?,
             server_number: value.server_number,
             gateway: value.gateway,
             locked: value.locked,
             failover: value.failover,
             traffic_warnings: value.traffic_warnings,
-        }
+        })
     }
 }
 
 /// IPv4/IPv6 subnet.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Subnet {
     /// Address
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub ip: IpNet,
 
     /// Server the subnet belongs to
@@ -299,9 +308,11 @@ pub struct Subnet {
     pub gateway: IpAddr,
 
     /// Status of locking.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub locked: bool,
 
     /// True if subnet is a failover subnet
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub failover: bool,
 
     /// Traffic warnings for this IP address.
@@ -309,18 +320,189 @@ pub struct Subnet {
     pub traffic_warnings: Option<TrafficWarnings>,
 }
 
+impl Subnet {
+    /// Plan the allocation of the addresses of an additional IPv4 subnet
+    /// routed to the server, for example to virtual machines.
+    ///
+    /// Returns `None` for IPv6 subnets, and for IPv4 subnets too small
+    /// to leave any addresses for guests, i.e. smaller than a `/30`.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::server::ServerId;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    ///
+    /// for subnet in robot.list_subnets().await.unwrap().remove(&ServerId(1234567)).unwrap_or_default() {
+    ///     let Some(allocation) = subnet.allocation() else {
+    ///         continue;
+    ///     };
+    ///
+    ///     println!("configure {} on the host bridge", allocation.host_address());
+    ///     for address in &allocation.addresses {
+    ///         println!("{}", allocation.guest(*address, "eth0").unwrap().netplan());
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn allocation(&self) -> Option<SubnetAllocation> {
+        let IpNet::V4(network) = self.ip.trunc() else {
+            return None;
+        };
+
+        if network.prefix_len() > 30 {
+            return None;
+        }
+
+        let mut hosts = network.hosts();
+        let gateway = hosts.next()?;
+
+        Some(SubnetAllocation {
+            network,
+            gateway,
+            addresses: hosts.collect(),
+        })
+    }
+}
+
+/// Allocation of the addresses of an additional IPv4 subnet routed to a server.
+///
+/// Hetzner routes additional subnets via the main IP address of the server,
+/// leaving it up to the server how to distribute the addresses within. The
+/// convention, used for example when hosting virtual machines, is to assign the
+/// first usable address of the subnet to the host itself, typically on the bridge
+/// the guests are connected to, and have the guests use it as their gateway.
+///
+/// Constructed using [`Subnet::allocation`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubnetAllocation {
+    /// The routed subnet.
+    pub network: Ipv4Net,
+
+    /// First usable address of the subnet, assigned to the host
+    /// and used by guests as their gateway.
+    pub gateway: Ipv4Addr,
+
+    /// Remaining usable addresses, excluding the network and
+    /// broadcast addresses, available for guests.
+    pub addresses: Vec<Ipv4Addr>,
+}
+
+impl SubnetAllocation {
+    /// Address and prefix to configure on the host, e.g. `123.123.123.121/29`.
+    pub fn host_address(&self) -> Ipv4Net {
+        // UNWRAP: Prefix lengths of valid networks are always valid.
+        Ipv4Net::new(self.gateway, self.network.prefix_len()).unwrap()
+    }
+
+    /// Describe the network interface of a guest using the given address.
+    ///
+    /// `interface` is the name of the guest's network interface, e.g. `eth0`.
+    ///
+    /// Returns `None` if the address is not available for guests.
+    pub fn guest(&self, address: Ipv4Addr, interface: &str) -> Option<GuestInterface> {
+        self.addresses.contains(&address).then(|| GuestInterface {
+            interface: interface.to_string(),
+            // UNWRAP: Prefix lengths of valid networks are always valid.
+            address: Ipv4Net::new(address, self.network.prefix_len()).unwrap(),
+            gateway: self.gateway,
+        })
+    }
+
+    /// Suggest a reverse DNS entry for each address available for guests,
+    /// naming each address after its octets within `domain`,
+    /// e.g. `123-123-123-122.example.com`.
+    ///
+    /// The entries can be applied using [`AsyncRobot::set_rdns_bulk`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::bulk::BulkOptions;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let subnet = robot.get_subnet("123.123.123.120".parse().unwrap()).await.unwrap();
+    ///
+    /// let entries = subnet.allocation().unwrap().rdns_entries("vms.example.com");
+    /// robot
+    ///     .set_rdns_bulk(
+    ///         entries.iter().map(|entry| (entry.ip, entry.ptr.as_str())),
+    ///         BulkOptions::default(),
+    ///     )
+    ///     .await;
+    /// # }
+    /// ```
+    pub fn rdns_entries(&self, domain: &str) -> Vec<RdnsEntry> {
+        self.addresses
+            .iter()
+            .map(|address| {
+                let [a, b, c, d] = address.octets();
+
+                RdnsEntry {
+                    ip: IpAddr::V4(*address),
+                    ptr: format!("{a}-{b}-{c}-{d}.{domain}"),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Network interface of a guest using an address of a [`SubnetAllocation`].
+///
+/// Used to generate OS-level network configuration for the guest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuestInterface {
+    /// Name of the guest's network interface, e.g. `eth0`.
+    pub interface: String,
+
+    /// Address and prefix of the guest.
+    pub address: Ipv4Net,
+
+    /// Gateway of the guest, which is the address assigned to the host.
+    pub gateway: Ipv4Addr,
+}
+
+impl GuestInterface {
+    /// Generate a systemd-networkd `.network` file for the interface,
+    /// e.g. `/etc/systemd/network/10-eth0.network`
+    pub fn networkd(&self) -> String {
+        format!(
+            "[Match]\nName={interface}\n\n[Network]\nAddress={address}\nGateway={gateway}\n",
+            interface = self.interface,
+            address = self.address,
+            gateway = self.gateway,
+        )
+    }
+
+    /// Generate netplan configuration for the interface.
+    pub fn netplan(&self) -> String {
+        format!(
+            "network:\n  version: 2\n  ethernets:\n    {interface}:\n      addresses:\n        - {address}\n      routes:\n        - to: default\n          via: {gateway}\n",
+            interface = self.interface,
+            address = self.address,
+            gateway = self.gateway,
+        )
+    }
+}
+
 /// IP address has been cancelled.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Cancelled {
     /// Date at which the IP address is terminated.
-    #[serde(rename = "cancellation_date")]
+    #[serde(rename = "cancellation_date", with = "crate::conversion::date")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub date: Date,
 }
 
 /// IP address has not yet been cancelled.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Cancellable {
     /// Earliest possible date at which the IP address can be cancelled.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    #[serde(with = "crate::conversion::date")]
     pub earliest_cancellation_date: Date,
 }
 
@@ -329,7 +511,8 @@ pub struct Cancellable {
 /// If the address has already been cancelled, this contains a [`Cancelled`]
 /// otherwise a [`Cancellable`] structure which describes the earliest date
 /// at which the IP address can be cancelled.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Cancellation {
     /// IP address has been cancelled.
@@ -337,3 +520,192 @@ pub enum Cancellation {
     /// IP address has not yet been cancelled.
     Cancellable(Cancellable),
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use bytesize::ByteSize;
+    use ipnet::{IpNet, Ipv4Net};
+
+    use crate::api::{
+        ip::TrafficWarnings,
+        server::ServerId,
+        wrapper::{List, Single},
+    };
+
+    use super::{InternalSubnet, Subnet};
+
+    #[test]
+    fn deserialize_subnet() {
+        let subnet: Subnet = serde_json::from_str::<Single<InternalSubnet>>(
+            r#"{
+                "subnet": {
+                    "ip": "2a01:4f8:111:4221::",
+                    "mask": 64,
+                    "gateway": "2a01:4f8:111:4221::1",
+                    "server_ip": "123.123.123.123",
+                    "server_number": 321,
+                    "failover": false,
+                    "locked": false,
+                    "traffic_warnings": true,
+                    "traffic_hourly": 200,
+                    "traffic_daily": 2000,
+                    "traffic_monthly": 20
+                }
+            }"#,
+        )
+        .unwrap()
+        .0
+        .try_into()
+        .unwrap();
+
+        assert_eq!(
+            subnet.ip,
+            "2a01:4f8:111:4221::/64".parse::<IpNet>().unwrap()
+        );
+        assert_eq!(
+            subnet.gateway,
+            "2a01:4f8:111:4221::1".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(subnet.server_number, ServerId(321));
+        assert!(!subnet.failover);
+        assert_eq!(
+            subnet.traffic_warnings,
+            Some(TrafficWarnings {
+                hourly: ByteSize::mib(200),
+                daily: ByteSize::mib(2000),
+                monthly: ByteSize::gib(20),
+            })
+        );
+    }
+
+    #[test]
+    fn deserialize_subnet_list() {
+        let subnets: Vec<Subnet> = serde_json::from_str::<List<InternalSubnet>>(
+            r#"[
+                {
+                    "subnet": {
+                        "ip": "123.123.123.123",
+                        "mask": 29,
+                        "gateway": "123.123.123.123",
+                        "server_ip": null,
+                        "server_number": 12345,
+                        "failover": true,
+                        "locked": false,
+                        "traffic_warnings": false,
+                        "traffic_hourly": 200,
+                        "traffic_daily": 2000,
+                        "traffic_monthly": 20
+                    }
+                }
+            ]"#,
+        )
+        .unwrap()
+        .0
+        .into_iter()
+        .map(|subnet| Subnet::try_from(subnet).unwrap())
+        .collect();
+
+        assert_eq!(subnets.len(), 1);
+        assert_eq!(
+            subnets[0].ip,
+            "123.123.123.123/29".parse::<IpNet>().unwrap()
+        );
+        assert!(subnets[0].failover);
+        assert_eq!(subnets[0].traffic_warnings, None);
+    }
+
+    #[test]
+    fn allocate_subnet() {
+        let subnet = Subnet {
+            ip: "123.123.123.120/29".parse().unwrap(),
+            server_number: ServerId(321),
+            gateway: "123.123.123.1".parse().unwrap(),
+            locked: false,
+            failover: false,
+            traffic_warnings: None,
+        };
+
+        let allocation = subnet.allocation().unwrap();
+        assert_eq!(allocation.gateway, Ipv4Addr::new(123, 123, 123, 121));
+        assert_eq!(
+            allocation.host_address(),
+            "123.123.123.121/29".parse::<Ipv4Net>().unwrap()
+        );
+        assert_eq!(allocation.addresses.len(), 5);
+        assert_eq!(
+            allocation.addresses.last(),
+            Some(&Ipv4Addr::new(123, 123, 123, 126))
+        );
+
+        assert!(allocation.guest(allocation.gateway, "eth0").is_none());
+
+        let guest = allocation
+            .guest(Ipv4Addr::new(123, 123, 123, 122), "eth0")
+            .unwrap();
+        assert_eq!(
+            guest.networkd(),
+            "[Match]\nName=eth0\n\n[Network]\nAddress=123.123.123.122/29\nGateway=123.123.123.121\n"
+        );
+        assert!(guest.netplan().contains("via: 123.123.123.121"));
+
+        let rdns = allocation.rdns_entries("vms.example.com");
+        assert_eq!(rdns.len(), 5);
+        assert_eq!(rdns[0].ptr, "123-123-123-122.vms.example.com");
+
+        let ipv6 = Subnet {
+            ip: "2a01:4f8:111:4221::/64".parse().unwrap(),
+            gateway: "fe80::1".parse().unwrap(),
+            ..subnet
+        };
+        assert!(ipv6.allocation().is_none());
+    }
+
+    #[test]
+    fn deserialize_cancellation() {
+        use time::macros::date;
+
+        use super::{Cancellable, Cancellation, Cancelled};
+
+        let cancellable: Single<Cancellation> = serde_json::from_str(
+            r#"{
+                "cancellation": {
+                    "ip": "123.123.123.123",
+                    "mask": 29,
+                    "server_number": 321,
+                    "earliest_cancellation_date": "2015-06-26",
+                    "cancelled": false,
+                    "cancellation_date": null
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            cancellable.0,
+            Cancellation::Cancellable(Cancellable {
+                earliest_cancellation_date: date!(2015 - 06 - 26)
+            })
+        );
+
+        let cancelled: Single<Cancellation> = serde_json::from_str(
+            r#"{
+                "cancellation": {
+                    "ip": "123.123.123.123",
+                    "mask": 29,
+                    "server_number": 321,
+                    "earliest_cancellation_date": "2015-06-26",
+                    "cancelled": true,
+                    "cancellation_date": "2015-06-30T00:00:00+02:00"
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            cancelled.0,
+            Cancellation::Cancelled(Cancelled {
+                date: date!(2015 - 06 - 30)
+            })
+        );
+    }
+}
@@ -1,5 +1,5 @@
 //! Traffic querying structs and implementation.
-use std::{collections::HashMap, net::IpAddr};
+use std::{collections::HashMap, convert::Infallible, fmt::Display, net::IpAddr, str::FromStr};
 
 use bytesize::ByteSize;
 use ipnet::IpNet;
@@ -22,7 +22,7 @@ fn get_traffic(
 
     impl<'a> UrlEncode for TrafficRequest<'a> {
         fn encode_into(&self, mut f: crate::urlencode::UrlEncodingBuffer<'_>) {
-            self.range.encode_into(f.clone());
+            self.range.encode_into(f.reborrow());
 
             let mut addresses = self.ips.to_vec();
             // Sort the list according to whether the address is a single IP address
@@ -73,41 +73,50 @@ impl AsyncRobot {
     ) -> Result<HashMap<IpNet, Vec<TrafficStatistic>>, Error> {
         let result = self.go(get_traffic(range, ips)).await?.0;
 
-        Ok(result
+        result
             .data
             .into_iter()
             .map(|(addr, results)| {
                 let mut results: Vec<_> = results.into_iter().collect();
 
-                let addr = addr.parse().unwrap_or_else(|_| {
-                    let addr: IpAddr = addr.parse().unwrap();
-                    IpNet::from(addr)
-                });
+                let addr = match addr.parse::<IpNet>() {
+                    Ok(network) => network,
+                    Err(_) => IpNet::from(addr.parse::<IpAddr>().map_err(|_| {
+                        Error::UnexpectedResponseShape {
+                            expected: "an ip address or network".to_string(),
+                            got: format!("{addr:?}"),
+                        }
+                    })?),
+                };
 
                 results.sort_by(|(a, _), (b, _)| a.cmp(b));
-                (
+                Ok((
                     addr,
                     results
                         .into_iter()
                         .map(|(_, statistic)| statistic)
                         .collect(),
-                )
+                ))
             })
-            .collect())
+            .collect()
     }
 }
 
 /// Traffic statistics for a single "unit". For hourly range, this is a single hour. For monthly it's a day, for yearly it's a month.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct TrafficStatistic {
     /// Amount of ingress (incoming) traffic within the specified time range.
     #[serde(rename = "in", deserialize_with = "crate::conversion::gib_float")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub ingress: ByteSize,
     /// Amount of egress (outgoing) traffic within the specified time range.
     #[serde(rename = "out", deserialize_with = "crate::conversion::gib_float")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub egress: ByteSize,
     /// Total amount of traffic (both incoming and outgoing) within the specified time range.
     #[serde(rename = "sum", deserialize_with = "crate::conversion::gib_float")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub total: ByteSize,
 }
 
@@ -116,6 +125,116 @@ struct StatisticContainer {
     data: HashMap<String, HashMap<String, TrafficStatistic>>,
 }
 
+/// Monthly traffic included with a server or product, e.g. `20 TB` or `unlimited`.
+///
+/// Parsing never fails: values which cannot be interpreted are preserved as
+/// [`TrafficQuota::Unknown`], so new or unexpected representations do not break
+/// deserialization of the surrounding model.
+///
+/// # Example
+/// ```rust
+/// # use hrobot::api::traffic::TrafficQuota;
+/// # use hrobot::bytesize::ByteSize;
+/// assert_eq!(TrafficQuota::parse("unlimited"), TrafficQuota::Unlimited);
+/// assert_eq!(TrafficQuota::parse("1,5 TB"), TrafficQuota::Limited(ByteSize::gb(1500)));
+/// assert_eq!(TrafficQuota::parse("lots"), TrafficQuota::Unknown("lots".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(from = "String", into = "String")]
+pub enum TrafficQuota {
+    /// No limit on traffic.
+    Unlimited,
+    /// Traffic is limited to the given amount per month.
+    Limited(ByteSize),
+    /// Traffic quota which could not be interpreted, as returned by the API.
+    Unknown(String),
+}
+
+impl TrafficQuota {
+    /// Interpret a traffic quota as returned by the API.
+    ///
+    /// Accepts `unlimited`, as well as any size accepted by
+    /// [`units::parse_byte_size`](crate::units::parse_byte_size),
+    /// so `1.5 TB` and `1,5 TB` are equivalent.
+    pub fn parse(quota: &str) -> TrafficQuota {
+        Self::parse_limited(quota.trim())
+            .unwrap_or_else(|| TrafficQuota::Unknown(quota.to_string()))
+    }
+
+    fn parse_limited(quota: &str) -> Option<TrafficQuota> {
+        if quota.eq_ignore_ascii_case("unlimited") {
+            return Some(TrafficQuota::Unlimited);
+        }
+
+        crate::units::parse_byte_size(quota).map(TrafficQuota::Limited)
+    }
+
+    /// Traffic limit, or `None` if unlimited or unknown.
+    pub fn limit(&self) -> Option<ByteSize> {
+        match self {
+            TrafficQuota::Limited(limit) => Some(*limit),
+            _ => None,
+        }
+    }
+
+    /// Indicates whether traffic is unlimited.
+    pub fn is_unlimited(&self) -> bool {
+        matches!(self, TrafficQuota::Unlimited)
+    }
+}
+
+impl From<&str> for TrafficQuota {
+    fn from(value: &str) -> Self {
+        TrafficQuota::parse(value)
+    }
+}
+
+impl From<String> for TrafficQuota {
+    fn from(value: String) -> Self {
+        TrafficQuota::parse(&value)
+    }
+}
+
+impl FromStr for TrafficQuota {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(TrafficQuota::parse(s))
+    }
+}
+
+impl From<TrafficQuota> for String {
+    fn from(value: TrafficQuota) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for TrafficQuota {
+    /// Formats limits exactly, using the largest decimal unit which divides the limit evenly.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrafficQuota::Unlimited => f.write_str("unlimited"),
+            TrafficQuota::Limited(limit) => {
+                let bytes = limit.as_u64();
+                let (unit, multiplier) = [
+                    ("PB", 1_000_000_000_000_000),
+                    ("TB", 1_000_000_000_000),
+                    ("GB", 1_000_000_000),
+                    ("MB", 1_000_000),
+                    ("KB", 1_000),
+                ]
+                .into_iter()
+                .find(|(_, multiplier)| bytes != 0 && bytes.is_multiple_of(*multiplier))
+                .unwrap_or(("B", 1));
+
+                write!(f, "{} {unit}", bytes / multiplier)
+            }
+            TrafficQuota::Unknown(quota) => f.write_str(quota),
+        }
+    }
+}
+
 /// Describes a time range used to retrieve traffic statistics.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TimeRange {
@@ -166,7 +285,7 @@ impl TimeRange {
             year,
             month,
             from: 1,
-            to: time::util::days_in_year_month(year as i32, month),
+            to: month.length(year as i32),
         }
     }
 
@@ -233,12 +352,14 @@ impl UrlEncode for TimeRange {
 #[cfg(test)]
 mod tests {
     use super::StatisticContainer;
+    use bytesize::ByteSize;
+    use proptest::prelude::*;
     use time::{Date, Month};
     use tracing_test::traced_test;
 
     use crate::urlencode::UrlEncode;
 
-    use super::TimeRange;
+    use super::{TimeRange, TrafficQuota};
 
     #[test]
     #[traced_test]
@@ -285,4 +406,75 @@ mod tests {
 
         let _container: StatisticContainer = serde_json::from_str(traffic).unwrap();
     }
+
+    #[test]
+    fn parse_traffic_quota() {
+        assert_eq!(TrafficQuota::parse("unlimited"), TrafficQuota::Unlimited);
+        assert_eq!(TrafficQuota::parse("Unlimited "), TrafficQuota::Unlimited);
+        assert_eq!(
+            TrafficQuota::parse("20 TB"),
+            TrafficQuota::Limited(ByteSize::tb(20))
+        );
+        assert_eq!(
+            TrafficQuota::parse("1,5 PB"),
+            TrafficQuota::Limited(ByteSize::tb(1500))
+        );
+        assert_eq!(
+            TrafficQuota::parse("1.000,5GB"),
+            TrafficQuota::Limited(ByteSize::mb(1_000_500))
+        );
+        assert_eq!(
+            TrafficQuota::parse("500 MiB"),
+            TrafficQuota::Limited(ByteSize::mib(500))
+        );
+        assert_eq!(
+            TrafficQuota::parse("5 XB"),
+            TrafficQuota::Unknown("5 XB".to_string())
+        );
+        assert_eq!(
+            TrafficQuota::parse(""),
+            TrafficQuota::Unknown(String::new())
+        );
+
+        let quota: TrafficQuota = serde_json::from_str(r#""5 TB""#).unwrap();
+        assert_eq!(quota.limit(), Some(ByteSize::tb(5)));
+        assert_eq!(serde_json::to_string(&quota).unwrap(), r#""5 TB""#);
+    }
+
+    proptest! {
+        #[test]
+        fn traffic_quota_never_panics(quota in "\\PC*") {
+            let _ = TrafficQuota::parse(&quota);
+        }
+
+        #[test]
+        fn traffic_quota_units(
+            amount in 0u64..10_000,
+            (unit, multiplier) in prop::sample::select(crate::units::UNITS.to_vec()),
+            space in prop::bool::ANY,
+            lowercase in prop::bool::ANY,
+        ) {
+            let unit = if lowercase { unit.to_lowercase() } else { unit.to_string() };
+            let quota = format!("{amount}{}{unit}", if space { " " } else { "" });
+
+            prop_assert_eq!(
+                TrafficQuota::parse(&quota),
+                TrafficQuota::Limited(ByteSize::b(amount * multiplier))
+            );
+        }
+
+        #[test]
+        fn traffic_quota_decimal_separators(whole in 0u64..1000, fraction in 0u64..10) {
+            let expected = TrafficQuota::Limited(ByteSize::gb(whole * 1000 + fraction * 100));
+
+            prop_assert_eq!(TrafficQuota::parse(&format!("{whole}.{fraction} TB")), expected.clone());
+            prop_assert_eq!(TrafficQuota::parse(&format!("{whole},{fraction} TB")), expected);
+        }
+
+        #[test]
+        fn traffic_quota_roundtrip(bytes in any::<u64>()) {
+            let quota = TrafficQuota::Limited(ByteSize::b(bytes));
+            prop_assert_eq!(TrafficQuota::parse(&quota.to_string()), quota);
+        }
+    }
 }
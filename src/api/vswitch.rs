@@ -1,6 +1,6 @@
 //! vSwitch structs and implementation.
 
-use std::{fmt::Display, net::IpAddr};
+use std::{collections::HashMap, fmt::Display, net::IpAddr, num::ParseIntError, str::FromStr};
 
 use ipnet::IpNet;
 use serde::{Deserialize, Serialize};
@@ -120,8 +120,9 @@ impl AsyncRobot {
     /// robot.get_vswitch(VSwitchId(123456)).await.unwrap();
     /// # }
     /// ```
-    pub async fn get_vswitch(&self, vswitch: VSwitchId) -> Result<VSwitch, Error> {
-        Ok(self.go(get_vswitch(vswitch)).await?.into())
+    pub async fn get_vswitch(&self, vswitch: impl Into<VSwitchId>) -> Result<VSwitch, Error> {
+        let vswitch = vswitch.into();
+        self.go(get_vswitch(vswitch)).await?.try_into()
     }
 
     /// Create a vSwitch
@@ -132,7 +133,7 @@ impl AsyncRobot {
     /// # #[tokio::main]
     /// # async fn main() {
     /// let robot = hrobot::AsyncRobot::default();
-    /// robot.create_vswitch("vswitch-test-1", VlanId(4078)).await.unwrap();
+    /// robot.create_vswitch("vswitch-test-1", VlanId::new(4078).unwrap()).await.unwrap();
     /// # }
     /// ```
     pub async fn create_vswitch(
@@ -154,16 +155,17 @@ impl AsyncRobot {
     /// robot.update_vswitch(
     ///     VSwitchId(124567),
     ///     "vswitch-test-2",
-    ///     VlanId(4079)
+    ///     VlanId::new(4079).unwrap(),
     /// ).await.unwrap();
     /// # }
     /// ```
     pub async fn update_vswitch(
         &self,
-        vswitch_id: VSwitchId,
+        vswitch_id: impl Into<VSwitchId>,
         name: &str,
         vlan_id: VlanId,
     ) -> Result<(), Error> {
+        let vswitch_id = vswitch_id.into();
         self.go(update_vswitch(vswitch_id, name, vlan_id)?)
             .await?
             .throw_away();
@@ -189,9 +191,10 @@ impl AsyncRobot {
     /// ```
     pub async fn cancel_vswitch(
         &self,
-        vswitch_id: VSwitchId,
+        vswitch_id: impl Into<VSwitchId>,
         cancellation_date: Option<Date>,
     ) -> Result<(), Error> {
+        let vswitch_id = vswitch_id.into();
         self.go(delete_vswitch(vswitch_id, cancellation_date))
             .await?
             .throw_away();
@@ -200,6 +203,9 @@ impl AsyncRobot {
 
     /// Connect dedicated servers to vSwitch.
     ///
+    /// All servers are connected using a single request. Use
+    /// [`AsyncRobot::add_vswitch_servers`] to also retrieve their connection statuses.
+    ///
     /// # Example
     /// ```rust,no_run
     /// # use hrobot::api::vswitch::VSwitchId;
@@ -215,10 +221,12 @@ impl AsyncRobot {
     /// ```
     pub async fn connect_vswitch_servers(
         &self,
-        vswitch_id: VSwitchId,
-        server_ids: &[ServerId],
+        vswitch_id: impl Into<VSwitchId>,
+        server_ids: impl IntoIterator<Item = impl Into<ServerId>>,
     ) -> Result<(), Error> {
-        self.go(add_servers(vswitch_id, server_ids))
+        let vswitch_id = vswitch_id.into();
+        let server_ids: Vec<ServerId> = server_ids.into_iter().map(Into::into).collect();
+        self.go(add_servers(vswitch_id, &server_ids))
             .await?
             .throw_away();
         Ok(())
@@ -226,6 +234,9 @@ impl AsyncRobot {
 
     /// Disconnect dedicated servers from vSwitch.
     ///
+    /// All servers are disconnected using a single request. Use
+    /// [`AsyncRobot::remove_vswitch_servers`] to also retrieve their connection statuses.
+    ///
     /// # Example
     /// ```rust,no_run
     /// # use hrobot::api::vswitch::VSwitchId;
@@ -241,29 +252,169 @@ impl AsyncRobot {
     /// ```
     pub async fn disconnect_vswitch_servers(
         &self,
-        vswitch_id: VSwitchId,
-        server_ids: &[ServerId],
+        vswitch_id: impl Into<VSwitchId>,
+        server_ids: impl IntoIterator<Item = impl Into<ServerId>>,
     ) -> Result<(), Error> {
-        self.go(remove_servers(vswitch_id, server_ids))
+        let vswitch_id = vswitch_id.into();
+        let server_ids: Vec<ServerId> = server_ids.into_iter().map(Into::into).collect();
+        self.go(remove_servers(vswitch_id, &server_ids))
             .await?
             .throw_away();
         Ok(())
     }
+
+    /// Connect a batch of dedicated servers to vSwitch using a single request,
+    /// returning the connection status of each of them afterwards.
+    ///
+    /// Connecting servers one request at a time leaves the vSwitch processing
+    /// between requests, which causes later requests to fail with
+    /// [`ApiError::VswitchInProcess`](crate::error::ApiError::VswitchInProcess).
+    ///
+    /// Servers are usually [`ConnectionStatus::InProcess`] right after being connected.
+    /// A status of `None` means the server is not listed on the vSwitch at all.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::vswitch::VSwitchId;
+    /// # use hrobot::api::server::ServerId;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let statuses = robot.add_vswitch_servers(
+    ///     VSwitchId(124567),
+    ///     &[ServerId(1234567), ServerId(2345678)],
+    /// ).await.unwrap();
+    ///
+    /// for (server, status) in statuses {
+    ///     println!("{server}: {status:?}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn add_vswitch_servers(
+        &self,
+        vswitch_id: impl Into<VSwitchId>,
+        server_ids: impl IntoIterator<Item = impl Into<ServerId>>,
+    ) -> Result<HashMap<ServerId, Option<ConnectionStatus>>, Error> {
+        let vswitch_id = vswitch_id.into();
+        let server_ids: Vec<ServerId> = server_ids.into_iter().map(Into::into).collect();
+        if !server_ids.is_empty() {
+            self.connect_vswitch_servers(vswitch_id, &server_ids)
+                .await?;
+        }
+
+        self.vswitch_server_statuses(vswitch_id, &server_ids).await
+    }
+
+    /// Disconnect a batch of dedicated servers from vSwitch using a single request,
+    /// returning the connection status of each of them afterwards.
+    ///
+    /// Servers are usually still listed as [`ConnectionStatus::InProcess`] right
+    /// after being disconnected. A status of `None` means the server is no longer
+    /// listed on the vSwitch, and has been disconnected.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::vswitch::VSwitchId;
+    /// # use hrobot::api::server::ServerId;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let statuses = robot.remove_vswitch_servers(
+    ///     VSwitchId(124567),
+    ///     &[ServerId(1234567), ServerId(2345678)],
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn remove_vswitch_servers(
+        &self,
+        vswitch_id: impl Into<VSwitchId>,
+        server_ids: impl IntoIterator<Item = impl Into<ServerId>>,
+    ) -> Result<HashMap<ServerId, Option<ConnectionStatus>>, Error> {
+        let vswitch_id = vswitch_id.into();
+        let server_ids: Vec<ServerId> = server_ids.into_iter().map(Into::into).collect();
+        if !server_ids.is_empty() {
+            self.disconnect_vswitch_servers(vswitch_id, &server_ids)
+                .await?;
+        }
+
+        self.vswitch_server_statuses(vswitch_id, &server_ids).await
+    }
+
+    /// Connection status of each of the given servers on the vSwitch.
+    async fn vswitch_server_statuses(
+        &self,
+        vswitch_id: VSwitchId,
+        server_ids: &[ServerId],
+    ) -> Result<HashMap<ServerId, Option<ConnectionStatus>>, Error> {
+        if server_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let vswitch = self.get_vswitch(vswitch_id).await?;
+
+        Ok(server_ids
+            .iter()
+            .map(|server_id| {
+                let status = vswitch
+                    .servers
+                    .iter()
+                    .find(|server| server.id == *server_id)
+                    .map(|server| server.status.clone());
+
+                (*server_id, status)
+            })
+            .collect())
+    }
 }
 
 /// VLAN ID.
 ///
 /// Simple wrapper around a u16, to avoid confusion with vSwitch ID, for example.
 ///
-/// VLAN IDs must be in the range 4000..=4091.
+/// VLAN IDs must be in the range 4000..=4091, which is enforced when
+/// constructing the ID using [`VlanId::new`] or [`VlanId::try_from`].
 ///
 /// Multiple vSwitches can have the same VLAN ID.
+///
+/// # Example
+/// ```rust
+/// # use hrobot::api::vswitch::VlanId;
+/// let vlan = VlanId::new(4000).unwrap();
+/// assert_eq!(u16::from(vlan), 4000);
+///
+/// assert!(VlanId::new(100).is_err());
+/// ```
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct VlanId(pub u16);
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct VlanId(u16);
 
-impl From<u16> for VlanId {
-    fn from(value: u16) -> Self {
-        VlanId(value)
+impl VlanId {
+    /// Lowest VLAN ID allowed.
+    pub const MIN: VlanId = VlanId(4000);
+
+    /// Highest VLAN ID allowed.
+    pub const MAX: VlanId = VlanId(4091);
+
+    /// Construct a VLAN ID, failing if it is outside the range 4000..=4091.
+    pub const fn new(vlan: u16) -> Result<Self, InvalidVlanId> {
+        if vlan < Self::MIN.0 || vlan > Self::MAX.0 {
+            return Err(InvalidVlanId(vlan));
+        }
+
+        Ok(VlanId(vlan))
+    }
+}
+
+/// VLAN ID is outside the range 4000..=4091 allowed by Hetzner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("vlan id {0} is outside the allowed range 4000..=4091")]
+pub struct InvalidVlanId(pub u16);
+
+impl TryFrom<u16> for VlanId {
+    type Error = InvalidVlanId;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        VlanId::new(value)
     }
 }
 
@@ -295,6 +446,7 @@ impl PartialEq<u16> for VlanId {
 /// my vSwitches, where the argument is in range `0..N` where `N` is the number of
 /// vswitches in my account, or is it a limiter, like get first `N` vswitches, for example.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VSwitchId(pub u32);
 
 impl From<u32> for VSwitchId {
@@ -321,12 +473,27 @@ impl PartialEq<u32> for VSwitchId {
     }
 }
 
+impl PartialEq<VSwitchId> for u32 {
+    fn eq(&self, other: &VSwitchId) -> bool {
+        self.eq(&other.0)
+    }
+}
+
+impl FromStr for VSwitchId {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u32::from_str(s).map(VSwitchId)
+    }
+}
+
 /// Simplified view of a VSwitch.
 ///
 /// This is returned when [listing](AsyncRobot::list_vswitches()) vSwitches, and only contains
 /// the basic vSwitch configuration options. For information on which servers, subnets and
 /// cloud networks are connected to the vSwitch see [`AsyncRobot::get_vswitch`]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VSwitchReference {
     /// Unique vSwitch ID.
     pub id: VSwitchId,
@@ -340,6 +507,7 @@ pub struct VSwitchReference {
     pub vlan: VlanId,
 
     /// Indicates if the vSwitch has been cancelled or not.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub cancelled: bool,
 }
 
@@ -348,27 +516,34 @@ struct InternalVSwitch {
     pub id: VSwitchId,
     pub name: String,
     pub vlan: VlanId,
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub cancelled: bool,
     pub server: Vec<VSwitchServer>,
     pub subnet: Vec<InternalSubnet>,
     pub cloud_network: Vec<InternalCloudNetwork>,
 }
 
-impl From<InternalVSwitch> for VSwitch {
-    fn from(value: InternalVSwitch) -> Self {
-        VSwitch {
+impl TryFrom<InternalVSwitch> for VSwitch {
+    type Error = Error;
+
+    fn try_from(value: InternalVSwitch) -> Result<Self, Error> {
+        Ok(VSwitch {
             id: value.id,
             name: value.name,
             vlan: value.vlan,
             cancelled: value.cancelled,
             servers: value.server,
-            subnets: value.subnet.into_iter().map(IpNet::from).collect(),
+            subnets: value
+                .subnet
+                .into_iter()
+                .map(IpNet::try_from)
+                .collect::<Result<_, _>>()?,
             cloud_networks: value
                 .cloud_network
                 .into_iter()
-                .map(CloudNetwork::from)
-                .collect(),
-        }
+                .map(CloudNetwork::try_from)
+                .collect::<Result<_, _>>()?,
+        })
     }
 }
 
@@ -378,14 +553,17 @@ struct InternalSubnet {
     pub mask: u8,
 }
 
-impl From<InternalSubnet> for IpNet {
-    fn from(value: InternalSubnet) -> Self {
-        IpNet::new(value.ip, value.mask).unwrap()
+impl TryFrom<InternalSubnet> for IpNet {
+    type Error = Error;
+
+    fn try_from(value: InternalSubnet) -> Result<Self, Error> {
+        crate::conversion::network(value.ip, value.mask)
     }
 }
 
 /// Describes a complete vSwitch configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VSwitch {
     /// Unique vSwitch ID.
     pub id: VSwitchId,
@@ -397,24 +575,170 @@ pub struct VSwitch {
     pub vlan: VlanId,
 
     /// Indicates if the vSwitch has been cancelled.
+    #[serde(deserialize_with = "crate::conversion::permissive_bool")]
     pub cancelled: bool,
 
     /// List of servers connected to this vSwitch.
     pub servers: Vec<VSwitchServer>,
 
     /// List of subnets associated with this vSwitch.
+    #[cfg_attr(feature = "schemars", schemars(with = "Vec<String>"))]
     pub subnets: Vec<IpNet>,
 
     /// List of Cloud Networks connected to this vSwitch.
     pub cloud_networks: Vec<CloudNetwork>,
 }
 
+impl VSwitch {
+    /// Describe the VLAN interface of the given server on this vSwitch.
+    ///
+    /// `parent` is the name of the server's physical network interface, e.g. `enp0s31f6`,
+    /// and `addresses` are the addresses the server should use on the vSwitch network.
+    ///
+    /// Returns `None` if the server is not connected to this vSwitch.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::api::{server::ServerId, vswitch::VSwitchId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let vswitch = robot.get_vswitch(VSwitchId(123456)).await.unwrap();
+    ///
+    /// let interface = vswitch
+    ///     .interface(ServerId(1234), "enp0s31f6", &["192.168.100.1/24".parse().unwrap()])
+    ///     .unwrap();
+    ///
+    /// println!("{}", interface.netplan());
+    /// # }
+    /// ```
+    pub fn interface(
+        &self,
+        server: impl Into<ServerId>,
+        parent: &str,
+        addresses: &[IpNet],
+    ) -> Option<VSwitchInterface> {
+        let server = server.into();
+
+        self.servers
+            .iter()
+            .any(|attachment| attachment.id == server)
+            .then(|| VSwitchInterface {
+                parent: parent.to_string(),
+                vlan: self.vlan,
+                mtu: VSwitchInterface::DEFAULT_MTU,
+                addresses: addresses.to_vec(),
+            })
+    }
+}
+
+/// VLAN interface through which a server communicates on a vSwitch.
+///
+/// Constructed using [`VSwitch::interface`], and used to generate
+/// OS-level network configuration for the interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VSwitchInterface {
+    /// Name of the physical network interface the VLAN interface is attached to.
+    pub parent: String,
+
+    /// VLAN ID of the vSwitch.
+    pub vlan: VlanId,
+
+    /// MTU of the VLAN interface.
+    ///
+    /// Defaults to 1400, which is the maximum supported by vSwitches.
+    pub mtu: u16,
+
+    /// Addresses assigned to the interface.
+    pub addresses: Vec<IpNet>,
+}
+
+impl VSwitchInterface {
+    /// Maximum MTU supported by Hetzner vSwitches.
+    pub const DEFAULT_MTU: u16 = 1400;
+
+    /// Override the MTU of the interface.
+    pub fn with_mtu(mut self, mtu: u16) -> Self {
+        self.mtu = mtu;
+        self
+    }
+
+    /// Name of the VLAN interface, e.g. `enp0s31f6.4000`.
+    pub fn name(&self) -> String {
+        format!("{}.{}", self.parent, self.vlan)
+    }
+
+    /// Generate systemd-networkd configuration for the interface.
+    pub fn networkd(&self) -> NetworkdConfig {
+        let name = self.name();
+
+        let netdev = format!(
+            "[NetDev]\nName={name}\nKind=vlan\nMTUBytes={mtu}\n\n[VLAN]\nId={vlan}\n",
+            mtu = self.mtu,
+            vlan = self.vlan
+        );
+
+        let addresses: String = self
+            .addresses
+            .iter()
+            .map(|address| format!("Address={address}\n"))
+            .collect();
+
+        let network = format!(
+            "[Match]\nName={name}\n\n[Link]\nMTUBytes={mtu}\n\n[Network]\n{addresses}",
+            mtu = self.mtu
+        );
+
+        let parent = format!("[Network]\nVLAN={name}\n");
+
+        NetworkdConfig {
+            netdev,
+            network,
+            parent,
+        }
+    }
+
+    /// Generate netplan configuration for the interface.
+    pub fn netplan(&self) -> String {
+        let addresses: String = self
+            .addresses
+            .iter()
+            .map(|address| format!("        - {address}\n"))
+            .collect();
+
+        format!(
+            "network:\n  version: 2\n  vlans:\n    {name}:\n      id: {vlan}\n      link: {parent}\n      mtu: {mtu}\n      addresses:\n{addresses}",
+            name = self.name(),
+            vlan = self.vlan,
+            parent = self.parent,
+            mtu = self.mtu,
+        )
+    }
+}
+
+/// systemd-networkd configuration files for a [`VSwitchInterface`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkdConfig {
+    /// Contents of the `.netdev` file declaring the VLAN interface,
+    /// e.g. `/etc/systemd/network/10-enp0s31f6.4000.netdev`
+    pub netdev: String,
+
+    /// Contents of the `.network` file configuring the VLAN interface,
+    /// e.g. `/etc/systemd/network/10-enp0s31f6.4000.network`
+    pub network: String,
+
+    /// Drop-in which attaches the VLAN interface to the parent interface's
+    /// existing `.network` file, e.g. `/etc/systemd/network/10-enp0s31f6.network.d/vlan-4000.conf`
+    pub parent: String,
+}
+
 /// Indicates the connection status of a server to a vSwitch.
 ///
 /// Connecting or disconnecting a server to/from a vSwitch requires some
 /// processing time, and the server won't be immediately available on the vSwitch
 /// network.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum ConnectionStatus {
     /// Server is connected and ready.
     #[serde(rename = "ready")]
@@ -430,7 +754,8 @@ pub enum ConnectionStatus {
 }
 
 /// Connection status of a server to a vSwitch.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VSwitchServer {
     /// Server's unique ID.
     #[serde(rename = "server_number")]
@@ -447,22 +772,26 @@ struct InternalCloudNetwork {
     pub mask: u8,
 }
 
-impl From<InternalCloudNetwork> for CloudNetwork {
-    fn from(value: InternalCloudNetwork) -> Self {
-        CloudNetwork {
+impl TryFrom<InternalCloudNetwork> for CloudNetwork {
+    type Error = Error;
+
+    fn try_from(value: InternalCloudNetwork) -> Result<Self, Error> {
+        Ok(CloudNetwork {
             id: value.id,
-            network: IpNet::new(value.ip, value.mask).unwrap(),
-        }
+            network: crate::conversion::network(value.ip, value.mask)?,
+        })
     }
 }
 
 /// Identifies a Cloud Network connected to a vSwitch.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct CloudNetwork {
     /// Unique ID for the Cloud Network the vSwitch is connected to.
     pub id: CloudNetworkId,
 
     /// Subnet of the Cloud Network the vSwitch inhabits.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub network: IpNet,
 }
 
@@ -470,6 +799,7 @@ pub struct CloudNetwork {
 ///
 /// Simple wrapper around a u32, to avoid confusion with for example [`VSwitchId`]
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct CloudNetworkId(pub u32);
 
 impl From<u32> for CloudNetworkId {
@@ -496,17 +826,42 @@ impl PartialEq<u32> for CloudNetworkId {
     }
 }
 
+impl PartialEq<CloudNetworkId> for u32 {
+    fn eq(&self, other: &CloudNetworkId) -> bool {
+        self.eq(&other.0)
+    }
+}
+
+impl FromStr for CloudNetworkId {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u32::from_str(s).map(CloudNetworkId)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{
+        collections::HashMap,
         net::{IpAddr, Ipv4Addr},
         str::FromStr,
     };
 
     use ipnet::{IpNet, Ipv4Net};
 
-    use crate::api::vswitch::{
-        CloudNetwork, CloudNetworkId, InternalCloudNetwork, InternalSubnet, VSwitchId, VlanId,
+    use serde_json::json;
+
+    use crate::{
+        api::{
+            server::ServerId,
+            vswitch::{
+                CloudNetwork, CloudNetworkId, ConnectionStatus, InternalCloudNetwork,
+                InternalSubnet, InvalidVlanId, VSwitch, VSwitchId, VSwitchServer, VlanId,
+            },
+        },
+        error::Error,
+        mock::{fixtures, MockClient},
     };
 
     use super::InternalVSwitch;
@@ -536,9 +891,12 @@ mod tests {
 
     #[test]
     fn vlan_construction() {
-        assert_eq!(VlanId::from(4001u16), 4001);
+        assert_eq!(VlanId::try_from(4001u16).unwrap(), 4001);
+        assert_eq!(VlanId::new(4001).unwrap().to_string(), "4001");
 
-        assert_eq!(VlanId(4001).to_string(), "4001");
+        assert_eq!(VlanId::new(3999), Err(InvalidVlanId(3999)));
+        assert_eq!(VlanId::new(4092), Err(InvalidVlanId(4092)));
+        assert_eq!(VlanId::new(4091), Ok(VlanId::MAX));
     }
 
     #[test]
@@ -553,22 +911,32 @@ mod tests {
     #[test]
     fn internal_subnet_conversion() {
         assert_eq!(
-            IpNet::from(InternalSubnet {
+            IpNet::try_from(InternalSubnet {
                 ip: IpAddr::from_str("127.0.0.0").unwrap(),
                 mask: 24
-            }),
+            })
+            .unwrap(),
             IpNet::V4(Ipv4Net::new(Ipv4Addr::new(127, 0, 0, 0), 24).unwrap())
         );
+
+        assert!(matches!(
+            IpNet::try_from(InternalSubnet {
+                ip: IpAddr::from_str("127.0.0.0").unwrap(),
+                mask: 33
+            }),
+            Err(Error::UnexpectedResponseShape { got, .. }) if got == "/33"
+        ));
     }
 
     #[test]
     fn cloud_network_construction() {
         assert_eq!(
-            CloudNetwork::from(InternalCloudNetwork {
+            CloudNetwork::try_from(InternalCloudNetwork {
                 id: CloudNetworkId::from(10),
                 ip: Ipv4Addr::LOCALHOST.into(),
                 mask: 8
-            }),
+            })
+            .unwrap(),
             CloudNetwork {
                 id: CloudNetworkId(10),
                 network: IpNet::new(Ipv4Addr::LOCALHOST.into(), 8).unwrap()
@@ -578,4 +946,112 @@ mod tests {
         assert_eq!(u32::from(CloudNetworkId(10)), 10);
         assert_eq!(CloudNetworkId(10), 10);
     }
+
+    #[test]
+    fn vswitch_interface_config() {
+        let vswitch = VSwitch {
+            id: VSwitchId(1),
+            name: "vswitch".to_string(),
+            vlan: VlanId::MIN,
+            cancelled: false,
+            servers: vec![VSwitchServer {
+                id: ServerId(1234),
+                status: ConnectionStatus::Ready,
+            }],
+            subnets: vec![],
+            cloud_networks: vec![],
+        };
+
+        let addresses = [IpNet::from_str("192.168.100.1/24").unwrap()];
+
+        assert!(vswitch
+            .interface(ServerId(4321), "enp0s31f6", &addresses)
+            .is_none());
+
+        let interface = vswitch
+            .interface(ServerId(1234), "enp0s31f6", &addresses)
+            .unwrap();
+
+        assert_eq!(interface.name(), "enp0s31f6.4000");
+
+        let networkd = interface.networkd();
+
+        assert_eq!(
+            networkd.netdev,
+            "[NetDev]\nName=enp0s31f6.4000\nKind=vlan\nMTUBytes=1400\n\n[VLAN]\nId=4000\n"
+        );
+
+        assert_eq!(
+            networkd.network,
+            "[Match]\nName=enp0s31f6.4000\n\n[Link]\nMTUBytes=1400\n\n[Network]\nAddress=192.168.100.1/24\n"
+        );
+
+        assert_eq!(networkd.parent, "[Network]\nVLAN=enp0s31f6.4000\n");
+
+        assert_eq!(
+            interface.with_mtu(1200).netplan(),
+            r#"network:
+  version: 2
+  vlans:
+    enp0s31f6.4000:
+      id: 4000
+      link: enp0s31f6
+      mtu: 1200
+      addresses:
+        - 192.168.100.1/24
+"#
+        );
+    }
+
+    #[tokio::test]
+    async fn batch_server_statuses() {
+        // Server 1234 is processing and server 2345 ready on the vSwitch.
+        let client = MockClient::new()
+            .on(
+                "GET",
+                "/vswitch/50301",
+                fixtures::patch(
+                    fixtures::vswitch(50301),
+                    json!({"server": [
+                        {"server_number": 1234, "status": "processing"},
+                        {"server_number": 2345, "status": "ready"}
+                    ]}),
+                ),
+            )
+            .on("*", "/vswitch/50301/server", "");
+        let requests = client.requests();
+        let robot = client.robot();
+
+        let statuses = robot
+            .add_vswitch_servers(VSwitchId(50301), &[ServerId(1234), ServerId(3456)])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            statuses,
+            HashMap::from([
+                (ServerId(1234), Some(ConnectionStatus::InProcess)),
+                (ServerId(3456), None),
+            ])
+        );
+        assert_eq!(requests.bodies(), ["server%5B%5D=1234&server%5B%5D=3456"]);
+
+        let statuses = robot
+            .remove_vswitch_servers(VSwitchId(50301), &[ServerId(2345)])
+            .await
+            .unwrap();
+        assert_eq!(
+            statuses,
+            HashMap::from([(ServerId(2345), Some(ConnectionStatus::Ready))])
+        );
+        assert_eq!(requests.bodies().len(), 2);
+
+        // Empty batches are not sent at all.
+        assert!(robot
+            .add_vswitch_servers(VSwitchId(50301), Vec::<ServerId>::new())
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(requests.bodies().len(), 2);
+    }
 }
@@ -0,0 +1,204 @@
+//! Watching a single resource for changes.
+//!
+//! [`AsyncRobot::watch_resource`] polls a resource and only reports a new
+//! [`ResourceSnapshot`] when its content actually changes, ignoring volatile
+//! fields as described by [`Server::content_eq`] and [`Firewall::content_eq`].
+
+use std::{net::IpAddr, time::Duration};
+
+use futures_util::{stream, Stream};
+
+use crate::{error::Error, AsyncRobot};
+
+use super::{
+    firewall::Firewall,
+    ip::Ip,
+    server::{Server, ServerId},
+    storagebox::{StorageBox, StorageBoxId},
+    subnet::Subnet,
+    vswitch::{VSwitch, VSwitchId},
+};
+
+/// Identifies a single resource which can be watched using [`AsyncRobot::watch_resource`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceId {
+    /// Server, see [`AsyncRobot::get_server`].
+    Server(ServerId),
+    /// Firewall of a server, see [`AsyncRobot::get_firewall`].
+    Firewall(ServerId),
+    /// Single IP address, see [`AsyncRobot::get_ip`].
+    Ip(IpAddr),
+    /// Subnet identified by its network address, see [`AsyncRobot::get_subnet`].
+    Subnet(IpAddr),
+    /// Storagebox, see [`AsyncRobot::get_storagebox`].
+    StorageBox(StorageBoxId),
+    /// vSwitch, see [`AsyncRobot::get_vswitch`].
+    VSwitch(VSwitchId),
+}
+
+/// Full description of a watched resource, as reported by [`AsyncRobot::watch_resource`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceSnapshot {
+    /// Server description.
+    Server(Server),
+    /// Firewall of a server.
+    Firewall(Firewall),
+    /// Single IP address.
+    Ip(Ip),
+    /// Subnet.
+    Subnet(Subnet),
+    /// Storagebox.
+    StorageBox(Box<StorageBox>),
+    /// vSwitch.
+    VSwitch(VSwitch),
+}
+
+impl ResourceSnapshot {
+    /// Compare the content of two snapshots, ignoring volatile fields
+    /// of servers and firewalls.
+    ///
+    /// See [`Server::content_eq`] and [`Firewall::content_eq`].
+    pub fn content_eq(&self, other: &ResourceSnapshot) -> bool {
+        match (self, other) {
+            (ResourceSnapshot::Server(a), ResourceSnapshot::Server(b)) => a.content_eq(b),
+            (ResourceSnapshot::Firewall(a), ResourceSnapshot::Firewall(b)) => a.content_eq(b),
+            (a, b) => a == b,
+        }
+    }
+}
+
+impl AsyncRobot {
+    /// Fetch the resource's current description.
+    async fn snapshot(&self, resource: ResourceId) -> Result<ResourceSnapshot, Error> {
+        Ok(match resource {
+            ResourceId::Server(id) => ResourceSnapshot::Server(self.get_server(id).await?),
+            ResourceId::Firewall(id) => ResourceSnapshot::Firewall(self.get_firewall(id).await?),
+            ResourceId::Ip(ip) => ResourceSnapshot::Ip(self.get_ip(ip).await?),
+            ResourceId::Subnet(ip) => ResourceSnapshot::Subnet(self.get_subnet(ip).await?),
+            ResourceId::StorageBox(id) => {
+                ResourceSnapshot::StorageBox(Box::new(self.get_storagebox(id).await?))
+            }
+            ResourceId::VSwitch(id) => ResourceSnapshot::VSwitch(self.get_vswitch(id).await?),
+        })
+    }
+
+    /// Poll a single resource every `interval`, reporting a snapshot whenever its content changes.
+    ///
+    /// The first snapshot is always reported. After that, snapshots are only reported
+    /// if they differ from the last reported one according to [`ResourceSnapshot::content_eq`].
+    /// Failed requests are reported as errors, after which polling continues.
+    ///
+    /// The stream never ends, so stop polling it once no longer interested.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use futures_util::StreamExt;
+    /// # use hrobot::api::{server::ServerId, watch::ResourceId};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// let snapshots = robot.watch_resource(
+    ///     ResourceId::Firewall(ServerId(1234567)),
+    ///     Duration::from_secs(30),
+    /// );
+    /// futures_util::pin_mut!(snapshots);
+    ///
+    /// while let Some(snapshot) = snapshots.next().await {
+    ///     println!("{snapshot:#?}");
+    /// }
+    /// # }
+    /// ```
+    pub fn watch_resource(
+        &self,
+        resource: ResourceId,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<ResourceSnapshot, Error>> + '_ {
+        stream::unfold(
+            (None::<ResourceSnapshot>, true),
+            move |(mut previous, mut first)| async move {
+                loop {
+                    if !first {
+                        tokio::time::sleep(interval).await;
+                    }
+                    first = false;
+
+                    match self.snapshot(resource).await {
+                        Ok(snapshot)
+                            if previous
+                                .as_ref()
+                                .is_some_and(|previous| previous.content_eq(&snapshot)) =>
+                        {
+                            continue
+                        }
+                        Ok(snapshot) => {
+                            previous = Some(snapshot.clone());
+                            return Some((Ok(snapshot), (previous, first)));
+                        }
+                        Err(err) => return Some((Err(err), (previous, first))),
+                    }
+                }
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures_util::StreamExt;
+    use serde_json::json;
+
+    use crate::{
+        api::server::ServerId,
+        mock::{fixtures, MockClient},
+    };
+
+    use super::{ResourceId, ResourceSnapshot};
+
+    #[tokio::test(start_paused = true)]
+    async fn watch_server() {
+        // The server changes status on every request, and is renamed on the fourth.
+        let server = |name, status| {
+            fixtures::patch(
+                fixtures::server(123),
+                json!({"server_name": name, "status": status}),
+            )
+        };
+        let robot = MockClient::new()
+            .on_sequence(
+                "GET",
+                "/server/123",
+                [
+                    server("n1", "ready"),
+                    server("n1", "in progress"),
+                    server("n1", "ready"),
+                ]
+                .into_iter()
+                .chain(
+                    ["in progress", "ready"]
+                        .into_iter()
+                        .cycle()
+                        .map(move |status| server("n2", status)),
+                ),
+            )
+            .robot();
+
+        let snapshots: Vec<_> = robot
+            .watch_resource(ResourceId::Server(ServerId(123)), Duration::from_secs(10))
+            .take(2)
+            .collect()
+            .await;
+
+        let names: Vec<_> = snapshots
+            .into_iter()
+            .map(|snapshot| match snapshot.unwrap() {
+                ResourceSnapshot::Server(server) => server.name,
+                snapshot => panic!("unexpected snapshot {snapshot:?}"),
+            })
+            .collect();
+
+        assert_eq!(names, ["n1", "n2"]);
+    }
+}
@@ -34,12 +34,16 @@ impl AsyncRobot {
     /// assert!(robot.is_wake_on_lan_available(ServerId(1234567)).await.unwrap());
     /// # }
     /// ```
-    pub async fn is_wake_on_lan_available(&self, server_number: ServerId) -> Result<bool, Error> {
+    pub async fn is_wake_on_lan_available(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<bool, Error> {
+        let server_number = server_number.into();
         let response = self.go(get_wake_on_lan(server_number)).await;
 
         match response {
             Ok(_) => Ok(true),
-            Err(Error::Api(ApiError::WolNotAvailable { .. })) => Ok(false),
+            Err(Error::Api(ApiError::WolNotAvailable { .. }, _)) => Ok(false),
             Err(e) => Err(e),
         }
     }
@@ -55,7 +59,11 @@ impl AsyncRobot {
     /// robot.trigger_wake_on_lan(ServerId(1234567)).await.unwrap();
     /// # }
     /// ```
-    pub async fn trigger_wake_on_lan(&self, server_number: ServerId) -> Result<(), Error> {
+    pub async fn trigger_wake_on_lan(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<(), Error> {
+        let server_number = server_number.into();
         self.go(post_wake_on_lan(server_number)).await.map(|_| ())
     }
 }
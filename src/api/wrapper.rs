@@ -2,6 +2,16 @@ use std::collections::HashMap;
 
 use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 
+use crate::error::{ApiError, Error};
+
+#[cfg(feature = "ordering")]
+use serde::de::Error as _;
+#[cfg(feature = "ordering")]
+use tracing::warn;
+
+#[cfg(feature = "ordering")]
+use crate::strict;
+
 /// Deserialize an array of objects where each object is nested
 /// under a key indicating its type.
 ///
@@ -25,9 +35,9 @@ pub(crate) fn deserialize_inner_vec<'de, T: Deserialize<'de>, D: Deserializer<'d
 
     tmp.into_iter()
         .map(|map| {
-            map.into_values()
-                .next()
-                .ok_or(serde::de::Error::custom("empty map"))
+            map.into_values().next().ok_or(serde::de::Error::custom(
+                "expected an object wrapping the value under a single key, got an empty object",
+            ))
         })
         .try_fold(Vec::new(), |mut acc, result| {
             acc.push(result?);
@@ -56,7 +66,9 @@ fn deserialize_inner<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
     HashMap::<&str, T>::deserialize(deserializer)?
         .into_values()
         .next()
-        .ok_or(serde::de::Error::custom("empty map"))
+        .ok_or(serde::de::Error::custom(
+            "expected an object wrapping the value under a single key, got an empty object",
+        ))
 }
 
 /// Deserialize a list of [`T`], where each T is wrapped.
@@ -65,14 +77,111 @@ pub struct List<T: DeserializeOwned>(
     #[serde(deserialize_with = "deserialize_inner_vec")] pub Vec<T>,
 );
 
+/// Normalizes "not found" errors returned by listing endpoints into empty lists.
+///
+/// Some listing endpoints respond with a `NOT_FOUND`-style error instead of
+/// an empty list when the account has no resources of the given kind.
+/// See the table in `errata.md` for which endpoints this applies to.
+///
+/// Must only be used for top-level listings, since a "not found" error
+/// for nested listings such as `/storagebox/{id}/snapshot` indicates that
+/// the parent resource does not exist.
+pub(crate) trait OrEmpty {
+    /// Replace a "not found" error with an empty list.
+    fn or_empty(self) -> Self;
+}
+
+impl<T: DeserializeOwned> OrEmpty for Result<List<T>, Error> {
+    fn or_empty(self) -> Self {
+        match self {
+            Err(Error::Api(
+                ApiError::NotFound { .. }
+                | ApiError::ServerNotFound { .. }
+                | ApiError::IpNotFound { .. }
+                | ApiError::SubnetNotFound { .. },
+                _,
+            )) => Ok(List(Vec::new())),
+            result => result,
+        }
+    }
+}
+
+/// Deserialize a list of wrapped [`T`], skipping entries which fail to deserialize.
+///
+/// Used for catalog listings, where a single malformed entry should not
+/// prevent the rest of the catalog from being retrieved. Skipped entries
+/// are logged as warnings. In strict mode, malformed entries and unknown
+/// fields within entries fail deserialization instead.
+#[cfg(feature = "ordering")]
+#[derive(Debug)]
+pub struct TolerantList<T>(pub Vec<T>);
+
+#[cfg(feature = "ordering")]
+impl<'de, T: DeserializeOwned> Deserialize<'de> for TolerantList<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let entries = Vec::<HashMap<String, serde_json::Value>>::deserialize(deserializer)?;
+        let strict = strict::is_active();
+
+        let mut list = Vec::with_capacity(entries.len());
+        for (index, entry) in entries.into_iter().enumerate() {
+            let Some((kind, value)) = entry.into_iter().next() else {
+                if strict {
+                    return Err(D::Error::custom(format!("empty entry #{index}")));
+                }
+                continue;
+            };
+
+            let mut unknown = None;
+            let result = if strict {
+                serde_ignored::deserialize(value, |path| {
+                    let _ = unknown.get_or_insert_with(|| path.to_string());
+                })
+            } else {
+                serde_json::from_value(value)
+            };
+
+            match (result, unknown) {
+                (Ok(entry), None) => list.push(entry),
+                (Ok(_), Some(field)) => {
+                    return Err(D::Error::custom(format!(
+                        "unknown field {field} in {kind} entry #{index}"
+                    )))
+                }
+                (Err(error), _) if strict => {
+                    return Err(D::Error::custom(format!(
+                        "malformed {kind} entry #{index}: {error}"
+                    )))
+                }
+                (Err(error), _) => warn!("skipping malformed {kind} entry: {error}"),
+            }
+        }
+
+        Ok(TolerantList(list))
+    }
+}
+
+#[cfg(feature = "ordering")]
+impl<T: DeserializeOwned> OrEmpty for Result<TolerantList<T>, Error> {
+    fn or_empty(self) -> Self {
+        match self {
+            Err(Error::Api(ApiError::NotFound { .. }, _)) => Ok(TolerantList(Vec::new())),
+            result => result,
+        }
+    }
+}
+
 /// Deserialize a single wrapped [`T`].
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Single<T: DeserializeOwned>(#[serde(deserialize_with = "deserialize_inner")] pub T);
 
 /// Some endpoints don't return anything.
 ///
-/// This type always deserializes correctly and unlike () succeeds
-/// even if the input is empty.
+/// Empty response bodies are deserialized as `null` by
+/// [`AsyncRobot::go`](crate::AsyncRobot), which this type accepts.
+/// Any other input, such as an error response, fails deserialization.
 #[derive(Debug)]
 pub struct Empty;
 
@@ -82,26 +191,34 @@ impl Empty {
 }
 
 impl<'de> Deserialize<'de> for Empty {
-    fn deserialize<D>(_: D) -> Result<Self, D::Error>
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        // It's safe to always return true, because even when the
-        // response body actually contains an error, this deserialization
-        // will ultimately fail with a "trailing characters" error,
-        // meaning we only *actually* succeed on truly empty input.
-        Ok(Empty)
+        <()>::deserialize(deserializer).map(|()| Empty)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use hyper::StatusCode;
     use serde::{Deserialize, Serialize};
 
-    use crate::api::server::Server;
+    use crate::{
+        api::server::Server,
+        mock::{MockClient, Reply},
+        AsyncRobot,
+    };
 
     use super::Empty;
 
+    #[cfg(feature = "ordering")]
+    use super::TolerantList;
+    #[cfg(feature = "storagebox")]
+    use crate::api::storagebox::StorageBoxId;
+    #[cfg(feature = "ordering")]
+    use crate::{api::server::ServerId, error::Error, strict};
+
     #[test]
     fn deserialize_wrapped() {
         let json = r#"{
@@ -212,9 +329,68 @@ mod tests {
         println!("{servers:#?}");
     }
 
+    #[cfg(feature = "ordering")]
+    #[test]
+    fn tolerant_list_skips_malformed_entries() {
+        #[derive(Debug, Deserialize)]
+        struct Entry {
+            #[serde(deserialize_with = "crate::conversion::price")]
+            price: rust_decimal::Decimal,
+        }
+
+        let TolerantList(entries) = serde_json::from_str::<TolerantList<Entry>>(
+            r#"[
+                {"product": {"price": "15.1261"}},
+                {"product": {"price": "n/a"}},
+                {"product": {}},
+                {},
+                {"product": {"price": " 39,00 "}}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| entry.price.to_string())
+                .collect::<Vec<_>>(),
+            ["15.1261", "39.00"]
+        );
+
+        assert!(serde_json::from_str::<TolerantList<Entry>>(
+            r#"{"error":{"status":404,"code":"NOT_FOUND","message":"Not found"}}"#
+        )
+        .is_err());
+
+        // Strict mode fails on malformed entries and unknown fields instead.
+        assert!(strict::from_slice::<TolerantList<Entry>>(
+            br#"[{"product": {"price": "15.1261"}}]"#
+        )
+        .is_ok());
+
+        for (body, expected) in [
+            (
+                &br#"[{"product": {"price": "15.1261"}}, {"product": {"price": "n/a"}}]"#[..],
+                "malformed product entry #1",
+            ),
+            (
+                br#"[{"product": {"price": "15.1261", "vat": "19"}}]"#,
+                "unknown field vat in product entry #0",
+            ),
+            (br#"[{}]"#, "empty entry #0"),
+        ] {
+            let Err(Error::ModelMismatch { message, .. }) =
+                strict::from_slice::<TolerantList<Entry>>(body)
+            else {
+                panic!("expected model mismatch for {expected}");
+            };
+            assert!(message.starts_with(expected), "{message}");
+        }
+    }
+
     #[test]
     fn deserialize_empty_response() {
-        let response = "";
+        let response = "null";
         let _empty: Empty = serde_json::from_str(response).unwrap();
     }
 
@@ -226,4 +402,123 @@ mod tests {
 
         let _err = serde_json::from_str::<Empty>(response).unwrap_err();
     }
+
+    /// Responds to every request with the given not found error.
+    fn robot(code: &str, message: &str) -> AsyncRobot {
+        MockClient::new()
+            .on("*", "*", Reply::error(StatusCode::NOT_FOUND, code, message))
+            .robot()
+    }
+
+    #[tokio::test]
+    async fn not_found_listings_are_empty() {
+        assert!(robot("SERVER_NOT_FOUND", "No server found")
+            .list_servers()
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert!(robot("SERVER_NOT_FOUND", "No server found")
+            .list_reset_options()
+            .await
+            .unwrap()
+            .is_empty());
+
+        #[cfg(feature = "ip")]
+        assert!(robot("IP_NOT_FOUND", "No IP found")
+            .list_ips()
+            .await
+            .unwrap()
+            .is_empty());
+
+        #[cfg(feature = "ip")]
+        assert!(robot("SUBNET_NOT_FOUND", "No subnet found")
+            .list_subnets()
+            .await
+            .unwrap()
+            .is_empty());
+
+        #[cfg(feature = "ip")]
+        assert!(robot("NOT_FOUND", "Failover not found")
+            .list_failover_ips()
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert!(robot("NOT_FOUND", "No reverse DNS entries found")
+            .list_rdns_entries()
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert!(robot("NOT_FOUND", "No keys found")
+            .list_ssh_keys()
+            .await
+            .unwrap()
+            .is_empty());
+
+        #[cfg(feature = "storagebox")]
+        assert!(robot("NOT_FOUND", "No storagebox found")
+            .list_storageboxes()
+            .await
+            .unwrap()
+            .is_empty());
+
+        #[cfg(feature = "firewall")]
+        assert!(robot("NOT_FOUND", "No firewall templates found")
+            .list_firewall_templates()
+            .await
+            .unwrap()
+            .is_empty());
+
+        #[cfg(feature = "ordering")]
+        assert!(robot("NOT_FOUND", "No transactions found")
+            .list_recent_product_transactions()
+            .await
+            .unwrap()
+            .is_empty());
+
+        #[cfg(feature = "ordering")]
+        assert!(robot("NOT_FOUND", "No transactions found")
+            .list_recent_market_transactions()
+            .await
+            .unwrap()
+            .is_empty());
+
+        #[cfg(feature = "ordering")]
+        assert!(robot("NOT_FOUND", "No transactions found")
+            .list_recent_addon_transactions()
+            .await
+            .unwrap()
+            .is_empty());
+
+        #[cfg(feature = "ordering")]
+        assert!(robot("NOT_FOUND", "No products found")
+            .list_products(.., .., None)
+            .await
+            .unwrap()
+            .is_empty());
+
+        #[cfg(feature = "ordering")]
+        assert!(robot("NOT_FOUND", "No products found")
+            .list_market_products()
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn nested_not_found_listings_fail() {
+        #[cfg(feature = "storagebox")]
+        assert!(robot("STORAGEBOX_NOT_FOUND", "Storage Box not found")
+            .list_subaccounts(StorageBoxId(1))
+            .await
+            .is_err());
+
+        #[cfg(feature = "ordering")]
+        assert!(robot("SERVER_NOT_FOUND", "Server not found")
+            .list_available_addons(ServerId(1))
+            .await
+            .is_err());
+    }
 }
@@ -0,0 +1,390 @@
+//! Audit log of account mutations.
+//!
+//! When configured using [`AsyncRobot::with_audit_log`](crate::AsyncRobot::with_audit_log),
+//! every request which modifies the account, that is every request other than `GET`,
+//! is recorded as an [`AuditEntry`] in the given [`AuditLog`]. Requests which are never
+//! sent, because of [dry-run](crate::AsyncRobot::dry_run) or
+//! [read-only](crate::AsyncRobot::read_only) mode, are not recorded.
+//!
+//! [`MemoryAuditLog`] keeps entries in memory, and can render them as
+//! human-readable change summaries, for example for attaching to change tickets.
+//!
+//! # Example
+//! ```rust,no_run
+//! # use hrobot::{api::server::ServerId, audit::{AuditFilter, MemoryAuditLog}};
+//! # #[tokio::main]
+//! # async fn main() {
+//! let log = MemoryAuditLog::default();
+//! let robot = hrobot::AsyncRobot::default().with_audit_log(log.clone());
+//!
+//! robot.rename_server(ServerId(1234567), "gateway").await.unwrap();
+//!
+//! // 2024-05-01 12:33 set server on server 1234567
+//! println!("{}", log.render(&AuditFilter::default().resource("server")));
+//! # }
+//! ```
+
+use std::{
+    fmt::Display,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use time::{macros::format_description, OffsetDateTime};
+
+use crate::{
+    api::{server::ServerId, UnauthenticatedRequest},
+    telemetry::Endpoint,
+};
+
+/// Record of a single request which modified the account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AuditEntry {
+    /// Time at which the request was sent.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    #[serde(with = "time::serde::rfc3339")]
+    pub time: OffsetDateTime,
+
+    /// HTTP method of the request, e.g. `POST`.
+    pub method: String,
+
+    /// Endpoint path, with parameters replaced by placeholders, e.g. `/boot/{server_id}/rescue`.
+    pub endpoint: String,
+
+    /// Type of resource modified, which is the first segment of the endpoint path, e.g. `boot`.
+    pub resource: String,
+
+    /// Server the request concerns, if any.
+    pub server: Option<ServerId>,
+
+    /// Resource other than a server the request concerns, if any, e.g. `storagebox 1234`.
+    pub target: Option<String>,
+
+    /// Additional details about the change, such as the number of firewall rules applied.
+    pub detail: Option<String>,
+
+    /// Error returned, if the request failed.
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    pub(crate) fn new<Response>(
+        request: &UnauthenticatedRequest<Response>,
+        endpoint: &Endpoint,
+    ) -> Self {
+        AuditEntry {
+            time: OffsetDateTime::now_utc(),
+            method: request.method().to_string(),
+            endpoint: endpoint.name.clone(),
+            resource: endpoint
+                .name
+                .split('/')
+                .find(|segment| !segment.is_empty())
+                .unwrap_or_default()
+                .to_string(),
+            server: endpoint.server,
+            target: endpoint.target.clone(),
+            detail: request.body().and_then(firewall_rule_counts),
+            error: None,
+        }
+    }
+
+    /// True if the request succeeded.
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+
+    fn verb(&self) -> &str {
+        match self.method.as_str() {
+            "POST" => "set",
+            "PUT" => "create",
+            "DELETE" => "delete",
+            method => method,
+        }
+    }
+}
+
+impl Display for AuditEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let time = self
+            .time
+            .format(format_description!("[year]-[month]-[day] [hour]:[minute]"))
+            .map_err(|_| std::fmt::Error)?;
+
+        let description = self
+            .endpoint
+            .split('/')
+            .filter(|segment| !segment.is_empty() && !segment.starts_with('{'))
+            .map(|segment| segment.replace('_', " "))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        write!(f, "{time} {} {description}", self.verb())?;
+
+        match (&self.server, &self.target) {
+            (Some(server), Some(target)) => write!(f, " on server {server}, {target}")?,
+            (Some(server), None) => write!(f, " on server {server}")?,
+            (None, Some(target)) => write!(f, " on {target}")?,
+            (None, None) => (),
+        }
+
+        if let Some(detail) = &self.detail {
+            write!(f, " ({detail})")?;
+        }
+
+        if let Some(error) = &self.error {
+            write!(f, " failed: {error}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Summarize the number of rules in a firewall configuration or template body.
+fn firewall_rule_counts(body: &str) -> Option<String> {
+    let (mut ingress, mut egress) = (0, 0);
+
+    for key in body
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, _)| key)
+    {
+        let key = urlencoding::decode(key).unwrap_or_default();
+
+        if !key.ends_with("][name]") {
+            continue;
+        }
+
+        if key.starts_with("rules[input][") {
+            ingress += 1;
+        } else if key.starts_with("rules[output][") {
+            egress += 1;
+        }
+    }
+
+    let plural = |count: usize| if count == 1 { "rule" } else { "rules" };
+
+    (ingress + egress > 0).then(|| {
+        format!(
+            "{ingress} ingress {}, {egress} egress {}",
+            plural(ingress),
+            plural(egress)
+        )
+    })
+}
+
+/// Destination for [`AuditEntry`] records.
+///
+/// Configured using [`AsyncRobot::with_audit_log`](crate::AsyncRobot::with_audit_log).
+pub trait AuditLog: std::fmt::Debug + Send + Sync {
+    /// Called after every request which modifies the account, whether it succeeded or not.
+    fn record(&self, entry: AuditEntry);
+}
+
+/// Selects [`AuditEntry`] records by resource, server and time range.
+///
+/// The default filter matches all entries.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    resource: Option<String>,
+    server: Option<ServerId>,
+    since: Option<OffsetDateTime>,
+    until: Option<OffsetDateTime>,
+}
+
+impl AuditFilter {
+    /// Only match entries modifying the given type of resource, e.g. `firewall`.
+    pub fn resource(mut self, resource: &str) -> Self {
+        self.resource = Some(resource.to_string());
+        self
+    }
+
+    /// Only match entries concerning the given server.
+    pub fn server(mut self, server: impl Into<ServerId>) -> Self {
+        self.server = Some(server.into());
+        self
+    }
+
+    /// Only match entries recorded at or after the given time.
+    pub fn since(mut self, time: OffsetDateTime) -> Self {
+        self.since = Some(time);
+        self
+    }
+
+    /// Only match entries recorded before the given time.
+    pub fn until(mut self, time: OffsetDateTime) -> Self {
+        self.until = Some(time);
+        self
+    }
+
+    /// Check whether the entry is selected by this filter.
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.resource
+            .as_ref()
+            .is_none_or(|resource| &entry.resource == resource)
+            && self
+                .server
+                .is_none_or(|server| entry.server == Some(server))
+            && self.since.is_none_or(|since| entry.time >= since)
+            && self.until.is_none_or(|until| entry.time < until)
+    }
+}
+
+/// [`AuditLog`] which keeps all entries in memory.
+///
+/// Clones share the same entries, so a clone can be handed to
+/// [`AsyncRobot::with_audit_log`](crate::AsyncRobot::with_audit_log)
+/// while keeping the original for reading the entries.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryAuditLog {
+    entries: Arc<Mutex<Vec<AuditEntry>>>,
+}
+
+impl MemoryAuditLog {
+    /// All entries selected by the filter, in the order they were recorded.
+    pub fn entries(&self, filter: &AuditFilter) -> Vec<AuditEntry> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .filter(|entry| filter.matches(entry))
+            .cloned()
+            .collect()
+    }
+
+    /// Render all entries selected by the filter as human-readable
+    /// change summaries, one per line.
+    pub fn render(&self, filter: &AuditFilter) -> String {
+        self.entries(filter)
+            .iter()
+            .map(|entry| format!("{entry}\n"))
+            .collect()
+    }
+}
+
+impl AuditLog for MemoryAuditLog {
+    fn record(&self, entry: AuditEntry) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::{
+        api::{
+            firewall::{FirewallConfig, Ipv4Filter, Rule, Rules, State},
+            server::ServerId,
+        },
+        mock::MockClient,
+        AsyncRobot,
+    };
+
+    use super::{AuditEntry, AuditFilter, AuditLog, MemoryAuditLog};
+
+    /// Responds to every request with an empty body, which fails deserialization.
+    fn empty_robot() -> AsyncRobot {
+        MockClient::new().on("*", "*", "").robot()
+    }
+
+    #[tokio::test]
+    async fn records_mutations() {
+        let log = MemoryAuditLog::default();
+        let robot = empty_robot().with_audit_log(log.clone());
+
+        let config = FirewallConfig {
+            status: State::Active,
+            filter_ipv6: false,
+            whitelist_hetzner_services: true,
+            rules: Rules {
+                ingress: vec![
+                    Rule::accept("SSH").matching(Ipv4Filter::tcp(None).to_port(22)),
+                    Rule::accept("HTTPS").matching(Ipv4Filter::tcp(None).to_port(443)),
+                ],
+                egress: vec![Rule::accept("Allow all")],
+            },
+        };
+
+        let _ = robot.get_server(ServerId(123)).await;
+        let _ = robot.set_firewall_config(ServerId(123), &config).await;
+        let _ = robot.delete_firewall_template(1234).await;
+
+        let entries = log.entries(&AuditFilter::default());
+        assert_eq!(entries.len(), 2);
+
+        let firewall = &log.entries(&AuditFilter::default().server(ServerId(123)))[0];
+        assert_eq!(firewall.endpoint, "/firewall/{server_id}");
+        assert_eq!(
+            firewall.detail.as_deref(),
+            Some("2 ingress rules, 1 egress rule")
+        );
+        assert!(!firewall.succeeded());
+
+        let template = &log.entries(&AuditFilter::default().resource("firewall"))[1];
+        assert_eq!(template.target.as_deref(), Some("template 1234"));
+        assert_eq!(template.method, "DELETE");
+    }
+
+    #[tokio::test]
+    async fn skips_unsent_requests() {
+        let log = MemoryAuditLog::default();
+
+        for robot in [empty_robot().dry_run(true), empty_robot().read_only(true)] {
+            let robot = robot.with_audit_log(log.clone());
+            let _ = robot.delete_firewall_template(1234).await;
+        }
+
+        assert!(log.entries(&AuditFilter::default()).is_empty());
+    }
+
+    #[test]
+    fn render_entries() {
+        let entry = AuditEntry {
+            time: datetime!(2024-05-01 12:33:12 UTC),
+            method: "POST".to_string(),
+            endpoint: "/firewall/{server_id}".to_string(),
+            resource: "firewall".to_string(),
+            server: Some(ServerId(123)),
+            target: None,
+            detail: Some("2 ingress rules, 0 egress rules".to_string()),
+            error: None,
+        };
+
+        assert_eq!(
+            entry.to_string(),
+            "2024-05-01 12:33 set firewall on server 123 (2 ingress rules, 0 egress rules)"
+        );
+
+        let snapshot = AuditEntry {
+            time: datetime!(2024-05-02 08:00 UTC),
+            method: "PUT".to_string(),
+            endpoint: "/storagebox/{storagebox}/snapshot".to_string(),
+            resource: "storagebox".to_string(),
+            server: None,
+            target: Some("storagebox 1234".to_string()),
+            detail: None,
+            error: Some("api error: snapshot limit exceeded".to_string()),
+        };
+
+        assert_eq!(
+            snapshot.to_string(),
+            "2024-05-02 08:00 create storagebox snapshot on storagebox 1234 failed: api error: snapshot limit exceeded"
+        );
+
+        let log = MemoryAuditLog::default();
+        log.record(entry);
+        log.record(snapshot);
+
+        let filter = AuditFilter::default()
+            .since(datetime!(2024-05-02 00:00 UTC))
+            .until(datetime!(2024-05-03 00:00 UTC));
+
+        assert_eq!(log.entries(&filter).len(), 1);
+        assert_eq!(log.render(&AuditFilter::default()).lines().count(), 2);
+    }
+}
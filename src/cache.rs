@@ -0,0 +1,416 @@
+//! Caching layer for read-only endpoints.
+//!
+//! Only available with the `cache` feature enabled.
+
+#[cfg(feature = "catalog-cache")]
+mod disk;
+#[cfg(feature = "firewall")]
+mod firewall;
+
+#[cfg(feature = "catalog-cache")]
+pub use disk::CatalogCache;
+
+use std::{collections::HashMap, hash::Hash, sync::Mutex, time::Duration};
+
+use tokio::time::Instant;
+
+#[cfg(feature = "firewall")]
+use crate::api::firewall::{Firewall, FirewallTemplate, FirewallTemplateReference, TemplateId};
+use crate::{
+    api::{
+        reset::{ensure_supported, Reset, ResetOptions},
+        server::{Cancel, Cancelled, Server, ServerId, ServerQuery},
+    },
+    error::Error,
+    AsyncRobot,
+};
+
+/// Map of values which expire a fixed duration after insertion.
+#[derive(Debug)]
+struct TtlMap<K, V> {
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+}
+
+impl<K: Eq + Hash, V: Clone> TtlMap<K, V> {
+    fn new() -> Self {
+        TtlMap {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, key: &K, ttl: Duration) -> Option<V> {
+        let entries = self.entries.lock().unwrap();
+        let (inserted, value) = entries.get(key)?;
+
+        (inserted.elapsed() < ttl).then(|| value.clone())
+    }
+
+    fn insert(&self, key: K, value: V) {
+        let _ = self
+            .entries
+            .lock()
+            .unwrap()
+            .insert(key, (Instant::now(), value));
+    }
+
+    fn remove(&self, key: &K) {
+        let _ = self.entries.lock().unwrap().remove(key);
+    }
+
+    fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+/// Wraps an [`AsyncRobot`], memoizing the responses of read-only endpoints
+/// for a configurable duration.
+///
+/// Write operations exposed by [`CachedRobot`] invalidate the affected entries.
+/// Write operations performed through [`CachedRobot::inner`] or another
+/// [`AsyncRobot`] are not seen by the cache, so use [`CachedRobot::invalidate_all`]
+/// or the more specific invalidation functions afterwards.
+///
+/// # Example
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use hrobot::{api::server::ServerId, cache::CachedRobot};
+/// # #[tokio::main]
+/// # async fn main() {
+/// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+///
+/// // Only the first call reaches the Hetzner Robot API.
+/// let server = robot.get_server(ServerId(1234567)).await.unwrap();
+/// let server = robot.get_server(ServerId(1234567)).await.unwrap();
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CachedRobot {
+    robot: AsyncRobot,
+    ttl: Duration,
+    servers: TtlMap<(), Vec<Server>>,
+    server: TtlMap<ServerId, Server>,
+    #[cfg(feature = "firewall")]
+    firewalls: TtlMap<ServerId, Firewall>,
+    #[cfg(feature = "firewall")]
+    firewall_templates: TtlMap<(), Vec<FirewallTemplateReference>>,
+    #[cfg(feature = "firewall")]
+    firewall_template: TtlMap<TemplateId, FirewallTemplate>,
+    reset_options: TtlMap<ServerId, Vec<Reset>>,
+}
+
+impl CachedRobot {
+    /// Wrap the given robot, caching responses for `ttl`.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::cache::CachedRobot;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// # }
+    /// ```
+    pub fn new(robot: AsyncRobot, ttl: Duration) -> Self {
+        CachedRobot {
+            robot,
+            ttl,
+            servers: TtlMap::new(),
+            server: TtlMap::new(),
+            #[cfg(feature = "firewall")]
+            firewalls: TtlMap::new(),
+            #[cfg(feature = "firewall")]
+            firewall_templates: TtlMap::new(),
+            #[cfg(feature = "firewall")]
+            firewall_template: TtlMap::new(),
+            reset_options: TtlMap::new(),
+        }
+    }
+
+    /// Access the wrapped [`AsyncRobot`] directly, bypassing the cache.
+    pub fn inner(&self) -> &AsyncRobot {
+        &self.robot
+    }
+
+    /// Discard all cached responses.
+    pub fn invalidate_all(&self) {
+        self.servers.clear();
+        self.server.clear();
+        #[cfg(feature = "firewall")]
+        {
+            self.firewalls.clear();
+            self.firewall_templates.clear();
+            self.firewall_template.clear();
+        }
+        self.reset_options.clear();
+    }
+
+    /// Discard cached responses describing the given server.
+    ///
+    /// This includes the server listing.
+    pub fn invalidate_server(&self, server_number: impl Into<ServerId>) {
+        let server_number = server_number.into();
+        self.servers.remove(&());
+        self.server.remove(&server_number);
+    }
+
+    /// List all owned servers, see [`AsyncRobot::list_servers`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::cache::CachedRobot;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// for server in robot.list_servers().await.unwrap() {
+    ///     println!("{}: {}", server.id, server.name);
+    /// }
+    /// # }
+    /// ```
+    pub async fn list_servers(&self) -> Result<Vec<Server>, Error> {
+        if let Some(servers) = self.servers.get(&(), self.ttl) {
+            return Ok(servers);
+        }
+
+        let servers = self.robot.list_servers().await?;
+        self.servers.insert((), servers.clone());
+        Ok(servers)
+    }
+
+    /// List owned servers matching the query, see [`AsyncRobot::search_servers`].
+    ///
+    /// Filters the cached server list, if any.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::{api::server::ServerQuery, cache::CachedRobot};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// let query = ServerQuery::default().with_name("web-1");
+    /// let servers = robot.search_servers(&query).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn search_servers(&self, query: &ServerQuery) -> Result<Vec<Server>, Error> {
+        let mut servers = self.list_servers().await?;
+        servers.retain(|server| query.matches(server));
+        Ok(servers)
+    }
+
+    /// Get server, see [`AsyncRobot::get_server`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::{api::server::ServerId, cache::CachedRobot};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// let server = robot.get_server(ServerId(1234567)).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_server(&self, server_number: impl Into<ServerId>) -> Result<Server, Error> {
+        let server_number = server_number.into();
+        if let Some(server) = self.server.get(&server_number, self.ttl) {
+            return Ok(server);
+        }
+
+        let server = self.robot.get_server(server_number).await?;
+        self.server.insert(server_number, server.clone());
+        Ok(server)
+    }
+
+    /// Rename a server, see [`AsyncRobot::rename_server`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::{api::server::ServerId, cache::CachedRobot};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// robot.rename_server(ServerId(1234567), "gibson").await.unwrap();
+    /// # }
+    /// ```
+    pub async fn rename_server(
+        &self,
+        server_number: impl Into<ServerId>,
+        name: &str,
+    ) -> Result<Server, Error> {
+        let server_number = server_number.into();
+        let result = self.robot.rename_server(server_number, name).await;
+        self.invalidate_server(server_number);
+        result
+    }
+
+    /// Cancel a server, see [`AsyncRobot::cancel_server`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::{api::server::{ServerId, Cancel}, cache::CachedRobot};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// robot.cancel_server(ServerId(1234567), Cancel {
+    ///     date: None,
+    ///     reason: None,
+    ///     reserved: false
+    /// }).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn cancel_server(
+        &self,
+        server_number: impl Into<ServerId>,
+        cancellation: Cancel,
+    ) -> Result<Cancelled, Error> {
+        let server_number = server_number.into();
+        let result = self.robot.cancel_server(server_number, cancellation).await;
+        self.invalidate_server(server_number);
+        result
+    }
+
+    /// Withdraw a server cancellation, see [`AsyncRobot::withdraw_server_cancellation`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::{api::server::ServerId, cache::CachedRobot};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// robot.withdraw_server_cancellation(ServerId(1234567)).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn withdraw_server_cancellation(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<(), Error> {
+        let server_number = server_number.into();
+        let result = self.robot.withdraw_server_cancellation(server_number).await;
+        self.invalidate_server(server_number);
+        result
+    }
+
+    /// Retrieve the reset options of a server, see [`AsyncRobot::get_reset_options`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::{api::server::ServerId, cache::CachedRobot};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// let options = robot.get_reset_options(ServerId(1234567)).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_reset_options(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<Vec<Reset>, Error> {
+        let server_number = server_number.into();
+        if let Some(options) = self.reset_options.get(&server_number, self.ttl) {
+            return Ok(options);
+        }
+
+        let options = self.robot.get_reset_options(server_number).await?;
+        self.reset_options.insert(server_number, options.clone());
+        Ok(options)
+    }
+
+    /// Trigger a reset for the server, see [`AsyncRobot::trigger_reset_with`].
+    ///
+    /// Validation uses the cached reset options of the server, if any.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::{api::{server::ServerId, reset::{Reset, ResetOptions}}, cache::CachedRobot};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// robot.trigger_reset_with(
+    ///     ServerId(1234567),
+    ///     Reset::Software,
+    ///     ResetOptions::default().with_validation(true),
+    /// ).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn trigger_reset_with(
+        &self,
+        server_number: impl Into<ServerId>,
+        reset: Reset,
+        options: ResetOptions,
+    ) -> Result<Reset, Error> {
+        let server_number = server_number.into();
+
+        if options.validate {
+            let supported = self.get_reset_options(server_number).await?;
+            ensure_supported(&reset, supported)?;
+        }
+
+        self.robot.trigger_reset(server_number, reset).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use crate::{
+        api::server::ServerId,
+        mock::{fixtures, MockClient},
+    };
+
+    use super::CachedRobot;
+
+    /// Serves server 321, renamed once a rename has been posted.
+    fn client() -> MockClient {
+        MockClient::new()
+            .on("GET", "/server/321", fixtures::server(321))
+            .on(
+                "POST",
+                "/server/321",
+                fixtures::patch(fixtures::server(321), json!({"server_name": "renamed"})),
+            )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cached_server_expires() {
+        let client = client();
+        let requests = client.requests();
+        let robot = CachedRobot::new(client.robot(), Duration::from_secs(60));
+
+        let _ = robot.get_server(ServerId(321)).await.unwrap();
+        let _ = robot.get_server(ServerId(321)).await.unwrap();
+        assert_eq!(requests.len(), 1);
+
+        tokio::time::advance(Duration::from_secs(61)).await;
+
+        let _ = robot.get_server(ServerId(321)).await.unwrap();
+        assert_eq!(requests.len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn writes_invalidate_cache() {
+        let client = client();
+        let requests = client.requests();
+        let robot = CachedRobot::new(client.robot(), Duration::from_secs(60));
+
+        assert_eq!(robot.get_server(ServerId(321)).await.unwrap().name, "n321");
+
+        let _ = robot.rename_server(ServerId(321), "renamed").await.unwrap();
+        assert_eq!(requests.len(), 2);
+
+        let _ = robot.get_server(ServerId(321)).await.unwrap();
+        assert_eq!(requests.len(), 3);
+
+        robot.invalidate_all();
+
+        let _ = robot.get_server(ServerId(321)).await.unwrap();
+        assert_eq!(requests.len(), 4);
+    }
+}
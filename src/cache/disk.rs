@@ -0,0 +1,408 @@
+//! Persistent on-disk cache for slow-changing catalogs.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    future::Future,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    api::{
+        firewall::FirewallTemplateReference,
+        ordering::{Location, Product},
+    },
+    error::Error,
+    AsyncRobot,
+};
+
+/// Catalogs written by a different crate version are discarded, since
+/// the serialized form of the models may have changed in between.
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const PRODUCTS: &str = "products";
+const FIREWALL_TEMPLATES: &str = "firewall_templates";
+
+/// Catalog as stored on disk.
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry<T> {
+    /// Crate version which wrote the entry.
+    version: String,
+    /// Seconds since the unix epoch at which the catalog was fetched.
+    fetched_at: u64,
+    data: T,
+}
+
+/// Outcome of looking up a catalog on disk.
+enum Lookup<T> {
+    Fresh(T),
+    Stale(T),
+    Missing,
+}
+
+/// Caches slow-changing catalogs, like the product list, as JSON files in a directory,
+/// so they survive restarts of short-lived processes such as CLI tools.
+///
+/// Catalogs younger than `max_age` are returned without contacting the API. Older
+/// catalogs are still returned immediately, but are refreshed in the background
+/// (stale-while-revalidate), so the next lookup sees the updated catalog. Catalogs
+/// written by another version of this crate are ignored, as are unreadable files.
+///
+/// Use the `refresh_*` functions to update a catalog before it goes stale,
+/// or [`CatalogCache::clear`] to discard all of them.
+///
+/// Background refreshes are spawned onto the current tokio runtime, and failures
+/// are only logged, since the stale catalog has already been returned.
+///
+/// # Example
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use hrobot::cache::CatalogCache;
+/// # #[tokio::main]
+/// # async fn main() {
+/// let catalogs = CatalogCache::new(
+///     hrobot::AsyncRobot::default(),
+///     "/var/cache/my-tool",
+///     Duration::from_secs(24 * 60 * 60),
+/// );
+///
+/// for product in catalogs.products().await.unwrap() {
+///     println!("{}: {}", product.id, product.name);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CatalogCache {
+    robot: AsyncRobot,
+    directory: PathBuf,
+    max_age: Duration,
+    refreshing: Arc<Mutex<HashSet<&'static str>>>,
+}
+
+impl CatalogCache {
+    /// Cache catalogs fetched using `robot` in `directory`, refreshing them once older than `max_age`.
+    ///
+    /// The directory is created when the first catalog is written.
+    pub fn new(robot: AsyncRobot, directory: impl Into<PathBuf>, max_age: Duration) -> Self {
+        CatalogCache {
+            robot,
+            directory: directory.into(),
+            max_age,
+            refreshing: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Directory in which the catalogs are stored.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Products available for purchase, see [`AsyncRobot::list_products`].
+    pub async fn products(&self) -> Result<Vec<Product>, Error> {
+        self.catalog(PRODUCTS, fetch_products).await
+    }
+
+    /// Locations in which at least one product is available, derived from [`CatalogCache::products`].
+    pub async fn locations(&self) -> Result<Vec<Location>, Error> {
+        Ok(self
+            .products()
+            .await?
+            .into_iter()
+            .flat_map(|product| product.locations)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect())
+    }
+
+    /// Firewall templates, see [`AsyncRobot::list_firewall_templates`].
+    pub async fn firewall_templates(&self) -> Result<Vec<FirewallTemplateReference>, Error> {
+        self.catalog(FIREWALL_TEMPLATES, fetch_firewall_templates)
+            .await
+    }
+
+    /// Fetch and store the product catalog, regardless of its age.
+    pub async fn refresh_products(&self) -> Result<Vec<Product>, Error> {
+        refresh(&self.robot, &self.directory, PRODUCTS, fetch_products).await
+    }
+
+    /// Fetch and store the firewall template catalog, regardless of its age.
+    pub async fn refresh_firewall_templates(
+        &self,
+    ) -> Result<Vec<FirewallTemplateReference>, Error> {
+        refresh(
+            &self.robot,
+            &self.directory,
+            FIREWALL_TEMPLATES,
+            fetch_firewall_templates,
+        )
+        .await
+    }
+
+    /// Fetch and store all catalogs, regardless of their age.
+    pub async fn refresh(&self) -> Result<(), Error> {
+        let _ = self.refresh_products().await?;
+        let _ = self.refresh_firewall_templates().await?;
+        Ok(())
+    }
+
+    /// Discard all stored catalogs, forcing them to be fetched on next use.
+    pub async fn clear(&self) {
+        for name in [PRODUCTS, FIREWALL_TEMPLATES] {
+            let _ = tokio::fs::remove_file(path(&self.directory, name)).await;
+        }
+    }
+
+    async fn catalog<T, F, Fut>(&self, name: &'static str, fetch: F) -> Result<T, Error>
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+        F: FnOnce(AsyncRobot) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, Error>> + Send,
+    {
+        match lookup(&self.directory, name, self.max_age).await {
+            Lookup::Fresh(data) => Ok(data),
+            Lookup::Stale(data) => {
+                self.revalidate(name, fetch);
+                Ok(data)
+            }
+            Lookup::Missing => refresh(&self.robot, &self.directory, name, fetch).await,
+        }
+    }
+
+    /// Refresh the catalog in the background, unless a refresh is already underway.
+    fn revalidate<T, F, Fut>(&self, name: &'static str, fetch: F)
+    where
+        T: Serialize + Send + Sync + 'static,
+        F: FnOnce(AsyncRobot) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, Error>> + Send,
+    {
+        if !self.refreshing.lock().unwrap().insert(name) {
+            return;
+        }
+
+        let robot = self.robot.clone();
+        let directory = self.directory.clone();
+        let refreshing = self.refreshing.clone();
+
+        // Dropping the handle detaches the task.
+        drop(tokio::spawn(async move {
+            if let Err(error) = refresh(&robot, &directory, name, fetch).await {
+                warn!("failed to refresh {name} catalog: {error}");
+            }
+
+            let _ = refreshing.lock().unwrap().remove(name);
+        }));
+    }
+}
+
+async fn fetch_products(robot: AsyncRobot) -> Result<Vec<Product>, Error> {
+    robot.list_products(.., .., None).await
+}
+
+async fn fetch_firewall_templates(
+    robot: AsyncRobot,
+) -> Result<Vec<FirewallTemplateReference>, Error> {
+    robot.list_firewall_templates().await
+}
+
+fn path(directory: &Path, name: &str) -> PathBuf {
+    directory.join(format!("{name}.json"))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+async fn lookup<T: DeserializeOwned>(directory: &Path, name: &str, max_age: Duration) -> Lookup<T> {
+    let Ok(contents) = tokio::fs::read(path(directory, name)).await else {
+        return Lookup::Missing;
+    };
+
+    let entry = match serde_json::from_slice::<Entry<T>>(&contents) {
+        Ok(entry) if entry.version == VERSION => entry,
+        Ok(_) => return Lookup::Missing,
+        Err(error) => {
+            warn!("ignoring unreadable {name} catalog: {error}");
+            return Lookup::Missing;
+        }
+    };
+
+    if now().saturating_sub(entry.fetched_at) < max_age.as_secs() {
+        Lookup::Fresh(entry.data)
+    } else {
+        Lookup::Stale(entry.data)
+    }
+}
+
+/// Fetch the catalog and store it, returning it even if it could not be stored.
+async fn refresh<T, F, Fut>(
+    robot: &AsyncRobot,
+    directory: &Path,
+    name: &str,
+    fetch: F,
+) -> Result<T, Error>
+where
+    T: Serialize,
+    F: FnOnce(AsyncRobot) -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let fetched_at = now();
+    let data = fetch(robot.clone()).await?;
+
+    let entry = Entry {
+        version: VERSION.to_string(),
+        fetched_at,
+        data,
+    };
+
+    if let Err(error) = store(directory, name, &entry).await {
+        warn!("failed to store {name} catalog: {error}");
+    }
+
+    Ok(entry.data)
+}
+
+/// Distinguishes temporary files of concurrent writes within the same process.
+static TEMPORARY_FILES: AtomicU64 = AtomicU64::new(0);
+
+/// Write the entry to a temporary file first, so readers never observe a partial catalog.
+async fn store<T: Serialize>(
+    directory: &Path,
+    name: &str,
+    entry: &Entry<T>,
+) -> std::io::Result<()> {
+    let contents = serde_json::to_vec(entry)?;
+    let temporary = directory.join(format!(
+        ".{name}.json.{}.{}",
+        std::process::id(),
+        TEMPORARY_FILES.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    tokio::fs::create_dir_all(directory).await?;
+    tokio::fs::write(&temporary, contents).await?;
+    tokio::fs::rename(&temporary, path(directory, name)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, time::Duration};
+
+    use serde_json::json;
+
+    use crate::mock::{fixtures, MockClient};
+
+    use super::{path, store, CatalogCache, Entry, VERSION};
+
+    fn directory() -> PathBuf {
+        std::env::temp_dir().join(format!("hrobot-catalogs-{}", rand::random::<u64>()))
+    }
+
+    #[tokio::test]
+    async fn stale_while_revalidate() {
+        // Each fetch returns a template named after the number of requests made so far.
+        let client = MockClient::new().on_sequence(
+            "GET",
+            "/firewall/template",
+            (1..=4).map(|fetch| json!([fixtures::firewall_template(1, &format!("fetch {fetch}"))])),
+        );
+        let requests = client.requests();
+        let directory = directory();
+        let catalogs = CatalogCache::new(client.robot(), &directory, Duration::from_secs(60));
+
+        // Missing catalogs are fetched immediately, and fresh ones served from disk.
+        assert_eq!(
+            catalogs.firewall_templates().await.unwrap()[0].name,
+            "fetch 1"
+        );
+        assert_eq!(
+            catalogs.firewall_templates().await.unwrap()[0].name,
+            "fetch 1"
+        );
+        assert_eq!(requests.len(), 1);
+
+        // Age the stored catalog past its maximum age.
+        let file = path(&directory, "firewall_templates");
+        let aged = std::fs::read_to_string(&file).unwrap().replacen(
+            r#""fetched_at":"#,
+            r#""fetched_at":0,"ignored":"#,
+            1,
+        );
+        std::fs::write(&file, aged).unwrap();
+
+        // Stale catalogs are returned as-is while being refreshed in the background.
+        assert_eq!(
+            catalogs.firewall_templates().await.unwrap()[0].name,
+            "fetch 1"
+        );
+
+        for _ in 0..100 {
+            if catalogs.firewall_templates().await.unwrap()[0].name == "fetch 2" {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(
+            catalogs.firewall_templates().await.unwrap()[0].name,
+            "fetch 2"
+        );
+        assert_eq!(requests.len(), 2);
+
+        // Catalogs written by other versions are discarded.
+        let other_version = std::fs::read_to_string(&file)
+            .unwrap()
+            .replace(VERSION, "0.0.0-other");
+        std::fs::write(&file, other_version).unwrap();
+        assert_eq!(
+            catalogs.firewall_templates().await.unwrap()[0].name,
+            "fetch 3"
+        );
+
+        // Explicit refreshes ignore the age of the catalog.
+        assert_eq!(
+            catalogs.refresh_firewall_templates().await.unwrap()[0].name,
+            "fetch 4"
+        );
+        assert_eq!(
+            catalogs.firewall_templates().await.unwrap()[0].name,
+            "fetch 4"
+        );
+
+        catalogs.clear().await;
+        assert!(!file.exists());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn concurrent_stores() {
+        let directory = directory();
+
+        let entries: Vec<_> = (0..8)
+            .map(|fetched_at| Entry {
+                version: VERSION.to_string(),
+                fetched_at,
+                data: vec![fetched_at],
+            })
+            .collect();
+
+        // Concurrent writes of the same catalog must not share a temporary file.
+        let results = futures_util::future::join_all(
+            entries
+                .iter()
+                .map(|entry| store(&directory, "products", entry)),
+        )
+        .await;
+        assert!(results.iter().all(Result::is_ok));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,272 @@
+//! Caching of firewall lookups, only available with the `firewall` feature enabled.
+
+use crate::{
+    api::{
+        firewall::{
+            Firewall, FirewallConfig, FirewallTemplate, FirewallTemplateConfig,
+            FirewallTemplateReference, TemplateId,
+        },
+        server::ServerId,
+    },
+    error::Error,
+};
+
+use super::CachedRobot;
+
+impl CachedRobot {
+    /// Discard the cached firewall of the given server.
+    pub fn invalidate_firewall(&self, server_number: impl Into<ServerId>) {
+        let server_number = server_number.into();
+        self.firewalls.remove(&server_number);
+    }
+
+    /// Discard cached responses describing the given firewall template.
+    ///
+    /// This includes the firewall template listing.
+    pub fn invalidate_firewall_template(&self, template_number: impl Into<TemplateId>) {
+        let template_number = template_number.into();
+        self.firewall_templates.remove(&());
+        self.firewall_template.remove(&template_number);
+    }
+
+    /// Get firewall configuration for a server,
+    /// see [`AsyncRobot::get_firewall`](crate::AsyncRobot::get_firewall).
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::{api::server::ServerId, cache::CachedRobot};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// let firewall = robot.get_firewall(ServerId(1234567)).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_firewall(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<Firewall, Error> {
+        let server_number = server_number.into();
+        if let Some(firewall) = self.firewalls.get(&server_number, self.ttl) {
+            return Ok(firewall);
+        }
+
+        let firewall = self.robot.get_firewall(server_number).await?;
+        self.firewalls.insert(server_number, firewall.clone());
+        Ok(firewall)
+    }
+
+    /// Replace a server's firewall configuration,
+    /// see [`AsyncRobot::set_firewall_config`](crate::AsyncRobot::set_firewall_config).
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::{api::server::ServerId, cache::CachedRobot};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// let config = robot.get_firewall(ServerId(1234567)).await.unwrap().config();
+    /// robot.set_firewall_config(ServerId(1234567), &config).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn set_firewall_config(
+        &self,
+        server_number: impl Into<ServerId>,
+        firewall: &FirewallConfig,
+    ) -> Result<Firewall, Error> {
+        let server_number = server_number.into();
+        let result = self
+            .robot
+            .set_firewall_config(server_number, firewall)
+            .await;
+        self.invalidate_firewall(server_number);
+        result
+    }
+
+    /// Apply a firewall template to a server,
+    /// see [`AsyncRobot::apply_firewall_template`](crate::AsyncRobot::apply_firewall_template).
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::{api::{server::ServerId, firewall::TemplateId}, cache::CachedRobot};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// robot.apply_firewall_template(ServerId(1234567), TemplateId(1234)).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn apply_firewall_template(
+        &self,
+        server_number: impl Into<ServerId>,
+        template_id: impl Into<TemplateId>,
+    ) -> Result<Firewall, Error> {
+        let server_number = server_number.into();
+        let template_id = template_id.into();
+        let result = self
+            .robot
+            .apply_firewall_template(server_number, template_id)
+            .await;
+        self.invalidate_firewall(server_number);
+        result
+    }
+
+    /// Clear a server's firewall configuration,
+    /// see [`AsyncRobot::delete_firewall`](crate::AsyncRobot::delete_firewall).
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::{api::server::ServerId, cache::CachedRobot};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// robot.delete_firewall(ServerId(1234567)).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn delete_firewall(
+        &self,
+        server_number: impl Into<ServerId>,
+    ) -> Result<Firewall, Error> {
+        let server_number = server_number.into();
+        let result = self.robot.delete_firewall(server_number).await;
+        self.invalidate_firewall(server_number);
+        result
+    }
+
+    /// List all firewall templates,
+    /// see [`AsyncRobot::list_firewall_templates`](crate::AsyncRobot::list_firewall_templates).
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::cache::CachedRobot;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// let templates = robot.list_firewall_templates().await.unwrap();
+    /// # }
+    /// ```
+    pub async fn list_firewall_templates(&self) -> Result<Vec<FirewallTemplateReference>, Error> {
+        if let Some(templates) = self.firewall_templates.get(&(), self.ttl) {
+            return Ok(templates);
+        }
+
+        let templates = self.robot.list_firewall_templates().await?;
+        self.firewall_templates.insert((), templates.clone());
+        Ok(templates)
+    }
+
+    /// Retrieve a complete firewall template,
+    /// see [`AsyncRobot::get_firewall_template`](crate::AsyncRobot::get_firewall_template).
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::{api::firewall::TemplateId, cache::CachedRobot};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// let template = robot.get_firewall_template(TemplateId(1234)).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_firewall_template(
+        &self,
+        template_number: impl Into<TemplateId>,
+    ) -> Result<FirewallTemplate, Error> {
+        let template_number = template_number.into();
+        if let Some(template) = self.firewall_template.get(&template_number, self.ttl) {
+            return Ok(template);
+        }
+
+        let template = self.robot.get_firewall_template(template_number).await?;
+        self.firewall_template
+            .insert(template_number, template.clone());
+        Ok(template)
+    }
+
+    /// Create a new firewall template,
+    /// see [`AsyncRobot::create_firewall_template`](crate::AsyncRobot::create_firewall_template).
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::{api::firewall::{FirewallTemplateConfig, Rules}, cache::CachedRobot};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// robot.create_firewall_template(FirewallTemplateConfig {
+    ///     name: "My Firewall".to_string(),
+    ///     filter_ipv6: false,
+    ///     whitelist_hetzner_services: true,
+    ///     is_default: false,
+    ///     rules: Rules { ingress: vec![], egress: vec![] },
+    /// }).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn create_firewall_template(
+        &self,
+        template: FirewallTemplateConfig,
+    ) -> Result<FirewallTemplate, Error> {
+        let result = self.robot.create_firewall_template(template).await;
+        self.firewall_templates.remove(&());
+        result
+    }
+
+    /// Delete a firewall template,
+    /// see [`AsyncRobot::delete_firewall_template`](crate::AsyncRobot::delete_firewall_template).
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::{api::firewall::TemplateId, cache::CachedRobot};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// robot.delete_firewall_template(TemplateId(1234)).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn delete_firewall_template(
+        &self,
+        template_number: impl Into<TemplateId>,
+    ) -> Result<(), Error> {
+        let template_number = template_number.into();
+        let result = self.robot.delete_firewall_template(template_number).await;
+        self.invalidate_firewall_template(template_number);
+        result
+    }
+
+    /// Modify a firewall template,
+    /// see [`AsyncRobot::update_firewall_template`](crate::AsyncRobot::update_firewall_template).
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::{api::firewall::{FirewallTemplateConfig, Rules, TemplateId}, cache::CachedRobot};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = CachedRobot::new(hrobot::AsyncRobot::default(), Duration::from_secs(60));
+    /// robot.update_firewall_template(TemplateId(1234), FirewallTemplateConfig {
+    ///     name: "My Updated Firewall".to_string(),
+    ///     filter_ipv6: false,
+    ///     whitelist_hetzner_services: true,
+    ///     is_default: false,
+    ///     rules: Rules { ingress: vec![], egress: vec![] },
+    /// }).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn update_firewall_template(
+        &self,
+        template_number: impl Into<TemplateId>,
+        template: FirewallTemplateConfig,
+    ) -> Result<FirewallTemplate, Error> {
+        let template_number = template_number.into();
+        let result = self
+            .robot
+            .update_firewall_template(template_number, template)
+            .await;
+        self.invalidate_firewall_template(template_number);
+        result
+    }
+}
@@ -1,19 +1,243 @@
 mod r#async {
-    use http_body_util::{BodyExt, Full};
-    use hyper::body::Bytes;
-    use hyper_rustls::HttpsConnector;
-    use hyper_util::{
-        client::legacy::{connect::HttpConnector, Client},
-        rt::TokioExecutor,
+    use std::{
+        future::Future,
+        pin::Pin,
+        sync::{Arc, RwLock},
+        time::Duration,
     };
-    use serde::de::DeserializeOwned;
-    use tracing::trace;
+
+    use bytes::Bytes;
+    use hyper::{
+        header::{HeaderValue, InvalidHeaderValue, USER_AGENT},
+        http::uri::PathAndQuery,
+        Request, Response, StatusCode, Uri,
+    };
+    use serde::de::{DeserializeOwned, IgnoredAny};
+    use tracing::{debug_span, trace, Instrument};
 
     use crate::{
         api::{Credentials, UnauthenticatedRequest},
-        error::{Error, MaybeTypedResponse},
+        audit::{AuditEntry, AuditLog},
+        error::{ApiError, Error, ErrorContext, MaybeTypedResponse},
+        expectations,
+        pacing::{Pacer, TokenBucket},
+        strict,
+        telemetry::{redact_form, redact_json},
     };
 
+    /// Future returned by [`AsyncHttpClient::send_request`].
+    pub type ResponseFuture<'a> =
+        Pin<Box<dyn Future<Output = Result<Response<Bytes>, Error>> + Send + 'a>>;
+
+    /// HTTP client capable of sending requests to the Hetzner Robot API.
+    ///
+    /// Implemented for [`hyper_util::client::legacy::Client`] when the `hyper-client`
+    /// feature is enabled (default), and for `reqwest::Client` when the `reqwest-client`
+    /// feature is enabled.
+    ///
+    /// Implement this trait yourself if you want to use an entirely different client.
+    pub trait AsyncHttpClient: std::fmt::Debug + Send + Sync {
+        /// Send the fully constructed and authenticated request, and return the
+        /// response including the collected body.
+        ///
+        /// Errors produced by the client itself should be wrapped
+        /// using [`Error::transport`].
+        fn send_request(&self, request: Request<String>) -> ResponseFuture<'_>;
+    }
+
+    /// Future returned by [`CredentialsProvider::credentials`].
+    pub type CredentialsFuture<'a> =
+        Pin<Box<dyn Future<Output = Result<Credentials, Error>> + Send + 'a>>;
+
+    /// Source of up-to-date [`Credentials`], for example a secret store.
+    ///
+    /// When configured using [`AsyncRobot::with_credentials_provider`], the provider
+    /// is queried for new credentials whenever the API rejects the current ones,
+    /// which allows long-running operations to survive password rotation.
+    ///
+    /// When configured using [`AsyncRobot::from_credentials_provider`], the provider
+    /// is instead queried before every request, so rotated credentials are picked up
+    /// before the old ones stop working.
+    pub trait CredentialsProvider: std::fmt::Debug + Send + Sync {
+        /// Fetch the current credentials.
+        fn credentials(&self) -> CredentialsFuture<'_>;
+    }
+
+    /// Static credentials which never change, as used by [`AsyncRobot::from_credentials`].
+    impl CredentialsProvider for Credentials {
+        fn credentials(&self) -> CredentialsFuture<'_> {
+            Box::pin(async move { Ok(self.clone()) })
+        }
+    }
+
+    /// Event emitted by an [`AsyncRobot`] to its [`RobotObserver`].
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum RobotEvent {
+        /// The API rejected the current credentials, so new credentials
+        /// were fetched from the [`CredentialsProvider`], and the failed
+        /// request is being retried.
+        CredentialsRefreshed {
+            /// Username of the newly fetched credentials.
+            username: String,
+        },
+    }
+
+    /// Receives [`RobotEvent`]s emitted by an [`AsyncRobot`].
+    ///
+    /// Configured using [`AsyncRobot::with_observer`].
+    pub trait RobotObserver: std::fmt::Debug + Send + Sync {
+        /// Called whenever an event occurs.
+        fn notify(&self, event: &RobotEvent);
+    }
+
+    #[cfg(feature = "hyper-client")]
+    impl<C> AsyncHttpClient for hyper_util::client::legacy::Client<C, http_body_util::Full<Bytes>>
+    where
+        C: hyper_util::client::legacy::connect::Connect + Clone + Send + Sync + 'static,
+    {
+        fn send_request(&self, request: Request<String>) -> ResponseFuture<'_> {
+            use http_body_util::{BodyExt, Full};
+
+            Box::pin(async move {
+                let response = self
+                    .request(request.map(Full::from))
+                    .await
+                    .map_err(Error::transport)?;
+
+                let (parts, body) = response.into_parts();
+
+                let body = body.collect().await.map_err(Error::transport)?.to_bytes();
+
+                Ok(Response::from_parts(parts, body))
+            })
+        }
+    }
+
+    #[cfg(feature = "reqwest-client")]
+    impl AsyncHttpClient for reqwest::Client {
+        fn send_request(&self, request: Request<String>) -> ResponseFuture<'_> {
+            Box::pin(async move {
+                let request = reqwest::Request::try_from(request).map_err(Error::transport)?;
+
+                let response = self.execute(request).await.map_err(Error::transport)?;
+
+                let status = response.status();
+                let headers = response.headers().clone();
+                let body = response.bytes().await.map_err(Error::transport)?;
+
+                let mut response = Response::new(body);
+                *response.status_mut() = status;
+                *response.headers_mut() = headers;
+
+                Ok(response)
+            })
+        }
+    }
+
+    /// Connection pool settings of the default client, configured using
+    /// [`AsyncRobotBuilder::pool_idle_timeout`] and [`AsyncRobotBuilder::pool_max_idle_per_host`].
+    ///
+    /// Unset values use the defaults of the underlying client.
+    #[cfg(any(feature = "hyper-client", feature = "reqwest-client"))]
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    struct PoolConfig {
+        idle_timeout: Option<Duration>,
+        max_idle_per_host: Option<usize>,
+    }
+
+    #[cfg(feature = "hyper-client")]
+    impl PoolConfig {
+        fn client_builder(self) -> hyper_util::client::legacy::Builder {
+            use hyper_util::{client::legacy::Client, rt::TokioExecutor};
+
+            let mut builder = Client::builder(TokioExecutor::new());
+            if let Some(idle_timeout) = self.idle_timeout {
+                let _ = builder.pool_idle_timeout(idle_timeout);
+            }
+            if let Some(max_idle_per_host) = self.max_idle_per_host {
+                let _ = builder.pool_max_idle_per_host(max_idle_per_host);
+            }
+
+            builder
+        }
+    }
+
+    #[cfg(all(feature = "reqwest-client", not(feature = "hyper-client")))]
+    impl PoolConfig {
+        fn client_builder(self) -> reqwest::ClientBuilder {
+            let mut builder = reqwest::Client::builder();
+            if let Some(idle_timeout) = self.idle_timeout {
+                builder = builder.pool_idle_timeout(idle_timeout);
+            }
+            if let Some(max_idle_per_host) = self.max_idle_per_host {
+                builder = builder.pool_max_idle_per_host(max_idle_per_host);
+            }
+
+            builder
+        }
+    }
+
+    /// Construct the client used by [`AsyncRobot::default`] and [`AsyncRobot::new_with_default_client`].
+    ///
+    /// Prefers [`hyper_util`] if the `hyper-client` feature is enabled, falling back
+    /// to `reqwest` otherwise.
+    #[cfg(feature = "hyper-client")]
+    fn default_client(pool: PoolConfig) -> impl AsyncHttpClient + 'static {
+        use hyper_rustls::HttpsConnector;
+        use hyper_util::client::legacy::connect::HttpConnector;
+
+        let https: HttpsConnector<HttpConnector> = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_webpki_roots()
+            .https_only()
+            .enable_http1()
+            .build();
+
+        pool.client_builder().build(https)
+    }
+
+    /// Construct the client used by [`AsyncRobot::default`] and [`AsyncRobot::new_with_default_client`].
+    #[cfg(all(feature = "reqwest-client", not(feature = "hyper-client")))]
+    fn default_client(pool: PoolConfig) -> impl AsyncHttpClient + 'static {
+        // Mirrors reqwest::Client::new, which also panics if the TLS backend fails to initialize.
+        pool.client_builder()
+            .build()
+            .expect("failed to construct reqwest client")
+    }
+
+    /// Construct the client used by [`AsyncRobotBuilder::build`] when a proxy is configured.
+    ///
+    /// HTTPS requests are tunneled through the proxy using `CONNECT`.
+    #[cfg(feature = "hyper-client")]
+    fn proxied_client(
+        proxy: Uri,
+        pool: PoolConfig,
+    ) -> Result<impl AsyncHttpClient + 'static, BuilderError> {
+        use hyper_util::client::legacy::connect::{proxy::Tunnel, HttpConnector};
+
+        let https = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_webpki_roots()
+            .https_only()
+            .enable_http1()
+            .wrap_connector(Tunnel::new(proxy, HttpConnector::new()));
+
+        Ok(pool.client_builder().build(https))
+    }
+
+    /// Construct the client used by [`AsyncRobotBuilder::build`] when a proxy is configured.
+    #[cfg(all(feature = "reqwest-client", not(feature = "hyper-client")))]
+    fn proxied_client(
+        proxy: Uri,
+        pool: PoolConfig,
+    ) -> Result<impl AsyncHttpClient + 'static, BuilderError> {
+        let proxy = reqwest::Proxy::all(proxy.to_string())
+            .map_err(|err| BuilderError::InvalidProxy(err.to_string()))?;
+
+        pool.client_builder()
+            .proxy(proxy)
+            .build()
+            .map_err(|err| BuilderError::InvalidProxy(err.to_string()))
+    }
+
     /// Handles authentication and exposes the Hetzner Robot API functionality
     /// with a simple interface.
     ///
@@ -29,26 +253,60 @@ mod r#async {
     /// # }
     /// ```
     ///
-    /// If you want to customize the [`hyper::Client`] see:
+    /// If you want to customize the HTTP client see:
     /// * [`AsyncRobot::from_env`] if you still want to use the environment variables, or
-    /// * [`AsyncRobot::new`] if you want to provide client and credentials yourself.
+    /// * [`AsyncRobot::new`] if you want to provide client and credentials yourself, or
+    /// * [`AsyncRobot::builder`] if you want to configure timeouts, proxies and the like.
     ///
     #[derive(Debug, Clone)]
     pub struct AsyncRobot {
-        credentials: Credentials,
-        client: Client<HttpsConnector<HttpConnector>, Full<Bytes>>,
+        credentials: CredentialSource,
+        observer: Option<Arc<dyn RobotObserver>>,
+        audit: Option<Arc<dyn AuditLog>>,
+        strict: bool,
+        read_only: bool,
+        dry_run: bool,
+        base_url: Option<Uri>,
+        proxy: Option<Uri>,
+        timeout: Option<Duration>,
+        user_agent: Option<HeaderValue>,
+        pacer: Option<Arc<Pacer>>,
+        limiter: Option<Arc<TokenBucket>>,
+        client: Arc<dyn AsyncHttpClient>,
+    }
+
+    /// Where an [`AsyncRobot`] gets the credentials for each request from.
+    #[derive(Debug, Clone)]
+    enum CredentialSource {
+        /// Fixed credentials, shared by all clones.
+        Static(Arc<RwLock<Credentials>>),
+
+        /// Credentials shared by all clones, replaced using the provider
+        /// whenever the API rejects them.
+        RefreshOnReject(Arc<RwLock<Credentials>>, Arc<dyn CredentialsProvider>),
+
+        /// Credentials fetched from the provider before every request.
+        PerRequest(Arc<dyn CredentialsProvider>),
+    }
+
+    impl CredentialSource {
+        fn fixed(credentials: Credentials) -> Self {
+            CredentialSource::Static(Arc::new(RwLock::new(credentials)))
+        }
+    }
+
+    /// Clone the current value of shared credentials.
+    fn current(credentials: &RwLock<Credentials>) -> Credentials {
+        credentials
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
     }
 
+    #[cfg(any(feature = "hyper-client", feature = "reqwest-client"))]
     impl Default for AsyncRobot {
         fn default() -> Self {
-            let https: HttpsConnector<HttpConnector> = hyper_rustls::HttpsConnectorBuilder::new()
-                .with_webpki_roots()
-                .https_only()
-                .enable_http1()
-                .build();
-            let client = Client::builder(TokioExecutor::new()).build(https);
-
-            Self::from_env(client).unwrap()
+            Self::from_env(default_client(PoolConfig::default())).unwrap()
         }
     }
 
@@ -76,12 +334,46 @@ mod r#async {
         /// # }
         /// ```
         pub fn from_env(
-            client: Client<HttpsConnector<HttpConnector>, Full<Bytes>>,
+            client: impl AsyncHttpClient + 'static,
+        ) -> Result<Self, std::env::VarError> {
+            Self::from_env_with_prefix(client, "HROBOT_")
+        }
+
+        /// Construct a new [`AsyncRobot`] using the environment variables
+        /// `{prefix}USERNAME` and `{prefix}PASSWORD` for credentials,
+        /// and the given client.
+        ///
+        /// Useful for applications managing several accounts, such as staging and production.
+        ///
+        /// # Example
+        /// ```rust
+        /// # std::env::set_var("STAGING_USERNAME", "#ws+staging");
+        /// # std::env::set_var("STAGING_PASSWORD", "p@ssw0rd");
+        /// # std::env::set_var("PRODUCTION_USERNAME", "#ws+production");
+        /// # std::env::set_var("PRODUCTION_PASSWORD", "p@ssw0rd");
+        /// # #[tokio::main]
+        /// # async fn main() {
+        /// let https = hyper_rustls::HttpsConnectorBuilder::new()
+        ///     .with_webpki_roots()
+        ///     .https_only()
+        ///     .enable_http1()
+        ///     .build();
+        ///
+        /// let client = hyper_util::client::legacy::Client::builder(
+        ///     hyper_util::rt::TokioExecutor::new()
+        /// ).build(https);
+        ///
+        /// let staging = hrobot::AsyncRobot::from_env_with_prefix(client.clone(), "STAGING_").unwrap();
+        /// let production = hrobot::AsyncRobot::from_env_with_prefix(client, "PRODUCTION_").unwrap();
+        /// # }
+        /// ```
+        pub fn from_env_with_prefix(
+            client: impl AsyncHttpClient + 'static,
+            prefix: &str,
         ) -> Result<Self, std::env::VarError> {
-            Ok(Self::new(
+            Ok(Self::from_credentials(
                 client,
-                &std::env::var("HROBOT_USERNAME")?,
-                &std::env::var("HROBOT_PASSWORD")?,
+                Credentials::from_env_with_prefix(prefix)?,
             ))
         }
 
@@ -105,20 +397,354 @@ mod r#async {
         /// let robot = hrobot::AsyncRobot::new(client, "#ws+username", "p@ssw0rd");
         /// # }
         /// ```
-        pub fn new(
-            client: Client<HttpsConnector<HttpConnector>, Full<Bytes>>,
-            username: &str,
-            password: &str,
+        pub fn new(client: impl AsyncHttpClient + 'static, username: &str, password: &str) -> Self {
+            Self::from_credentials(client, Credentials::new(username, password))
+        }
+
+        /// Construct a new [`AsyncRobot`], using the given client and credentials.
+        ///
+        /// Any [`AsyncHttpClient`] can be used, including `reqwest::Client`
+        /// if the `reqwest-client` feature is enabled.
+        ///
+        /// # Example
+        /// Construct an [`AsyncRobot`] using a custom [`hyper_util::client::legacy::Client`].
+        /// ```rust
+        /// # use hrobot::api::Credentials;
+        /// # #[tokio::main]
+        /// # async fn main() {
+        /// let https = hyper_rustls::HttpsConnectorBuilder::new()
+        ///     .with_webpki_roots()
+        ///     .https_only()
+        ///     .enable_http1()
+        ///     .build();
+        ///
+        /// let client = hyper_util::client::legacy::Client::builder(
+        ///     hyper_util::rt::TokioExecutor::new()
+        /// ).build(https);
+        ///
+        /// let robot = hrobot::AsyncRobot::from_credentials(
+        ///     client,
+        ///     Credentials::new("#ws+username", "p@ssw0rd"),
+        /// );
+        /// # }
+        /// ```
+        pub fn from_credentials(
+            client: impl AsyncHttpClient + 'static,
+            credentials: Credentials,
+        ) -> Self {
+            Self::from_source(client, CredentialSource::fixed(credentials))
+        }
+
+        fn from_source(
+            client: impl AsyncHttpClient + 'static,
+            credentials: CredentialSource,
         ) -> Self {
             AsyncRobot {
-                credentials: Credentials::new(username, password),
-                client,
+                credentials,
+                observer: None,
+                audit: None,
+                strict: false,
+                read_only: false,
+                dry_run: false,
+                base_url: None,
+                proxy: None,
+                timeout: None,
+                user_agent: None,
+                pacer: None,
+                limiter: None,
+                client: Arc::new(client),
             }
         }
 
-        /// Construct a new [`AsyncRobot`], using the default [`hyper_util::client::legacy::Client`]
+        /// Construct a new [`AsyncRobot`], using the given client and fetching
+        /// credentials from `provider` before every request.
+        ///
+        /// Use this when credentials are rotated externally, for example by a secret
+        /// store. If the API rejects the fetched credentials anyway, they are fetched
+        /// once more and the request is retried, as with
+        /// [`AsyncRobot::with_credentials_provider`].
+        ///
+        /// # Example
+        /// ```rust
+        /// # use hrobot::{api::Credentials, CredentialsFuture, CredentialsProvider};
+        /// #[derive(Debug)]
+        /// struct VaultProvider;
+        ///
+        /// impl CredentialsProvider for VaultProvider {
+        ///     fn credentials(&self) -> CredentialsFuture<'_> {
+        ///         Box::pin(async {
+        ///             // Read the current credentials from the secret store.
+        ///             Ok(Credentials::new("#ws+username", "p@ssw0rd"))
+        ///         })
+        ///     }
+        /// }
+        ///
+        /// # #[tokio::main]
+        /// # async fn main() {
+        /// let https = hyper_rustls::HttpsConnectorBuilder::new()
+        ///     .with_webpki_roots()
+        ///     .https_only()
+        ///     .enable_http1()
+        ///     .build();
+        ///
+        /// let client = hyper_util::client::legacy::Client::builder(
+        ///     hyper_util::rt::TokioExecutor::new()
+        /// ).build(https);
+        ///
+        /// let robot = hrobot::AsyncRobot::from_credentials_provider(client, VaultProvider);
+        /// # }
+        /// ```
+        pub fn from_credentials_provider(
+            client: impl AsyncHttpClient + 'static,
+            provider: impl CredentialsProvider + 'static,
+        ) -> Self {
+            Self::from_source(client, CredentialSource::PerRequest(Arc::new(provider)))
+        }
+
+        /// Fetch new credentials from `provider` whenever the API rejects the current ones.
+        ///
+        /// The request which was rejected is retried once using the new credentials,
+        /// and a [`RobotEvent::CredentialsRefreshed`] event is emitted. The refreshed
+        /// credentials are shared with all clones of this [`AsyncRobot`].
+        ///
+        /// On robots constructed using [`AsyncRobot::from_credentials_provider`],
+        /// this replaces the provider queried before every request instead.
+        ///
+        /// # Example
+        /// ```rust
+        /// # use hrobot::{api::Credentials, CredentialsFuture, CredentialsProvider};
+        /// #[derive(Debug)]
+        /// struct EnvironmentProvider;
+        ///
+        /// impl CredentialsProvider for EnvironmentProvider {
+        ///     fn credentials(&self) -> CredentialsFuture<'_> {
+        ///         Box::pin(async {
+        ///             let username = std::env::var("HROBOT_USERNAME").map_err(hrobot::error::Error::transport)?;
+        ///             let password = std::env::var("HROBOT_PASSWORD").map_err(hrobot::error::Error::transport)?;
+        ///
+        ///             Ok(Credentials::new(&username, &password))
+        ///         })
+        ///     }
+        /// }
+        ///
+        /// # #[tokio::main]
+        /// # async fn main() {
+        /// let robot = hrobot::AsyncRobot::new_with_default_client("#ws+username", "p@ssw0rd")
+        ///     .with_credentials_provider(EnvironmentProvider);
+        /// # }
+        /// ```
+        pub fn with_credentials_provider(
+            mut self,
+            provider: impl CredentialsProvider + 'static,
+        ) -> Self {
+            let provider: Arc<dyn CredentialsProvider> = Arc::new(provider);
+
+            self.credentials = match self.credentials {
+                CredentialSource::Static(credentials)
+                | CredentialSource::RefreshOnReject(credentials, _) => {
+                    CredentialSource::RefreshOnReject(credentials, provider)
+                }
+                CredentialSource::PerRequest(_) => CredentialSource::PerRequest(provider),
+            };
+            self
+        }
+
+        /// Notify `observer` of [`RobotEvent`]s, such as credentials being refreshed.
+        pub fn with_observer(mut self, observer: impl RobotObserver + 'static) -> Self {
+            self.observer = Some(Arc::new(observer));
+            self
+        }
+
+        /// Record every request which modifies the account in `log`.
+        ///
+        /// See the [`audit`](crate::audit) module for details.
+        pub fn with_audit_log(mut self, log: impl AuditLog + 'static) -> Self {
+            self.audit = Some(Arc::new(log));
+            self
+        }
+
+        /// Fail on any response containing fields or enum variants unknown to the models.
+        ///
+        /// By default unknown fields are ignored, so that additions to the API do not
+        /// break existing users. Strict models are instead meant for detecting drift
+        /// between the API and the models early, for example in CI, and produce
+        /// [`Error::ModelMismatch`] errors pointing to the offending value.
+        ///
+        /// Fields nested within untagged or flattened structures are not checked.
+        ///
+        /// # Example
+        /// ```rust,no_run
+        /// # use hrobot::error::Error;
+        /// # #[tokio::main]
+        /// # async fn main() {
+        /// let robot = hrobot::AsyncRobot::default().strict_models(true);
+        ///
+        /// if let Err(Error::ModelMismatch { pointer, message }) = robot.list_servers().await {
+        ///     println!("api drift at {pointer}: {message}");
+        /// }
+        /// # }
+        /// ```
+        pub fn strict_models(mut self, strict: bool) -> Self {
+            self.strict = strict;
+            self
+        }
+
+        /// Fail every request which could modify the account, that is every request
+        /// other than `GET`, with [`Error::ReadOnlyMode`] without sending it.
+        ///
+        /// Useful for monitoring and reporting deployments which share credentials
+        /// with deployments that do modify the account.
+        ///
+        /// # Example
+        /// ```rust,no_run
+        /// # use hrobot::{api::server::ServerId, error::Error};
+        /// # #[tokio::main]
+        /// # async fn main() {
+        /// let robot = hrobot::AsyncRobot::default().read_only(true);
+        ///
+        /// let servers = robot.list_servers().await.unwrap();
+        /// assert!(matches!(
+        ///     robot.rename_server(ServerId(1234567), "gateway").await,
+        ///     Err(Error::ReadOnlyMode { .. })
+        /// ));
+        /// # }
+        /// ```
+        pub fn read_only(mut self, read_only: bool) -> Self {
+            self.read_only = read_only;
+            self
+        }
+
+        /// Space requests at least `interval` apart, plus up to 10% random jitter.
+        ///
+        /// Pacing applies process-wide to all paced robots using the same username,
+        /// so spawning several tasks does not multiply the request rate. It is a
+        /// simpler alternative to [`BulkOptions::with_rate_limit`](crate::api::bulk::BulkOptions::with_rate_limit)
+        /// for scripts which sequentially iterate over many resources.
+        ///
+        /// # Example
+        /// ```rust,no_run
+        /// # use std::time::Duration;
+        /// # #[tokio::main]
+        /// # async fn main() {
+        /// // Stays well within the limit of 200 requests per hour.
+        /// let robot = hrobot::AsyncRobot::default().paced(Duration::from_secs(20));
+        ///
+        /// for server in robot.list_servers().await.unwrap() {
+        ///     let cancellation = robot.get_server_cancellation(server.id).await.unwrap();
+        ///     println!("{}: {cancellation:?}", server.name);
+        /// }
+        /// # }
+        /// ```
+        pub fn paced(mut self, interval: Duration) -> Self {
+            self.pacer = Some(Arc::new(Pacer::new(interval)));
+            self
+        }
+
+        /// Allow at most `max_requests` requests per `interval`, in bursts of up to `max_requests`.
+        ///
+        /// The token bucket is shared by all clones of the robot, so tasks each holding a
+        /// clone are limited collectively. Robots constructed separately do not share
+        /// a limit, even if they use the same credentials; see [`AsyncRobot::paced`] for that.
+        ///
+        /// Requests issued by bulk operations are subject to this limit as well as the
+        /// one set in their [`BulkOptions`](crate::api::bulk::BulkOptions), and a bulk
+        /// operation exceeding the API rate limit holds back all clones of the robot.
+        ///
+        /// # Example
+        /// ```rust,no_run
+        /// # use std::time::Duration;
+        /// # #[tokio::main]
+        /// # async fn main() {
+        /// let robot = hrobot::AsyncRobot::default()
+        ///     .rate_limited(200, Duration::from_secs(3600));
+        ///
+        /// let tasks: Vec<_> = (0..4).map(|_| {
+        ///     let robot = robot.clone();
+        ///     tokio::spawn(async move { robot.list_servers().await.unwrap().len() })
+        /// }).collect();
+        ///
+        /// for task in tasks {
+        ///     println!("{} servers", task.await.unwrap());
+        /// }
+        /// # }
+        /// ```
+        pub fn rate_limited(mut self, max_requests: u32, interval: Duration) -> Self {
+            self.limiter = Some(Arc::new(TokenBucket::new(max_requests, interval)));
+            self
+        }
+
+        /// Fail every request which could modify the account with [`Error::DryRun`],
+        /// containing the method, URL and encoded body of the request which would
+        /// otherwise have been sent.
+        ///
+        /// `GET` requests are still sent, so the current state of the account can be
+        /// inspected while planning changes. Takes precedence over [`AsyncRobot::read_only`].
+        ///
+        /// # Example
+        /// ```rust,no_run
+        /// # use hrobot::{api::server::ServerId, error::Error};
+        /// # #[tokio::main]
+        /// # async fn main() {
+        /// let robot = hrobot::AsyncRobot::default().dry_run(true);
+        ///
+        /// if let Err(Error::DryRun(request)) = robot.rename_server(ServerId(1234567), "gateway").await {
+        ///     // POST https://robot-ws.your-server.de/server/1234567
+        ///     //
+        ///     // server_name=gateway
+        ///     println!("{request}");
+        /// }
+        /// # }
+        /// ```
+        pub fn dry_run(mut self, dry_run: bool) -> Self {
+            self.dry_run = dry_run;
+            self
+        }
+
+        /// Fail requests which do not complete within `timeout`.
+        ///
+        /// Clones of an [`AsyncRobot`] are cheap and share credentials, so this can
+        /// also be used to override the timeout for individual requests.
+        ///
+        /// Requests which time out fail with an [`Error::Transport`] wrapping
+        /// a [`tokio::time::error::Elapsed`].
+        ///
+        /// # Example
+        /// ```rust,no_run
+        /// # use std::time::Duration;
+        /// # use hrobot::api::server::ServerId;
+        /// # #[tokio::main]
+        /// # async fn main() {
+        /// let robot = hrobot::AsyncRobot::builder()
+        ///     .timeout(Duration::from_secs(10))
+        ///     .build()
+        ///     .unwrap();
+        ///
+        /// // Allow this one request a little more time.
+        /// let servers = robot
+        ///     .clone()
+        ///     .with_timeout(Duration::from_secs(60))
+        ///     .list_servers()
+        ///     .await
+        ///     .unwrap();
+        /// # }
+        /// ```
+        pub fn with_timeout(mut self, timeout: Duration) -> Self {
+            self.timeout = Some(timeout);
+            self
+        }
+
+        /// Construct an [`AsyncRobotBuilder`] for configuring
+        /// credentials, timeouts, proxies and the like.
+        pub fn builder() -> AsyncRobotBuilder {
+            AsyncRobotBuilder::default()
+        }
+
+        /// Construct a new [`AsyncRobot`], using the default client
         /// and the provided username and password.
         ///
+        /// The default client is based on [`hyper_util`] if the `hyper-client` feature
+        /// is enabled, otherwise `reqwest` is used.
+        ///
         /// # Example
         /// Construct an [`AsyncRobot`] using a given username and password
         /// ```rust
@@ -127,73 +753,985 @@ mod r#async {
         /// let robot = hrobot::AsyncRobot::new_with_default_client("#ws+username", "p@ssw0rd");
         /// # }
         /// ```
+        #[cfg(any(feature = "hyper-client", feature = "reqwest-client"))]
         pub fn new_with_default_client(username: &str, password: &str) -> Self {
-            let https: HttpsConnector<HttpConnector> = hyper_rustls::HttpsConnectorBuilder::new()
-                .with_webpki_roots()
-                .https_only()
-                .enable_http1()
-                .build();
-            let client = Client::builder(TokioExecutor::new()).build(https);
+            Self::new(default_client(PoolConfig::default()), username, password)
+        }
+
+        /// HTTP client used for sending requests.
+        pub(crate) fn client(&self) -> &dyn AsyncHttpClient {
+            self.client.as_ref()
+        }
+
+        /// URL requests are sent to instead of the API, if set using [`AsyncRobotBuilder::base_url`].
+        pub(crate) fn base_url(&self) -> Option<&Uri> {
+            self.base_url.as_ref()
+        }
+
+        /// HTTP proxy requests are sent through, if set using [`AsyncRobotBuilder::proxy`].
+        pub(crate) fn proxy(&self) -> Option<&Uri> {
+            self.proxy.as_ref()
+        }
 
-            Self::new(client, username, password)
+        /// Token bucket shared by all clones, if set using [`AsyncRobot::rate_limited`].
+        pub(crate) fn limiter(&self) -> Option<&Arc<TokenBucket>> {
+            self.limiter.as_ref()
         }
 
         /// Shorthand for authenticating and sending the request.
-        #[tracing::instrument]
+        ///
+        /// The request is traced within a span named after the endpoint, and
+        /// sensitive fields such as passwords are redacted from the logged
+        /// request and response bodies.
         pub(crate) async fn go<Response: DeserializeOwned + Send + 'static>(
             &self,
             request: UnauthenticatedRequest<Response>,
         ) -> Result<Response, Error> {
-            trace!("{request:?}");
+            let endpoint = request.endpoint();
+            let span = debug_span!(
+                "request",
+                method = request.method(),
+                endpoint = endpoint.name,
+                server_id = endpoint.server.map(|server| server.0),
+            );
+
+            async move {
+                trace!("{request:?}");
+
+                let audit = self
+                    .audit
+                    .as_ref()
+                    .filter(|_| request.method() != "GET")
+                    .map(|log| (log, AuditEntry::new(&request, &endpoint)));
+
+                let result = self.send(request).await.and_then(|response| {
+                    let (parts, body) = response.into_parts();
+
+                    trace!("response body: {}", redact_json(&body));
 
-            let authenticated_request = request.authenticate(&self.credentials);
+                    // Rate limiting is also reported as 403 Forbidden, but with an API error body.
+                    if parts.status == StatusCode::UNAUTHORIZED
+                        || (parts.status == StatusCode::FORBIDDEN
+                            && serde_json::from_slice::<MaybeTypedResponse>(&body).is_err())
+                    {
+                        return Err(Error::Unauthorized {
+                            status: parts.status,
+                        });
+                    }
+
+                    // We do explicit deserialization here, since some endpoints can return empty responses.
+                    //
+                    // I initialize used a #[derive(Serialize, Deserialize)] enum which encapsulated both success and
+                    // error states, but deserializing an untagged enum, even when the encapsulated "Ok"
+                    // result is just a unit type (), deserialization will fail on empty input.
+                    //
+                    // Empty bodies, as returned by most DELETE endpoints, are instead deserialized
+                    // as `null`, which `Empty`, `()` and `Option` all accept.
+                    let content: &[u8] = if body.iter().all(u8::is_ascii_whitespace) {
+                        b"null"
+                    } else {
+                        &body
+                    };
+
+                    // During maintenance, the API responds with 503 and/or an HTML page.
+                    if serde_json::from_slice::<IgnoredAny>(content).is_err()
+                        || (parts.status == StatusCode::SERVICE_UNAVAILABLE
+                            && serde_json::from_slice::<MaybeTypedResponse>(&body).is_err())
+                    {
+                        return Err(Error::Unavailable {
+                            retry_after: ErrorContext::from_response(parts.status, &parts.headers)
+                                .retry_after,
+                        });
+                    }
+
+                    let response = if self.strict {
+                        strict::from_slice::<Response>(content)
+                    } else {
+                        serde_json::from_slice::<Response>(content).map_err(Error::from)
+                    };
+
+                    match response {
+                        Ok(result) => Ok(result),
+                        Err(err) => match serde_json::from_slice::<MaybeTypedResponse>(&body) {
+                            Ok(api_error) => {
+                                let api_error = ApiError::from(api_error.error);
+                                let mut context =
+                                    ErrorContext::from_response(parts.status, &parts.headers);
+                                context.hint = expectations::hint(&endpoint, &api_error);
+
+                                Err(Error::Api(api_error, context))
+                            }
+                            Err(_) if self.strict => Err(err),
+                            Err(serde) => Err(Error::Deserialization(serde)),
+                        },
+                    }
+                });
+
+                // Requests stopped by dry-run or read-only mode never reached the API.
+                let sent = !matches!(result, Err(Error::DryRun(_) | Error::ReadOnlyMode { .. }));
+
+                if let Some((log, mut entry)) = audit.filter(|_| sent) {
+                    entry.error = result.as_ref().err().map(ToString::to_string);
+                    log.record(entry);
+                }
 
-            let body = match authenticated_request.body() {
-                None => Full::default(),
-                Some(value) => Full::from(value.to_owned()),
+                result
+            }
+            .instrument(span)
+            .await
+        }
+
+        /// Authenticate and send the request, returning the raw response
+        /// without attempting to deserialize it.
+        ///
+        /// If the credentials are rejected and a [`CredentialsProvider`] is configured,
+        /// the credentials are refreshed and the request is retried once.
+        pub(crate) async fn send<Response>(
+            &self,
+            request: UnauthenticatedRequest<Response>,
+        ) -> Result<hyper::Response<Bytes>, Error> {
+            if self.dry_run && request.method() != "GET" {
+                let url = match &self.base_url {
+                    Some(base_url) => rebase(request.uri(), base_url)?,
+                    None => request.uri().clone(),
+                };
+
+                return Err(Error::DryRun(RenderedRequest {
+                    method: request.method().to_string(),
+                    url: url.to_string(),
+                    body: request.body().map(str::to_owned),
+                }));
+            }
+
+            if self.read_only && request.method() != "GET" {
+                return Err(Error::ReadOnlyMode {
+                    method: request.method().to_string(),
+                    endpoint: request.endpoint().name,
+                });
+            }
+
+            let (credentials, provider) = match &self.credentials {
+                CredentialSource::Static(credentials) => {
+                    return self
+                        .send_authenticated(request, &current(credentials))
+                        .await;
+                }
+                CredentialSource::RefreshOnReject(credentials, provider) => {
+                    (current(credentials), provider)
+                }
+                CredentialSource::PerRequest(provider) => (provider.credentials().await?, provider),
             };
 
-            let request = hyper::Request::builder()
-                .uri(authenticated_request.uri())
+            let response = self
+                .send_authenticated(request.clone(), &credentials)
+                .await?;
+            if response.status() != StatusCode::UNAUTHORIZED {
+                return Ok(response);
+            }
+
+            let credentials = provider.credentials().await?;
+            if let CredentialSource::RefreshOnReject(shared, _) = &self.credentials {
+                *shared
+                    .write()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()) = credentials.clone();
+            }
+
+            if let Some(observer) = &self.observer {
+                observer.notify(&RobotEvent::CredentialsRefreshed {
+                    username: credentials.username().to_string(),
+                });
+            }
+
+            self.send_authenticated(request, &credentials).await
+        }
+
+        /// Authenticate the request using `credentials` and send it.
+        async fn send_authenticated<Response>(
+            &self,
+            request: UnauthenticatedRequest<Response>,
+            credentials: &Credentials,
+        ) -> Result<hyper::Response<Bytes>, Error> {
+            if let Some(limiter) = &self.limiter {
+                limiter.acquire().await;
+            }
+
+            if let Some(pacer) = &self.pacer {
+                pacer.wait(credentials.username()).await;
+            }
+
+            let authenticated_request = request.authenticate(credentials);
+
+            let mut authorization =
+                HeaderValue::from_str(authenticated_request.authorization_header())
+                    .map_err(Error::transport)?;
+            authorization.set_sensitive(true);
+
+            let uri = match &self.base_url {
+                Some(base_url) => rebase(authenticated_request.uri(), base_url)?,
+                None => authenticated_request.uri().clone(),
+            };
+
+            let mut request = Request::builder()
+                .uri(uri)
                 .method(authenticated_request.method())
-                .header(
-                    "Authorization",
-                    authenticated_request.authorization_header(),
-                )
+                .header("Authorization", authorization)
                 .header("Content-Type", "application/x-www-form-urlencoded")
-                .header("Accept", "application/json")
-                .body(body)
+                .header("Accept", "application/json");
+
+            if let Some(user_agent) = &self.user_agent {
+                request = request.header(USER_AGENT, user_agent);
+            }
+
+            let request = request
+                .body(
+                    authenticated_request
+                        .body()
+                        .map(str::to_owned)
+                        .unwrap_or_default(),
+                )
                 .map_err(Error::transport)?;
 
-            let response = self
-                .client
-                .request(request)
+            let response = self.client.send_request(request);
+
+            match self.timeout {
+                Some(timeout) => tokio::time::timeout(timeout, response)
+                    .await
+                    .map_err(Error::transport)?,
+                None => response.await,
+            }
+        }
+    }
+
+    /// Replace the scheme and authority of `uri` with those of `base_url`,
+    /// prefixing the path with the path of `base_url`, if any.
+    fn rebase(uri: &Uri, base_url: &Uri) -> Result<Uri, Error> {
+        let path = uri.path_and_query().map_or("/", PathAndQuery::as_str);
+
+        format!("{}{path}", base_url.to_string().trim_end_matches('/'))
+            .parse()
+            .map_err(Error::transport)
+    }
+
+    /// Request which would have been sent, had [`AsyncRobot::dry_run`] not been enabled.
+    ///
+    /// Displayed as the method and URL, followed by the body if any, with the values of
+    /// sensitive fields such as passwords masked. The [`body`](RenderedRequest::body)
+    /// field itself is not masked.
+    #[derive(Clone, PartialEq, Eq)]
+    pub struct RenderedRequest {
+        /// HTTP method, e.g. `POST`.
+        pub method: String,
+        /// Complete URL, including the query string if any.
+        pub url: String,
+        /// `application/x-www-form-urlencoded` body, exactly as it would have been sent.
+        pub body: Option<String>,
+    }
+
+    impl RenderedRequest {
+        /// Body with the values of sensitive fields masked.
+        pub fn redacted_body(&self) -> Option<String> {
+            self.body.as_deref().map(redact_form)
+        }
+    }
+
+    impl std::fmt::Debug for RenderedRequest {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("RenderedRequest")
+                .field("method", &self.method)
+                .field("url", &self.url)
+                .field("body", &self.redacted_body())
+                .finish()
+        }
+    }
+
+    impl std::fmt::Display for RenderedRequest {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{} {}", self.method, self.url)?;
+
+            match self.redacted_body() {
+                Some(body) if !body.is_empty() => write!(f, "\n\n{body}"),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Error produced when an [`AsyncRobotBuilder`] is misconfigured.
+    #[derive(Debug, thiserror::Error)]
+    pub enum BuilderError {
+        /// No credentials were provided, and they could not be read
+        /// from the `HROBOT_USERNAME` and `HROBOT_PASSWORD` environment variables.
+        #[error("missing credentials: {0}")]
+        MissingCredentials(#[from] std::env::VarError),
+
+        /// The base URL is not an absolute URL.
+        #[error("invalid base url: {0}")]
+        InvalidBaseUrl(String),
+
+        /// The User-Agent contains characters not allowed in a header value.
+        #[error("invalid user agent: {0}")]
+        InvalidUserAgent(#[from] InvalidHeaderValue),
+
+        /// The proxy URL could not be parsed.
+        #[error("invalid proxy url: {0}")]
+        InvalidProxy(String),
+
+        /// A proxy was configured along with a custom client. Proxies
+        /// for custom clients must be configured on the client itself.
+        #[error("proxies can only be configured for the default client")]
+        ProxyUnsupported,
+    }
+
+    /// Builder for an [`AsyncRobot`], constructed using [`AsyncRobot::builder`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use hrobot::api::Credentials;
+    /// let robot = hrobot::AsyncRobot::builder()
+    ///     .credentials(Credentials::new("#ws+username", "p@ssw0rd"))
+    ///     .timeout(Duration::from_secs(30))
+    ///     .user_agent("inventory-sync/1.0")
+    ///     .proxy("http://proxy.example.com:3128")
+    ///     .pool_idle_timeout(Duration::from_secs(60))
+    ///     .pool_max_idle_per_host(4)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[derive(Debug, Default)]
+    pub struct AsyncRobotBuilder {
+        credentials: Option<Credentials>,
+        provider: Option<Arc<dyn CredentialsProvider>>,
+        base_url: Option<String>,
+        timeout: Option<Duration>,
+        user_agent: Option<String>,
+        proxy: Option<String>,
+        #[cfg(any(feature = "hyper-client", feature = "reqwest-client"))]
+        pool: PoolConfig,
+        read_only: bool,
+        dry_run: bool,
+    }
+
+    impl AsyncRobotBuilder {
+        /// Credentials to authenticate with.
+        ///
+        /// Defaults to the `HROBOT_USERNAME` and `HROBOT_PASSWORD` environment variables.
+        pub fn credentials(mut self, credentials: Credentials) -> Self {
+            self.credentials = Some(credentials);
+            self
+        }
+
+        /// Fetch credentials from `provider` before every request,
+        /// see [`AsyncRobot::from_credentials_provider`].
+        ///
+        /// Takes precedence over [`AsyncRobotBuilder::credentials`].
+        pub fn credentials_provider(
+            mut self,
+            provider: impl CredentialsProvider + 'static,
+        ) -> Self {
+            self.provider = Some(Arc::new(provider));
+            self
+        }
+
+        /// Send requests to `base_url` instead of `https://robot-ws.your-server.de`,
+        /// for example a mock server or a reverse proxy.
+        pub fn base_url(mut self, base_url: &str) -> Self {
+            self.base_url = Some(base_url.to_string());
+            self
+        }
+
+        /// Default timeout for all requests.
+        ///
+        /// See [`AsyncRobot::with_timeout`] for overriding it for individual requests.
+        pub fn timeout(mut self, timeout: Duration) -> Self {
+            self.timeout = Some(timeout);
+            self
+        }
+
+        /// Value of the `User-Agent` header sent with every request.
+        pub fn user_agent(mut self, user_agent: &str) -> Self {
+            self.user_agent = Some(user_agent.to_string());
+            self
+        }
+
+        /// Refuse to send requests which could modify the account.
+        ///
+        /// See [`AsyncRobot::read_only`].
+        pub fn read_only(mut self, read_only: bool) -> Self {
+            self.read_only = read_only;
+            self
+        }
+
+        /// Render requests which could modify the account instead of sending them.
+        ///
+        /// See [`AsyncRobot::dry_run`].
+        pub fn dry_run(mut self, dry_run: bool) -> Self {
+            self.dry_run = dry_run;
+            self
+        }
+
+        /// Connect to the API through the HTTP proxy at `proxy`, e.g. `http://proxy.example.com:3128`.
+        ///
+        /// Only supported when building with the default client.
+        pub fn proxy(mut self, proxy: &str) -> Self {
+            self.proxy = Some(proxy.to_string());
+            self
+        }
+
+        /// Close pooled connections which have been idle for longer than `timeout`.
+        ///
+        /// Only applies to the default client, custom clients manage their own pool.
+        #[cfg(any(feature = "hyper-client", feature = "reqwest-client"))]
+        pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+            self.pool.idle_timeout = Some(timeout);
+            self
+        }
+
+        /// Keep at most `max` idle connections to the API in the pool.
+        ///
+        /// Setting it to zero disables connection reuse entirely.
+        /// Only applies to the default client, custom clients manage their own pool.
+        #[cfg(any(feature = "hyper-client", feature = "reqwest-client"))]
+        pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+            self.pool.max_idle_per_host = Some(max);
+            self
+        }
+
+        /// Construct the [`AsyncRobot`], using the default client.
+        ///
+        /// The default client is based on [`hyper_util`] if the `hyper-client` feature
+        /// is enabled, otherwise `reqwest` is used.
+        #[cfg(any(feature = "hyper-client", feature = "reqwest-client"))]
+        pub fn build(mut self) -> Result<AsyncRobot, BuilderError> {
+            match self.proxy.take() {
+                Some(proxy) => {
+                    let proxy = proxy
+                        .parse::<Uri>()
+                        .map_err(|err| BuilderError::InvalidProxy(err.to_string()))?;
+
+                    let client = proxied_client(proxy.clone(), self.pool)?;
+                    Ok(AsyncRobot {
+                        proxy: Some(proxy),
+                        ..self.build_with_client(client)?
+                    })
+                }
+                None => {
+                    let client = default_client(self.pool);
+                    self.build_with_client(client)
+                }
+            }
+        }
+
+        /// Construct the [`AsyncRobot`], using the given client.
+        ///
+        /// Fails with [`BuilderError::ProxyUnsupported`] if a proxy is configured,
+        /// since the proxy must be configured on the client itself.
+        pub fn build_with_client(
+            self,
+            client: impl AsyncHttpClient + 'static,
+        ) -> Result<AsyncRobot, BuilderError> {
+            if self.proxy.is_some() {
+                return Err(BuilderError::ProxyUnsupported);
+            }
+
+            let credentials = match (self.credentials, self.provider) {
+                (_, Some(provider)) => CredentialSource::PerRequest(provider),
+                (Some(credentials), None) => CredentialSource::fixed(credentials),
+                (None, None) => {
+                    CredentialSource::fixed(Credentials::from_env_with_prefix("HROBOT_")?)
+                }
+            };
+
+            let base_url = self
+                .base_url
+                .map(|base_url| {
+                    base_url
+                        .parse::<Uri>()
+                        .ok()
+                        .filter(|uri| uri.scheme().is_some() && uri.authority().is_some())
+                        .ok_or(BuilderError::InvalidBaseUrl(base_url))
+                })
+                .transpose()?;
+
+            let user_agent = self
+                .user_agent
+                .map(|user_agent| HeaderValue::from_str(&user_agent))
+                .transpose()?;
+
+            Ok(AsyncRobot {
+                base_url,
+                timeout: self.timeout,
+                user_agent,
+                read_only: self.read_only,
+                dry_run: self.dry_run,
+                ..AsyncRobot::from_source(client, credentials)
+            })
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::{
+            sync::{Arc, Mutex},
+            time::Duration,
+        };
+
+        use hyper::{
+            header::{RETRY_AFTER, USER_AGENT},
+            StatusCode,
+        };
+
+        use crate::{
+            api::{server::ServerId, storagebox::StorageBoxId, Credentials},
+            error::{ApiError, Error},
+            mock::{MockClient, Reply},
+        };
+
+        use super::{
+            current, AsyncRobot, BuilderError, CredentialSource, CredentialsFuture,
+            CredentialsProvider, RobotEvent, RobotObserver,
+        };
+
+        /// Only accepts the credentials `#ws+username:rotated`.
+        fn rotated_client() -> MockClient {
+            MockClient::new().on_with("*", "*", |request| {
+                if request.headers()["Authorization"] == "Basic I3dzK3VzZXJuYW1lOnJvdGF0ZWQ=" {
+                    Reply::ok("[]")
+                } else {
+                    Reply::status(StatusCode::UNAUTHORIZED, "")
+                }
+            })
+        }
+
+        #[derive(Debug)]
+        struct RotatedProvider;
+
+        impl CredentialsProvider for RotatedProvider {
+            fn credentials(&self) -> CredentialsFuture<'_> {
+                Box::pin(async { Ok(Credentials::new("#ws+username", "rotated")) })
+            }
+        }
+
+        #[derive(Debug, Default)]
+        struct RecordingObserver(Arc<Mutex<Vec<RobotEvent>>>);
+
+        impl RobotObserver for RecordingObserver {
+            fn notify(&self, event: &RobotEvent) {
+                self.0.lock().unwrap().push(event.clone());
+            }
+        }
+
+        #[tokio::test]
+        async fn refresh_rejected_credentials() {
+            let events = Arc::new(Mutex::new(Vec::new()));
+
+            let robot = AsyncRobot::new(rotated_client(), "#ws+username", "expired")
+                .with_credentials_provider(RotatedProvider)
+                .with_observer(RecordingObserver(events.clone()));
+
+            assert!(robot.list_servers().await.unwrap().is_empty());
+            assert!(robot.clone().list_servers().await.unwrap().is_empty());
+
+            assert_eq!(
+                *events.lock().unwrap(),
+                vec![RobotEvent::CredentialsRefreshed {
+                    username: "#ws+username".to_string()
+                }]
+            );
+        }
+
+        /// Counts how often credentials are fetched.
+        #[derive(Debug, Default)]
+        struct CountingProvider(Arc<Mutex<usize>>);
+
+        impl CredentialsProvider for CountingProvider {
+            fn credentials(&self) -> CredentialsFuture<'_> {
+                *self.0.lock().unwrap() += 1;
+                RotatedProvider.credentials()
+            }
+        }
+
+        #[tokio::test]
+        async fn credentials_per_request() {
+            let events = Arc::new(Mutex::new(Vec::new()));
+            let fetched = Arc::new(Mutex::new(0));
+
+            let robot = AsyncRobot::from_credentials_provider(
+                rotated_client(),
+                CountingProvider(fetched.clone()),
+            )
+            .with_observer(RecordingObserver(events.clone()));
+
+            assert!(robot.list_servers().await.unwrap().is_empty());
+            assert!(robot.clone().list_servers().await.unwrap().is_empty());
+            assert_eq!(*fetched.lock().unwrap(), 2);
+            assert!(events.lock().unwrap().is_empty());
+
+            let robot = AsyncRobot::builder()
+                .credentials_provider(Credentials::new("#ws+username", "rotated"))
+                .build_with_client(rotated_client())
+                .unwrap();
+            assert!(robot.list_servers().await.unwrap().is_empty());
+        }
+
+        #[tokio::test(start_paused = true)]
+        async fn paced_by_fetched_username() {
+            let robot = |username| {
+                AsyncRobot::from_credentials_provider(
+                    MockClient::new().on("GET", "/server", "[]"),
+                    Credentials::new(username, "p@ssw0rd"),
+                )
+                .paced(Duration::from_secs(10))
+            };
+
+            let start = tokio::time::Instant::now();
+            let _ = robot("#ws+provided").list_servers().await.unwrap();
+            let _ = robot("#ws+other").list_servers().await.unwrap();
+            assert_eq!(start.elapsed(), Duration::ZERO);
+
+            let _ = robot("#ws+provided").list_servers().await.unwrap();
+            assert!(start.elapsed() >= Duration::from_secs(10));
+        }
+
+        #[tokio::test]
+        async fn api_error_context() {
+            let robot = MockClient::new()
+                .on("*", "*", Reply::rate_limited().header(RETRY_AFTER, "120"))
+                .robot();
+
+            let err = robot.get_server(ServerId(1234567)).await.unwrap_err();
+
+            assert!(matches!(
+                err.api_error(),
+                Some(ApiError::RateLimitExceeded { .. })
+            ));
+            assert_eq!(err.status(), Some(StatusCode::FORBIDDEN));
+            assert_eq!(err.retry_after(), Some(Duration::from_secs(120)));
+        }
+
+        #[tokio::test]
+        async fn credentials_and_permissions() {
+            let forbidden =
+                r#"{"error":{"status":403,"code":"FORBIDDEN","message":"Permission denied"}}"#;
+
+            let err = MockClient::new()
+                .on("*", "*", Reply::status(StatusCode::FORBIDDEN, forbidden))
+                .robot()
+                .list_servers()
                 .await
-                .map_err(Error::transport)?;
+                .unwrap_err();
+            assert!(err.is_permission_denied());
+            assert!(!err.is_credentials_rejected());
+            assert_eq!(err.status(), Some(StatusCode::FORBIDDEN));
+            assert_eq!(err.hint(), None);
+
+            for (status, body) in [
+                (StatusCode::UNAUTHORIZED, ""),
+                (StatusCode::UNAUTHORIZED, forbidden),
+                (StatusCode::FORBIDDEN, ""),
+            ] {
+                let err = MockClient::new()
+                    .on("*", "*", Reply::status(status, body))
+                    .robot()
+                    .list_servers()
+                    .await
+                    .unwrap_err();
+                assert!(err.is_credentials_rejected(), "{status} {body}");
+                assert!(!err.is_permission_denied(), "{status} {body}");
+                assert_eq!(err.status(), Some(status));
+            }
+        }
+
+        /// Responds like the API does during maintenance windows.
+        fn maintenance_client(status: StatusCode) -> MockClient {
+            let reply = Reply::status(status, "<html><body><h1>Maintenance</h1></body></html>");
+
+            MockClient::new().on(
+                "*",
+                "*",
+                if status == StatusCode::SERVICE_UNAVAILABLE {
+                    reply.header(RETRY_AFTER, "300")
+                } else {
+                    reply
+                },
+            )
+        }
+
+        #[tokio::test]
+        async fn maintenance_responses() {
+            let robot = maintenance_client(StatusCode::SERVICE_UNAVAILABLE).robot();
 
-            let body = response
-                .into_body()
-                .collect()
+            let err = robot.list_servers().await.unwrap_err();
+            assert!(matches!(
+                err,
+                Error::Unavailable {
+                    retry_after: Some(delay)
+                } if delay == Duration::from_secs(300)
+            ));
+            assert_eq!(err.retry_after(), Some(Duration::from_secs(300)));
+
+            for strict in [false, true] {
+                let robot = maintenance_client(StatusCode::OK)
+                    .robot()
+                    .strict_models(strict);
+
+                assert!(matches!(
+                    robot.delete_firewall_template(1234).await,
+                    Err(Error::Unavailable { retry_after: None })
+                ));
+            }
+        }
+
+        /// Responds to every request with an error the endpoint does not document.
+        fn snapshot_not_found_client() -> MockClient {
+            MockClient::new().on(
+                "*",
+                "*",
+                Reply::error(
+                    StatusCode::NOT_FOUND,
+                    "SNAPSHOT_NOT_FOUND",
+                    "Snapshot not found",
+                ),
+            )
+        }
+
+        #[tokio::test]
+        async fn unexpected_api_error_hint() {
+            let robot = snapshot_not_found_client().robot();
+
+            let err = robot.get_firewall(ServerId(1234567)).await.unwrap_err();
+
+            assert!(matches!(
+                err.api_error(),
+                Some(ApiError::SnapshotNotFound { .. })
+            ));
+            assert!(err
+                .hint()
+                .is_some_and(|hint| hint.contains("possible routing/ID mixup")));
+            assert!(err.to_string().ends_with("possible routing/ID mixup)"));
+        }
+
+        #[tokio::test]
+        async fn empty_delete_responses() {
+            for strict in [false, true] {
+                let robot = MockClient::new()
+                    .on("*", "*", "")
+                    .robot()
+                    .strict_models(strict);
+
+                robot.delete_firewall_template(1234).await.unwrap();
+                robot
+                    .delete_snapshot(1234, time::macros::datetime!(2015-12-21 13:13:03 UTC))
+                    .await
+                    .unwrap();
+                robot.cancel_vswitch(1234, None).await.unwrap();
+            }
+
+            let err = snapshot_not_found_client()
+                .robot()
+                .delete_snapshot(1234, time::macros::datetime!(2015-12-21 13:13:03 UTC))
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err.api_error(),
+                Some(ApiError::SnapshotNotFound { .. })
+            ));
+        }
+
+        /// Serves empty lists under `/robot`, and never responds to requests for server 1234567.
+        fn server_list_client() -> MockClient {
+            MockClient::new()
+                .on("GET", "/robot/server/1234567", Reply::pending())
+                .on("GET", "*", "[]")
+        }
+
+        #[tokio::test(start_paused = true)]
+        async fn builder() {
+            let client = server_list_client();
+            let requests = client.requests();
+
+            let robot = AsyncRobot::builder()
+                .credentials(Credentials::new("#ws+username", "p@ssw0rd"))
+                .base_url("http://localhost:8080/robot/")
+                .user_agent("inventory-sync/1.0")
+                .timeout(Duration::from_secs(10))
+                .build_with_client(client)
+                .unwrap();
+
+            assert!(robot.list_servers().await.unwrap().is_empty());
+            let (uri, user_agent) = requests
+                .map(|request| (request.uri().clone(), request.headers()[USER_AGENT].clone()))
+                .remove(0);
+            assert_eq!(uri, "http://localhost:8080/robot/server");
+            assert_eq!(user_agent, "inventory-sync/1.0");
+
+            let err = robot
+                .clone()
+                .with_timeout(Duration::from_secs(60))
+                .get_server(ServerId(1234567))
                 .await
-                .map_err(Error::transport)?
-                .to_bytes();
-
-            let stringified = String::from_utf8_lossy(&body);
-            trace!("response body: {stringified}");
-
-            // We do explicit deserialization here, since some endpoints can return empty responses.
-            //
-            // I initialize used a #[derive(Serialize, Deserialize)] enum which encapsulated both success and
-            // error states, but deserializing an untagged enum, even when the encapsulated "Ok"
-            // result is just a unit type (), deserialization will fail on empty input.
-            if let Ok(result) = serde_json::from_str::<Response>(&stringified) {
-                Ok(result)
-            } else {
-                match serde_json::from_str::<MaybeTypedResponse>(&stringified) {
-                    Ok(api_error) => Err(Error::Api(api_error.error.into())),
-                    Err(serde) => Err(Error::Deserialization(serde)),
+                .unwrap_err();
+            assert!(matches!(err, Error::Transport(_)));
+
+            assert!(matches!(
+                AsyncRobot::builder()
+                    .credentials(Credentials::new("#ws+username", "p@ssw0rd"))
+                    .proxy("http://proxy.example.com:3128")
+                    .build_with_client(MockClient::new()),
+                Err(BuilderError::ProxyUnsupported)
+            ));
+
+            assert!(matches!(
+                AsyncRobot::builder()
+                    .credentials(Credentials::new("#ws+username", "p@ssw0rd"))
+                    .base_url("/robot")
+                    .build_with_client(MockClient::new()),
+                Err(BuilderError::InvalidBaseUrl(_))
+            ));
+        }
+
+        #[cfg(any(feature = "hyper-client", feature = "reqwest-client"))]
+        #[tokio::test]
+        async fn proxied_default_client() {
+            let robot = AsyncRobot::builder()
+                .credentials(Credentials::new("#ws+username", "p@ssw0rd"))
+                .proxy("http://proxy.example.com:3128")
+                .build()
+                .unwrap();
+
+            assert_eq!(
+                robot.proxy().map(ToString::to_string).as_deref(),
+                Some("http://proxy.example.com:3128/")
+            );
+        }
+
+        #[cfg(feature = "hyper-client")]
+        #[test]
+        fn pooled_default_client() {
+            use super::PoolConfig;
+
+            let builder = AsyncRobot::builder()
+                .pool_idle_timeout(Duration::from_secs(30))
+                .pool_max_idle_per_host(0);
+            assert_eq!(
+                builder.pool,
+                PoolConfig {
+                    idle_timeout: Some(Duration::from_secs(30)),
+                    max_idle_per_host: Some(0),
                 }
-            }
+            );
+
+            // hyper_util only exposes its pool settings through Debug.
+            let pool_config = |pool: PoolConfig| {
+                let client_builder = format!("{:?}", pool.client_builder());
+                client_builder
+                    .split_once("pool_config: ")
+                    .unwrap()
+                    .1
+                    .to_string()
+            };
+
+            assert_eq!(
+                pool_config(builder.pool),
+                "Config { idle_timeout: Some(30s), max_idle_per_host: 0 } }"
+            );
+
+            // Unset values keep the defaults of the underlying client.
+            let builder = AsyncRobot::builder().pool_max_idle_per_host(2);
+            assert_eq!(
+                pool_config(builder.pool),
+                "Config { idle_timeout: Some(90s), max_idle_per_host: 2 } }"
+            );
+        }
+
+        #[test]
+        fn prefixed_environment() {
+            std::env::set_var("HROBOT_PREFIX_TEST_USERNAME", "#ws+prefixed");
+            std::env::set_var("HROBOT_PREFIX_TEST_PASSWORD", "p@ssw0rd");
+
+            let robot =
+                AsyncRobot::from_env_with_prefix(MockClient::new(), "HROBOT_PREFIX_TEST_").unwrap();
+            let CredentialSource::Static(credentials) = &robot.credentials else {
+                panic!("environment credentials should be static");
+            };
+            assert_eq!(current(credentials).username(), "#ws+prefixed");
+
+            assert!(matches!(
+                AsyncRobot::from_env_with_prefix(MockClient::new(), "HROBOT_UNSET_"),
+                Err(std::env::VarError::NotPresent)
+            ));
+        }
+
+        #[tokio::test]
+        async fn read_only() {
+            let client = server_list_client();
+            let requests = client.requests();
+
+            let robot = AsyncRobot::builder()
+                .credentials(Credentials::new("#ws+username", "p@ssw0rd"))
+                .read_only(true)
+                .build_with_client(client)
+                .unwrap();
+
+            assert!(robot.list_servers().await.unwrap().is_empty());
+
+            let err = robot
+                .rename_server(ServerId(123), "gateway")
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                Error::ReadOnlyMode { method, endpoint }
+                    if method == "POST" && endpoint == "/server/{server_id}"
+            ));
+
+            assert_eq!(requests.len(), 1);
+        }
+
+        #[tokio::test]
+        async fn dry_run() {
+            let client = server_list_client();
+            let requests = client.requests();
+
+            let robot = AsyncRobot::builder()
+                .credentials(Credentials::new("#ws+username", "p@ssw0rd"))
+                .base_url("http://localhost:8080/robot")
+                .dry_run(true)
+                .read_only(true)
+                .build_with_client(client)
+                .unwrap();
+
+            assert!(robot.list_servers().await.unwrap().is_empty());
+
+            let Error::DryRun(request) = robot
+                .rename_server(ServerId(123), "gateway")
+                .await
+                .unwrap_err()
+            else {
+                panic!("expected dry run error");
+            };
+
+            assert_eq!(request.method, "POST");
+            assert_eq!(request.url, "http://localhost:8080/robot/server/123");
+            assert_eq!(request.body.as_deref(), Some("server_name=gateway"));
+            assert_eq!(
+                request.to_string(),
+                "POST http://localhost:8080/robot/server/123\n\nserver_name=gateway"
+            );
+
+            let Error::DryRun(request) = robot
+                .reset_storagebox_password(StorageBoxId(123))
+                .await
+                .unwrap_err()
+            else {
+                panic!("expected dry run error");
+            };
+            assert_eq!(
+                request.to_string(),
+                "POST http://localhost:8080/robot/storagebox/123/password"
+            );
+
+            assert_eq!(requests.len(), 1);
         }
     }
 }
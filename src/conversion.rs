@@ -11,6 +11,14 @@ use serde::{de::Error, Deserialize, Deserializer};
 use time::{macros::format_description, OffsetDateTime, PrimitiveDateTime};
 use time_tz::PrimitiveDateTimeExt;
 
+#[cfg(any(feature = "ip", feature = "vswitch"))]
+use std::net::IpAddr;
+
+#[cfg(any(feature = "ip", feature = "vswitch"))]
+use ipnet::IpNet;
+#[cfg(feature = "ordering")]
+use rust_decimal::Decimal;
+
 /// Deserializes a null value as the default instantiation of itself instead.
 ///
 /// Useful when the API might return null for empty arrays.
@@ -30,15 +38,27 @@ pub(crate) fn assume_berlin_timezone<'de, D: Deserializer<'de>>(
 ) -> Result<OffsetDateTime, D::Error> {
     let datetime = <&str>::deserialize(deserializer)?;
 
-    Ok(PrimitiveDateTime::parse(
+    PrimitiveDateTime::parse(
         datetime,
         &format_description!("[year]-[month]-[day] [hour]:[minute]:[second]"),
     )
     .map_err(D::Error::custom)?
     .assume_timezone(time_tz::timezones::db::europe::BERLIN)
-    .unwrap())
+    // Ambiguous times occur when clocks are turned back, and both are equally likely.
+    .take_first()
+    .ok_or_else(|| D::Error::custom(format!("{datetime} does not exist in Europe/Berlin")))
+}
+
+/// Construct a network from the address and prefix length of an API response.
+#[cfg(any(feature = "ip", feature = "vswitch"))]
+pub(crate) fn network(ip: IpAddr, mask: u8) -> Result<IpNet, crate::error::Error> {
+    IpNet::new(ip, mask).map_err(|_| crate::error::Error::UnexpectedResponseShape {
+        expected: format!("a valid prefix length for {ip}"),
+        got: format!("/{mask}"),
+    })
 }
 
+#[cfg(feature = "storagebox")]
 pub(crate) mod weekday_plus_one {
     use serde::{Deserialize, Deserializer, Serializer};
     use time::Weekday;
@@ -66,63 +86,218 @@ pub(crate) mod weekday_plus_one {
     }
 }
 
-use std::str::FromStr;
-
-pub use bytesize::ByteSize;
-use bytesize::GIB;
+/// Dates such as cancellation dates, which the API usually formats as `YYYY-MM-DD`,
+/// but some endpoints return as `YYYY-MM-DD HH:MM:SS` or RFC 3339 timestamps.
+///
+/// The time of day of timestamps is discarded, keeping the date as given by the API.
+/// Dates are always serialized as `YYYY-MM-DD`.
+pub(crate) mod date {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+    use time::{
+        format_description::well_known::Rfc3339, macros::format_description, Date, OffsetDateTime,
+        PrimitiveDateTime,
+    };
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Date, D::Error> {
+        let value = String::deserialize(deserializer)?;
+
+        parse(&value).ok_or_else(|| {
+            D::Error::invalid_value(
+                serde::de::Unexpected::Str(&value),
+                &"a date formatted as YYYY-MM-DD",
+            )
+        })
+    }
 
-pub(crate) fn traffic<'de, D: Deserializer<'de>>(
-    deserializer: D,
-) -> Result<Option<ByteSize>, D::Error> {
-    let traffic = <&str>::deserialize(deserializer)?;
+    pub fn serialize<S: Serializer>(date: &Date, serializer: S) -> Result<S::Ok, S::Error> {
+        date.serialize(serializer)
+    }
 
-    if traffic == "unlimited" {
-        Ok(None)
-    } else {
-        ByteSize::from_str(traffic)
-            .map_err(D::Error::custom)
-            .map(Some)
+    fn parse(value: &str) -> Option<Date> {
+        let value = value.trim();
+
+        Date::parse(value, &format_description!("[year]-[month]-[day]"))
+            .ok()
+            .or_else(|| {
+                PrimitiveDateTime::parse(
+                    value,
+                    &format_description!("[year]-[month]-[day] [hour]:[minute]:[second]"),
+                )
+                .ok()
+                .map(|datetime| datetime.date())
+            })
+            .or_else(|| {
+                OffsetDateTime::parse(value, &Rfc3339)
+                    .ok()
+                    .map(|datetime| datetime.date())
+            })
     }
 }
 
+pub use bytesize::ByteSize;
+
+#[cfg(any(feature = "ip", feature = "storagebox"))]
 pub(crate) mod mib {
-    use bytesize::{ByteSize, MIB};
+    use bytesize::ByteSize;
     use serde::{Deserialize, Deserializer, Serializer};
 
+    use crate::units::{bytesize_to_mib, mib_to_bytesize};
+
     pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ByteSize, D::Error> {
-        u64::deserialize(deserializer).map(ByteSize::mib)
+        u64::deserialize(deserializer).map(mib_to_bytesize)
     }
 
     pub fn serialize<S>(bytesize: &ByteSize, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_u64(bytesize.as_u64() / MIB)
+        serializer.serialize_u64(bytesize_to_mib(*bytesize))
     }
 }
 
+#[cfg(feature = "ip")]
 pub(crate) mod gib {
-    use bytesize::{ByteSize, GIB};
+    use bytesize::ByteSize;
     use serde::{Deserialize, Deserializer, Serializer};
 
+    use crate::units::{bytesize_to_gib, gib_to_bytesize};
+
     pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ByteSize, D::Error> {
-        u64::deserialize(deserializer).map(ByteSize::gib)
+        u64::deserialize(deserializer).map(gib_to_bytesize)
     }
 
     pub fn serialize<S>(bytesize: &ByteSize, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_u64(bytesize.as_u64() / GIB)
+        serializer.serialize_u64(bytesize_to_gib(*bytesize))
     }
 }
 
 pub(crate) fn gib_float<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ByteSize, D::Error> {
-    f64::deserialize(deserializer).map(|gb| ByteSize::b((gb * GIB as f64) as u64))
+    f64::deserialize(deserializer).map(crate::units::gib_float_to_bytesize)
 }
 
+#[cfg(feature = "ordering")]
 pub(crate) fn gb<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ByteSize, D::Error> {
-    u64::deserialize(deserializer).map(ByteSize::gb)
+    u64::deserialize(deserializer).map(crate::units::gb_to_bytesize)
+}
+
+/// Deserializes booleans which the API represents inconsistently across endpoints,
+/// accepting `true`/`false`, `"true"`/`"false"`, `0`/`1` and `"0"`/`"1"`.
+pub(crate) fn permissive_bool<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<bool, D::Error> {
+    struct PermissiveBool;
+
+    impl serde::de::Visitor<'_> for PermissiveBool {
+        type Value = bool;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str(
+                "a boolean, 0 or 1, or one of the strings \"true\", \"false\", \"0\" or \"1\"",
+            )
+        }
+
+        fn visit_bool<E: Error>(self, value: bool) -> Result<bool, E> {
+            Ok(value)
+        }
+
+        fn visit_u64<E: Error>(self, value: u64) -> Result<bool, E> {
+            match value {
+                0 => Ok(false),
+                1 => Ok(true),
+                _ => Err(E::invalid_value(
+                    serde::de::Unexpected::Unsigned(value),
+                    &self,
+                )),
+            }
+        }
+
+        fn visit_i64<E: Error>(self, value: i64) -> Result<bool, E> {
+            match u64::try_from(value) {
+                Ok(value) => self.visit_u64(value),
+                Err(_) => Err(E::invalid_value(
+                    serde::de::Unexpected::Signed(value),
+                    &self,
+                )),
+            }
+        }
+
+        fn visit_str<E: Error>(self, value: &str) -> Result<bool, E> {
+            match value {
+                "true" | "1" => Ok(true),
+                "false" | "0" => Ok(false),
+                _ => Err(E::invalid_value(serde::de::Unexpected::Str(value), &self)),
+            }
+        }
+    }
+
+    deserializer.deserialize_any(PermissiveBool)
+}
+
+/// Deserializes prices, which the API usually represents as strings like `"15.1261"`,
+/// tolerating surrounding whitespace, plain numbers, missing decimals (`"39"`, `"39."`, `".5"`),
+/// and German formatting such as `"39,00"` or `"1.234,56"`.
+#[cfg(feature = "ordering")]
+pub(crate) fn price<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+    struct Price;
+
+    impl serde::de::Visitor<'_> for Price {
+        type Value = Decimal;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a price as a number or string")
+        }
+
+        fn visit_u64<E: Error>(self, value: u64) -> Result<Decimal, E> {
+            Ok(Decimal::from(value))
+        }
+
+        fn visit_i64<E: Error>(self, value: i64) -> Result<Decimal, E> {
+            Ok(Decimal::from(value))
+        }
+
+        fn visit_f64<E: Error>(self, value: f64) -> Result<Decimal, E> {
+            Decimal::try_from(value).map_err(E::custom)
+        }
+
+        fn visit_str<E: Error>(self, value: &str) -> Result<Decimal, E> {
+            parse_price(value)
+                .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(value), &self))
+        }
+    }
+
+    deserializer.deserialize_any(Price)
+}
+
+/// Parse a price string, see [`price`].
+#[cfg(feature = "ordering")]
+fn parse_price(value: &str) -> Option<Decimal> {
+    let value = value.trim();
+
+    // Whichever separator comes last is the decimal separator,
+    // any others are thousands separators.
+    let normalized = match (value.rfind('.'), value.rfind(',')) {
+        (Some(dot), Some(comma)) if comma > dot => value.replace('.', "").replace(',', "."),
+        (Some(_), Some(_)) => value.replace(',', ""),
+        (None, Some(_)) if value.matches(',').count() == 1 => value.replace(',', "."),
+        (None, Some(_)) => return None,
+        _ => value.to_string(),
+    };
+
+    let (sign, unsigned) = match normalized.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", normalized.as_str()),
+    };
+
+    let unsigned = unsigned.trim_end_matches('.');
+    if unsigned.is_empty() || unsigned.starts_with('+') {
+        return None;
+    }
+
+    let leading_zero = if unsigned.starts_with('.') { "0" } else { "" };
+    Decimal::from_str_exact(&format!("{sign}{leading_zero}{unsigned}")).ok()
 }
 
 #[cfg(test)]
@@ -148,7 +323,56 @@ mod tests {
                 timestamp: datetime!(2023-06-10 21:34:12 +02:00),
             },
             serde_json::from_str(container).unwrap()
-        )
+        );
+
+        // Clocks were turned back from 03:00 to 02:00, so 02:30 happened twice.
+        assert_eq!(
+            serde_json::from_str::<Container>(r#"{"timestamp": "2023-10-29 02:30:00"}"#)
+                .unwrap()
+                .timestamp,
+            datetime!(2023-10-29 02:30:00 +02:00)
+        );
+
+        // Clocks were turned forward from 02:00 to 03:00, so 02:30 never happened.
+        assert!(
+            serde_json::from_str::<Container>(r#"{"timestamp": "2023-03-26 02:30:00"}"#).is_err()
+        );
+    }
+
+    #[test]
+    fn deserialize_tolerant_date() {
+        #[derive(Debug, Serialize, Deserialize, PartialEq)]
+        struct Container {
+            #[serde(with = "super::date")]
+            date: Date,
+        }
+
+        let expected = Date::from_calendar_date(2024, Month::January, 31).unwrap();
+
+        for date in [
+            "2024-01-31",
+            " 2024-01-31 ",
+            "2024-01-31 23:30:00",
+            "2024-01-31T23:30:00+01:00",
+            "2024-01-31T00:00:00Z",
+        ] {
+            let container: Container =
+                serde_json::from_value(serde_json::json!({ "date": date })).unwrap();
+            assert_eq!(container.date, expected, "{date}");
+        }
+
+        for date in ["", "now", "31.01.2024", "2024-02-30", "2024-01-31 25:00:00"] {
+            assert!(
+                serde_json::from_value::<Container>(serde_json::json!({ "date": date })).is_err(),
+                "{date}"
+            );
+        }
+        assert!(serde_json::from_str::<Container>(r#"{"date": null}"#).is_err());
+
+        assert_eq!(
+            serde_json::to_string(&Container { date: expected }).unwrap(),
+            r#"{"date":"2024-01-31"}"#
+        );
     }
 
     #[test]
@@ -240,4 +464,100 @@ mod tests {
                 .unwrap()
         )
     }
+
+    #[test]
+    fn deserialize_permissive_bool() {
+        #[derive(Debug, Deserialize)]
+        struct Container {
+            #[serde(deserialize_with = "crate::conversion::permissive_bool")]
+            flag: bool,
+        }
+
+        for (json, expected) in [
+            (r#"{"flag":true}"#, true),
+            (r#"{"flag":false}"#, false),
+            (r#"{"flag":"true"}"#, true),
+            (r#"{"flag":"false"}"#, false),
+            (r#"{"flag":1}"#, true),
+            (r#"{"flag":0}"#, false),
+            (r#"{"flag":"1"}"#, true),
+            (r#"{"flag":"0"}"#, false),
+        ] {
+            assert_eq!(
+                serde_json::from_str::<Container>(json).unwrap().flag,
+                expected,
+                "{json}"
+            );
+        }
+
+        for json in [
+            r#"{"flag":2}"#,
+            r#"{"flag":-1}"#,
+            r#"{"flag":"yes"}"#,
+            r#"{"flag":null}"#,
+        ] {
+            assert!(serde_json::from_str::<Container>(json).is_err(), "{json}");
+        }
+    }
+
+    #[cfg(feature = "ordering")]
+    #[test]
+    fn deserialize_price() {
+        use rust_decimal::Decimal;
+
+        #[derive(Debug, Deserialize)]
+        struct Container {
+            #[serde(deserialize_with = "crate::conversion::price")]
+            price: Decimal,
+        }
+
+        let price = |json: &str| {
+            serde_json::from_str::<Container>(&format!(r#"{{"price":{json}}}"#)).map(|c| c.price)
+        };
+
+        // Price formats appearing in the API documentation.
+        for (json, expected) in [
+            (r#""0""#, "0"),
+            (r#""0.0014""#, "0.0014"),
+            (r#""0.0108""#, "0.0108"),
+            (r#""0.0242""#, "0.0242"),
+            (r#""0.1""#, "0.1"),
+            (r#""0.8403""#, "0.8403"),
+            (r#""6.7227""#, "6.7227"),
+            (r#""15.1261""#, "15.1261"),
+            (r#""19.0000""#, "19.0000"),
+            (r#""152.0000""#, "152.0000"),
+        ] {
+            assert_eq!(price(json).unwrap().to_string(), expected, "{json}");
+        }
+
+        // Formatting quirks.
+        for (json, expected) in [
+            (r#"" 39.00 ""#, "39.00"),
+            (r#""39""#, "39"),
+            (r#""39.""#, "39"),
+            (r#"".5""#, "0.5"),
+            (r#""-.5""#, "-0.5"),
+            (r#""39,00""#, "39.00"),
+            (r#""1.234,56""#, "1234.56"),
+            (r#""1,234.56""#, "1234.56"),
+            ("39", "39"),
+            ("39.5", "39.5"),
+        ] {
+            assert_eq!(price(json).unwrap().to_string(), expected, "{json}");
+        }
+
+        for json in [
+            r#""""#,
+            r#""  ""#,
+            r#"".""#,
+            r#""1,234,567""#,
+            r#""+5""#,
+            r#""EUR""#,
+            "null",
+            "true",
+        ] {
+            assert!(price(json).is_err(), "{json}");
+        }
+    }
 }
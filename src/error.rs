@@ -1,12 +1,28 @@
 //! Typed error handling for API responses.
 
-use std::fmt::Display;
+use std::{fmt::Display, net::IpAddr, time::Duration};
 
+use hyper::{header::RETRY_AFTER, HeaderMap, StatusCode};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::{
+    api::{
+        keys::Fingerprint,
+        reset::Reset,
+        server::{ReversalRefusal, ServerId},
+    },
+    RenderedRequest,
+};
+
+#[cfg(feature = "firewall")]
+use crate::api::firewall::ValidationError;
+#[cfg(feature = "storagebox")]
+use crate::api::storagebox::SnapshotName;
+
 /// Error returned by the Hetzner Robot API.
 #[derive(Debug, Serialize, Deserialize, Error)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(tag = "code", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ApiError {
     /// Resource Unavailable.
@@ -357,6 +373,17 @@ pub enum ApiError {
         message: String,
     },
 
+    /// Webservice user lacks permission for the endpoint.
+    ///
+    /// Returned with `403 Forbidden` when the credentials are valid, but the
+    /// permissions of the webservice user do not cover the requested resource.
+    /// Rejected credentials are instead reported as [`Error::Unauthorized`].
+    #[error("permission denied: {message}")]
+    Forbidden {
+        /// Human-readable message associated with the error.
+        message: String,
+    },
+
     /// Internal Error.
     #[error("internal error: {message}")]
     InternalError {
@@ -437,11 +464,85 @@ pub enum ApiError {
     /// Unknown/generic error.
     #[serde(skip_deserializing)]
     #[error("unknown error {0}")]
-    Generic(GenericError),
+    Generic(Box<GenericError>),
+}
+
+impl ApiError {
+    /// Error code as returned by the API, e.g. `"SERVER_NOT_FOUND"`.
+    pub fn code(&self) -> &str {
+        match self {
+            ApiError::Unavailable => "UNAVAILABLE",
+            ApiError::NotFound { .. } => "NOT_FOUND",
+            ApiError::ServerNotFound { .. } => "SERVER_NOT_FOUND",
+            ApiError::IpNotFound { .. } => "IP_NOT_FOUND",
+            ApiError::SubnetNotFound { .. } => "SUBNET_NOT_FOUND",
+            ApiError::MacNotFound { .. } => "MAC_NOT_FOUND",
+            ApiError::MacNotAvailable { .. } => "MAC_NOT_AVAILABLE",
+            ApiError::MacAlreadySet { .. } => "MAC_ALREADY_SET",
+            ApiError::MacFailed { .. } => "MAC_FAILED",
+            ApiError::WolNotAvailable { .. } => "WOL_NOT_AVAILABLE",
+            ApiError::WolFailed { .. } => "WOL_FAILED",
+            ApiError::WindowsOutdatedVersion { .. } => "WINDOWS_OUTDATED_VERSION",
+            ApiError::WindowsMissingAddon { .. } => "WINDOWS_MISSING_ADDON",
+            ApiError::PleskMissingAddon { .. } => "PLESK_MISSING_ADDON",
+            ApiError::CpanelMissingAddon { .. } => "CPANEL_MISSING_ADDON",
+            ApiError::RateLimitExceeded { .. } => "RATE_LIMIT_EXCEEDED",
+            ApiError::Forbidden { .. } => "FORBIDDEN",
+            ApiError::ResetNotAvailable { .. } => "RESET_NOT_AVAILABLE",
+            ApiError::StorageboxNotFound { .. } => "STORAGEBOX_NOT_FOUND",
+            ApiError::StorageboxSubaccountNotFound { .. } => "STORAGEBOX_SUBACCOUNT_NOT_FOUND",
+            ApiError::StorageboxSubaccountLimitExceeded { .. } => {
+                "STORAGEBOX_SUBACCOUNT_LIMIT_EXCEEDED"
+            }
+            ApiError::SnapshotNotFound { .. } => "SNAPSHOT_NOT_FOUND",
+            ApiError::SnapshotLimitExceeded { .. } => "SNAPSHOT_LIMIT_EXCEEDED",
+            ApiError::FirewallPortNotFound { .. } => "FIREWALL_PORT_NOT_FOUND",
+            ApiError::FirewallNotAvailable { .. } => "FIREWALL_NOT_AVAILABLE",
+            ApiError::FirewallTemplateNotFound { .. } => "FIREWALL_TEMPLATE_NOT_FOUND",
+            ApiError::FirewallInProcess { .. } => "FIREWALL_IN_PROCESS",
+            ApiError::VswitchLimitReached { .. } => "VSWITCH_LIMIT_REACHED",
+            ApiError::VswitchNotAvailable { .. } => "VSWITCH_NOT_AVAILABLE",
+            ApiError::VswitchServerLimitReached { .. } => "VSWITCH_SERVER_LIMIT_REACHED",
+            ApiError::VswitchPerServerLimitReached { .. } => "VSWITCH_PER_SERVER_LIMIT_REACHED",
+            ApiError::VswitchInProcess { .. } => "VSWITCH_IN_PROCESS",
+            ApiError::VswitchVlanNotUnique { .. } => "VSWITCH_VLAN_NOT_UNIQUE",
+            ApiError::ResetManualActive { .. } => "RESET_MANUAL_ACTIVE",
+            ApiError::KeyUpdateFailed { .. } => "KEY_UPDATE_FAILED",
+            ApiError::KeyCreateFailed { .. } => "KEY_CREATE_FAILED",
+            ApiError::KeyDeleteFailed { .. } => "KEY_DELETE_FAILED",
+            ApiError::KeyAlreadyExists { .. } => "KEY_ALREADY_EXISTS",
+            ApiError::RdnsNotFound { .. } => "RDNS_NOT_FOUND",
+            ApiError::RdnsCreateFailed { .. } => "RDNS_CREATE_FAILED",
+            ApiError::RdnsUpdateFailed { .. } => "RDNS_UPDATE_FAILED",
+            ApiError::RdnsDeleteFailed { .. } => "RDNS_DELETE_FAILED",
+            ApiError::RdnsAlreadyExists { .. } => "RDNS_ALREADY_EXISTS",
+            ApiError::ResetFailed { .. } => "RESET_FAILED",
+            ApiError::InvalidInput { .. } => "INVALID_INPUT",
+            ApiError::Conflict { .. } => "CONFLICT",
+            ApiError::ServerCancellationReserveLocationFalseOnly { .. } => {
+                "SERVER_CANCELLATION_RESERVE_LOCATION_FALSE_ONLY"
+            }
+            ApiError::TrafficWarningUpdateFailed { .. } => "TRAFFIC_WARNING_UPDATE_FAILED",
+            ApiError::BootNotAvailable { .. } => "BOOT_NOT_AVAILABLE",
+            ApiError::InternalError { .. } => "INTERNAL_ERROR",
+            ApiError::FailoverAlreadyRouted { .. } => "FAILOVER_ALREADY_ROUTED",
+            ApiError::FailoverFailed { .. } => "FAILOVER_FAILED",
+            ApiError::FailoverLocked { .. } => "FAILOVER_LOCKED",
+            ApiError::FailoverNotComplete { .. } => "FAILOVER_NOT_COMPLETE",
+            ApiError::FailoverNewServerNotFound { .. } => "FAILOVER_NEW_SERVER_NOT_FOUND",
+            ApiError::ServerReversalNotPossible { .. } => "SERVER_REVERSAL_NOT_POSSIBLE",
+            ApiError::BootActivationFailed { .. } => "BOOT_ACTIVATION_FAILED",
+            ApiError::BootDeactivationFailed { .. } => "BOOT_DEACTIVATION_FAILED",
+            ApiError::BootAlreadyEnabled { .. } => "BOOT_ALREADY_ENABLED",
+            ApiError::BootBlocked { .. } => "BOOT_BLOCKED",
+            ApiError::Generic(error) => &error.code,
+        }
+    }
 }
 
 /// Provided input parameters were either incomplete or invalid.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct InvalidInputError {
     /// Missing input fields.
     #[serde(default)]
@@ -454,6 +555,7 @@ pub struct InvalidInputError {
 
 /// Hetzner Robot API rate-limit has been exceeded.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct RateLimitError {
     /// Time interval in which the [`max_request`](RateLimitError::max_request)
     /// limit applies.
@@ -465,6 +567,7 @@ pub struct RateLimitError {
 
 /// Catches generic error cases not explicitly defined in [`ApiError`]
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct GenericError {
     /// HTTP Status Code, e.g. `404`.
     pub status: u32,
@@ -534,13 +637,51 @@ impl From<MaybeTyped> for ApiError {
     fn from(maybe: MaybeTyped) -> Self {
         match maybe {
             MaybeTyped::Typed(t) => t,
-            MaybeTyped::Untyped(t) => ApiError::Generic(t),
+            MaybeTyped::Untyped(t) => ApiError::Generic(Box::new(t)),
+        }
+    }
+}
+
+/// HTTP response details accompanying an [`ApiError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// HTTP status code of the response, e.g. `404`.
+    pub status: StatusCode,
+
+    /// Time to wait before retrying, if the response contained a `Retry-After` header.
+    ///
+    /// Only the delay-seconds form of the header is supported.
+    pub retry_after: Option<Duration>,
+
+    /// Explanation of why the error is surprising, if the endpoint
+    /// is not documented to return it.
+    ///
+    /// This usually indicates that an ID of the wrong kind was passed,
+    /// or that the API has changed.
+    pub hint: Option<String>,
+}
+
+impl ErrorContext {
+    /// Extract the error context from the status and headers of a response.
+    pub fn from_response(status: StatusCode, headers: &HeaderMap) -> Self {
+        ErrorContext {
+            status,
+            retry_after: headers
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse().ok())
+                .map(Duration::from_secs),
+            hint: None,
         }
     }
 }
 
 /// Error which can originate at any stage of the API request.
+///
+/// Some variants only exist with the feature of the API area returning them,
+/// so matching on this enum requires a wildcard arm.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum Error {
     /// Covers any errors produced by the Client implementations.
     #[error("transport error: {0}")]
@@ -552,9 +693,167 @@ pub enum Error {
     /// parameters as `application/x-www-form-urlencoded`
     #[error("html form encoding error: {0}")]
     Serialization(#[from] serde_html_form::ser::Error),
-    /// Error returned by the Hetzner Robot API.
-    #[error("api error: {0}")]
-    Api(#[from] ApiError),
+    /// Error returned by the Hetzner Robot API, along with
+    /// the HTTP status code and relevant headers of the response.
+    #[error("api error: {0}{}", .1.hint.as_ref().map(|hint| format!(" ({hint})")).unwrap_or_default())]
+    Api(#[source] ApiError, ErrorContext),
+    /// A purchase transaction which was being waited on was
+    /// cancelled before it could complete.
+    ///
+    /// Contains the ID of the cancelled transaction.
+    #[error("transaction {0} was cancelled")]
+    TransactionCancelled(String),
+    /// A purchase transaction which was being waited on did not
+    /// complete within the configured timeout.
+    ///
+    /// Contains the ID of the transaction.
+    #[error("timed out waiting for transaction {0}")]
+    TransactionTimeout(String),
+    /// Waiting for a purchase transaction was stopped by the caller
+    /// before the transaction completed.
+    ///
+    /// Contains the ID of the transaction.
+    #[error("stopped waiting for transaction {0}")]
+    TransactionWaitCancelled(String),
+    /// Response contained a field or enum variant unknown to the models.
+    ///
+    /// Only returned when strict models are enabled using
+    /// [`AsyncRobot::strict_models`](crate::AsyncRobot::strict_models).
+    #[error("response does not match models at {pointer:?}: {message}")]
+    ModelMismatch {
+        /// JSON pointer to the offending value within the response body, e.g. `/0/rdns/ttl`.
+        pointer: String,
+        /// Description of the mismatch.
+        message: String,
+    },
+    /// Response was well-formed, but did not have the shape the library relies on,
+    /// such as an empty list where at least one element is required,
+    /// or a network with an out-of-range prefix length.
+    #[error("unexpected response shape: expected {expected}, got {got}")]
+    UnexpectedResponseShape {
+        /// Description of what the library expected, e.g. `a failover address resource`.
+        expected: String,
+        /// Description of what the response contained instead.
+        got: String,
+    },
+    /// Order of the server cannot be withdrawn.
+    ///
+    /// Returned by [`AsyncRobot::withdraw_order`](crate::AsyncRobot::withdraw_order)
+    /// in place of [`ApiError::ServerReversalNotPossible`].
+    #[error("order of server {server} cannot be withdrawn: {reason}")]
+    ReversalNotPossible {
+        /// Server whose order could not be withdrawn.
+        server: ServerId,
+        /// Why the order could not be withdrawn.
+        reason: ReversalRefusal,
+    },
+    /// Failover address cannot be routed to the server, because the server
+    /// has no address of the same family, such as an IPv4 failover address
+    /// and a server without an IPv4 address.
+    ///
+    /// Returned by [`AsyncRobot::order_and_route_failover`](crate::AsyncRobot::order_and_route_failover)
+    /// after the failover address was ordered, but before routing it.
+    #[error("failover address {failover} cannot be routed to server {server}, which has no address of the same family")]
+    FailoverFamilyMismatch {
+        /// Failover address which could not be routed.
+        failover: IpAddr,
+        /// Server the failover address was to be routed to.
+        server: ServerId,
+    },
+    /// IP address was given more than once, and was therefore not used.
+    ///
+    /// Returned by [`AsyncRobot::set_rdns_bulk`](crate::AsyncRobot::set_rdns_bulk)
+    /// for each IP address given with more than one entry.
+    #[error("ip address {0} was given more than once")]
+    DuplicateIp(IpAddr),
+    /// A bulk operation was cancelled using the cancellation token
+    /// in its [`BulkOptions`](crate::api::bulk::BulkOptions).
+    #[error("operation was cancelled")]
+    Cancelled,
+    /// Order authorizes ssh key fingerprints which do not belong to any key in the account.
+    ///
+    /// Returned by [`AsyncRobot::validate_order_auth`](crate::AsyncRobot::validate_order_auth).
+    #[error("unknown ssh key fingerprints: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    UnknownSshKeys(Vec<Fingerprint>),
+    /// SSH key fingerprint is not in the MD5 format the API identifies keys by.
+    ///
+    /// Returned by the [`AsyncRobot`](crate::AsyncRobot) methods which address
+    /// a single key, or place an order authorizing keys.
+    #[error("ssh key fingerprint {0} must be in md5 format")]
+    UnsupportedFingerprint(Fingerprint),
+    /// Firewall configuration violates a constraint imposed by Hetzner, and was not sent.
+    ///
+    /// Returned by the [`AsyncRobot`](crate::AsyncRobot) methods which set a firewall
+    /// configuration or template, if the rules fail [`Rules::validate`](crate::api::firewall::Rules::validate).
+    #[error("invalid firewall configuration: {0}")]
+    #[cfg(feature = "firewall")]
+    InvalidFirewall(ValidationError),
+    /// Server does not support the requested reset type.
+    ///
+    /// Only returned by [`AsyncRobot::trigger_reset_with`](crate::AsyncRobot::trigger_reset_with)
+    /// with validation enabled.
+    #[error(
+        "reset type {reset:?} is not supported by the server, supported types are: {supported:?}"
+    )]
+    ResetUnsupported {
+        /// Requested reset type.
+        reset: Reset,
+        /// Reset types supported by the server.
+        supported: Vec<Reset>,
+    },
+    /// Credentials were rejected by the API.
+    ///
+    /// Returned for `401 Unauthorized` responses, which indicate a wrong username or
+    /// password, and for `403 Forbidden` responses without an API error body, which
+    /// indicate that the webservice user is disabled or locked, or that the
+    /// request originated from an IP address excluded by its IP restrictions.
+    ///
+    /// Valid credentials lacking permission for an endpoint are instead reported
+    /// as [`ApiError::Forbidden`], see [`Error::is_permission_denied`].
+    #[error("credentials were rejected by the api (HTTP {status})")]
+    Unauthorized {
+        /// HTTP status code of the response, either `401` or `403`.
+        status: StatusCode,
+    },
+    /// Storagebox has no snapshot with the given name.
+    ///
+    /// Returned by [`AsyncRobot::plan_snapshot_revert`](crate::AsyncRobot::plan_snapshot_revert).
+    #[error("snapshot {0} does not exist")]
+    #[cfg(feature = "storagebox")]
+    UnknownSnapshot(SnapshotName),
+    /// Revert plan no longer reflects the state of the storagebox, and was not carried out.
+    ///
+    /// Returned by [`AsyncRobot::confirm_revert`](crate::AsyncRobot::confirm_revert).
+    #[error("revert plan is stale: {0}")]
+    #[cfg(feature = "storagebox")]
+    StaleRevertPlan(String),
+    /// API is temporarily unavailable, for example due to maintenance.
+    ///
+    /// Returned for `503 Service Unavailable` responses without an API error
+    /// body, and for responses which are not JSON at all, such as the HTML
+    /// maintenance page.
+    #[error("api is temporarily unavailable{}", .retry_after.map(|delay| format!(", retry after {delay:?}")).unwrap_or_default())]
+    Unavailable {
+        /// Time to wait before retrying, if the response contained a `Retry-After` header.
+        retry_after: Option<Duration>,
+    },
+    /// Request would modify the account, and was rendered instead of being sent.
+    ///
+    /// Only returned when dry-run mode is enabled using
+    /// [`AsyncRobot::dry_run`](crate::AsyncRobot::dry_run).
+    #[error("dry run: {0}")]
+    DryRun(RenderedRequest),
+    /// Request would modify the account, and was therefore not sent.
+    ///
+    /// Only returned when read-only mode is enabled using
+    /// [`AsyncRobot::read_only`](crate::AsyncRobot::read_only).
+    #[error("refusing to send {method} {endpoint} in read-only mode")]
+    ReadOnlyMode {
+        /// HTTP method of the refused request, e.g. `POST`.
+        method: String,
+        /// Endpoint path of the refused request, e.g. `/server/{server_id}`.
+        endpoint: String,
+    },
 }
 
 impl Error {
@@ -564,6 +863,81 @@ impl Error {
     pub fn transport(error: impl std::error::Error + 'static) -> Error {
         Error::Transport(Box::new(error))
     }
+
+    /// Returns the [`ApiError`], if this error was returned by the API.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Error::Api(error, _) => Some(error),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the credentials were rejected, meaning the username or password
+    /// is wrong, or the webservice user is disabled or restricted to other IP addresses.
+    pub fn is_credentials_rejected(&self) -> bool {
+        matches!(self, Error::Unauthorized { .. })
+    }
+
+    /// Returns true if the credentials were accepted, but the webservice user
+    /// lacks permission for the requested endpoint or resource.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// match robot.list_storageboxes().await {
+    ///     Err(err) if err.is_permission_denied() => println!("storageboxes are out of scope"),
+    ///     Err(err) if err.is_credentials_rejected() => panic!("check the credentials: {err}"),
+    ///     result => println!("{:?}", result.unwrap()),
+    /// }
+    /// # }
+    /// ```
+    pub fn is_permission_denied(&self) -> bool {
+        matches!(self, Error::Api(ApiError::Forbidden { .. }, _))
+    }
+
+    /// Returns the HTTP status code of the response, if this error was returned by the API.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use hrobot::hyper::StatusCode;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let robot = hrobot::AsyncRobot::default();
+    /// match robot.list_servers().await {
+    ///     Err(err) if err.status() == Some(StatusCode::CONFLICT) => println!("try again later"),
+    ///     result => println!("{:?}", result.unwrap()),
+    /// }
+    /// # }
+    /// ```
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::Api(_, context) => Some(context.status),
+            Error::Unauthorized { status } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// Returns the delay requested by the `Retry-After` header of the response, if any.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::Api(_, context) => context.retry_after,
+            Error::Unavailable { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Returns a hint explaining why the error is surprising, if it
+    /// was returned by an endpoint not documented to return it.
+    ///
+    /// See [`ErrorContext::hint`].
+    pub fn hint(&self) -> Option<&str> {
+        match self {
+            Error::Api(_, context) => context.hint.as_deref(),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -590,6 +964,7 @@ mod tests {
         "FIREWALL_NOT_AVAILABLE",
         "FIREWALL_PORT_NOT_FOUND",
         "FIREWALL_TEMPLATE_NOT_FOUND",
+        "FORBIDDEN",
         "INTERNAL_ERROR",
         "INVALID_INPUT",
         "IP_NOT_FOUND",
@@ -0,0 +1,260 @@
+//! Catalog of the error codes each endpoint is documented to return.
+//!
+//! Used for attaching a [hint](crate::error::ErrorContext::hint) to errors which
+//! an endpoint is not expected to return, since these usually indicate that an ID
+//! of the wrong kind was passed, e.g. a storagebox ID where a server ID was expected,
+//! or that the API surface has changed.
+
+use crate::{error::ApiError, telemetry::Endpoint};
+
+/// Error codes which any endpoint may return.
+const UNIVERSAL: &[&str] = &[
+    "UNAVAILABLE",
+    "NOT_FOUND",
+    "INVALID_INPUT",
+    "CONFLICT",
+    "INTERNAL_ERROR",
+    "RATE_LIMIT_EXCEEDED",
+    "FORBIDDEN",
+];
+
+/// Error codes documented per endpoint, keyed by the endpoint path prefix.
+///
+/// More specific prefixes must precede the prefixes they extend.
+const CATALOG: &[(&str, &[&str])] = &[
+    (
+        "/boot",
+        &[
+            "SERVER_NOT_FOUND",
+            "BOOT_NOT_AVAILABLE",
+            "BOOT_ACTIVATION_FAILED",
+            "BOOT_DEACTIVATION_FAILED",
+            "BOOT_ALREADY_ENABLED",
+            "BOOT_BLOCKED",
+            "WINDOWS_OUTDATED_VERSION",
+            "WINDOWS_MISSING_ADDON",
+            "PLESK_MISSING_ADDON",
+            "CPANEL_MISSING_ADDON",
+        ],
+    ),
+    (
+        "/failover",
+        &[
+            "FAILOVER_ALREADY_ROUTED",
+            "FAILOVER_FAILED",
+            "FAILOVER_LOCKED",
+            "FAILOVER_NOT_COMPLETE",
+            "FAILOVER_NEW_SERVER_NOT_FOUND",
+        ],
+    ),
+    ("/firewall/template", &["FIREWALL_TEMPLATE_NOT_FOUND"]),
+    (
+        "/firewall",
+        &[
+            "SERVER_NOT_FOUND",
+            "FIREWALL_PORT_NOT_FOUND",
+            "FIREWALL_NOT_AVAILABLE",
+            "FIREWALL_TEMPLATE_NOT_FOUND",
+            "FIREWALL_IN_PROCESS",
+        ],
+    ),
+    (
+        "/ip",
+        &[
+            "IP_NOT_FOUND",
+            "MAC_NOT_FOUND",
+            "MAC_NOT_AVAILABLE",
+            "MAC_ALREADY_SET",
+            "MAC_FAILED",
+            "TRAFFIC_WARNING_UPDATE_FAILED",
+        ],
+    ),
+    (
+        "/key",
+        &[
+            "KEY_UPDATE_FAILED",
+            "KEY_CREATE_FAILED",
+            "KEY_DELETE_FAILED",
+            "KEY_ALREADY_EXISTS",
+        ],
+    ),
+    (
+        "/rdns",
+        &[
+            "IP_NOT_FOUND",
+            "RDNS_NOT_FOUND",
+            "RDNS_CREATE_FAILED",
+            "RDNS_UPDATE_FAILED",
+            "RDNS_DELETE_FAILED",
+            "RDNS_ALREADY_EXISTS",
+        ],
+    ),
+    (
+        "/reset",
+        &[
+            "SERVER_NOT_FOUND",
+            "RESET_NOT_AVAILABLE",
+            "RESET_MANUAL_ACTIVE",
+            "RESET_FAILED",
+        ],
+    ),
+    (
+        "/server",
+        &[
+            "SERVER_NOT_FOUND",
+            "SERVER_CANCELLATION_RESERVE_LOCATION_FALSE_ONLY",
+            "SERVER_REVERSAL_NOT_POSSIBLE",
+        ],
+    ),
+    (
+        "/storagebox",
+        &[
+            "STORAGEBOX_NOT_FOUND",
+            "STORAGEBOX_SUBACCOUNT_NOT_FOUND",
+            "STORAGEBOX_SUBACCOUNT_LIMIT_EXCEEDED",
+            "SNAPSHOT_NOT_FOUND",
+            "SNAPSHOT_LIMIT_EXCEEDED",
+        ],
+    ),
+    (
+        "/subnet",
+        &[
+            "SUBNET_NOT_FOUND",
+            "MAC_NOT_FOUND",
+            "MAC_NOT_AVAILABLE",
+            "MAC_ALREADY_SET",
+            "MAC_FAILED",
+            "TRAFFIC_WARNING_UPDATE_FAILED",
+        ],
+    ),
+    (
+        "/vswitch",
+        &[
+            "SERVER_NOT_FOUND",
+            "VSWITCH_LIMIT_REACHED",
+            "VSWITCH_NOT_AVAILABLE",
+            "VSWITCH_SERVER_LIMIT_REACHED",
+            "VSWITCH_PER_SERVER_LIMIT_REACHED",
+            "VSWITCH_IN_PROCESS",
+            "VSWITCH_VLAN_NOT_UNIQUE",
+        ],
+    ),
+    (
+        "/wol",
+        &["SERVER_NOT_FOUND", "WOL_NOT_AVAILABLE", "WOL_FAILED"],
+    ),
+];
+
+/// Error codes the endpoint is documented to return, in addition to the [`UNIVERSAL`] ones.
+///
+/// Returns `None` for endpoints not covered by the catalog, such as ordering.
+fn documented(endpoint: &str) -> Option<&'static [&'static str]> {
+    CATALOG
+        .iter()
+        .find(|(prefix, _)| {
+            endpoint
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+        .map(|(_, codes)| *codes)
+}
+
+/// Explain why `error` is surprising, if `endpoint` is not documented to return it.
+pub(crate) fn hint(endpoint: &Endpoint, error: &ApiError) -> Option<String> {
+    let code = error.code();
+    let documented = documented(&endpoint.name)?;
+
+    if UNIVERSAL.contains(&code) || documented.contains(&code) {
+        return None;
+    }
+
+    let area = endpoint
+        .name
+        .trim_start_matches('/')
+        .split('/')
+        .next()
+        .unwrap_or_default();
+
+    Some(if matches!(error, ApiError::Generic(_)) {
+        format!(
+            "{code} returned by {area} endpoint {} is not a known error code, the API may have changed",
+            endpoint.name
+        )
+    } else {
+        format!(
+            "{code} returned by {area} endpoint {} — possible routing/ID mixup",
+            endpoint.name
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::Uri;
+
+    use crate::{error::ApiError, telemetry::Endpoint};
+
+    use super::hint;
+
+    fn endpoint(uri: &'static str) -> Endpoint {
+        Endpoint::from(&Uri::from_static(uri))
+    }
+
+    #[test]
+    fn unexpected_error_hint() {
+        let firewall = endpoint("https://robot-ws.your-server.de/firewall/1234567");
+
+        assert_eq!(
+            hint(
+                &firewall,
+                &ApiError::SnapshotNotFound {
+                    message: "snapshot not found".to_string()
+                }
+            )
+            .as_deref(),
+            Some(
+                "SNAPSHOT_NOT_FOUND returned by firewall endpoint /firewall/{server_id} — possible routing/ID mixup"
+            )
+        );
+
+        assert_eq!(
+            hint(
+                &firewall,
+                &ApiError::FirewallInProcess {
+                    message: "firewall in process".to_string()
+                }
+            ),
+            None
+        );
+
+        assert_eq!(
+            hint(
+                &firewall,
+                &ApiError::NotFound {
+                    message: "not found".to_string()
+                }
+            ),
+            None
+        );
+
+        // Template endpoints are not documented to return server errors.
+        assert!(hint(
+            &endpoint("https://robot-ws.your-server.de/firewall/template/1234"),
+            &ApiError::ServerNotFound {
+                message: "server not found".to_string()
+            }
+        )
+        .is_some());
+
+        // Endpoints outside the catalog never produce hints.
+        assert_eq!(
+            hint(
+                &endpoint("https://robot-ws.your-server.de/order/server/product"),
+                &ApiError::SnapshotNotFound {
+                    message: "snapshot not found".to_string()
+                }
+            ),
+            None
+        );
+    }
+}
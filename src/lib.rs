@@ -9,6 +9,33 @@
 //!
 //! If you already have a Hetzner account, you can create one through the [Hetzner Robot](https://robot.your-server.de) web interface under [Settings/Preferences](https://robot.your-server.de/preferences/index).
 //!
+//! # Features
+//! * `hyper-client` (default) implements [`AsyncHttpClient`] for the [`hyper-util`](https://docs.rs/hyper-util) legacy client,
+//!   and uses it as the default client.
+//! * `reqwest-client` implements [`AsyncHttpClient`] for [`reqwest::Client`](https://docs.rs/reqwest), which is used as the
+//!   default client if `hyper-client` is disabled. Note that no TLS backend is enabled for `reqwest` by hrobot,
+//!   so you must enable one yourself.
+//! * `firewall`, `ip`, `ordering`, `storagebox` and `vswitch` (all default) enable the endpoints
+//!   and models of the corresponding [`api`] modules, where `ip` also covers failover IPs and subnets.
+//!   Servers, resets, boot configurations, keys, reverse DNS, traffic and Wake-on-LAN are always
+//!   included, since the other areas depend on them. Modules spanning several areas, such as
+//!   [`api::inventory`] and [`api::watch`], require all of the areas they cover.
+//! * `cache` adds [`CachedRobot`](crate::cache::CachedRobot), which memoizes responses from read-only
+//!   endpoints like [`AsyncRobot::get_server`] for a configurable duration, including firewall
+//!   lookups if `firewall` is enabled.
+//! * `catalog-cache` adds [`CatalogCache`](crate::cache::CatalogCache), which persists slow-changing
+//!   catalogs to disk. It enables `cache`, `firewall` and `ordering`.
+//! * `schemars` derives [`JsonSchema`](https://docs.rs/schemars/latest/schemars/trait.JsonSchema.html)
+//!   for all public models, for generating JSON schemas describing their serialized form.
+//! * `provider` adds the [`provider`] module, with a provider-agnostic
+//!   [`DedicatedServerProvider`](crate::provider::DedicatedServerProvider) trait implemented by [`AsyncRobot`].
+//! * `test-harness` adds the [`test_harness`] module, with helpers for locating
+//!   provisioned test resources and waiting for firewall and vSwitch changes in integration tests.
+//! * `relaxed-firewall` accepts the source and destination network filters of
+//!   [`Ipv6Filter`](crate::api::firewall::Ipv6Filter), which are otherwise rejected with
+//!   [`Error::InvalidFirewall`](crate::error::Error::InvalidFirewall) before being sent.
+//!   Hetzner does not officially support these yet, so rules using them may be rejected by the API.
+//!
 //! # Example
 //! Here's a quick example showing how to instantiate the [`AsyncRobot`] client object
 //! and fetching a list of all dedicated servers owned by the account identified by `username`
@@ -62,13 +89,30 @@
 )]
 #![forbid(unsafe_code)]
 pub mod api;
+pub mod audit;
+#[cfg(feature = "cache")]
+pub mod cache;
 pub mod error;
+pub mod poll;
+#[cfg(feature = "provider")]
+pub mod provider;
+#[cfg(feature = "test-harness")]
+pub mod test_harness;
+pub mod units;
 
 mod client;
 mod conversion;
+mod expectations;
+#[cfg(test)]
+mod mock;
+mod pacing;
+mod strict;
+mod telemetry;
 mod urlencode;
 
 pub use ::bytesize;
+pub use ::hyper;
 pub use ::rust_decimal;
 pub use ::time;
+pub use ::tokio_util;
 pub use client::*;
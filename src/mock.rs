@@ -0,0 +1,286 @@
+//! Canned-response HTTP client shared by the unit tests.
+//!
+//! [`MockClient`] routes requests by method and path to a [`Reply`], recording
+//! every request it receives, while [`fixtures`] provides the response bodies
+//! the Robot API would return.
+
+pub(crate) mod fixtures;
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
+use hyper::{
+    header::{HeaderName, HeaderValue},
+    Request, Response, StatusCode,
+};
+use serde_json::{json, Value};
+
+use crate::{AsyncHttpClient, AsyncRobot, ResponseFuture};
+
+/// Response served by a [`MockClient`] route.
+#[derive(Debug, Clone)]
+pub(crate) struct Reply {
+    status: StatusCode,
+    headers: Vec<(HeaderName, HeaderValue)>,
+    body: Bytes,
+    pending: bool,
+}
+
+impl Reply {
+    /// `200 OK` with the given body.
+    pub fn ok(body: impl Into<Bytes>) -> Self {
+        Reply::status(StatusCode::OK, body)
+    }
+
+    /// Response with the given status and body.
+    pub fn status(status: StatusCode, body: impl Into<Bytes>) -> Self {
+        Reply {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+            pending: false,
+        }
+    }
+
+    /// Robot API error body with the given status and error code.
+    pub fn error(status: StatusCode, code: &str, message: &str) -> Self {
+        let body = json!({
+            "error": {
+                "status": status.as_u16(),
+                "code": code,
+                "message": message,
+            }
+        });
+
+        Reply::status(status, body.to_string())
+    }
+
+    /// `403 Forbidden` with the Robot API's rate limit error.
+    pub fn rate_limited() -> Self {
+        let body = json!({
+            "error": {
+                "status": 403,
+                "code": "RATE_LIMIT_EXCEEDED",
+                "max_requests": 200,
+                "interval": 3600,
+                "message": "Rate limit exceeded",
+            }
+        });
+
+        Reply::status(StatusCode::FORBIDDEN, body.to_string())
+    }
+
+    /// Response which never arrives.
+    pub fn pending() -> Self {
+        Reply {
+            pending: true,
+            ..Reply::ok("")
+        }
+    }
+
+    /// Add a header to the response.
+    pub fn header(mut self, name: HeaderName, value: &'static str) -> Self {
+        self.headers.push((name, HeaderValue::from_static(value)));
+        self
+    }
+
+    fn into_response(self) -> Response<Bytes> {
+        let mut response = Response::new(self.body);
+        *response.status_mut() = self.status;
+        response.headers_mut().extend(self.headers);
+        response
+    }
+}
+
+impl From<&'static str> for Reply {
+    fn from(body: &'static str) -> Self {
+        Reply::ok(body)
+    }
+}
+
+impl From<String> for Reply {
+    fn from(body: String) -> Self {
+        Reply::ok(body)
+    }
+}
+
+impl From<Value> for Reply {
+    fn from(body: Value) -> Self {
+        Reply::ok(body.to_string())
+    }
+}
+
+type Handler = Box<dyn Fn(&Request<String>) -> Reply + Send + Sync>;
+
+struct Route {
+    method: &'static str,
+    path: &'static str,
+    handler: Handler,
+}
+
+impl Route {
+    /// `*` matches any method, any single path segment, or as the whole
+    /// path, any path at all.
+    fn matches(&self, request: &Request<String>) -> bool {
+        if self.method != "*" && self.method != request.method().as_str() {
+            return false;
+        }
+
+        if self.path == "*" {
+            return true;
+        }
+
+        let mut pattern = self.path.split('/');
+        let mut path = request.uri().path().split('/');
+        loop {
+            match (pattern.next(), path.next()) {
+                (None, None) => return true,
+                (Some(expected), Some(actual)) if expected == "*" || expected == actual => {}
+                _ => return false,
+            }
+        }
+    }
+}
+
+/// Requests received by a [`MockClient`], shared between clones.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RequestLog(Arc<Mutex<Vec<Request<String>>>>);
+
+impl RequestLog {
+    /// Number of requests received.
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    /// Apply `f` to every request received, in order.
+    pub fn map<T>(&self, f: impl FnMut(&Request<String>) -> T) -> Vec<T> {
+        self.0.lock().unwrap().iter().map(f).collect()
+    }
+
+    /// `METHOD /path` of every request received.
+    pub fn lines(&self) -> Vec<String> {
+        self.map(|request| format!("{} {}", request.method(), request.uri().path()))
+    }
+
+    /// Bodies of every request which isn't a `GET`.
+    pub fn bodies(&self) -> Vec<String> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request.method() != hyper::Method::GET)
+            .map(|request| request.body().clone())
+            .collect()
+    }
+}
+
+/// HTTP client serving canned responses routed by method and path.
+///
+/// Routes are tried in the order they were added, and requests matching none
+/// of them get the Robot API's `404 NOT_FOUND` error.
+#[derive(Default)]
+pub(crate) struct MockClient {
+    routes: Vec<Route>,
+    requests: RequestLog,
+}
+
+impl fmt::Debug for MockClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockClient")
+            .field(
+                "routes",
+                &self
+                    .routes
+                    .iter()
+                    .map(|route| format!("{} {}", route.method, route.path))
+                    .collect::<Vec<_>>(),
+            )
+            .field("requests", &self.requests.len())
+            .finish()
+    }
+}
+
+impl MockClient {
+    /// Client without any routes.
+    pub fn new() -> Self {
+        MockClient::default()
+    }
+
+    /// Serve `reply` to every request matching `method` and `path`.
+    ///
+    /// `*` matches any method or any single path segment.
+    pub fn on(self, method: &'static str, path: &'static str, reply: impl Into<Reply>) -> Self {
+        let reply = reply.into();
+        self.on_with(method, path, move |_| reply.clone())
+    }
+
+    /// Serve `replies` in turn, repeating the last one once exhausted.
+    ///
+    /// Replies are drawn lazily, so the sequence may be arbitrarily long.
+    pub fn on_sequence<I>(self, method: &'static str, path: &'static str, replies: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Reply>,
+        I::IntoIter: Send + 'static,
+    {
+        let sequence = Mutex::new((replies.into_iter(), None));
+        self.on_with(method, path, move |_| {
+            let mut sequence = sequence.lock().unwrap();
+            let (replies, last) = &mut *sequence;
+            if let Some(reply) = replies.next() {
+                *last = Some(reply.into());
+            }
+
+            last.clone()
+                .expect("sequence should contain at least one reply")
+        })
+    }
+
+    /// Build the reply to requests matching `method` and `path` from the request itself.
+    pub fn on_with(
+        mut self,
+        method: &'static str,
+        path: &'static str,
+        handler: impl Fn(&Request<String>) -> Reply + Send + Sync + 'static,
+    ) -> Self {
+        self.routes.push(Route {
+            method,
+            path,
+            handler: Box::new(handler),
+        });
+        self
+    }
+
+    /// Log of the requests received by this client.
+    pub fn requests(&self) -> RequestLog {
+        self.requests.clone()
+    }
+
+    /// Robot sending its requests through this client.
+    pub fn robot(self) -> AsyncRobot {
+        AsyncRobot::new(self, "#ws+username", "p@ssw0rd")
+    }
+}
+
+impl AsyncHttpClient for MockClient {
+    fn send_request(&self, request: Request<String>) -> ResponseFuture<'_> {
+        let reply = self
+            .routes
+            .iter()
+            .find(|route| route.matches(&request))
+            .map(|route| (route.handler)(&request))
+            .unwrap_or_else(|| Reply::error(StatusCode::NOT_FOUND, "NOT_FOUND", "Not found"));
+        self.requests.0.lock().unwrap().push(request);
+
+        Box::pin(async move {
+            if reply.pending {
+                std::future::pending::<()>().await;
+            }
+
+            Ok(reply.into_response())
+        })
+    }
+}
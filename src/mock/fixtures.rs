@@ -0,0 +1,304 @@
+//! Response bodies as returned by the Robot API.
+//!
+//! Fixtures are plain [`Value`]s, so tests can adjust them with [`patch`]
+//! before handing them to a [`MockClient`](super::MockClient).
+
+use std::net::Ipv4Addr;
+
+use serde_json::{json, Value};
+
+/// Overwrite fields of a fixture, or of the object it wraps, as in `{"server": {...}}`.
+pub(crate) fn patch(mut fixture: Value, fields: Value) -> Value {
+    let object = fixture
+        .as_object_mut()
+        .expect("fixture should be an object");
+    let object = if object.len() == 1 && object.values().all(Value::is_object) {
+        object.values_mut().find_map(Value::as_object_mut).unwrap()
+    } else {
+        object
+    };
+
+    for (key, value) in fields.as_object().expect("fields should be an object") {
+        let _ = object.insert(key.clone(), value.clone());
+    }
+
+    fixture
+}
+
+/// IPv4 address of server `id`, `1.1.1.{id}` for ids up to 255.
+pub(crate) fn server_ip(id: u32) -> Ipv4Addr {
+    Ipv4Addr::from(u32::from(Ipv4Addr::new(1, 1, 1, 0)) + id)
+}
+
+/// Dedicated server `n{id}`, ready and not cancelled.
+pub(crate) fn server(id: u32) -> Value {
+    let ip = server_ip(id).to_string();
+
+    json!({
+        "server": {
+            "server_ip": ip,
+            "server_ipv6_net": "2a01:4f8:1::",
+            "server_number": id,
+            "server_name": format!("n{id}"),
+            "product": "Server Auction",
+            "dc": "FSN1-DC1",
+            "traffic": "unlimited",
+            "status": "ready",
+            "cancelled": false,
+            "paid_until": "2070-01-01",
+            "ip": [ip],
+            "subnet": [],
+            "linked_storagebox": null
+        }
+    })
+}
+
+/// Firewall template reference, as listed without its rules.
+pub(crate) fn firewall_template(id: u32, name: &str) -> Value {
+    json!({
+        "firewall_template": {
+            "id": id,
+            "name": name,
+            "filter_ipv6": false,
+            "whitelist_hos": true,
+            "is_default": false
+        }
+    })
+}
+
+/// Reset options of server `id`, with `types` being a single reset or a list of them.
+pub(crate) fn reset(id: u32, types: Value) -> Value {
+    json!({
+        "reset": {
+            "server_ip": server_ip(id).to_string(),
+            "server_ipv6_net": "2a01:4f8:1::",
+            "server_number": id,
+            "type": types
+        }
+    })
+}
+
+/// Firewall of server `id` with no rules.
+pub(crate) fn firewall(id: u32, status: &str) -> Value {
+    json!({
+        "firewall": {
+            "server_ip": server_ip(id).to_string(),
+            "server_number": id,
+            "status": status,
+            "filter_ipv6": false,
+            "whitelist_hos": true,
+            "port": "main",
+            "rules": {"input": [], "output": []}
+        }
+    })
+}
+
+/// Failover IP `ip` belonging to server `id`, routed to `active_server_ip` if any.
+#[cfg(all(
+    feature = "firewall",
+    feature = "ip",
+    feature = "storagebox",
+    feature = "vswitch"
+))]
+pub(crate) fn failover(ip: &str, id: u32, active_server_ip: Option<&str>) -> Value {
+    json!({
+        "failover": {
+            "ip": ip,
+            "mask": 32,
+            "server_number": id,
+            "active_server_ip": active_server_ip
+        }
+    })
+}
+
+/// Reverse DNS entry pointing `ip` at `ptr`.
+pub(crate) fn rdns(ip: &str, ptr: &str) -> Value {
+    json!({"rdns": {"ip": ip, "ptr": ptr}})
+}
+
+/// vSwitch without any servers, subnets or cloud networks.
+pub(crate) fn vswitch(id: u32) -> Value {
+    json!({
+        "id": id,
+        "name": "vswitch",
+        "vlan": 4001,
+        "cancelled": false,
+        "server": [],
+        "subnet": [],
+        "cloud_network": []
+    })
+}
+
+/// Storage box `id` with a quarter of its 10000 GiB quota in use.
+pub(crate) fn storagebox(id: u32) -> Value {
+    json!({
+        "storagebox": {
+            "id": id,
+            "login": format!("u{id}"),
+            "name": "Backup Server 1",
+            "product": "BX60",
+            "cancelled": false,
+            "locked": false,
+            "location": "FSN1",
+            "linked_server": null,
+            "paid_until": "2070-01-01",
+            "disk_quota": 10240000,
+            "disk_usage": 2560000,
+            "disk_usage_data": 2048000,
+            "disk_usage_snapshots": 512000,
+            "webdav": true,
+            "samba": true,
+            "ssh": true,
+            "external_reachability": true,
+            "zfs": false,
+            "server": format!("u{id}.your-storagebox.de"),
+            "host_system": "FSN1-BX355"
+        }
+    })
+}
+
+/// Automatic snapshot taken at `timestamp`, given as `YYYY-MM-DDTHH:MM:SS` in UTC.
+pub(crate) fn snapshot(timestamp: &str, filesystem_size: u64) -> Value {
+    json!({
+        "snapshot": {
+            "name": timestamp.replace(':', "-"),
+            "timestamp": format!("{timestamp}+00:00"),
+            "size": 10,
+            "filesystem_size": filesystem_size,
+            "automatic": true,
+            "comment": ""
+        }
+    })
+}
+
+/// Sub-account `u{storagebox}-{name}` of a storage box.
+#[cfg(all(
+    feature = "firewall",
+    feature = "ip",
+    feature = "storagebox",
+    feature = "vswitch"
+))]
+pub(crate) fn subaccount(storagebox: u32, name: &str, comment: &str) -> Value {
+    json!({
+        "subaccount": {
+            "username": format!("u{storagebox}-{name}"),
+            "accountid": format!("u{storagebox}"),
+            "server": format!("u{storagebox}-{name}.your-storagebox.de"),
+            "homedirectory": name,
+            "samba": false,
+            "ssh": true,
+            "external_reachability": false,
+            "webdav": false,
+            "readonly": false,
+            "createtime": "2017-05-24 00:00:00",
+            "comment": comment
+        }
+    })
+}
+
+/// Ed25519 SSH key with the given MD5 fingerprint.
+#[cfg(feature = "ordering")]
+pub(crate) fn ssh_key(name: &str, fingerprint: &str) -> Value {
+    json!({
+        "key": {
+            "name": name,
+            "fingerprint": fingerprint,
+            "type": "ED25519",
+            "size": 256,
+            "data": "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIJmtYuESeEb0dwTL5ExcAXGpHmWDPNOvXp5HGwK6Rk6A",
+            "created_at": "2021-12-31T18:10:33+01:00"
+        }
+    })
+}
+
+/// Transaction `B20220210-1843193-S33055` ordering addon `product` for server 1.
+#[cfg(feature = "ip")]
+pub(crate) fn addon_transaction(product: &str, status: &str) -> Value {
+    json!({
+        "transaction": {
+            "id": "B20220210-1843193-S33055",
+            "date": "2022-02-10T12:20:11+01:00",
+            "status": status,
+            "server_number": 1,
+            "product": {
+                "id": product,
+                "name": product,
+                "price": {
+                    "location": "FSN1",
+                    "price": {
+                        "net": "15.1261",
+                        "gross": "15.1261",
+                        "hourly_net": "0.0242",
+                        "hourly_gross": "0.0242"
+                    },
+                    "price_setup": {"net": "152.0000", "gross": "152.0000"}
+                }
+            },
+            "resources": []
+        }
+    })
+}
+
+/// Server auction product `SB{id}` with two 4 TB drives described by `hdd_text`.
+#[cfg(feature = "ordering")]
+pub(crate) fn market_product(id: u32, price: &str, datacenter: &str, hdd_text: &str) -> Value {
+    json!({
+        "product": {
+            "id": id,
+            "name": format!("SB{id}"),
+            "description": ["Intel Core i7-6700", "2x HDD 4 TB SATA"],
+            "traffic": "unlimited",
+            "dist": ["Rescue system"],
+            "lang": ["en"],
+            "datacenter": datacenter,
+            "cpu": "Intel Core i7-6700",
+            "cpu_benchmark": 10000,
+            "memory_size": 64,
+            "hdd_size": 4096,
+            "hdd_text": hdd_text,
+            "hdd_count": 2,
+            "price": price,
+            "price_vat": price,
+            "price_setup": "0",
+            "price_setup_vat": "0",
+            "price_hourly": "0.1",
+            "price_hourly_vat": "0.1",
+            "fixed_price": false,
+            "next_reduce": 3600,
+            "next_reduce_date": "2018-05-01 12:22:00",
+            "orderable_addons": []
+        }
+    })
+}
+
+/// Single IP address `ip` of server `id`.
+#[cfg(feature = "ip")]
+pub(crate) fn ip(ip: &str, id: u32) -> Value {
+    json!({
+        "ip": {
+            "ip": ip,
+            "server_ip": server_ip(id).to_string(),
+            "server_number": id,
+            "locked": false,
+            "separate_mac": null,
+            "traffic_warnings": false,
+            "traffic_hourly": 50,
+            "traffic_daily": 50,
+            "traffic_monthly": 5
+        }
+    })
+}
+
+/// Cancellation of IP address `ip` of server `id`, cancelled if `cancellation_date` is set.
+#[cfg(feature = "ip")]
+pub(crate) fn ip_cancellation(ip: &str, id: u32, cancellation_date: Option<&str>) -> Value {
+    json!({
+        "cancellation": {
+            "ip": ip,
+            "server_number": id,
+            "earliest_cancellation_date": "2024-01-31",
+            "cancelled": cancellation_date.is_some(),
+            "cancellation_date": cancellation_date
+        }
+    })
+}
@@ -0,0 +1,198 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::{BuildHasher, Hasher},
+    sync::{Arc, Mutex, OnceLock},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+/// Maximum jitter added to the pacing interval, as a fraction of the interval.
+const MAX_JITTER: f64 = 0.1;
+
+/// Time at which the last request was sent using a given set of credentials.
+type Slot = Arc<tokio::sync::Mutex<Option<Instant>>>;
+
+/// Slots shared by all pacers within the process, keyed by username.
+fn slots() -> &'static Mutex<HashMap<String, Slot>> {
+    static SLOTS: OnceLock<Mutex<HashMap<String, Slot>>> = OnceLock::new();
+    SLOTS.get_or_init(Default::default)
+}
+
+/// Spaces requests made using the same credentials at least `interval` apart,
+/// across all pacers in the process.
+#[derive(Debug)]
+pub(crate) struct Pacer {
+    interval: Duration,
+}
+
+impl Pacer {
+    pub(crate) fn new(interval: Duration) -> Self {
+        Pacer { interval }
+    }
+
+    /// Wait until at least the interval, plus up to 10% jitter, has
+    /// passed since the previous request made using `username`.
+    pub(crate) async fn wait(&self, username: &str) {
+        let slot = slots()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry(username.to_string())
+            .or_default()
+            .clone();
+
+        let mut last = slot.lock().await;
+
+        if let Some(previous) = *last {
+            tokio::time::sleep_until(
+                previous + self.interval + jitter(self.interval.mul_f64(MAX_JITTER)),
+            )
+            .await;
+        }
+
+        *last = Some(Instant::now());
+    }
+}
+
+/// Random duration between zero and `max`.
+pub(crate) fn jitter(max: Duration) -> Duration {
+    // RandomState is seeded randomly, which is plenty for spreading out requests.
+    let random = RandomState::new().build_hasher().finish();
+
+    max.mul_f64(random as f64 / u64::MAX as f64)
+}
+
+/// Token bucket allowing bursts of up to `max_requests`, refilled
+/// at a rate of `max_requests` per `interval`.
+///
+/// The bucket can additionally be paused, holding back all requests
+/// regardless of the tokens available.
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    /// Capacity and refill rate in tokens per second, if limited.
+    limit: Option<(f64, f64)>,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    updated: Instant,
+    paused_until: Option<Instant>,
+}
+
+impl TokenBucket {
+    pub(crate) fn new(max_requests: u32, interval: Duration) -> Self {
+        let capacity = f64::from(max_requests.max(1));
+        let rate = capacity / interval.as_secs_f64().max(f64::EPSILON);
+
+        TokenBucket::with_limit(Some((capacity, rate)))
+    }
+
+    /// Bucket which never runs out of tokens, but can still be paused.
+    pub(crate) fn unlimited() -> Self {
+        TokenBucket::with_limit(None)
+    }
+
+    fn with_limit(limit: Option<(f64, f64)>) -> Self {
+        TokenBucket {
+            limit,
+            state: Mutex::new(BucketState {
+                tokens: limit.map_or(0.0, |(capacity, _)| capacity),
+                updated: Instant::now(),
+                paused_until: None,
+            }),
+        }
+    }
+
+    /// Wait until the bucket is not paused and holds a token, and take it.
+    pub(crate) async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.lock();
+                let now = Instant::now();
+
+                match (state.paused_until, self.limit) {
+                    (Some(until), _) if until > now => until - now,
+                    (_, None) => return,
+                    (_, Some((capacity, rate))) => {
+                        state.tokens = (state.tokens + (now - state.updated).as_secs_f64() * rate)
+                            .min(capacity);
+                        state.updated = now;
+
+                        if state.tokens >= 1.0 {
+                            state.tokens -= 1.0;
+                            return;
+                        }
+
+                        Duration::from_secs_f64((1.0 - state.tokens) / rate)
+                    }
+                }
+            };
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Hold back all requests for at least `delay`.
+    pub(crate) fn pause(&self, delay: Duration) {
+        let mut state = self.lock();
+
+        let until = Instant::now() + delay;
+        state.paused_until = Some(state.paused_until.map_or(until, |paused| paused.max(until)));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BucketState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+
+    use super::{Pacer, TokenBucket};
+
+    #[tokio::test(start_paused = true)]
+    async fn shared_between_pacers() {
+        let first = Pacer::new(Duration::from_secs(1));
+        let second = Pacer::new(Duration::from_secs(1));
+
+        let start = Instant::now();
+        first.wait("#ws+paced").await;
+        first.wait("#ws+unpaced").await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        second.wait("#ws+paced").await;
+        first.wait("#ws+paced").await;
+
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_secs(2), "{elapsed:?}");
+        assert!(elapsed <= Duration::from_millis(2200), "{elapsed:?}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn token_bucket_bursts() {
+        let limiter = std::sync::Arc::new(TokenBucket::new(3, Duration::from_secs(3)));
+
+        let start = Instant::now();
+        let tasks: Vec<_> = (0..5)
+            .map(|_| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move { limiter.acquire().await })
+            })
+            .collect();
+
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_secs(2), "{elapsed:?}");
+        assert!(elapsed < Duration::from_millis(2100), "{elapsed:?}");
+    }
+}
@@ -0,0 +1,182 @@
+//! Options shared by all helpers which wait for a long-running operation to complete.
+//!
+//! Helpers such as [`AsyncRobot::wait_for_transaction`](crate::AsyncRobot::wait_for_transaction),
+//! [`AsyncRobot::rollout_firewall_template`](crate::AsyncRobot::rollout_firewall_template)
+//! and [`AsyncRobot::order_and_route_failover`](crate::AsyncRobot::order_and_route_failover)
+//! all take a [`PollOptions`], controlling how often to poll, when to give up, and how
+//! to stop waiting early.
+//!
+//! # Example
+//! ```rust
+//! # use std::time::Duration;
+//! # use hrobot::poll::PollOptions;
+//! # use hrobot::tokio_util::sync::CancellationToken;
+//! let token = CancellationToken::new();
+//!
+//! let options = PollOptions::new(Duration::from_secs(60))
+//!     .with_timeout(Duration::from_secs(6 * 60 * 60))
+//!     .with_jitter(Duration::from_secs(5))
+//!     .with_cancellation(token.clone());
+//!
+//! // Calling token.cancel() from elsewhere stops the wait.
+//! ```
+
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+
+#[cfg(any(feature = "firewall", feature = "ordering"))]
+use std::pin::pin;
+
+#[cfg(any(feature = "firewall", feature = "ordering"))]
+use futures_util::future::{self, Either};
+#[cfg(any(feature = "firewall", feature = "ordering"))]
+use tokio::time::Instant;
+
+#[cfg(any(feature = "firewall", feature = "ordering"))]
+use crate::pacing::jitter;
+
+/// Controls how long-running operations are polled until they complete.
+///
+/// See the [module documentation](self) for an example.
+#[derive(Debug, Clone)]
+pub struct PollOptions {
+    /// Time to wait between each poll.
+    pub interval: Duration,
+
+    /// Maximum time to wait for the operation to complete, if any.
+    pub timeout: Option<Duration>,
+
+    /// Upper bound of the random delay added to each interval,
+    /// spreading out requests from concurrent waits.
+    pub jitter: Duration,
+
+    /// Token which stops the wait when cancelled, if any.
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl PollOptions {
+    /// Poll every `interval` without any timeout, jitter or cancellation.
+    pub fn new(interval: Duration) -> Self {
+        PollOptions {
+            interval,
+            timeout: None,
+            jitter: Duration::ZERO,
+            cancellation: None,
+        }
+    }
+
+    /// Give up waiting after `timeout` has elapsed.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Add a random delay of up to `jitter` to each interval.
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Stop waiting as soon as `token` is cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+}
+
+#[cfg(any(feature = "firewall", feature = "ordering"))]
+impl PollOptions {
+    /// Point in time at which a wait started now times out, if any.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.timeout.map(|timeout| Instant::now() + timeout)
+    }
+
+    /// Sleep until it is time to poll again, which is after [`PollOptions::interval`]
+    /// plus jitter, unless the `deadline` passes or the wait is cancelled first.
+    pub(crate) async fn sleep(&self, deadline: Option<Instant>) -> Result<(), PollInterrupted> {
+        let interval = self.interval + jitter(self.jitter);
+
+        let delay = match deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    return Err(PollInterrupted::Timeout);
+                }
+
+                remaining.min(interval)
+            }
+            None => interval,
+        };
+
+        let sleep = pin!(tokio::time::sleep(delay));
+
+        match &self.cancellation {
+            Some(token) => {
+                let cancelled = pin!(token.cancelled());
+
+                match future::select(cancelled, sleep).await {
+                    Either::Left(_) => Err(PollInterrupted::Cancelled),
+                    Either::Right(_) => Ok(()),
+                }
+            }
+            None => {
+                sleep.await;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Default for PollOptions {
+    /// Poll every 30 seconds without any timeout, jitter or cancellation.
+    fn default() -> Self {
+        PollOptions::new(Duration::from_secs(30))
+    }
+}
+
+/// Reason a wait using [`PollOptions`] was interrupted.
+#[cfg(any(feature = "firewall", feature = "ordering"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PollInterrupted {
+    /// The timeout elapsed.
+    Timeout,
+    /// The cancellation token was cancelled.
+    Cancelled,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+    use tokio_util::sync::CancellationToken;
+
+    use super::{PollInterrupted, PollOptions};
+
+    #[tokio::test(start_paused = true)]
+    async fn poll_sleep() {
+        let options = PollOptions::new(Duration::from_secs(10))
+            .with_timeout(Duration::from_secs(25))
+            .with_jitter(Duration::from_secs(2));
+
+        let start = Instant::now();
+        let deadline = options.deadline();
+
+        options.sleep(deadline).await.unwrap();
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_secs(10) && elapsed <= Duration::from_secs(12));
+
+        options.sleep(deadline).await.unwrap();
+        options.sleep(deadline).await.unwrap();
+        assert_eq!(start.elapsed(), Duration::from_secs(25));
+        assert_eq!(options.sleep(deadline).await, Err(PollInterrupted::Timeout));
+
+        let token = CancellationToken::new();
+        token.cancel();
+        assert_eq!(
+            options.with_cancellation(token).sleep(None).await,
+            Err(PollInterrupted::Cancelled)
+        );
+    }
+}
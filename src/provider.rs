@@ -0,0 +1,277 @@
+//! Provider-agnostic abstraction over dedicated server management.
+//!
+//! [`DedicatedServerProvider`] covers the small set of operations most
+//! bare-metal providers have in common, so higher-level tools can be written
+//! against the trait rather than against [`AsyncRobot`] directly.
+//!
+//! Servers are described using the provider-neutral [`DedicatedServer`], while
+//! identifiers and firewall configurations are left to each provider, since
+//! their shape differs too much to be usefully unified.
+
+use std::{future::Future, net::Ipv4Addr, pin::Pin};
+
+use ipnet::Ipv6Net;
+
+use crate::{
+    api::{
+        firewall::FirewallConfig,
+        reset::{Reset, ResetOptions},
+        server::{Server, ServerId},
+    },
+    error::Error,
+    AsyncRobot,
+};
+
+/// Future returned by the methods of [`DedicatedServerProvider`].
+pub type ProviderFuture<'a, T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'a>>;
+
+/// Provider-neutral description of a dedicated server.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DedicatedServer<Id> {
+    /// Provider-specific identifier of the server.
+    pub id: Id,
+
+    /// Name of the server, as set using [`DedicatedServerProvider::rename_server`].
+    pub name: String,
+
+    /// Product or model name of the server.
+    pub product: String,
+
+    /// Location of the server, such as a datacenter name.
+    pub location: String,
+
+    /// Primary IPv4 address, if any.
+    pub ipv4: Option<Ipv4Addr>,
+
+    /// Primary IPv6 network, if any.
+    pub ipv6: Option<Ipv6Net>,
+
+    /// Whether the server has been cancelled.
+    pub cancelled: bool,
+}
+
+impl From<Server> for DedicatedServer<ServerId> {
+    fn from(server: Server) -> Self {
+        DedicatedServer {
+            id: server.id,
+            name: server.name,
+            product: server.product,
+            location: server.dc.to_string(),
+            ipv4: server.ipv4,
+            // Hetzner assigns a /64 prefix to every server.
+            ipv6: Ipv6Net::new(server.ipv6_net, 64).ok(),
+            cancelled: server.cancelled,
+        }
+    }
+}
+
+/// Operations common to dedicated server providers.
+///
+/// # Example
+/// ```rust,no_run
+/// use hrobot::provider::DedicatedServerProvider;
+///
+/// async fn print_servers<P: DedicatedServerProvider>(provider: &P) -> Result<(), P::Error> {
+///     for server in provider.list_servers().await? {
+///         println!("{} ({})", server.name, server.location);
+///     }
+///
+///     Ok(())
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// print_servers(&hrobot::AsyncRobot::default()).await.unwrap();
+/// # }
+/// ```
+pub trait DedicatedServerProvider: Send + Sync {
+    /// Identifier of a server.
+    type ServerId: Clone + Send + Sync;
+
+    /// Firewall configuration of a single server.
+    type Firewall: Send + Sync;
+
+    /// Error returned by all operations.
+    type Error: std::error::Error + 'static;
+
+    /// List all servers.
+    fn list_servers(&self)
+        -> ProviderFuture<'_, Vec<DedicatedServer<Self::ServerId>>, Self::Error>;
+
+    /// Get a single server.
+    fn get_server(
+        &self,
+        id: Self::ServerId,
+    ) -> ProviderFuture<'_, DedicatedServer<Self::ServerId>, Self::Error>;
+
+    /// Rename a server, returning the updated server.
+    fn rename_server<'a>(
+        &'a self,
+        id: Self::ServerId,
+        name: &'a str,
+    ) -> ProviderFuture<'a, DedicatedServer<Self::ServerId>, Self::Error>;
+
+    /// Get the firewall configuration of a server.
+    fn get_firewall(&self, id: Self::ServerId) -> ProviderFuture<'_, Self::Firewall, Self::Error>;
+
+    /// Replace the firewall configuration of a server.
+    fn set_firewall<'a>(
+        &'a self,
+        id: Self::ServerId,
+        firewall: &'a Self::Firewall,
+    ) -> ProviderFuture<'a, (), Self::Error>;
+
+    /// Reboot a server.
+    fn reboot(&self, id: Self::ServerId) -> ProviderFuture<'_, (), Self::Error>;
+}
+
+/// Implemented using [`AsyncRobot`]'s existing methods.
+///
+/// [`reboot`](DedicatedServerProvider::reboot) triggers the mildest reset supported by
+/// the server, a [`Reset::Software`] if available and a [`Reset::Hardware`] otherwise.
+/// Servers supporting neither fail with [`Error::ResetUnsupported`].
+impl DedicatedServerProvider for AsyncRobot {
+    type ServerId = ServerId;
+    type Firewall = FirewallConfig;
+    type Error = Error;
+
+    fn list_servers(&self) -> ProviderFuture<'_, Vec<DedicatedServer<ServerId>>, Error> {
+        Box::pin(async move {
+            Ok(AsyncRobot::list_servers(self)
+                .await?
+                .into_iter()
+                .map(DedicatedServer::from)
+                .collect())
+        })
+    }
+
+    fn get_server(&self, id: ServerId) -> ProviderFuture<'_, DedicatedServer<ServerId>, Error> {
+        Box::pin(async move { Ok(AsyncRobot::get_server(self, id).await?.into()) })
+    }
+
+    fn rename_server<'a>(
+        &'a self,
+        id: ServerId,
+        name: &'a str,
+    ) -> ProviderFuture<'a, DedicatedServer<ServerId>, Error> {
+        Box::pin(async move { Ok(AsyncRobot::rename_server(self, id, name).await?.into()) })
+    }
+
+    fn get_firewall(&self, id: ServerId) -> ProviderFuture<'_, FirewallConfig, Error> {
+        Box::pin(async move { Ok(AsyncRobot::get_firewall(self, id).await?.config()) })
+    }
+
+    fn set_firewall<'a>(
+        &'a self,
+        id: ServerId,
+        firewall: &'a FirewallConfig,
+    ) -> ProviderFuture<'a, (), Error> {
+        Box::pin(async move {
+            let _ = self.set_firewall_config(id, firewall).await?;
+            Ok(())
+        })
+    }
+
+    fn reboot(&self, id: ServerId) -> ProviderFuture<'_, (), Error> {
+        Box::pin(async move {
+            let validated = ResetOptions::default().with_validation(true);
+
+            let fallback = match self
+                .trigger_reset_with(id, Reset::Software, validated)
+                .await
+            {
+                Err(Error::ResetUnsupported { supported, .. })
+                    if supported.contains(&Reset::Hardware) =>
+                {
+                    Reset::Hardware
+                }
+                result => return result.map(|_| ()),
+            };
+
+            let _ = self.trigger_reset(id, fallback).await?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        api::{reset::Reset, server::ServerId},
+        error::Error,
+        mock::{fixtures, MockClient},
+    };
+
+    use super::DedicatedServerProvider;
+
+    async fn servers<P: DedicatedServerProvider>(provider: &P) -> Vec<String> {
+        provider
+            .list_servers()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|server| format!("{} {} {:?}", server.name, server.location, server.ipv4))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn async_robot_provider() {
+        let client = MockClient::new()
+            .on("GET", "/server", json!([fixtures::server(1)]))
+            .on("GET", "/server/*", fixtures::server(1))
+            .on(
+                "GET",
+                "/reset/1",
+                fixtures::reset(1, json!(["sw", "hw", "man"])),
+            )
+            .on("GET", "/reset/2", fixtures::reset(2, json!(["hw", "man"])))
+            .on("GET", "/reset/3", fixtures::reset(3, json!(["man"])))
+            .on_with("POST", "/reset/*", |request| {
+                let server = request.uri().path().rsplit_once('/').unwrap().1;
+                let reset = request.body().strip_prefix("type=").unwrap();
+
+                fixtures::reset(server.parse().unwrap(), json!(reset)).into()
+            });
+        let requests = client.requests();
+        let robot = client.robot();
+
+        assert_eq!(servers(&robot).await, ["n1 FSN1-DC1 Some(1.1.1.1)"]);
+
+        let server = DedicatedServerProvider::get_server(&robot, ServerId(1))
+            .await
+            .unwrap();
+        assert_eq!(server.id, ServerId(1));
+        assert_eq!(server.product, "Server Auction");
+        assert_eq!(server.ipv6, Some("2a01:4f8:1::/64".parse().unwrap()));
+
+        // The mildest reset supported by each server is used.
+        for server in [ServerId(1), ServerId(2)] {
+            DedicatedServerProvider::reboot(&robot, server)
+                .await
+                .unwrap();
+        }
+        assert!(matches!(
+            DedicatedServerProvider::reboot(&robot, ServerId(3)).await,
+            Err(Error::ResetUnsupported {
+                reset: Reset::Software,
+                ..
+            })
+        ));
+
+        assert_eq!(requests.bodies(), ["type=sw", "type=hw"]);
+        assert_eq!(
+            requests.lines(),
+            [
+                "GET /server",
+                "GET /server/1",
+                "GET /reset/1",
+                "POST /reset/1",
+                "GET /reset/2",
+                "POST /reset/2",
+                "GET /reset/3"
+            ]
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Strict deserialization of API responses.
+//!
+//! Used when [`AsyncRobot::strict_models`](crate::AsyncRobot::strict_models) is enabled,
+//! to detect drift between the API and the models as early as possible.
+
+use std::cell::Cell;
+
+use serde::de::DeserializeOwned;
+
+use crate::error::Error;
+
+thread_local! {
+    /// Set while [`from_slice`] is deserializing a body on this thread.
+    static ACTIVE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Returns true if called from within [`from_slice`], allowing deserializers
+/// which are lenient by design to fail instead.
+#[cfg(feature = "ordering")]
+pub(crate) fn is_active() -> bool {
+    ACTIVE.get()
+}
+
+/// Marks strict deserialization as active until dropped.
+struct ActiveGuard(bool);
+
+impl ActiveGuard {
+    fn enter() -> Self {
+        ActiveGuard(ACTIVE.replace(true))
+    }
+}
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        ACTIVE.set(self.0);
+    }
+}
+
+/// Deserialize `body`, failing on any field the models do not know about,
+/// in addition to any of the usual deserialization failures such as unknown
+/// enum variants.
+///
+/// Errors carry a JSON pointer to the offending value within the body.
+pub(crate) fn from_slice<T: DeserializeOwned>(body: &[u8]) -> Result<T, Error> {
+    let _active = ActiveGuard::enter();
+    let mut unknown = None;
+    let mut deserializer = serde_json::Deserializer::from_slice(body);
+
+    // Ignoring the entire body is deliberate, e.g. for empty responses.
+    let mut record_unknown = |path: serde_ignored::Path| {
+        if unknown.is_none() && !matches!(path, serde_ignored::Path::Root) {
+            unknown = Some(ignored_pointer(&path));
+        }
+    };
+
+    let result = serde_path_to_error::deserialize(serde_ignored::Deserializer::new(
+        &mut deserializer,
+        &mut record_unknown,
+    ));
+
+    let value = result.map_err(|err| Error::ModelMismatch {
+        pointer: error_pointer(err.path()),
+        message: err.inner().to_string(),
+    })?;
+
+    deserializer.end()?;
+
+    match unknown {
+        Some(pointer) => Err(Error::ModelMismatch {
+            pointer,
+            message: "unknown field".to_string(),
+        }),
+        None => Ok(value),
+    }
+}
+
+/// Escape a single reference token of a JSON pointer, as per RFC 6901.
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn ignored_pointer(path: &serde_ignored::Path) -> String {
+    match path {
+        serde_ignored::Path::Root => String::new(),
+        serde_ignored::Path::Seq { parent, index } => {
+            format!("{}/{index}", ignored_pointer(parent))
+        }
+        serde_ignored::Path::Map { parent, key } => {
+            format!("{}/{}", ignored_pointer(parent), escape(key))
+        }
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => ignored_pointer(parent),
+    }
+}
+
+fn error_pointer(path: &serde_path_to_error::Path) -> String {
+    path.iter()
+        .filter_map(|segment| match segment {
+            serde_path_to_error::Segment::Seq { index } => Some(format!("/{index}")),
+            serde_path_to_error::Segment::Map { key } => Some(format!("/{}", escape(key))),
+            serde_path_to_error::Segment::Enum { variant } => Some(format!("/{}", escape(variant))),
+            serde_path_to_error::Segment::Unknown => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use crate::{
+        error::Error,
+        mock::{fixtures, MockClient},
+    };
+
+    use super::from_slice;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Color {
+        Red,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Paint {
+        #[allow(dead_code)]
+        colors: Vec<Color>,
+    }
+
+    #[test]
+    fn unknown_variant() {
+        let Err(Error::ModelMismatch { pointer, message }) =
+            from_slice::<Paint>(br#"{"colors":["red","blue"]}"#)
+        else {
+            panic!("expected model mismatch");
+        };
+
+        assert_eq!(pointer, "/colors/1");
+        assert!(message.contains("unknown variant `blue`"));
+    }
+
+    #[tokio::test]
+    async fn unknown_field() {
+        // The entry contains a field unknown to `RdnsEntry`.
+        let robot = MockClient::new()
+            .on(
+                "GET",
+                "/rdns",
+                json!([fixtures::patch(
+                    fixtures::rdns("1.1.1.1", "one.example.com"),
+                    json!({"ttl": 3600})
+                )]),
+            )
+            .robot();
+        assert_eq!(robot.list_rdns_entries().await.unwrap().len(), 1);
+
+        let Err(Error::ModelMismatch { pointer, message }) =
+            robot.strict_models(true).list_rdns_entries().await
+        else {
+            panic!("expected model mismatch");
+        };
+
+        assert_eq!(pointer, "/0/rdns/ttl");
+        assert_eq!(message, "unknown field");
+    }
+}
@@ -0,0 +1,200 @@
+use std::fmt::Display;
+
+use hyper::Uri;
+use serde_json::Value;
+
+use crate::api::server::ServerId;
+
+/// Replacement for the values of sensitive fields.
+const REDACTED: &str = "<redacted>";
+
+/// Returns true if the value of a field with the given name must not be logged.
+fn is_sensitive(field: &str) -> bool {
+    field.to_ascii_lowercase().contains("password")
+}
+
+/// Endpoint being requested, used for populating tracing spans.
+///
+/// Path parameters are replaced by placeholders, so that all requests for the same
+/// endpoint share the same name, e.g. `/boot/{server_id}/rescue`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Endpoint {
+    /// Path of the endpoint, with parameters replaced by placeholders.
+    pub name: String,
+    /// Server the request concerns, if any.
+    pub server: Option<ServerId>,
+    /// First resource other than a server addressed by the path, if any, e.g. `storagebox 1234`.
+    pub target: Option<String>,
+}
+
+impl From<&Uri> for Endpoint {
+    fn from(uri: &Uri) -> Self {
+        // Resources which are addressed by a server number.
+        const SERVER_SCOPED: &[&str] =
+            &["boot", "firewall", "reset", "server", "server_addon", "wol"];
+
+        let mut name = String::new();
+        let mut server = None;
+        let mut target = None;
+        let mut previous = "";
+
+        for segment in uri.path().split('/').filter(|segment| !segment.is_empty()) {
+            name.push('/');
+
+            // Fixed path segments only ever consist of lowercase letters and underscores.
+            if segment.chars().all(|c| c.is_ascii_lowercase() || c == '_') {
+                name.push_str(segment);
+            } else if let (true, Ok(id)) = (SERVER_SCOPED.contains(&previous), segment.parse()) {
+                server = Some(ServerId(id));
+                name.push_str("{server_id}");
+            } else {
+                name.push_str(&format!("{{{previous}}}"));
+
+                if target.is_none() {
+                    let value = urlencoding::decode(segment).unwrap_or_default();
+                    target = Some(format!("{} {value}", previous.replace('_', " ")));
+                }
+            }
+
+            previous = segment;
+        }
+
+        Endpoint {
+            name,
+            server,
+            target,
+        }
+    }
+}
+
+/// Mask the values of sensitive fields in an `application/x-www-form-urlencoded` body.
+pub(crate) fn redact_form(body: &str) -> String {
+    body.split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if is_sensitive(&urlencoding::decode(key).unwrap_or_default()) => {
+                format!("{key}={REDACTED}")
+            }
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Mask the values of sensitive fields in a response body, for logging.
+///
+/// Bodies which are not valid JSON are returned as-is, lossily converted to UTF-8.
+pub(crate) fn redact_json(body: &[u8]) -> RedactedBody {
+    match serde_json::from_slice::<Value>(body) {
+        Ok(mut value) => {
+            redact_value(&mut value);
+            RedactedBody(value.to_string())
+        }
+        Err(_) => RedactedBody(String::from_utf8_lossy(body).into_owned()),
+    }
+}
+
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_sensitive(key) && !value.is_null() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(value);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact_value),
+        _ => (),
+    }
+}
+
+/// Response body with sensitive fields masked.
+#[derive(Debug)]
+pub(crate) struct RedactedBody(String);
+
+impl Display for RedactedBody {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::Uri;
+
+    use crate::api::server::ServerId;
+
+    use super::{redact_form, redact_json, Endpoint};
+
+    fn endpoint(uri: &'static str) -> Endpoint {
+        Endpoint::from(&Uri::from_static(uri))
+    }
+
+    #[test]
+    fn endpoint_names() {
+        assert_eq!(
+            endpoint("https://robot-ws.your-server.de/boot/1234567/rescue"),
+            Endpoint {
+                name: "/boot/{server_id}/rescue".to_string(),
+                server: Some(ServerId(1234567)),
+                target: None,
+            }
+        );
+
+        assert_eq!(
+            endpoint("https://robot-ws.your-server.de/firewall/template/1234"),
+            Endpoint {
+                name: "/firewall/template/{template}".to_string(),
+                server: None,
+                target: Some("template 1234".to_string()),
+            }
+        );
+
+        assert_eq!(
+            endpoint(
+                "https://robot-ws.your-server.de/storagebox/1234/subaccount/u1234-sub1/password"
+            ),
+            Endpoint {
+                name: "/storagebox/{storagebox}/subaccount/{subaccount}/password".to_string(),
+                server: None,
+                target: Some("storagebox 1234".to_string()),
+            }
+        );
+
+        assert_eq!(
+            endpoint("https://robot-ws.your-server.de/order/server_addon/1234567/product?x=1"),
+            Endpoint {
+                name: "/order/server_addon/{server_id}/product".to_string(),
+                server: Some(ServerId(1234567)),
+                target: None,
+            }
+        );
+    }
+
+    #[test]
+    fn redact_request_body() {
+        assert_eq!(
+            redact_form("product_id=AX41&password=trustno1&location=FSN1"),
+            "product_id=AX41&password=<redacted>&location=FSN1"
+        );
+    }
+
+    #[test]
+    fn redact_response_body() {
+        assert_eq!(
+            redact_json(
+                br#"{"rescue":{"server_number":321,"password":"trustno1","authorized_key":[]}}"#
+            )
+            .to_string(),
+            r#"{"rescue":{"authorized_key":[],"password":"<redacted>","server_number":321}}"#
+        );
+
+        assert_eq!(
+            redact_json(br#"{"rescue":{"password":null}}"#).to_string(),
+            r#"{"rescue":{"password":null}}"#
+        );
+
+        assert_eq!(redact_json(b"not json").to_string(), "not json");
+    }
+}
@@ -0,0 +1,417 @@
+//! Provisioning helpers for integration tests against the Hetzner Robot API.
+//!
+//! These are the helpers used by hrobot's own integration tests, for locating
+//! the server and storagebox dedicated to testing, and for waiting until firewall
+//! and vSwitch changes have been applied, which can take several minutes.
+//!
+//! # Example
+//! ```rust,no_run
+//! # use hrobot::test_harness::{HarnessConfig, TestHarness};
+//! # use std::time::Duration;
+//! # #[tokio::main]
+//! # async fn main() {
+//! let harness = TestHarness::new(
+//!     hrobot::AsyncRobot::default(),
+//!     HarnessConfig::default()
+//!         .with_storagebox_id_var("MY_TEST_STORAGEBOX_ID")
+//!         .with_ready_timeout(Duration::from_secs(600)),
+//! );
+//!
+//! let server = harness.provisioned_server().await.unwrap();
+//! let storagebox = harness.provisioned_storagebox().await.unwrap();
+//! # }
+//! ```
+
+use std::{path::PathBuf, time::Duration};
+
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use crate::{
+    api::{
+        firewall::{Firewall, State},
+        server::{self, Server, ServerId},
+        storagebox::{StorageBox, StorageBoxId},
+        vswitch::{ConnectionStatus, VSwitch, VSwitchId},
+    },
+    error::{ApiError, Error},
+    poll::{PollInterrupted, PollOptions},
+    AsyncRobot,
+};
+
+/// Error produced by the [`TestHarness`].
+#[derive(Debug, thiserror::Error)]
+pub enum HarnessError {
+    /// Environment variable identifying a provisioned resource is not set.
+    #[error("environment variable {0} is not set")]
+    MissingVariable(String),
+
+    /// Provisioned resource ID is not a valid number.
+    #[error("{origin} contains invalid id {value:?}")]
+    InvalidId {
+        /// Environment variable or file the ID was read from.
+        origin: String,
+        /// Contents which could not be parsed.
+        value: String,
+    },
+
+    /// Resource did not become ready within the configured timeout.
+    #[error("timed out after {after:?} waiting for {resource}")]
+    Timeout {
+        /// Description of the resource being waited on, e.g. `vswitch 50301`.
+        resource: String,
+        /// Timeout which was exceeded.
+        after: Duration,
+    },
+
+    /// Wait was cancelled using the [`CancellationToken`] of the [`PollOptions`].
+    #[error("cancelled while waiting for {resource}")]
+    Cancelled {
+        /// Description of the resource being waited on, e.g. `vswitch 50301`.
+        resource: String,
+    },
+
+    /// Error returned by the Hetzner Robot API.
+    #[error(transparent)]
+    Api(#[from] Error),
+}
+
+/// Configuration of the [`TestHarness`].
+///
+/// The defaults match the conventions of hrobot's own integration tests.
+#[derive(Debug, Clone)]
+pub struct HarnessConfig {
+    /// Environment variable containing the ID of the test server.
+    ///
+    /// Takes precedence over [`server_id_path`](HarnessConfig::server_id_path).
+    /// Defaults to `HETZNER_INTEGRATION_TEST_SERVER_ID`.
+    pub server_id_var: String,
+
+    /// File the ID of the test server is written to once it has been
+    /// ordered by a setup step. Defaults to `provisioned-server`.
+    pub server_id_path: PathBuf,
+
+    /// Environment variable containing the ID of the test storagebox.
+    /// Defaults to `HETZNER_INTEGRATION_TEST_STORAGEBOX_ID`.
+    pub storagebox_id_var: String,
+
+    /// How to poll while waiting for the test server to be provisioned.
+    /// Defaults to every 60 seconds, for up to 60 minutes.
+    pub provision: PollOptions,
+
+    /// How to poll while waiting for firewalls and vSwitches to become ready.
+    /// Defaults to every 15 seconds, for up to 5 minutes.
+    pub ready: PollOptions,
+}
+
+impl Default for HarnessConfig {
+    fn default() -> Self {
+        HarnessConfig {
+            server_id_var: "HETZNER_INTEGRATION_TEST_SERVER_ID".to_string(),
+            server_id_path: PathBuf::from("provisioned-server"),
+            storagebox_id_var: "HETZNER_INTEGRATION_TEST_STORAGEBOX_ID".to_string(),
+            provision: PollOptions::new(Duration::from_secs(60))
+                .with_timeout(Duration::from_secs(60 * 60)),
+            ready: PollOptions::new(Duration::from_secs(15))
+                .with_timeout(Duration::from_secs(5 * 60)),
+        }
+    }
+}
+
+impl HarnessConfig {
+    /// Read the test server ID from the given environment variable.
+    pub fn with_server_id_var(mut self, name: impl Into<String>) -> Self {
+        self.server_id_var = name.into();
+        self
+    }
+
+    /// Read the test server ID from the given file.
+    pub fn with_server_id_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.server_id_path = path.into();
+        self
+    }
+
+    /// Read the test storagebox ID from the given environment variable.
+    pub fn with_storagebox_id_var(mut self, name: impl Into<String>) -> Self {
+        self.storagebox_id_var = name.into();
+        self
+    }
+
+    /// Wait at most `timeout` for the test server to be provisioned.
+    pub fn with_provision_timeout(mut self, timeout: Duration) -> Self {
+        self.provision.timeout = Some(timeout);
+        self
+    }
+
+    /// Check whether the test server has been provisioned every `interval`.
+    pub fn with_provision_interval(mut self, interval: Duration) -> Self {
+        self.provision.interval = interval;
+        self
+    }
+
+    /// Wait at most `timeout` for firewalls and vSwitches to become ready.
+    pub fn with_ready_timeout(mut self, timeout: Duration) -> Self {
+        self.ready.timeout = Some(timeout);
+        self
+    }
+
+    /// Check whether firewalls and vSwitches are ready every `interval`.
+    pub fn with_ready_interval(mut self, interval: Duration) -> Self {
+        self.ready.interval = interval;
+        self
+    }
+
+    /// Stop all waits as soon as `token` is cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.provision.cancellation = Some(token.clone());
+        self.ready.cancellation = Some(token);
+        self
+    }
+}
+
+/// Locates provisioned test resources, and waits for changes to them to be applied.
+#[derive(Debug, Clone)]
+pub struct TestHarness {
+    robot: AsyncRobot,
+    config: HarnessConfig,
+}
+
+impl TestHarness {
+    /// Construct a harness using the given client and configuration.
+    pub fn new(robot: AsyncRobot, config: HarnessConfig) -> Self {
+        TestHarness { robot, config }
+    }
+
+    /// Client used for all requests made by the harness.
+    pub fn robot(&self) -> &AsyncRobot {
+        &self.robot
+    }
+
+    /// Configuration of the harness.
+    pub fn config(&self) -> &HarnessConfig {
+        &self.config
+    }
+
+    /// Record the ID of a newly ordered test server in the
+    /// [`server_id_path`](HarnessConfig::server_id_path) file,
+    /// for [`TestHarness::provisioned_server_id`] to pick up.
+    pub fn record_provisioned_server(&self, id: impl Into<ServerId>) -> std::io::Result<()> {
+        std::fs::write(&self.config.server_id_path, id.into().to_string())
+    }
+
+    /// Wait for the test server to be provisioned and marked as ready.
+    ///
+    /// The ID is read from the [`server_id_var`](HarnessConfig::server_id_var) environment
+    /// variable if set, otherwise from the [`server_id_path`](HarnessConfig::server_id_path) file,
+    /// which is re-read until it has been written.
+    pub async fn provisioned_server_id(&self) -> Result<ServerId, HarnessError> {
+        let options = &self.config.provision;
+        let deadline = options.deadline();
+
+        loop {
+            match self.read_server_id()? {
+                Some(server_id) => match self.robot.get_server(server_id).await {
+                    Ok(server) if server.status == server::Status::Ready => return Ok(server_id),
+                    Ok(_) => info!("server available, but not yet marked as ready."),
+                    Err(_) => {
+                        info!("got server id, but server is not available in robot interface yet.")
+                    }
+                },
+                None => info!("provisioned server id has not been written to path yet."),
+            }
+
+            wait(options, deadline, || "provisioned server".to_string()).await?;
+        }
+    }
+
+    /// Wait for the test server to be provisioned, and retrieve it.
+    pub async fn provisioned_server(&self) -> Result<Server, HarnessError> {
+        let id = self.provisioned_server_id().await?;
+
+        Ok(self.robot.get_server(id).await?)
+    }
+
+    /// Read the test storagebox ID from the
+    /// [`storagebox_id_var`](HarnessConfig::storagebox_id_var) environment variable.
+    pub fn provisioned_storagebox_id(&self) -> Result<StorageBoxId, HarnessError> {
+        let name = &self.config.storagebox_id_var;
+        let value = std::env::var(name).map_err(|_| HarnessError::MissingVariable(name.clone()))?;
+
+        parse_id(name, &value).map(StorageBoxId)
+    }
+
+    /// Retrieve the test storagebox.
+    pub async fn provisioned_storagebox(&self) -> Result<StorageBox, HarnessError> {
+        let id = self.provisioned_storagebox_id()?;
+
+        Ok(self.robot.get_storagebox(id).await?)
+    }
+
+    /// Wait for pending changes to the firewall of the server to be applied.
+    ///
+    /// Waits one [`ready`](HarnessConfig::ready) interval before the first
+    /// check, since a recently updated firewall is not always immediately reported
+    /// as [`State::InProcess`].
+    pub async fn wait_firewall_ready(
+        &self,
+        server_id: impl Into<ServerId>,
+    ) -> Result<Firewall, HarnessError> {
+        let server_id = server_id.into();
+        let options = &self.config.ready;
+        let deadline = options.deadline();
+        let resource = || format!("firewall of server {server_id}");
+
+        loop {
+            wait(options, deadline, resource).await?;
+
+            let firewall = self.robot.get_firewall(server_id).await?;
+            if firewall.status != State::InProcess {
+                return Ok(firewall);
+            }
+
+            info!(
+                "firewall state for {server_id} is still \"in process\", checking again in {:?}.",
+                options.interval
+            );
+        }
+    }
+
+    /// Wait for the vSwitch to become available, and for all of its servers to be connected.
+    pub async fn wait_vswitch_ready(
+        &self,
+        id: impl Into<VSwitchId>,
+    ) -> Result<VSwitch, HarnessError> {
+        let id = id.into();
+        let options = &self.config.ready;
+        let deadline = options.deadline();
+
+        loop {
+            match self.robot.get_vswitch(id).await {
+                Ok(vswitch)
+                    if vswitch
+                        .servers
+                        .iter()
+                        .all(|server| server.status == ConnectionStatus::Ready) =>
+                {
+                    return Ok(vswitch)
+                }
+                Ok(_) => info!("vswitch servers not yet ready, waiting.."),
+                Err(Error::Api(ApiError::VswitchNotAvailable { .. }, _)) => {
+                    info!("vswitch not available, waiting..")
+                }
+                Err(Error::Api(ApiError::VswitchInProcess { .. }, _)) => {
+                    info!("vswitch in process, waiting..")
+                }
+                Err(err) => return Err(err.into()),
+            }
+
+            wait(options, deadline, || format!("vswitch {id}")).await?;
+        }
+    }
+
+    fn read_server_id(&self) -> Result<Option<ServerId>, HarnessError> {
+        if let Ok(value) = std::env::var(&self.config.server_id_var) {
+            return parse_id(&self.config.server_id_var, &value).map(|id| Some(ServerId(id)));
+        }
+
+        match std::fs::read_to_string(&self.config.server_id_path) {
+            Ok(contents) => parse_id(&self.config.server_id_path.display().to_string(), &contents)
+                .map(|id| Some(ServerId(id))),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+/// Sleep until it is time to poll again, describing the resource if the wait is interrupted.
+async fn wait(
+    options: &PollOptions,
+    deadline: Option<Instant>,
+    resource: impl Fn() -> String,
+) -> Result<(), HarnessError> {
+    options
+        .sleep(deadline)
+        .await
+        .map_err(|interrupted| match interrupted {
+            PollInterrupted::Timeout => HarnessError::Timeout {
+                resource: resource(),
+                after: options.timeout.unwrap_or_default(),
+            },
+            PollInterrupted::Cancelled => HarnessError::Cancelled {
+                resource: resource(),
+            },
+        })
+}
+
+fn parse_id(origin: &str, value: &str) -> Result<u32, HarnessError> {
+    value.trim().parse().map_err(|_| HarnessError::InvalidId {
+        origin: origin.to_string(),
+        value: value.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{iter, time::Duration};
+
+    use serde_json::json;
+    use tokio_util::sync::CancellationToken;
+
+    use crate::{
+        api::vswitch::VSwitchId,
+        mock::{fixtures, MockClient},
+    };
+
+    use super::{HarnessConfig, HarnessError, TestHarness};
+
+    #[tokio::test(start_paused = true)]
+    async fn wait_vswitch_ready() {
+        // Reports the vSwitch server as processing for the first `ready_after` requests.
+        let harness = |ready_after| {
+            let vswitch = |status| {
+                fixtures::patch(
+                    fixtures::vswitch(50301),
+                    json!({"server": [{"server_number": 1234, "status": status}]}),
+                )
+            };
+            let client = MockClient::new().on_sequence(
+                "GET",
+                "/vswitch/50301",
+                iter::repeat_n(vswitch("processing"), ready_after).chain([vswitch("ready")]),
+            );
+            let requests = client.requests();
+
+            let harness = TestHarness::new(
+                client.robot(),
+                HarnessConfig::default().with_ready_timeout(Duration::from_secs(60)),
+            );
+
+            (harness, requests)
+        };
+
+        let (ready, requests) = harness(2);
+        let vswitch = ready.wait_vswitch_ready(VSwitchId(50301)).await.unwrap();
+        assert_eq!(vswitch.id, 50301);
+        assert_eq!(requests.len(), 3);
+
+        let (stuck, requests) = harness(usize::MAX);
+        assert!(matches!(
+            stuck.wait_vswitch_ready(50301).await,
+            Err(HarnessError::Timeout { after, .. }) if after == Duration::from_secs(60)
+        ));
+        assert_eq!(requests.len(), 5);
+
+        let (stuck, requests) = harness(usize::MAX);
+        let token = CancellationToken::new();
+        let cancelled = TestHarness::new(
+            stuck.robot().clone(),
+            stuck.config().clone().with_cancellation(token.clone()),
+        );
+        token.cancel();
+        assert!(matches!(
+            cancelled.wait_vswitch_ready(50301).await,
+            Err(HarnessError::Cancelled { resource }) if resource == "vswitch 50301"
+        ));
+        assert_eq!(requests.len(), 1);
+    }
+}
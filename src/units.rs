@@ -0,0 +1,259 @@
+//! Parsers and converters for the sizes used throughout the Robot API.
+//!
+//! The API is inconsistent in how it represents sizes: traffic quotas are
+//! strings like `"2 TB"` or `"1,5 TB"`, storagebox quotas are integer MiB,
+//! memory and drive sizes of products are integer GB, and traffic statistics
+//! are fractional GiB. These are the converters used by the models of this
+//! crate, exposed for tools consuming raw Hetzner exports.
+//!
+//! # Example
+//! ```rust
+//! # use hrobot::bytesize::ByteSize;
+//! use hrobot::units;
+//!
+//! assert_eq!(units::parse_byte_size("1,5 TB"), Some(ByteSize::gb(1500)));
+//! assert_eq!(units::gb_to_bytesize(64), ByteSize::gb(64));
+//! assert_eq!(units::mib_to_bytesize(1024), ByteSize::gib(1));
+//! ```
+
+use bytesize::{ByteSize, GB, GIB, MIB};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+use crate::api::traffic::TrafficQuota;
+
+/// Decimal and binary units recognized by [`parse_byte_size`], case-insensitively.
+pub(crate) const UNITS: [(&str, u64); 12] = [
+    ("", 1),
+    ("B", 1),
+    ("KB", 1_000),
+    ("MB", 1_000_000),
+    ("GB", 1_000_000_000),
+    ("TB", 1_000_000_000_000),
+    ("PB", 1_000_000_000_000_000),
+    ("KIB", 1 << 10),
+    ("MIB", 1 << 20),
+    ("GIB", 1 << 30),
+    ("TIB", 1 << 40),
+    ("PIB", 1 << 50),
+];
+
+/// Parse an amount followed by an optional unit, such as `"2 TB"` or `"1,5 TiB"`.
+///
+/// Decimal units (`KB` through `PB`) and binary units (`KiB` through `PiB`) are
+/// accepted case-insensitively, and amounts without a unit are bytes. The last
+/// `.` or `,` is treated as the decimal separator and any others as thousands
+/// separators, so `1.000,5 GB` and `1,000.5 GB` are equivalent. Fractional
+/// bytes are rounded.
+///
+/// Returns `None` if the input is not a non-negative amount with a known unit,
+/// or does not fit in a [`ByteSize`].
+///
+/// # Example
+/// ```rust
+/// # use hrobot::bytesize::ByteSize;
+/// # use hrobot::units::parse_byte_size;
+/// assert_eq!(parse_byte_size("2 TB"), Some(ByteSize::tb(2)));
+/// assert_eq!(parse_byte_size("1.5GiB"), Some(ByteSize::mib(1536)));
+/// assert_eq!(parse_byte_size("unlimited"), None);
+/// ```
+pub fn parse_byte_size(size: &str) -> Option<ByteSize> {
+    let size = size.trim();
+
+    let split = size
+        .find(|c: char| !c.is_ascii_digit() && c != '.' && c != ',')
+        .unwrap_or(size.len());
+    let (amount, unit) = size.split_at(split);
+
+    let unit = unit.trim();
+    let (_, multiplier) = UNITS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(unit))?;
+
+    // Treat the last separator as the decimal separator, and any others as
+    // thousands separators, e.g. "1.000,5" or "1,000.5".
+    let amount = match amount.rfind(['.', ',']) {
+        Some(separator) => format!(
+            "{}.{}",
+            amount[..separator].replace(['.', ','], ""),
+            &amount[separator + 1..]
+        ),
+        None => amount.to_string(),
+    };
+
+    let amount = Decimal::from_str_exact(&amount).ok()?;
+    let bytes = amount.checked_mul(Decimal::from(*multiplier))?.round();
+
+    Some(ByteSize::b(bytes.to_u64()?))
+}
+
+/// Parse a traffic quota, such as `"unlimited"` or `"20 TB"`.
+///
+/// Shorthand for [`TrafficQuota::parse`], which never fails, and instead
+/// preserves unrecognized quotas as [`TrafficQuota::Unknown`].
+///
+/// # Example
+/// ```rust
+/// # use hrobot::bytesize::ByteSize;
+/// # use hrobot::api::traffic::TrafficQuota;
+/// # use hrobot::units::parse_traffic;
+/// assert_eq!(parse_traffic("20 TB").limit(), Some(ByteSize::tb(20)));
+/// assert!(parse_traffic("unlimited").is_unlimited());
+/// ```
+pub fn parse_traffic(quota: &str) -> TrafficQuota {
+    TrafficQuota::parse(quota)
+}
+
+/// Convert decimal gigabytes, as used for memory and drive sizes of products.
+///
+/// Saturates at [`u64::MAX`] bytes, see [`checked_gb_to_bytesize`] for detecting overflow.
+pub fn gb_to_bytesize(gb: u64) -> ByteSize {
+    ByteSize::b(gb.saturating_mul(GB))
+}
+
+/// Convert decimal gigabytes, or `None` if the result does not fit in a [`ByteSize`].
+pub fn checked_gb_to_bytesize(gb: u64) -> Option<ByteSize> {
+    gb.checked_mul(GB).map(ByteSize::b)
+}
+
+/// Convert binary gibibytes, as used for traffic warning limits.
+///
+/// Saturates at [`u64::MAX`] bytes, see [`checked_gib_to_bytesize`] for detecting overflow.
+pub fn gib_to_bytesize(gib: u64) -> ByteSize {
+    ByteSize::b(gib.saturating_mul(GIB))
+}
+
+/// Convert binary gibibytes, or `None` if the result does not fit in a [`ByteSize`].
+pub fn checked_gib_to_bytesize(gib: u64) -> Option<ByteSize> {
+    gib.checked_mul(GIB).map(ByteSize::b)
+}
+
+/// Convert fractional binary gibibytes, as used for traffic statistics.
+///
+/// Rounds down to whole bytes. Negative and non-finite amounts become zero.
+pub fn gib_float_to_bytesize(gib: f64) -> ByteSize {
+    ByteSize::b((gib * GIB as f64) as u64)
+}
+
+/// Convert binary mebibytes, as used for storagebox quotas and traffic warning limits.
+///
+/// Saturates at [`u64::MAX`] bytes, see [`checked_mib_to_bytesize`] for detecting overflow.
+pub fn mib_to_bytesize(mib: u64) -> ByteSize {
+    ByteSize::b(mib.saturating_mul(MIB))
+}
+
+/// Convert binary mebibytes, or `None` if the result does not fit in a [`ByteSize`].
+pub fn checked_mib_to_bytesize(mib: u64) -> Option<ByteSize> {
+    mib.checked_mul(MIB).map(ByteSize::b)
+}
+
+/// Convert to whole binary gibibytes, rounding down.
+pub fn bytesize_to_gib(size: ByteSize) -> u64 {
+    size.as_u64() / GIB
+}
+
+/// Convert to whole binary mebibytes, rounding down.
+pub fn bytesize_to_mib(size: ByteSize) -> u64 {
+    size.as_u64() / MIB
+}
+
+#[cfg(test)]
+mod tests {
+    use bytesize::ByteSize;
+
+    use crate::api::traffic::TrafficQuota;
+
+    use super::*;
+
+    #[test]
+    fn parse_byte_sizes() {
+        for (input, expected) in [
+            ("2 TB", ByteSize::tb(2)),
+            ("2TB", ByteSize::tb(2)),
+            ("  2 tb  ", ByteSize::tb(2)),
+            ("1,5 TB", ByteSize::gb(1500)),
+            ("1.5 TB", ByteSize::gb(1500)),
+            ("1.000,5 GB", ByteSize::mb(1_000_500)),
+            ("1,000.5 GB", ByteSize::mb(1_000_500)),
+            ("512 MiB", ByteSize::mib(512)),
+            ("1.5 GiB", ByteSize::mib(1536)),
+            ("4 KiB", ByteSize::kib(4)),
+            ("1 PiB", ByteSize::b(1 << 50)),
+            ("1 PB", ByteSize::pb(1)),
+            ("1024", ByteSize::b(1024)),
+            ("10 B", ByteSize::b(10)),
+            ("0 TB", ByteSize::b(0)),
+            ("2.6 B", ByteSize::b(3)),
+        ] {
+            assert_eq!(parse_byte_size(input), Some(expected), "{input}");
+        }
+
+        for input in [
+            "",
+            "TB",
+            "unlimited",
+            "-1 TB",
+            "2 XB",
+            "2 TB TB",
+            "99999999999 PB",
+        ] {
+            assert_eq!(parse_byte_size(input), None, "{input}");
+        }
+    }
+
+    #[test]
+    fn parse_traffic_quotas() {
+        assert_eq!(parse_traffic("unlimited"), TrafficQuota::Unlimited);
+        assert_eq!(
+            parse_traffic("5 TB"),
+            TrafficQuota::Limited(ByteSize::tb(5))
+        );
+        assert_eq!(
+            parse_traffic("lots"),
+            TrafficQuota::Unknown("lots".to_string())
+        );
+    }
+
+    #[test]
+    fn integer_conversions() {
+        assert_eq!(gb_to_bytesize(64), ByteSize::b(64_000_000_000));
+        assert_eq!(gib_to_bytesize(2), ByteSize::b(2 << 30));
+        assert_eq!(mib_to_bytesize(3), ByteSize::b(3 << 20));
+
+        assert_eq!(bytesize_to_gib(ByteSize::gib(7)), 7);
+        assert_eq!(bytesize_to_gib(ByteSize::mib(1535)), 1);
+        assert_eq!(bytesize_to_mib(ByteSize::mib(7)), 7);
+        assert_eq!(bytesize_to_mib(ByteSize::kib(1023)), 0);
+
+        for mib in [0, 1, 1024, 10_000_000] {
+            assert_eq!(bytesize_to_mib(mib_to_bytesize(mib)), mib);
+        }
+
+        assert_eq!(checked_gb_to_bytesize(64), Some(ByteSize::gb(64)));
+        assert_eq!(checked_gib_to_bytesize(2), Some(ByteSize::gib(2)));
+        assert_eq!(checked_mib_to_bytesize(3), Some(ByteSize::mib(3)));
+    }
+
+    #[test]
+    fn integer_conversions_overflow() {
+        assert_eq!(gb_to_bytesize(u64::MAX), ByteSize::b(u64::MAX));
+        assert_eq!(gib_to_bytesize(u64::MAX), ByteSize::b(u64::MAX));
+        assert_eq!(mib_to_bytesize(u64::MAX), ByteSize::b(u64::MAX));
+
+        assert_eq!(checked_gb_to_bytesize(u64::MAX), None);
+        assert_eq!(checked_gib_to_bytesize(u64::MAX), None);
+        assert_eq!(checked_mib_to_bytesize(u64::MAX), None);
+
+        // Largest amounts which still fit.
+        assert!(checked_gb_to_bytesize(u64::MAX / GB).is_some());
+        assert!(checked_gib_to_bytesize(u64::MAX / GIB).is_some());
+        assert!(checked_mib_to_bytesize(u64::MAX / MIB).is_some());
+    }
+
+    #[test]
+    fn float_conversions() {
+        assert_eq!(gib_float_to_bytesize(1.5), ByteSize::mib(1536));
+        assert_eq!(gib_float_to_bytesize(0.0), ByteSize::b(0));
+        assert_eq!(gib_float_to_bytesize(-1.0), ByteSize::b(0));
+        assert_eq!(gib_float_to_bytesize(f64::NAN), ByteSize::b(0));
+    }
+}
@@ -1,40 +1,102 @@
-use std::fmt::Display;
+use std::fmt::{Display, Write};
 
+/// Writes `application/x-www-form-urlencoded` key-value pairs into a single
+/// output string.
+///
+/// Nested keys such as `rules[input][0][name]` are built by pushing segments
+/// onto a shared prefix using [`UrlEncodingBuffer::append`], which are popped
+/// again when the returned buffer is dropped, so encoding does not allocate
+/// beyond growing the output and prefix.
 #[derive(Debug)]
 pub(crate) struct UrlEncodingBuffer<'a> {
-    buffer: &'a mut Vec<String>,
-    prefix: String,
+    output: &'a mut String,
+    prefix: &'a mut String,
+    /// Length of the prefix when this buffer was created, restored on drop.
+    restore: usize,
 }
 
 impl<'a> UrlEncodingBuffer<'a> {
-    pub fn from(buffer: &'a mut Vec<String>) -> Self {
+    pub fn new(output: &'a mut String, prefix: &'a mut String) -> Self {
+        let restore = prefix.len();
+
         UrlEncodingBuffer {
-            buffer,
-            prefix: String::new(),
+            output,
+            prefix,
+            restore,
         }
     }
 
-    pub fn append(&mut self, prefix: &str) -> UrlEncodingBuffer<'_> {
+    /// Buffer whose keys are prefixed by `segment`, in addition to the current prefix.
+    #[cfg(feature = "firewall")]
+    pub fn append(&mut self, segment: impl Display) -> UrlEncodingBuffer<'_> {
+        let restore = self.prefix.len();
+        let _ = write!(Encoder::new(self.prefix, false), "{segment}");
+
         UrlEncodingBuffer {
-            buffer: self.buffer,
-            prefix: format!("{}{}", self.prefix, prefix),
+            output: self.output,
+            prefix: self.prefix,
+            restore,
         }
     }
 
-    pub fn clone(&mut self) -> UrlEncodingBuffer<'_> {
-        UrlEncodingBuffer {
-            buffer: self.buffer,
-            prefix: self.prefix.clone(),
-        }
+    /// Buffer sharing the current prefix, for passing to nested encoders.
+    pub fn reborrow(&mut self) -> UrlEncodingBuffer<'_> {
+        UrlEncodingBuffer::new(self.output, self.prefix)
     }
 
     pub fn set<V: Display>(&mut self, key: &str, value: V) {
-        self.buffer.push(format!(
-            "{}{}={}",
-            urlencoding::encode(&self.prefix),
-            urlencoding::encode(key),
-            urlencoding::encode(&value.to_string()).replace("%20", "+")
-        ));
+        if !self.output.is_empty() {
+            self.output.push('&');
+        }
+
+        self.output.push_str(self.prefix);
+        let _ = Encoder::new(self.output, false).write_str(key);
+        self.output.push('=');
+        let _ = write!(Encoder::new(self.output, true), "{value}");
+    }
+}
+
+impl<'a> Drop for UrlEncodingBuffer<'a> {
+    fn drop(&mut self) {
+        self.prefix.truncate(self.restore);
+    }
+}
+
+/// Percent-encodes everything written to it into the wrapped string,
+/// leaving only unreserved characters as-is.
+struct Encoder<'a> {
+    output: &'a mut String,
+    space_as_plus: bool,
+}
+
+impl<'a> Encoder<'a> {
+    fn new(output: &'a mut String, space_as_plus: bool) -> Self {
+        Encoder {
+            output,
+            space_as_plus,
+        }
+    }
+}
+
+impl<'a> Write for Encoder<'a> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+        for byte in s.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    self.output.push(byte as char)
+                }
+                b' ' if self.space_as_plus => self.output.push('+'),
+                _ => {
+                    self.output.push('%');
+                    self.output.push(HEX[usize::from(byte >> 4)] as char);
+                    self.output.push(HEX[usize::from(byte & 0xf)] as char);
+                }
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -42,10 +104,63 @@ impl<'a> UrlEncodingBuffer<'a> {
 pub(crate) trait UrlEncode {
     fn encode_into(&self, f: UrlEncodingBuffer<'_>);
     fn encode(&self) -> String {
-        let mut buffer = Vec::new();
-        let encoder = UrlEncodingBuffer::from(&mut buffer);
+        let mut output = String::new();
+        let mut prefix = String::new();
+
+        self.encode_into(UrlEncodingBuffer::new(&mut output, &mut prefix));
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UrlEncode, UrlEncodingBuffer};
+
+    struct Nested;
+
+    impl UrlEncode for Nested {
+        fn encode_into(&self, mut f: UrlEncodingBuffer<'_>) {
+            f.set("name", "Allow all & more");
+            {
+                let mut rules = f.append("rules");
+                for index in 0..2 {
+                    rules
+                        .append(format_args!("[{index}]"))
+                        .set("[port]", "80-443");
+                }
+                rules.reborrow().set("[comment]", "æ/ø?");
+            }
+            f.set("key[]", 1);
+        }
+    }
+
+    #[test]
+    fn nested_keys() {
+        assert_eq!(
+            Nested.encode(),
+            "name=Allow+all+%26+more\
+            &rules%5B0%5D%5Bport%5D=80-443\
+            &rules%5B1%5D%5Bport%5D=80-443\
+            &rules%5Bcomment%5D=%C3%A6%2F%C3%B8%3F\
+            &key%5B%5D=1"
+        );
+    }
+
+    #[test]
+    fn matches_urlencoding() {
+        let input = "a b+c&d=e/f?g~h_i.j-k%l\u{1F600}";
+
+        let mut output = String::new();
+        let mut prefix = String::new();
+        UrlEncodingBuffer::new(&mut output, &mut prefix).set(input, input);
 
-        self.encode_into(encoder);
-        buffer.join("&")
+        assert_eq!(
+            output,
+            format!(
+                "{}={}",
+                urlencoding::encode(input),
+                urlencoding::encode(input).replace("%20", "+")
+            )
+        );
     }
 }
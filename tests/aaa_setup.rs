@@ -34,7 +34,7 @@ async fn provision_cheapest_server() {
 
     let order = MarketProductOrder {
         id: cheapest.id,
-        auth: AuthorizationMethod::Keys(vec![fingerprint.clone()]),
+        auth: AuthorizationMethod::Keys(vec![fingerprint]),
         distribution: None,
         language: None,
         addons: vec![AddonId::from("primary_ipv4")],
@@ -47,9 +47,7 @@ async fn provision_cheapest_server() {
 
     info!("{result:#?}");
 
-    let id = result
-        .server_id
-        .unwrap_or_else(|| ServerId(result.product.id.0));
+    let id = result.server_id.unwrap_or(ServerId(result.product.id.0));
 
-    std::fs::write(common::PROVISIONED_SERVER_ID_PATH, id.to_string()).unwrap();
+    common::harness().record_provisioned_server(id).unwrap();
 }
@@ -1,5 +1,7 @@
 mod common;
 
+use std::net::IpAddr;
+
 use hrobot::{
     api::ip::TrafficWarnings,
     error::{ApiError, Error},
@@ -28,7 +30,10 @@ async fn get_server_ip_information() {
     let robot = AsyncRobot::default();
 
     let server = common::provisioned_server().await;
-    let ip = robot.get_ip(server.ipv4.unwrap()).await.unwrap();
+    let ip = robot
+        .get_ip(IpAddr::V4(server.ipv4.unwrap()))
+        .await
+        .unwrap();
     info!("{ip:#?}");
 }
 
@@ -42,8 +47,10 @@ async fn get_separate_mac() {
     let server = common::provisioned_server().await;
     // Server primary IPs do not have configurable MAC addresses
     assert!(matches!(
-        robot.get_ip_separate_mac(server.ipv4.unwrap()).await,
-        Err(Error::Api(ApiError::MacNotAvailable { .. })),
+        robot
+            .get_ip_separate_mac(IpAddr::V4(server.ipv4.unwrap()))
+            .await,
+        Err(Error::Api(ApiError::MacNotAvailable { .. }, _)),
     ));
 }
 
@@ -54,7 +61,7 @@ async fn get_server_ip_cancellation() {
 
     let robot = crate::AsyncRobot::default();
 
-    let ip = common::provisioned_server().await.ipv4.unwrap();
+    let ip = IpAddr::V4(common::provisioned_server().await.ipv4.unwrap());
     let cancellation = robot.get_ip_cancellation(ip).await.unwrap();
     info!("{cancellation:#?}");
 }
@@ -68,7 +75,7 @@ async fn enable_and_disable_traffic_warnings() {
     let robot = AsyncRobot::default();
 
     let server = common::provisioned_server().await;
-    let ip = server.ipv4.unwrap();
+    let ip = IpAddr::V4(server.ipv4.unwrap());
     let ip = robot.get_ip(ip).await.unwrap();
     info!("{ip:#?}");
 
@@ -5,7 +5,6 @@ use hrobot::{
         AddonId, AddonOrder, AuthorizationMethod, ImSeriousAboutSpendingMoney, MarketProductOrder,
         ProductOrder,
     },
-    error::{ApiError, Error},
     AsyncRobot,
 };
 use rust_decimal::Decimal;
@@ -44,18 +43,7 @@ async fn list_recent_product_transactions() {
 
     let robot = AsyncRobot::default();
 
-    for transaction in robot
-        .list_recent_product_transactions()
-        .await
-        .or_else(|err| {
-            if matches!(err, Error::Api(ApiError::NotFound { .. })) {
-                Ok(vec![])
-            } else {
-                Err(err)
-            }
-        })
-        .unwrap()
-    {
+    for transaction in robot.list_recent_product_transactions().await.unwrap() {
         info!("{transaction:#?}");
     }
 }
@@ -70,13 +58,6 @@ async fn get_recent_product_transactions() {
     if let Some(transaction) = robot
         .list_recent_product_transactions()
         .await
-        .or_else(|err| {
-            if matches!(err, Error::Api(ApiError::NotFound { .. })) {
-                Ok(vec![])
-            } else {
-                Err(err)
-            }
-        })
         .unwrap()
         .first()
     {
@@ -120,18 +101,7 @@ async fn list_recent_market_transactions() {
 
     let robot = AsyncRobot::default();
 
-    for transaction in robot
-        .list_recent_market_transactions()
-        .await
-        .or_else(|err| {
-            if matches!(err, Error::Api(ApiError::NotFound { .. })) {
-                Ok(vec![])
-            } else {
-                Err(err)
-            }
-        })
-        .unwrap()
-    {
+    for transaction in robot.list_recent_market_transactions().await.unwrap() {
         info!("{transaction:#?}");
     }
 }
@@ -146,13 +116,6 @@ async fn get_recent_market_transactions() {
     if let Some(transaction) = robot
         .list_recent_market_transactions()
         .await
-        .or_else(|err| {
-            if matches!(err, Error::Api(ApiError::NotFound { .. })) {
-                Ok(vec![])
-            } else {
-                Err(err)
-            }
-        })
         .unwrap()
         .first()
     {
@@ -69,7 +69,7 @@ async fn create_update_delete_rdns() {
     if let Some(ip) = ip {
         assert!(matches!(
             robot.get_rdns_entry(ip).await,
-            Err(Error::Api(ApiError::RdnsNotFound { .. }))
+            Err(Error::Api(ApiError::RdnsNotFound { .. }, _))
         ));
 
         let _ = robot
@@ -44,7 +44,7 @@ async fn get_nonexistent_server() {
 
     assert!(matches!(
         result,
-        Err(Error::Api(ApiError::ServerNotFound { .. }))
+        Err(Error::Api(ApiError::ServerNotFound { .. }, _))
     ));
 }
 
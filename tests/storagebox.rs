@@ -289,7 +289,7 @@ async fn create_revert_delete_snapshot() {
     tokio::time::sleep(Duration::from_secs(6)).await;
 
     robot
-        .revert_to_snapshot(storagebox.id, &snapshot.name)
+        .revert_to_snapshot(storagebox.id, snapshot.name)
         .await
         .unwrap();
 
@@ -304,7 +304,7 @@ async fn create_revert_delete_snapshot() {
     tokio::time::sleep(Duration::from_secs(6)).await;
 
     robot
-        .delete_snapshot(storagebox.id, &snapshot.name)
+        .delete_snapshot(storagebox.id, snapshot.name)
         .await
         .unwrap();
 
@@ -325,14 +325,14 @@ async fn create_comment_delete_snapshot() {
     tokio::time::sleep(Duration::from_secs(6)).await;
 
     robot
-        .change_snapshot_comment(storagebox.id, &snapshot.name, "this is the updated comment")
+        .change_snapshot_comment(storagebox.id, snapshot.name, "this is the updated comment")
         .await
         .unwrap();
 
     tokio::time::sleep(Duration::from_secs(6)).await;
 
     robot
-        .delete_snapshot(storagebox.id, &snapshot.name)
+        .delete_snapshot(storagebox.id, snapshot.name)
         .await
         .unwrap();
 
@@ -432,7 +432,7 @@ async fn create_update_delete_subaccount() {
         .update_subaccount(
             storagebox.id,
             &created_subaccount.username,
-            "/home/dir",
+            Some("/home/dir"),
             None,
             None,
             Some("test comment"),
@@ -442,6 +442,20 @@ async fn create_update_delete_subaccount() {
 
     tokio::time::sleep(Duration::from_secs(6)).await;
 
+    robot
+        .update_subaccount(
+            storagebox.id,
+            &created_subaccount.username,
+            None,
+            None,
+            Some(Permission::ReadOnly.into()),
+            None,
+        )
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_secs(6)).await;
+
     robot
         .set_subaccount_home_directory(
             storagebox.id,
@@ -453,6 +467,38 @@ async fn create_update_delete_subaccount() {
 
     tokio::time::sleep(Duration::from_secs(6)).await;
 
+    robot
+        .enable_subaccount_ssh(storagebox.id, &created_subaccount.username)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_secs(6)).await;
+
+    robot
+        .set_subaccount_readonly(storagebox.id, &created_subaccount.username, false)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_secs(6)).await;
+
+    let subaccount = robot
+        .list_subaccounts(storagebox.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|subaccount| subaccount.username == created_subaccount.username)
+        .unwrap();
+
+    assert!(subaccount.accessibility.ssh);
+    assert_eq!(subaccount.permissions.readonly, Permission::ReadWrite);
+
+    robot
+        .disable_subaccount_ssh(storagebox.id, &created_subaccount.username)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_secs(6)).await;
+
     robot
         .delete_subaccount(storagebox.id, created_subaccount.username)
         .await
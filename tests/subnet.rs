@@ -26,10 +26,7 @@ async fn get_subnets() {
     let subnets = robot.list_subnets().await.unwrap();
     info!("{subnets:#?}");
 
-    let subnet = subnets
-        .values()
-        .into_iter()
-        .find_map(|subnet| subnet.first());
+    let subnet = subnets.values().find_map(|subnet| subnet.first());
 
     if let Some(subnet) = subnet {
         let subnet = robot.get_subnet(subnet.ip.addr()).await.unwrap();
@@ -48,7 +45,6 @@ async fn get_subnet_cancellation() {
 
     let subnet = subnets
         .values()
-        .into_iter()
         .filter_map(|subnet| subnet.first())
         .find_map(|subnet| match subnet.ip.addr() {
             IpAddr::V4(addr) => Some(addr),
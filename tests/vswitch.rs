@@ -61,7 +61,7 @@ async fn switch_end_to_end() {
             .await
             .unwrap()
             .into_iter()
-            .map(|switch| switch.vlan.0)
+            .map(|switch| u16::from(switch.vlan))
             .collect();
 
         let available_vlans: Vec<_> = (4030..4091)
@@ -74,19 +74,22 @@ async fn switch_end_to_end() {
 
     info!("using vlans {first} -> {second}");
 
-    let vswitch = robot.create_vswitch(&name, VlanId(first)).await.unwrap();
+    let vswitch = robot
+        .create_vswitch(&name, VlanId::new(first).unwrap())
+        .await
+        .unwrap();
 
     // Rename and change the VLAN ID.
     let name = format!("{name}-re");
     robot
-        .update_vswitch(vswitch.id, &name, VlanId(second))
+        .update_vswitch(vswitch.id, &name, VlanId::new(second).unwrap())
         .await
         .unwrap();
 
     let vswitch = common::wait_vswitch_ready(&robot, vswitch.id).await;
 
     assert_eq!(vswitch.name, name);
-    assert_eq!(vswitch.vlan, VlanId(second));
+    assert_eq!(vswitch.vlan, VlanId::new(second).unwrap());
 
     assert!(vswitch.subnets.is_empty());
     assert!(vswitch.servers.is_empty());